@@ -9,8 +9,10 @@ use std::fs;
 use std::path::PathBuf;
 use std::time::Instant;
 
+use vectorize_core::algorithms::gradient_normal_map;
 use vectorize_core::config::SvgConfig;
-use vectorize_core::svg::generate_svg_document;
+use vectorize_core::preprocessing::{apply_embedded_dpi, decode_image};
+use vectorize_core::svg::{generate_svg_document, generate_svg_sprite};
 use vectorize_core::{vectorize_trace_low, vectorize_trace_low_rgba, ConfigBuilder};
 
 #[derive(Parser)]
@@ -53,7 +55,7 @@ enum Commands {
         /// Output SVG file
         output: PathBuf,
 
-        /// Tracing backend to use (edge, centerline, superpixel, dots)
+        /// Tracing backend to use (edge, centerline, superpixel, dots, lowpoly)
         #[arg(long, default_value = "edge")]
         backend: String,
 
@@ -192,6 +194,51 @@ enum Commands {
         /// Only use if automatic threshold detection produces poor results.
         #[arg(long, help = "Manual background threshold override (0-255)")]
         bg_threshold: Option<u8>,
+
+        /// Treat pixels as sRGB even when the file embeds an ICC profile.
+        /// By default, wide-gamut images (Adobe RGB, ProPhoto) are converted to sRGB.
+        #[arg(long, help = "Ignore embedded ICC color profiles")]
+        ignore_icc_profile: bool,
+
+        /// Also write the image gradient field as a normal-map-style PNG.
+        /// Red/green hold the gradient direction (dx, dy), blue the magnitude.
+        #[arg(long, help = "Export gradient field as a PNG (RG = direction, B = magnitude)")]
+        export_gradient: Option<PathBuf>,
+
+        /// Size the SVG in inches using the DPI stored in the PNG/JPEG file.
+        /// Useful for scans, which otherwise come out at screen size.
+        #[arg(long, help = "Use the image's embedded DPI for physical output size")]
+        use_embedded_dpi: bool,
+    },
+
+    /// Trace several images with the same trace-low settings.
+    ///
+    /// Each input is written next to itself as `<name>.svg`, or with `--sprite`
+    /// combined into one SVG holding a `<symbol id="<name>">` per input.
+    ///
+    /// Examples:
+    ///   # Icon sprite sheet, used in HTML via <svg><use href="#home"/></svg>
+    ///   vectorize-cli batch --sprite icons.svg home.png gear.png search.png
+    Batch {
+        /// Input image files
+        #[arg(required = true)]
+        inputs: Vec<PathBuf>,
+
+        /// Write one sprite SVG with a <symbol> per input (id = input file name)
+        #[arg(long)]
+        sprite: Option<PathBuf>,
+
+        /// Tracing backend to use (edge, centerline, superpixel, dots, lowpoly)
+        #[arg(long, default_value = "edge")]
+        backend: String,
+
+        /// Detail level (0.0 = very sparse, 1.0 = more detail)
+        #[arg(long, default_value = "0.3")]
+        detail: f32,
+
+        /// Stroke width at 1080p reference resolution
+        #[arg(long, default_value = "1.2")]
+        stroke_width: f32,
     },
 
     /// Simple vectorization using default trace-low settings
@@ -256,6 +303,9 @@ fn main() -> Result<()> {
             bg_strength,
             bg_algorithm,
             bg_threshold,
+            ignore_icc_profile,
+            export_gradient,
+            use_embedded_dpi,
         } => {
             // No manual validation needed - ConfigBuilder handles all validation
 
@@ -287,8 +337,18 @@ fn main() -> Result<()> {
                 bg_strength,
                 &bg_algorithm,
                 bg_threshold,
+                ignore_icc_profile,
+                export_gradient,
+                use_embedded_dpi,
             )
         }
+        Commands::Batch {
+            inputs,
+            sprite,
+            backend,
+            detail,
+            stroke_width,
+        } => batch_command(&inputs, sprite, &backend, detail, stroke_width),
         Commands::Convert {
             input,
             output,
@@ -326,6 +386,9 @@ fn main() -> Result<()> {
                 0.5,                // default background removal strength
                 "auto",             // default background removal algorithm
                 None,               // no background threshold override
+                false,              // apply embedded ICC profiles
+                None,               // no gradient export
+                false,              // pixel-sized output
             )
         }
     }
@@ -360,6 +423,9 @@ fn vectorize_trace_low_command(
     bg_strength: f32,
     bg_algorithm: &str,
     bg_threshold: Option<u8>,
+    ignore_icc_profile: bool,
+    export_gradient: Option<PathBuf>,
+    use_embedded_dpi: bool,
 ) -> Result<()> {
     let start_time = Instant::now();
 
@@ -367,17 +433,25 @@ fn vectorize_trace_low_command(
     let image_data = fs::read(&input)
         .with_context(|| format!("Failed to read input file: {}", input.display()))?;
 
-    let image = image::load_from_memory(&image_data)
+    let rgba_image = decode_image(&image_data, !ignore_icc_profile)
         .with_context(|| format!("Failed to decode image: {}", input.display()))?;
 
-    let rgba_image = image.to_rgba8();
-
     println!(
         "Processing {}x{} image with trace-low algorithm...",
         rgba_image.width(),
         rgba_image.height()
     );
     println!("Backend: {backend}, Detail: {detail:.2}, Stroke Width: {stroke_width:.2}");
+
+    if let Some(gradient_path) = &export_gradient {
+        let gray = image::imageops::grayscale(&rgba_image);
+        gradient_normal_map(&gray)
+            .save(gradient_path)
+            .with_context(|| {
+                format!("Failed to write gradient map: {}", gradient_path.display())
+            })?;
+        println!("✓ Gradient map saved to: {}", gradient_path.display());
+    }
     
     if background_removal {
         println!("Background removal: Enabled (Algorithm: {}, Strength: {:.2})", bg_algorithm, bg_strength);
@@ -419,7 +493,9 @@ fn vectorize_trace_low_command(
         .context("Invalid background removal strength")?
         .background_removal_algorithm_by_name(bg_algorithm)
         .context("Invalid background removal algorithm")?
-        .background_removal_threshold(bg_threshold);
+        .background_removal_threshold(bg_threshold)
+        .apply_icc_profile(!ignore_icc_profile)
+        .use_embedded_dpi(use_embedded_dpi);
 
     // Add hand-drawn preset if specified
     if hand_drawn != "none" {
@@ -441,9 +517,10 @@ fn vectorize_trace_low_command(
     }
 
     // Build final configuration with validation
-    let (config, hand_drawn_config) = config_builder
+    let (mut config, hand_drawn_config) = config_builder
         .build_with_hand_drawn()
         .context("Configuration validation failed")?;
+    apply_embedded_dpi(&mut config, &image_data);
 
     // Show dot-specific parameters when using dots backend
     if backend == "dots" {
@@ -553,6 +630,65 @@ fn vectorize_trace_low_command(
     Ok(())
 }
 
+fn batch_command(
+    inputs: &[PathBuf],
+    sprite: Option<PathBuf>,
+    backend: &str,
+    detail: f32,
+    stroke_width: f32,
+) -> Result<()> {
+    let start_time = Instant::now();
+
+    let config = ConfigBuilder::new()
+        .backend_by_name(backend)
+        .context("Invalid backend specified")?
+        .detail(detail)
+        .context("Invalid detail parameter")?
+        .stroke_width(stroke_width)
+        .context("Invalid stroke width")?
+        .build()
+        .context("Configuration validation failed")?;
+
+    println!("Batch tracing {} images (Backend: {backend}, Detail: {detail:.2})", inputs.len());
+
+    let mut documents = Vec::with_capacity(inputs.len());
+    for input in inputs {
+        let image_data = fs::read(input)
+            .with_context(|| format!("Failed to read input file: {}", input.display()))?;
+        let rgba_image = decode_image(&image_data, config.apply_icc_profile)
+            .with_context(|| format!("Failed to decode image: {}", input.display()))?;
+        let svg_content = vectorize_trace_low_rgba(&rgba_image, &config, None)
+            .with_context(|| format!("Vectorization failed: {}", input.display()))?;
+
+        if sprite.is_none() {
+            let output = input.with_extension("svg");
+            fs::write(&output, &svg_content)
+                .with_context(|| format!("Failed to write output file: {}", output.display()))?;
+            println!("✓ {} -> {}", input.display(), output.display());
+        }
+
+        let name = input
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("symbol")
+            .to_string();
+        documents.push((name, svg_content));
+    }
+
+    if let Some(sprite_path) = sprite {
+        fs::write(&sprite_path, generate_svg_sprite(&documents))
+            .with_context(|| format!("Failed to write sprite file: {}", sprite_path.display()))?;
+        println!(
+            "✓ Sprite with {} symbols saved to: {}",
+            documents.len(),
+            sprite_path.display()
+        );
+    }
+
+    println!("✓ Total time: {:.2}s", start_time.elapsed().as_secs_f64());
+    Ok(())
+}
+
 #[derive(Debug)]
 struct SimpleStats {
     paths: usize,
@@ -4,10 +4,12 @@
 //! using distance fields and ridge detection.
 
 use super::distance_transform::DistanceFieldCenterlineExtractor;
+use super::preprocessing::remove_small_components;
 use super::thresholding::BradleyRothThresholding;
 use super::{
     CenterlineAlgorithm, Complexity, MemoryUsage, PerformanceProfile, ThresholdingStrategy,
 };
+use crate::algorithms::tracing::path_utils::douglas_peucker_by;
 use crate::algorithms::{Point, SvgPath};
 use crate::error::VectorizeError;
 use crate::utils::Instant;
@@ -96,6 +98,11 @@ impl CenterlineAlgorithm for DistanceTransformCenterlineAlgorithm {
         } else {
             binary
         };
+        let processed = if config.prebinary_despeckle_px > 0 {
+            remove_small_components(&processed, config.prebinary_despeckle_px)
+        } else {
+            processed
+        };
         let preprocessing_time = phase_start.elapsed();
 
         // Phase 5: Distance Transform-based centerline extraction (the key innovation)
@@ -405,54 +412,30 @@ fn simplify_polylines_curvature_aware(polylines: Vec<Vec<Point>>, epsilon: f32)
 }
 
 fn douglas_peucker_simple(points: &[Point], epsilon: f32) -> Vec<Point> {
-    if points.len() <= 2 {
-        return points.to_vec();
-    }
-
-    let mut result = Vec::new();
-    result.push(points[0]);
-    dp_recursive_simple(points, 0, points.len() - 1, epsilon, &mut result);
-    result.push(points[points.len() - 1]);
-    result
-}
+    douglas_peucker_by(points, |start, end| {
+        let start_point = &points[start];
+        let end_point = &points[end];
 
-fn dp_recursive_simple(
-    points: &[Point],
-    start: usize,
-    end: usize,
-    epsilon: f32,
-    result: &mut Vec<Point>,
-) {
-    if end <= start + 1 {
-        return;
-    }
-
-    let start_point = &points[start];
-    let end_point = &points[end];
-
-    let dx = end_point.x - start_point.x;
-    let dy = end_point.y - start_point.y;
-    let line_length = (dx * dx + dy * dy).sqrt().max(1e-6);
+        let dx = end_point.x - start_point.x;
+        let dy = end_point.y - start_point.y;
+        let line_length = (dx * dx + dy * dy).sqrt().max(1e-6);
 
-    let mut max_distance = 0.0;
-    let mut max_index = start;
+        let mut max_distance = 0.0;
+        let mut max_index = start;
 
-    for i in (start + 1)..end {
-        let point = &points[i];
-        let distance =
-            ((point.x - start_point.x) * dy - (point.y - start_point.y) * dx).abs() / line_length;
+        for i in (start + 1)..end {
+            let point = &points[i];
+            let distance = ((point.x - start_point.x) * dy - (point.y - start_point.y) * dx).abs()
+                / line_length;
 
-        if distance > max_distance {
-            max_distance = distance;
-            max_index = i;
+            if distance > max_distance {
+                max_distance = distance;
+                max_index = i;
+            }
         }
-    }
 
-    if max_distance > epsilon {
-        dp_recursive_simple(points, start, max_index, epsilon, result);
-        result.push(points[max_index]);
-        dp_recursive_simple(points, max_index, end, epsilon, result);
-    }
+        (max_distance > epsilon).then_some(max_index)
+    })
 }
 
 fn bridge_endpoints_smart(
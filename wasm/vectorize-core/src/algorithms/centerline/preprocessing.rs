@@ -168,6 +168,67 @@ impl PreprocessingStrategy for MinimalPreprocessing {
     }
 }
 
+/// Remove 8-connected foreground components smaller than `min_area_px` pixels
+///
+/// Operates on the binarized image before tracing so that specks never turn into
+/// paths in the first place. A `min_area_px` of 0 or 1 leaves the image untouched.
+pub fn remove_small_components(binary: &GrayImage, min_area_px: u32) -> GrayImage {
+    if min_area_px <= 1 {
+        return binary.clone();
+    }
+
+    let (width, height) = binary.dimensions();
+    let mut result = binary.clone();
+    let mut visited = vec![false; (width * height) as usize];
+    let mut component = Vec::new();
+    let mut stack = Vec::new();
+
+    for start_y in 0..height {
+        for start_x in 0..width {
+            let start_idx = (start_y * width + start_x) as usize;
+            if visited[start_idx] || binary.get_pixel(start_x, start_y).0[0] <= 127 {
+                continue;
+            }
+
+            // Flood fill the component starting at this pixel
+            component.clear();
+            stack.push((start_x, start_y));
+            visited[start_idx] = true;
+
+            while let Some((x, y)) = stack.pop() {
+                component.push((x, y));
+
+                for dy in -1i32..=1 {
+                    for dx in -1i32..=1 {
+                        if dx == 0 && dy == 0 {
+                            continue;
+                        }
+                        let nx = x as i32 + dx;
+                        let ny = y as i32 + dy;
+                        if nx < 0 || ny < 0 || nx as u32 >= width || ny as u32 >= height {
+                            continue;
+                        }
+                        let (nx, ny) = (nx as u32, ny as u32);
+                        let idx = (ny * width + nx) as usize;
+                        if !visited[idx] && binary.get_pixel(nx, ny).0[0] > 127 {
+                            visited[idx] = true;
+                            stack.push((nx, ny));
+                        }
+                    }
+                }
+            }
+
+            if (component.len() as u32) < min_area_px {
+                for &(x, y) in &component {
+                    result.put_pixel(x, y, Luma([0]));
+                }
+            }
+        }
+    }
+
+    result
+}
+
 // Implementation functions
 
 fn morphological_open_close(binary: &GrayImage) -> GrayImage {
@@ -373,3 +434,28 @@ fn bilateral_filter(image: &GrayImage, spatial_sigma: f32, intensity_sigma: f32)
 
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remove_small_components() {
+        let mut binary = GrayImage::new(20, 20);
+        // Single-pixel speck
+        binary.put_pixel(2, 2, Luma([255]));
+        // 3x3 block (9 pixels)
+        for y in 10..13 {
+            for x in 10..13 {
+                binary.put_pixel(x, y, Luma([255]));
+            }
+        }
+
+        let cleaned = remove_small_components(&binary, 4);
+        assert_eq!(cleaned.get_pixel(2, 2).0[0], 0, "Speck should be removed");
+        assert_eq!(cleaned.get_pixel(11, 11).0[0], 255, "Block should be kept");
+
+        let untouched = remove_small_components(&binary, 0);
+        assert_eq!(untouched, binary, "Zero area should leave image unchanged");
+    }
+}
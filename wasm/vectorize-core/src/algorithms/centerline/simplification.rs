@@ -1,6 +1,7 @@
 //! Path simplification strategies for centerline extraction
 
 use super::SimplificationStrategy;
+use crate::algorithms::tracing::path_utils::douglas_peucker_by;
 use crate::algorithms::Point;
 use crate::error::VectorizeError;
 use crate::TraceLowConfig;
@@ -166,91 +167,43 @@ fn curvature_aware_douglas_peucker(
     // Normalize curvatures
     let normalized_curvatures: Vec<f32> = curvatures.iter().map(|&c| c / max_curvature).collect();
 
-    // Recursive simplification with adaptive epsilon
-    let mut result = Vec::new();
-    result.push(path[0]);
-
-    curvature_aware_dp_recursive(
-        path,
-        &normalized_curvatures,
-        0,
-        path.len() - 1,
-        base_epsilon,
-        curvature_sensitivity,
-        &mut result,
-    );
-
-    result.push(path[path.len() - 1]);
-    result
-}
-
-fn curvature_aware_dp_recursive(
-    path: &[Point],
-    curvatures: &[f32],
-    start: usize,
-    end: usize,
-    base_epsilon: f32,
-    curvature_sensitivity: f32,
-    result: &mut Vec<Point>,
-) {
-    if end <= start + 1 {
-        return;
-    }
-
-    let start_point = &path[start];
-    let end_point = &path[end];
+    // Douglas-Peucker with the epsilon adapted to each point's curvature
+    douglas_peucker_by(path, |start, end| {
+        let start_point = &path[start];
+        let end_point = &path[end];
 
-    // Calculate line equation coefficients for perpendicular distance
-    let dx = end_point.x - start_point.x;
-    let dy = end_point.y - start_point.y;
-    let line_length = (dx * dx + dy * dy).sqrt().max(1e-6);
+        // Calculate line equation coefficients for perpendicular distance
+        let dx = end_point.x - start_point.x;
+        let dy = end_point.y - start_point.y;
+        let line_length = (dx * dx + dy * dy).sqrt().max(1e-6);
 
-    let mut max_distance = -1.0;
-    let mut max_index = start;
+        let mut max_distance = -1.0;
+        let mut max_index = start;
 
-    // Find point with maximum weighted distance
-    for i in (start + 1)..end {
-        let point = &path[i];
+        // Find point with maximum weighted distance
+        for i in (start + 1)..end {
+            let point = &path[i];
 
-        // Calculate perpendicular distance to line
-        let distance =
-            ((point.x - start_point.x) * dy - (point.y - start_point.y) * dx).abs() / line_length;
+            // Calculate perpendicular distance to line
+            let distance = ((point.x - start_point.x) * dy - (point.y - start_point.y) * dx).abs()
+                / line_length;
 
-        // Apply curvature-based epsilon adjustment
-        let curvature_factor = 1.0 - curvature_sensitivity * curvatures[i];
-        let adjusted_epsilon = base_epsilon * curvature_factor.clamp(0.2, 1.0);
+            // Apply curvature-based epsilon adjustment
+            let curvature_factor = 1.0 - curvature_sensitivity * normalized_curvatures[i];
+            let adjusted_epsilon = base_epsilon * curvature_factor.clamp(0.2, 1.0);
 
-        // Weight distance by inverse curvature (keep high curvature points)
-        let weighted_distance = distance / adjusted_epsilon;
+            // Weight distance by inverse curvature (keep high curvature points)
+            let weighted_distance = distance / adjusted_epsilon;
 
-        if weighted_distance > max_distance && distance > adjusted_epsilon {
-            max_distance = weighted_distance;
-            max_index = i;
+            if weighted_distance > max_distance && distance > adjusted_epsilon {
+                max_distance = weighted_distance;
+                max_index = i;
+            }
         }
-    }
 
-    // If a significant point was found, recursively process segments
-    if max_distance > 1.0 {
-        curvature_aware_dp_recursive(
-            path,
-            curvatures,
-            start,
-            max_index,
-            base_epsilon,
-            curvature_sensitivity,
-            result,
-        );
-        result.push(path[max_index]);
-        curvature_aware_dp_recursive(
-            path,
-            curvatures,
-            max_index,
-            end,
-            base_epsilon,
-            curvature_sensitivity,
-            result,
-        );
-    }
+        // Keep the point when it is significant for its local epsilon
+        (max_distance > 1.0).then_some(max_index)
+    })
 }
 
 fn compute_local_curvatures(path: &[Point]) -> Vec<f32> {
@@ -301,51 +254,30 @@ fn compute_curvature_at_point(p1: &Point, p2: &Point, p3: &Point) -> f32 {
 }
 
 fn douglas_peucker_simplify(path: &[Point], epsilon: f32) -> Vec<Point> {
-    if path.len() <= 2 {
-        return path.to_vec();
-    }
-
-    let mut result = Vec::new();
-    result.push(path[0]);
-
-    dp_recursive(path, 0, path.len() - 1, epsilon, &mut result);
-
-    result.push(path[path.len() - 1]);
-    result
-}
-
-fn dp_recursive(path: &[Point], start: usize, end: usize, epsilon: f32, result: &mut Vec<Point>) {
-    if end <= start + 1 {
-        return;
-    }
-
-    let start_point = &path[start];
-    let end_point = &path[end];
-
-    // Calculate line equation coefficients for perpendicular distance
-    let dx = end_point.x - start_point.x;
-    let dy = end_point.y - start_point.y;
-    let line_length = (dx * dx + dy * dy).sqrt().max(1e-6);
-
-    let mut max_distance = 0.0;
-    let mut max_index = start;
-
-    // Find point with maximum distance from line
-    for i in (start + 1)..end {
-        let point = &path[i];
-        let distance =
-            ((point.x - start_point.x) * dy - (point.y - start_point.y) * dx).abs() / line_length;
-
-        if distance > max_distance {
-            max_distance = distance;
-            max_index = i;
+    douglas_peucker_by(path, |start, end| {
+        let start_point = &path[start];
+        let end_point = &path[end];
+
+        // Calculate line equation coefficients for perpendicular distance
+        let dx = end_point.x - start_point.x;
+        let dy = end_point.y - start_point.y;
+        let line_length = (dx * dx + dy * dy).sqrt().max(1e-6);
+
+        let mut max_distance = 0.0;
+        let mut max_index = start;
+
+        // Find point with maximum distance from line
+        for i in (start + 1)..end {
+            let point = &path[i];
+            let distance = ((point.x - start_point.x) * dy - (point.y - start_point.y) * dx).abs()
+                / line_length;
+
+            if distance > max_distance {
+                max_distance = distance;
+                max_index = i;
+            }
         }
-    }
 
-    // If the maximum distance is greater than epsilon, recursively simplify
-    if max_distance > epsilon {
-        dp_recursive(path, start, max_index, epsilon, result);
-        result.push(path[max_index]);
-        dp_recursive(path, max_index, end, epsilon, result);
-    }
+        (max_distance > epsilon).then_some(max_index)
+    })
 }
@@ -157,6 +157,20 @@ fn lab_f(t: f32) -> f32 {
     }
 }
 
+/// Convert LAB color back to an opaque RGBA color
+///
+/// Inverse of [`rgba_to_lab`]; colors outside the sRGB gamut are clamped.
+///
+/// # Arguments
+/// * `lab` - Lab color to convert
+///
+/// # Returns
+/// RGBA color with full opacity
+pub fn lab_to_rgba(lab: &LabColor) -> Rgba<u8> {
+    let (r, g, b) = crate::preprocessing::lab_to_rgb(lab.l, lab.a, lab.b);
+    Rgba([r, g, b, 255])
+}
+
 /// Calculate color similarity between two RGBA colors using Lab color space
 ///
 /// Returns normalized similarity score (0.0 = identical, 1.0 = maximum difference).
@@ -537,6 +551,22 @@ mod tests {
         assert!(lab_red.a > 40.0, "Red should have positive a value");
     }
 
+    #[test]
+    fn test_lab_to_rgba_round_trip() {
+        for color in [
+            Rgba([255, 255, 255, 255]),
+            Rgba([0, 0, 0, 255]),
+            Rgba([255, 0, 0, 255]),
+            Rgba([12, 200, 87, 255]),
+            Rgba([90, 60, 230, 255]),
+        ] {
+            let round_trip = lab_to_rgba(&rgba_to_lab(&color));
+            for (a, b) in round_trip.0.iter().zip(color.0.iter()) {
+                assert!(a.abs_diff(*b) <= 1, "{color:?} round-tripped to {round_trip:?}");
+            }
+        }
+    }
+
     #[test]
     fn test_color_similarity_identical_colors() {
         let color1 = Rgba([128, 128, 128, 255]);
@@ -59,6 +59,53 @@ impl Default for GridPattern {
     }
 }
 
+/// How dot fill colors are chosen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "generate-ts", derive(ts_rs::TS))]
+#[cfg_attr(
+    feature = "generate-ts",
+    ts(export, export_to = "../../../../frontend/src/lib/types/generated/")
+)]
+pub enum DotColorMode {
+    /// Every dot uses the flat default color
+    Black,
+    /// Gray fill whose darkness tracks source luminance (tonal stipple)
+    Grayscale,
+    /// Original pixel colors
+    SourceColor,
+}
+
+impl DotColorMode {
+    /// Mode equivalent to the plain `preserve_colors` flag
+    pub fn from_preserve_colors(preserve_colors: bool) -> Self {
+        if preserve_colors {
+            DotColorMode::SourceColor
+        } else {
+            DotColorMode::Black
+        }
+    }
+
+    /// Whether dots need source colors sampled during placement
+    pub fn samples_source(self) -> bool {
+        self != DotColorMode::Black
+    }
+}
+
+/// Replace each dot's sampled `#rrggbb` color with a gray of the same luminance
+pub fn apply_grayscale_colors(dots: &mut [Dot]) {
+    for dot in dots {
+        let hex = dot.color.trim_start_matches('#');
+        let channel = |i: usize| {
+            hex.get(i..i + 2)
+                .and_then(|c| u8::from_str_radix(c, 16).ok())
+                .unwrap_or(0) as f32
+        };
+        let luma = 0.2126 * channel(0) + 0.7152 * channel(2) + 0.0722 * channel(4);
+        let gray = luma.round().clamp(0.0, 255.0) as u8;
+        dot.color = format!("#{gray:02x}{gray:02x}{gray:02x}");
+    }
+}
+
 /// Represents a single dot in the output
 #[derive(Debug, Clone, PartialEq)]
 pub struct Dot {
@@ -146,6 +193,8 @@ pub struct DotConfig {
     pub gradient_based_sizing: bool,
     /// Amount of random variation in dot sizes (0.0 = no variation, 1.0 = maximum variation)
     pub size_variation: f32,
+    /// Never place dots on detected background, even when detection looks too aggressive
+    pub skip_background: bool,
 }
 
 impl Default for DotConfig {
@@ -165,6 +214,7 @@ impl Default for DotConfig {
             grid_pattern: GridPattern::default(),
             gradient_based_sizing: false,
             size_variation: 0.0,
+            skip_background: false,
         }
     }
 }
@@ -1222,8 +1272,9 @@ pub fn generate_dots_from_image(
     );
 
     // Smart fallback: If >95% of pixels are marked as background, disable background filtering
-    // This handles cases where the background detection is too aggressive
-    let use_background_filtering = background_percentage < 95.0;
+    // This handles cases where the background detection is too aggressive.
+    // `skip_background` is a hard switch that keeps the mask applied regardless.
+    let use_background_filtering = dot_config.skip_background || background_percentage < 95.0;
     if !use_background_filtering {
         log::debug!(
             "Background detection marked {background_percentage:.1}% as background - disabling background filtering"
@@ -1673,6 +1724,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_generate_dots_skip_background() {
+        // Small textured subject on a plain background (>95% background triggers the fallback)
+        let mut img = RgbaImage::from_pixel(100, 100, Rgba([255, 255, 255, 255]));
+        for y in 40..60 {
+            for x in 40..60 {
+                let value = if (x / 2 + y / 2) % 2 == 0 { 0 } else { 128 };
+                img.put_pixel(x, y, Rgba([value, value, value, 255]));
+            }
+        }
+
+        let config = DotConfig {
+            skip_background: true,
+            ..DotConfig::default()
+        };
+        let dots = generate_dots_from_image(&img, &config, None, None);
+        assert!(!dots.is_empty(), "Subject should still receive dots");
+
+        let background_mask = detect_background_advanced(&img, &BackgroundConfig::default());
+        for dot in &dots {
+            let index = (dot.y.floor() as u32 * 100 + dot.x.floor() as u32) as usize;
+            assert!(
+                !background_mask[index],
+                "Dot at ({}, {}) was placed on background",
+                dot.x,
+                dot.y
+            );
+        }
+    }
+
     #[test]
     fn test_generate_dots_auto_background() {
         let img = create_test_image();
@@ -0,0 +1,173 @@
+//! Halftone screen placement for the dots backend
+//!
+//! Dots sit on a regular grid rotated by the screen angle, one per cell, with
+//! each dot's area proportional to the average darkness of its cell.
+
+use super::dots::{Dot, DotShape};
+use crate::algorithms::visual::color_processing::rgba_to_hex;
+use image::{Rgba, RgbaImage};
+
+/// Cells lighter than this darkness fraction produce no dot
+const MIN_HALFTONE_DARKNESS: f32 = 0.01;
+
+/// Configuration for halftone dot placement
+#[derive(Debug, Clone)]
+pub struct HalftoneConfig {
+    /// Screen angle in degrees
+    pub angle_deg: f32,
+    /// Grid cell size in pixels
+    pub cell_px: f32,
+    /// Color dots by their cell's average color instead of `default_color`
+    pub preserve_colors: bool,
+    /// Dot color when colors are not preserved
+    pub default_color: String,
+}
+
+impl Default for HalftoneConfig {
+    fn default() -> Self {
+        Self {
+            angle_deg: 45.0,
+            cell_px: 8.0,
+            preserve_colors: false,
+            default_color: "#000000".to_string(),
+        }
+    }
+}
+
+/// Place one darkness-sized dot per cell of a rotated halftone grid
+pub fn generate_halftone_dots(image: &RgbaImage, config: &HalftoneConfig) -> Vec<Dot> {
+    let (width, height) = (image.width() as f32, image.height() as f32);
+    let cell = config.cell_px.max(1.0);
+    if width == 0.0 || height == 0.0 {
+        return Vec::new();
+    }
+
+    let (sin, cos) = config.angle_deg.to_radians().sin_cos();
+    let (cx, cy) = (width / 2.0, height / 2.0);
+
+    // Grid extent in rotated coordinates that covers the whole image
+    let half_extent = (width * width + height * height).sqrt() / 2.0;
+    let steps = (half_extent / cell).ceil() as i32;
+
+    let mut dots = Vec::new();
+    for v in -steps..=steps {
+        for u in -steps..=steps {
+            let (gu, gv) = ((u as f32 + 0.5) * cell, (v as f32 + 0.5) * cell);
+            let x = cx + gu * cos - gv * sin;
+            let y = cy + gu * sin + gv * cos;
+            if x < 0.0 || y < 0.0 || x >= width || y >= height {
+                continue;
+            }
+
+            let (darkness, color) = cell_average(image, x, y, cell);
+            if darkness < MIN_HALFTONE_DARKNESS {
+                continue;
+            }
+
+            // Dot area tracks darkness: a fully dark cell is (just) covered
+            let radius = cell * (darkness / std::f32::consts::PI).sqrt();
+            let color = if config.preserve_colors {
+                rgba_to_hex(&color)
+            } else {
+                config.default_color.clone()
+            };
+            dots.push(Dot::new_with_shape(
+                x,
+                y,
+                radius,
+                1.0,
+                color,
+                DotShape::Circle,
+            ));
+        }
+    }
+
+    log::debug!(
+        "Halftone screen: {} dots (angle {:.1}°, cell {:.1}px)",
+        dots.len(),
+        config.angle_deg,
+        cell
+    );
+    dots
+}
+
+/// Average darkness (0 = white/transparent, 1 = black) and color around a cell center
+fn cell_average(image: &RgbaImage, x: f32, y: f32, cell: f32) -> (f32, Rgba<u8>) {
+    let half = cell / 2.0;
+    let x0 = (x - half).floor().max(0.0) as u32;
+    let y0 = (y - half).floor().max(0.0) as u32;
+    let x1 = ((x + half).ceil() as u32).min(image.width());
+    let y1 = ((y + half).ceil() as u32).min(image.height());
+
+    let mut darkness = 0.0;
+    let mut sum = [0u64; 3];
+    let mut count = 0u64;
+    for py in y0..y1 {
+        for px in x0..x1 {
+            let [r, g, b, a] = image.get_pixel(px, py).0;
+            let luminance = (0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32) / 255.0;
+            darkness += (1.0 - luminance) * (a as f32 / 255.0);
+            sum[0] += r as u64;
+            sum[1] += g as u64;
+            sum[2] += b as u64;
+            count += 1;
+        }
+    }
+
+    if count == 0 {
+        return (0.0, Rgba([255, 255, 255, 255]));
+    }
+    let [r, g, b] = sum.map(|total| (total / count) as u8);
+    (darkness / count as f32, Rgba([r, g, b, 255]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_halftone_dots_grid_and_track_darkness() {
+        // Horizontal gradient from white (left) to black (right)
+        let image = RgbaImage::from_fn(96, 48, |x, _| {
+            let value = 255 - (x * 255 / 95) as u8;
+            Rgba([value, value, value, 255])
+        });
+        let config = HalftoneConfig {
+            angle_deg: 0.0,
+            cell_px: 8.0,
+            ..HalftoneConfig::default()
+        };
+
+        let dots = generate_halftone_dots(&image, &config);
+        assert!(!dots.is_empty());
+
+        // Unrotated screen: every center sits in the middle of an 8px cell
+        for dot in &dots {
+            assert!((dot.x % 8.0 - 4.0).abs() < 1e-3 && (dot.y % 8.0 - 4.0).abs() < 1e-3);
+        }
+
+        // Radii grow left to right along a row
+        let mut row: Vec<&Dot> = dots.iter().filter(|d| (d.y - 20.0).abs() < 1e-3).collect();
+        row.sort_by(|a, b| a.x.total_cmp(&b.x));
+        assert!(row.windows(2).all(|pair| pair[1].radius > pair[0].radius));
+    }
+
+    #[test]
+    fn test_halftone_rotation_keeps_spacing() {
+        let image = RgbaImage::from_pixel(64, 64, Rgba([0, 0, 0, 255]));
+        let config = HalftoneConfig {
+            angle_deg: 45.0,
+            cell_px: 10.0,
+            ..HalftoneConfig::default()
+        };
+
+        let dots = generate_halftone_dots(&image, &config);
+        let nearest = dots
+            .iter()
+            .skip(1)
+            .map(|d| ((d.x - dots[0].x).powi(2) + (d.y - dots[0].y).powi(2)).sqrt())
+            .fold(f32::MAX, f32::min);
+        assert!((nearest - 10.0).abs() < 1e-3);
+        assert!(dots.iter().all(|d| (d.radius - dots[0].radius).abs() < 0.2));
+    }
+}
@@ -8,6 +8,7 @@ pub mod background;
 pub mod dot_styles;
 pub mod dots;
 pub mod dots_optimized;
+pub mod halftone;
 pub mod svg_dots;
 
 // Re-export commonly used types
@@ -17,8 +18,8 @@ pub use adaptive_dots::{
     Region,
 };
 pub use background::{
-    calculate_color_similarity, detect_background_advanced, detect_background_mask, rgba_to_lab,
-    BackgroundConfig, LabColor,
+    calculate_color_similarity, detect_background_advanced, detect_background_mask, lab_to_rgba,
+    rgba_to_lab, BackgroundConfig, LabColor,
 };
 pub use dot_styles::{
     add_artistic_jitter, add_opacity_variation, add_size_variation, apply_artistic_effects,
@@ -32,7 +33,8 @@ pub use dots_optimized::{
     analyze_gradients_optimized, detect_background_optimized, generate_dots_optimized_pipeline,
     OptimizedDotConfig, OptimizedDotGenerator,
 };
+pub use halftone::{generate_halftone_dots, HalftoneConfig};
 pub use svg_dots::{
-    dots_to_svg_elements, dots_to_svg_paths, dots_to_svg_with_config, generate_dot_svg_document,
-    optimize_dot_svg, SvgDotConfig, SvgElement,
+    dots_to_dash_paths, dots_to_svg_elements, dots_to_svg_paths, dots_to_svg_with_config,
+    generate_dot_svg_document, optimize_dot_svg, SvgDotConfig, SvgElement,
 };
@@ -6,6 +6,7 @@
 
 use crate::algorithms::dots::{Dot, dots::DotShape};
 use crate::algorithms::{SvgElementType, SvgPath};
+use std::collections::{HashMap, VecDeque};
 use std::fmt::Write;
 
 /// Configuration for SVG dot rendering
@@ -696,12 +697,202 @@ pub fn dots_to_svg_paths(dots: &[Dot]) -> Vec<SvgPath> {
                 fill: dot.color.clone(),
                 stroke: "none".to_string(),
                 stroke_width: 0.0,
+                stroke_opacity: None,
+                source_color: None,
+                region_metadata: None,
                 element_type,
             }
         })
         .collect()
 }
 
+/// Minimum number of aligned dots worth replacing with a dash
+const MIN_DOTS_PER_DASH: usize = 3;
+/// Maximum number of dots merged into one dash, so dashes stay short
+const MAX_DOTS_PER_DASH: usize = 8;
+/// Dots link into a dash when their centers are within this multiple of their combined radii
+const DASH_LINK_DISTANCE_FACTOR: f32 = 1.5;
+/// Maximum distance of a dot center from the dash line, as a fraction of the dot radius
+const DASH_COLLINEAR_TOLERANCE: f32 = 0.5;
+
+/// Convert dots to SVG paths, merging runs of nearly-collinear dots into short dashes
+///
+/// Intended for pen plotters, where every dot costs a pen-up/pen-down cycle. Runs of
+/// at least three adjacent circular dots with the same color that lie on a common line
+/// become a single stroked segment whose width matches the dots' mean diameter. All
+/// other dots are emitted exactly as [`dots_to_svg_paths`] would.
+///
+/// # Arguments
+/// * `dots` - Slice of dots to convert
+///
+/// # Returns
+/// Dash paths followed by the remaining unmerged dots
+pub fn dots_to_dash_paths(dots: &[Dot]) -> Vec<SvgPath> {
+    let candidates: Vec<usize> = (0..dots.len())
+        .filter(|&i| {
+            let dot = &dots[i];
+            dot.opacity > 0.0 && dot.radius > 0.0 && dot.shape == DotShape::Circle
+        })
+        .collect();
+    let max_radius = candidates
+        .iter()
+        .map(|&i| dots[i].radius)
+        .fold(0.0f32, f32::max);
+    if candidates.len() < MIN_DOTS_PER_DASH || max_radius <= 0.0 {
+        return dots_to_svg_paths(dots);
+    }
+
+    // Spatial hash sized so every linkable neighbor lies in the adjacent cells
+    let cell_size = DASH_LINK_DISTANCE_FACTOR * 2.0 * max_radius;
+    let cell_of = |dot: &Dot| {
+        (
+            (dot.x / cell_size).floor() as i32,
+            (dot.y / cell_size).floor() as i32,
+        )
+    };
+    let mut grid: HashMap<(i32, i32), Vec<usize>> = HashMap::new();
+    for &i in &candidates {
+        grid.entry(cell_of(&dots[i])).or_default().push(i);
+    }
+
+    let mut merged = vec![false; dots.len()];
+    let mut paths = Vec::new();
+    for &start in &candidates {
+        if merged[start] {
+            continue;
+        }
+        let neighbors = dash_neighbors(dots, &grid, cell_of(&dots[start]), start, &merged);
+        let best_run = neighbors
+            .iter()
+            .map(|&next| collinear_run(dots, &grid, &cell_of, start, next, &merged))
+            .max_by_key(|run| run.len())
+            .unwrap_or_default();
+        if best_run.len() < MIN_DOTS_PER_DASH {
+            continue;
+        }
+        for &i in &best_run {
+            merged[i] = true;
+        }
+        paths.push(dash_path(dots, &best_run));
+    }
+
+    let remaining: Vec<Dot> = dots
+        .iter()
+        .zip(&merged)
+        .filter(|(_, &is_merged)| !is_merged)
+        .map(|(dot, _)| dot.clone())
+        .collect();
+    paths.extend(dots_to_svg_paths(&remaining));
+    paths
+}
+
+/// Unmerged dots close enough to `index` to share a dash and with the same color
+fn dash_neighbors(
+    dots: &[Dot],
+    grid: &HashMap<(i32, i32), Vec<usize>>,
+    (cx, cy): (i32, i32),
+    index: usize,
+    merged: &[bool],
+) -> Vec<usize> {
+    let dot = &dots[index];
+    let mut neighbors = Vec::new();
+    for gy in cy - 1..=cy + 1 {
+        for gx in cx - 1..=cx + 1 {
+            for &other in grid.get(&(gx, gy)).into_iter().flatten() {
+                let candidate = &dots[other];
+                let link_distance = DASH_LINK_DISTANCE_FACTOR * (dot.radius + candidate.radius);
+                if other != index
+                    && !merged[other]
+                    && candidate.color == dot.color
+                    && (candidate.x - dot.x).hypot(candidate.y - dot.y) <= link_distance
+                {
+                    neighbors.push(other);
+                }
+            }
+        }
+    }
+    neighbors
+}
+
+/// Grow a run of collinear dots along the line through `start` and `next`
+///
+/// The run is extended greedily in both directions, always taking the nearest
+/// unmerged neighbor ahead of the current end such that every dot in the run stays
+/// close to the chord between the run's far end and that neighbor.
+fn collinear_run(
+    dots: &[Dot],
+    grid: &HashMap<(i32, i32), Vec<usize>>,
+    cell_of: &impl Fn(&Dot) -> (i32, i32),
+    start: usize,
+    next: usize,
+    merged: &[bool],
+) -> Vec<usize> {
+    let mut run = VecDeque::from([start, next]);
+    for forward in [true, false] {
+        while run.len() < MAX_DOTS_PER_DASH {
+            let (end, far) = if forward {
+                (run[run.len() - 1], run[0])
+            } else {
+                (run[0], run[run.len() - 1])
+            };
+            let (end_dot, far_dot) = (&dots[end], &dots[far]);
+            let ahead = dash_neighbors(dots, grid, cell_of(end_dot), end, merged)
+                .into_iter()
+                .filter(|candidate| !run.contains(candidate))
+                .filter_map(|candidate| {
+                    let dot = &dots[candidate];
+                    let along = (dot.x - end_dot.x) * (end_dot.x - far_dot.x)
+                        + (dot.y - end_dot.y) * (end_dot.y - far_dot.y);
+                    (along > 0.0 && run.iter().all(|&i| within_chord(&dots[i], far_dot, dot)))
+                        .then_some((candidate, along))
+                })
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+            match ahead {
+                Some((candidate, _)) if forward => run.push_back(candidate),
+                Some((candidate, _)) => run.push_front(candidate),
+                None => break,
+            }
+        }
+    }
+    run.into()
+}
+
+/// Whether `dot` lies within the collinearity tolerance of the line through `a` and `b`
+fn within_chord(dot: &Dot, a: &Dot, b: &Dot) -> bool {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let length = dx.hypot(dy);
+    let offset = ((dot.x - a.x) * dy - (dot.y - a.y) * dx).abs() / length;
+    offset <= DASH_COLLINEAR_TOLERANCE * dot.radius
+}
+
+/// Build a stroked segment covering the dots of a run from first to last
+fn dash_path(dots: &[Dot], run: &[usize]) -> SvgPath {
+    let first = &dots[run[0]];
+    let last = &dots[run[run.len() - 1]];
+    let (dx, dy) = (last.x - first.x, last.y - first.y);
+    let length = dx.hypot(dy);
+    let (ux, uy) = (dx / length, dy / length);
+    let mean_radius = run.iter().map(|&i| dots[i].radius).sum::<f32>() / run.len() as f32;
+
+    // Extend by the end dots' radii so the stroke covers the same extent as the dots
+    SvgPath {
+        data: format!(
+            "M {:.2} {:.2} L {:.2} {:.2}",
+            first.x - ux * first.radius,
+            first.y - uy * first.radius,
+            last.x + ux * last.radius,
+            last.y + uy * last.radius
+        ),
+        fill: "none".to_string(),
+        stroke: first.color.clone(),
+        stroke_width: mean_radius * 2.0,
+        stroke_opacity: None,
+        source_color: None,
+        region_metadata: None,
+        element_type: SvgElementType::Path,
+    }
+}
+
 /// Generate complete SVG document from dots using existing infrastructure
 ///
 /// This function integrates with the existing SVG generation system in svg.rs,
@@ -1273,4 +1464,39 @@ mod tests {
         // Different radius should not be similar
         assert!(!props1.is_similar_to(&props3, &config));
     }
+
+    #[test]
+    fn test_dots_to_dash_paths_merges_collinear_row() {
+        // Row of five slightly jittered dots plus an isolated dot and an off-color dot
+        let mut dots: Vec<Dot> = (0..5)
+            .map(|i| {
+                let jitter = if i % 2 == 0 { 0.2 } else { -0.2 };
+                let color = "#000000".to_string();
+                Dot::new(10.0 + i as f32 * 2.5, 10.0 + jitter, 1.0, 1.0, color)
+            })
+            .collect();
+        dots.push(Dot::new(40.0, 40.0, 1.0, 1.0, "#000000".to_string()));
+        dots.push(Dot::new(22.5, 10.0, 1.0, 1.0, "#ff0000".to_string()));
+
+        let paths = dots_to_dash_paths(&dots);
+        assert_eq!(paths.len(), 3, "Row should collapse into a single dash");
+
+        let dash = &paths[0];
+        assert!(matches!(dash.element_type, SvgElementType::Path));
+        assert_eq!(dash.data, "M 9.00 10.20 L 21.00 10.20");
+        assert_eq!(dash.stroke, "#000000");
+        assert_eq!(dash.fill, "none");
+        assert!((dash.stroke_width - 2.0).abs() < 1e-6);
+        assert!(paths[1..]
+            .iter()
+            .all(|path| matches!(path.element_type, SvgElementType::Circle { .. })));
+
+        // A short or bent run stays as individual dots
+        let bent = vec![
+            Dot::new(0.0, 0.0, 1.0, 1.0, "#000000".to_string()),
+            Dot::new(3.0, 0.0, 1.0, 1.0, "#000000".to_string()),
+            Dot::new(3.0, 3.0, 1.0, 1.0, "#000000".to_string()),
+        ];
+        assert_eq!(dots_to_dash_paths(&bent).len(), 3);
+    }
 }
@@ -5,7 +5,10 @@
 //! and sliding window variance calculation for texture analysis.
 
 use crate::execution::*;
-use image::GrayImage;
+use image::{GrayImage, Rgb, RgbImage};
+
+/// Largest Sobel magnitude an 8-bit image can produce (|gx| = |gy| = 4 * 255)
+const MAX_SOBEL_MAGNITUDE: f32 = 1020.0 * std::f32::consts::SQRT_2;
 
 /// Configuration for gradient analysis
 #[derive(Debug, Clone)]
@@ -81,6 +84,21 @@ impl GradientAnalysis {
 /// # Returns
 /// Gradient magnitude value (0.0 to ~362.0 for 8-bit images)
 pub fn calculate_gradient_magnitude(gray: &GrayImage, x: u32, y: u32) -> f32 {
+    let (gx, gy) = calculate_gradient_components(gray, x, y);
+
+    // Calculate magnitude: sqrt(gx² + gy²)
+    (gx * gx + gy * gy).sqrt()
+}
+
+/// Calculate the Sobel X and Y gradient components at a specific pixel
+///
+/// Positive X points towards brighter pixels on the right, positive Y towards
+/// brighter pixels below. Boundary pixels are clamped like
+/// [`calculate_gradient_magnitude`].
+///
+/// # Returns
+/// `(gx, gy)` gradient components (each -1020.0 to 1020.0 for 8-bit images)
+pub fn calculate_gradient_components(gray: &GrayImage, x: u32, y: u32) -> (f32, f32) {
     let width = gray.width();
     let height = gray.height();
 
@@ -140,8 +158,7 @@ pub fn calculate_gradient_magnitude(gray: &GrayImage, x: u32, y: u32) -> f32 {
         }
     }
 
-    // Calculate magnitude: sqrt(gx² + gy²)
-    ((gx * gx + gy * gy) as f32).sqrt()
+    (gx as f32, gy as f32)
 }
 
 /// Calculate local variance using sliding window approach
@@ -290,6 +307,35 @@ pub fn analyze_image_gradients_with_config(
     }
 }
 
+/// Encode the image gradient field as a normal-map-style RGB image
+///
+/// Red and green hold the unit gradient direction (dx, dy) mapped from
+/// [-1, 1] to [0, 255], so flat regions encode as 128. Blue holds the
+/// gradient magnitude from [`analyze_image_gradients`], scaled so the
+/// strongest possible Sobel response maps to 255.
+///
+/// # Arguments
+/// * `gray` - Input grayscale image
+///
+/// # Returns
+/// RGB image with the same dimensions as the input
+pub fn gradient_normal_map(gray: &GrayImage) -> RgbImage {
+    let analysis = analyze_image_gradients(gray);
+    let encode_direction = |v: f32| ((v * 0.5 + 0.5) * 255.0).round() as u8;
+
+    RgbImage::from_fn(gray.width(), gray.height(), |x, y| {
+        let (gx, gy) = calculate_gradient_components(gray, x, y);
+        let magnitude = analysis.get_magnitude(x, y).unwrap_or_else(|| gx.hypot(gy));
+        let (dx, dy) = if magnitude > 0.0 {
+            (gx / magnitude, gy / magnitude)
+        } else {
+            (0.0, 0.0)
+        };
+        let strength = (magnitude / MAX_SOBEL_MAGNITUDE * 255.0).round().min(255.0) as u8;
+        Rgb([encode_direction(dx), encode_direction(dy), strength])
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -503,4 +549,28 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_gradient_normal_map_encodes_edge_directions() {
+        // Dark-to-bright vertical edge at x = 25
+        let vertical = create_test_edge_image();
+        let map = gradient_normal_map(&vertical);
+        assert_eq!(map.dimensions(), (50, 50));
+
+        let edge = map.get_pixel(25, 25).0;
+        assert_eq!(edge[0], 255, "Gradient should point towards +X");
+        assert_eq!(edge[1], 128, "Vertical edge has no Y component");
+        let decoded = edge[2] as f32 / 255.0 * MAX_SOBEL_MAGNITUDE;
+        let expected = calculate_gradient_magnitude(&vertical, 25, 25);
+        assert!((decoded - expected).abs() < MAX_SOBEL_MAGNITUDE / 255.0);
+
+        assert_eq!(map.get_pixel(10, 25).0, [128, 128, 0], "Flat area");
+
+        // Bright-to-dark horizontal edge at y = 25
+        let horizontal = GrayImage::from_fn(50, 50, |_, y| Luma([if y < 25 { 255 } else { 0 }]));
+        let edge = gradient_normal_map(&horizontal).get_pixel(25, 25).0;
+        assert_eq!(edge[0], 128, "Horizontal edge has no X component");
+        assert_eq!(edge[1], 0, "Gradient should point towards -Y");
+        assert!(edge[2] > 0);
+    }
 }
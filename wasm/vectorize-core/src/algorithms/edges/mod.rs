@@ -6,6 +6,7 @@
 pub mod edges;
 pub mod etf;
 pub mod gradients;
+pub mod text_detection;
 
 // Re-export commonly used types
 pub use edges::{
@@ -14,6 +15,8 @@ pub use edges::{
 };
 pub use etf::{compute_etf, EtfConfig, EtfField};
 pub use gradients::{
-    analyze_image_gradients, analyze_image_gradients_with_config, calculate_gradient_magnitude,
-    calculate_local_variance, GradientAnalysis, GradientConfig,
+    analyze_image_gradients, analyze_image_gradients_with_config, calculate_gradient_components,
+    calculate_gradient_magnitude, calculate_local_variance, gradient_normal_map, GradientAnalysis,
+    GradientConfig,
 };
+pub use text_detection::{detect_text_regions, TextRegion};
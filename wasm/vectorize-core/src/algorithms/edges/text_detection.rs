@@ -0,0 +1,369 @@
+//! Text region detection with the stroke width transform (SWT)
+//!
+//! Glyph strokes keep a nearly constant width, unlike most other image content.
+//! The stroke width transform (Epshtein et al.) casts a ray from every edge pixel
+//! against its gradient to the opposite side of the stroke and records the ray
+//! length on each pixel it crosses. Connected components with consistent widths
+//! and glyph-like proportions that line up with similar neighbours are text.
+//!
+//! Only dark text on a lighter background is detected, matching the centerline
+//! backend used to trace it.
+
+use super::gradients::calculate_gradient_components;
+use image::GrayImage;
+
+/// Canny thresholds for the glyph outlines the rays are cast between
+const CANNY_LOW_THRESHOLD: f32 = 20.0;
+const CANNY_HIGH_THRESHOLD: f32 = 60.0;
+
+/// Longest stroke a ray may cross, in pixels
+const MAX_STROKE_WIDTH: f32 = 30.0;
+
+/// Rays only end on edges whose gradient faces back within 60 degrees
+const MAX_OPPOSITE_GRADIENT_DOT: f32 = -0.5;
+
+/// Stroke pixels this close (in either axis) belong to the same component
+const COMPONENT_LINK_DISTANCE: u32 = 2;
+
+/// Glyph height range in pixels
+const MIN_GLYPH_HEIGHT: u32 = 6;
+const MAX_GLYPH_HEIGHT: u32 = 300;
+
+/// Largest stroke width standard deviation relative to its mean within a glyph
+const MAX_WIDTH_VARIATION: f32 = 0.6;
+
+/// Glyph height relative to median stroke width; blobs sit below, outlines of
+/// boxes and other large shapes above
+const MIN_HEIGHT_TO_STROKE_RATIO: f32 = 1.5;
+const MAX_HEIGHT_TO_STROKE_RATIO: f32 = 8.0;
+
+/// Largest bounding box aspect ratio (either orientation) of a glyph
+const MAX_GLYPH_ASPECT_RATIO: f32 = 10.0;
+
+/// Glyphs pair up into a line when heights and stroke widths are within this factor
+const MAX_PAIR_SIZE_RATIO: f32 = 2.0;
+
+/// Largest horizontal gap between paired glyphs, relative to the taller one
+const MAX_PAIR_GAP_TO_HEIGHT: f32 = 2.0;
+
+/// Axis-aligned rectangle containing a line of text, in pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl TextRegion {
+    /// Whether the pixel at `(x, y)` lies inside the region
+    pub fn contains(&self, x: u32, y: u32) -> bool {
+        (self.x..self.x + self.width).contains(&x) && (self.y..self.y + self.height).contains(&y)
+    }
+}
+
+/// Candidate glyph: a connected component of the stroke width map
+#[derive(Debug, Clone, Copy)]
+struct Glyph {
+    min_x: u32,
+    min_y: u32,
+    max_x: u32,
+    max_y: u32,
+    median_width: f32,
+}
+
+impl Glyph {
+    fn height(&self) -> f32 {
+        (self.max_y - self.min_y + 1) as f32
+    }
+
+    fn center_y(&self) -> f32 {
+        (self.min_y + self.max_y) as f32 / 2.0
+    }
+
+    /// Whether two glyphs look like neighbouring letters of the same line
+    fn pairs_with(&self, other: &Glyph) -> bool {
+        let ratio = |a: f32, b: f32| a.max(b) / a.min(b);
+        let taller = self.height().max(other.height());
+        let gap = (self.min_x.max(other.min_x) as f32) - (self.max_x.min(other.max_x) as f32);
+        ratio(self.height(), other.height()) < MAX_PAIR_SIZE_RATIO
+            && ratio(self.median_width, other.median_width) < MAX_PAIR_SIZE_RATIO
+            && (self.center_y() - other.center_y()).abs() < taller / 2.0
+            && gap < taller * MAX_PAIR_GAP_TO_HEIGHT
+    }
+}
+
+/// Find lines of dark text in a grayscale image
+///
+/// Each region is the bounding box of two or more aligned glyphs, padded by the
+/// widest stroke so anti-aliased glyph edges are included.
+pub fn detect_text_regions(gray: &GrayImage) -> Vec<TextRegion> {
+    let (width, height) = gray.dimensions();
+    if width < MIN_GLYPH_HEIGHT || height < MIN_GLYPH_HEIGHT {
+        return Vec::new();
+    }
+
+    let widths = stroke_width_transform(gray);
+    let glyphs = glyph_components(&widths, width, height);
+
+    // Union-find over glyph pairs groups letters into lines
+    let mut parent: Vec<usize> = (0..glyphs.len()).collect();
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+    for i in 0..glyphs.len() {
+        for j in (i + 1)..glyphs.len() {
+            if glyphs[i].pairs_with(&glyphs[j]) {
+                let (a, b) = (root(&mut parent, i), root(&mut parent, j));
+                parent[a] = b;
+            }
+        }
+    }
+
+    let mut lines: Vec<(usize, Vec<Glyph>)> = Vec::new();
+    for (i, glyph) in glyphs.iter().enumerate() {
+        let line = root(&mut parent, i);
+        match lines.iter_mut().find(|(id, _)| *id == line) {
+            Some((_, members)) => members.push(*glyph),
+            None => lines.push((line, vec![*glyph])),
+        }
+    }
+
+    let regions: Vec<TextRegion> = lines
+        .into_iter()
+        .filter(|(_, members)| members.len() >= 2)
+        .map(|(_, members)| {
+            let margin = members
+                .iter()
+                .map(|glyph| glyph.median_width.ceil() as u32)
+                .max()
+                .unwrap_or(0)
+                + 1;
+            let min_x = members.iter().map(|g| g.min_x).min().unwrap_or(0);
+            let min_y = members.iter().map(|g| g.min_y).min().unwrap_or(0);
+            let max_x = members.iter().map(|g| g.max_x).max().unwrap_or(0);
+            let max_y = members.iter().map(|g| g.max_y).max().unwrap_or(0);
+            let (x, y) = (min_x.saturating_sub(margin), min_y.saturating_sub(margin));
+            TextRegion {
+                x,
+                y,
+                width: (max_x + margin + 1).min(width) - x,
+                height: (max_y + margin + 1).min(height) - y,
+            }
+        })
+        .collect();
+
+    log::debug!(
+        "Text detection: {} glyph candidates, {} text regions",
+        glyphs.len(),
+        regions.len()
+    );
+    regions
+}
+
+/// Stroke width of every pixel inside a dark stroke (`f32::INFINITY` elsewhere)
+fn stroke_width_transform(gray: &GrayImage) -> Vec<f32> {
+    let (width, height) = gray.dimensions();
+    let edges = imageproc::edges::canny(gray, CANNY_LOW_THRESHOLD, CANNY_HIGH_THRESHOLD);
+    let is_edge = |x: u32, y: u32| edges.get_pixel(x, y).0[0] > 0;
+    let unit_gradient = |x: u32, y: u32| {
+        let (gx, gy) = calculate_gradient_components(gray, x, y);
+        let norm = (gx * gx + gy * gy).sqrt();
+        (norm > f32::EPSILON).then(|| (gx / norm, gy / norm))
+    };
+    let index = |x: u32, y: u32| (y * width + x) as usize;
+
+    let mut widths = vec![f32::INFINITY; (width * height) as usize];
+    let mut rays: Vec<Vec<usize>> = Vec::new();
+    for (x, y, _) in edges.enumerate_pixels().filter(|(_, _, p)| p.0[0] > 0) {
+        let Some((gx, gy)) = unit_gradient(x, y) else {
+            continue;
+        };
+        // The gradient points from the dark stroke towards the background
+        let (dx, dy) = (-gx, -gy);
+        let (start_x, start_y) = (x as f32 + 0.5, y as f32 + 0.5);
+
+        let mut ray = vec![index(x, y)];
+        let mut step = 0.5f32;
+        while step <= MAX_STROKE_WIDTH {
+            let (px, py) = (start_x + dx * step, start_y + dy * step);
+            step += 0.5;
+            if px < 0.0 || py < 0.0 || px >= width as f32 || py >= height as f32 {
+                break;
+            }
+            let (qx, qy) = (px as u32, py as u32);
+            let q = index(qx, qy);
+            if ray.last() == Some(&q) {
+                continue;
+            }
+            ray.push(q);
+            if !is_edge(qx, qy) {
+                continue;
+            }
+
+            let opposite = unit_gradient(qx, qy)
+                .is_some_and(|(qgx, qgy)| gx * qgx + gy * qgy < MAX_OPPOSITE_GRADIENT_DOT);
+            if opposite {
+                let length = (qx as f32 - x as f32).hypot(qy as f32 - y as f32) + 1.0;
+                for &pixel in &ray {
+                    widths[pixel] = widths[pixel].min(length);
+                }
+                rays.push(ray);
+            }
+            break;
+        }
+    }
+
+    // Corners get overestimated widths; cap each ray at its median
+    for ray in &rays {
+        let mut values: Vec<f32> = ray.iter().map(|&pixel| widths[pixel]).collect();
+        values.sort_by(f32::total_cmp);
+        let median = values[values.len() / 2];
+        for &pixel in ray {
+            widths[pixel] = widths[pixel].min(median);
+        }
+    }
+    widths
+}
+
+/// Connected stroke components that pass the glyph filters
+///
+/// Components are not split on width changes: junctions such as the bar of an
+/// 'H' are crossed wider than the strokes, and the width variation filter already
+/// rejects shapes with inconsistent strokes. Pixels link across small gaps since
+/// Canny leaves stroke corners without edges for rays to start from.
+fn glyph_components(widths: &[f32], width: u32, height: u32) -> Vec<Glyph> {
+    let mut visited = vec![false; widths.len()];
+    let mut glyphs = Vec::new();
+    let mut stack = Vec::new();
+
+    for start in 0..widths.len() {
+        if visited[start] || !widths[start].is_finite() {
+            continue;
+        }
+        visited[start] = true;
+        stack.push(start);
+        let mut members: Vec<f32> = Vec::new();
+        let (mut min_x, mut min_y, mut max_x, mut max_y) = (width, height, 0, 0);
+
+        while let Some(pixel) = stack.pop() {
+            let (x, y) = ((pixel as u32) % width, (pixel as u32) / width);
+            members.push(widths[pixel]);
+            (min_x, min_y) = (min_x.min(x), min_y.min(y));
+            (max_x, max_y) = (max_x.max(x), max_y.max(y));
+
+            let xs = x.saturating_sub(COMPONENT_LINK_DISTANCE)
+                ..=(x + COMPONENT_LINK_DISTANCE).min(width - 1);
+            let ys = y.saturating_sub(COMPONENT_LINK_DISTANCE)
+                ..=(y + COMPONENT_LINK_DISTANCE).min(height - 1);
+            for (nx, ny) in ys.flat_map(|ny| xs.clone().map(move |nx| (nx, ny))) {
+                let neighbor = (ny * width + nx) as usize;
+                if !visited[neighbor] && widths[neighbor].is_finite() {
+                    visited[neighbor] = true;
+                    stack.push(neighbor);
+                }
+            }
+        }
+
+        let glyph_height = max_y - min_y + 1;
+        let glyph_width = max_x - min_x + 1;
+        if !(MIN_GLYPH_HEIGHT..=MAX_GLYPH_HEIGHT).contains(&glyph_height) {
+            continue;
+        }
+        let aspect = glyph_width.max(glyph_height) as f32 / glyph_width.min(glyph_height) as f32;
+        if aspect > MAX_GLYPH_ASPECT_RATIO {
+            continue;
+        }
+
+        let count = members.len() as f32;
+        let mean = members.iter().sum::<f32>() / count;
+        let variance = members.iter().map(|w| (w - mean).powi(2)).sum::<f32>() / count;
+        if variance.sqrt() > MAX_WIDTH_VARIATION * mean {
+            continue;
+        }
+        members.sort_by(f32::total_cmp);
+        let median_width = members[members.len() / 2];
+        let height_to_stroke = glyph_height as f32 / median_width;
+        if !(MIN_HEIGHT_TO_STROKE_RATIO..=MAX_HEIGHT_TO_STROKE_RATIO).contains(&height_to_stroke) {
+            continue;
+        }
+
+        glyphs.push(Glyph {
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+            median_width,
+        });
+    }
+    glyphs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Luma;
+
+    /// Draw a 2px-stroke block letter: 'I', 'L', 'T' or 'H', 14px tall
+    fn draw_glyph(image: &mut GrayImage, letter: char, left: u32, top: u32) {
+        let mut fill = |x0: u32, y0: u32, w: u32, h: u32| {
+            for y in top + y0..top + y0 + h {
+                for x in left + x0..left + x0 + w {
+                    image.put_pixel(x, y, Luma([20]));
+                }
+            }
+        };
+        match letter {
+            'I' => fill(4, 0, 2, 14),
+            'L' => {
+                fill(0, 0, 2, 14);
+                fill(0, 12, 8, 2);
+            }
+            'T' => {
+                fill(0, 0, 10, 2);
+                fill(4, 0, 2, 14);
+            }
+            _ => {
+                fill(0, 0, 2, 14);
+                fill(8, 0, 2, 14);
+                fill(0, 6, 10, 2);
+            }
+        }
+    }
+
+    #[test]
+    fn test_detect_text_regions_finds_label_but_not_shapes() {
+        let mut image = GrayImage::from_pixel(160, 100, Luma([245]));
+        // Label "HILT" at (20, 20)
+        for (i, letter) in "HILT".chars().enumerate() {
+            draw_glyph(&mut image, letter, 20 + i as u32 * 14, 20);
+        }
+        // A large outlined box and a filled blob elsewhere
+        for y in 50..95 {
+            for x in 90..150 {
+                let border = !(93..147).contains(&x) || !(53..92).contains(&y);
+                if border {
+                    image.put_pixel(x, y, Luma([20]));
+                }
+            }
+        }
+        for y in 60..85 {
+            for x in 20..45 {
+                image.put_pixel(x, y, Luma([20]));
+            }
+        }
+
+        let regions = detect_text_regions(&image);
+        assert_eq!(regions.len(), 1, "regions: {regions:?}");
+        let label = regions[0];
+        assert!(label.contains(21, 21) && label.contains(74, 33));
+        assert!(!label.contains(100, 60) && !label.contains(30, 70));
+
+        let blank = GrayImage::from_pixel(40, 40, Luma([245]));
+        assert!(detect_text_regions(&blank).is_empty());
+    }
+}
@@ -0,0 +1,161 @@
+//! Delaunay triangulation using the Bowyer–Watson algorithm
+
+use std::collections::HashSet;
+
+/// Triangle with a cached circumcircle
+struct Triangle {
+    vertices: [usize; 3],
+    center: (f64, f64),
+    radius_sq: f64,
+}
+
+impl Triangle {
+    fn new(vertices: [usize; 3], points: &[(f64, f64)]) -> Self {
+        let (ax, ay) = points[vertices[0]];
+        let (bx, by) = points[vertices[1]];
+        let (cx, cy) = points[vertices[2]];
+
+        let d = 2.0 * (ax * (by - cy) + bx * (cy - ay) + cx * (ay - by));
+        if d.abs() < f64::EPSILON {
+            // Degenerate (collinear) triangle: treat the circumcircle as unbounded
+            return Self {
+                vertices,
+                center: (ax, ay),
+                radius_sq: f64::INFINITY,
+            };
+        }
+
+        let a_sq = ax * ax + ay * ay;
+        let b_sq = bx * bx + by * by;
+        let c_sq = cx * cx + cy * cy;
+        let ux = (a_sq * (by - cy) + b_sq * (cy - ay) + c_sq * (ay - by)) / d;
+        let uy = (a_sq * (cx - bx) + b_sq * (ax - cx) + c_sq * (bx - ax)) / d;
+        let radius_sq = (ax - ux).powi(2) + (ay - uy).powi(2);
+
+        Self {
+            vertices,
+            center: (ux, uy),
+            radius_sq,
+        }
+    }
+
+    fn circumcircle_contains(&self, (x, y): (f64, f64)) -> bool {
+        let dist_sq = (x - self.center.0).powi(2) + (y - self.center.1).powi(2);
+        dist_sq < self.radius_sq * (1.0 - 1e-12)
+    }
+
+    fn edges(&self) -> [(usize, usize); 3] {
+        let [a, b, c] = self.vertices;
+        [(a, b), (b, c), (c, a)]
+    }
+}
+
+/// Triangulate a point set, returning triangles as indices into `points`
+///
+/// Exact duplicate points are skipped; fewer than three distinct points
+/// produce no triangles.
+pub fn triangulate(points: &[(f32, f32)]) -> Vec<[usize; 3]> {
+    if points.len() < 3 {
+        return Vec::new();
+    }
+
+    let mut vertices: Vec<(f64, f64)> = points.iter().map(|&(x, y)| (x as f64, y as f64)).collect();
+    let n = vertices.len();
+
+    let (mut min_x, mut min_y) = (f64::MAX, f64::MAX);
+    let (mut max_x, mut max_y) = (f64::MIN, f64::MIN);
+    for &(x, y) in &vertices {
+        min_x = min_x.min(x);
+        min_y = min_y.min(y);
+        max_x = max_x.max(x);
+        max_y = max_y.max(y);
+    }
+    let span = (max_x - min_x).max(max_y - min_y).max(1.0);
+    let (mid_x, mid_y) = ((min_x + max_x) / 2.0, (min_y + max_y) / 2.0);
+
+    // Super-triangle comfortably enclosing every input point
+    vertices.push((mid_x - 20.0 * span, mid_y - span));
+    vertices.push((mid_x, mid_y + 20.0 * span));
+    vertices.push((mid_x + 20.0 * span, mid_y - span));
+    let mut triangles = vec![Triangle::new([n, n + 1, n + 2], &vertices)];
+
+    let mut seen = HashSet::with_capacity(n);
+    for (i, &(x, y)) in points.iter().enumerate() {
+        if !seen.insert((x.to_bits(), y.to_bits())) {
+            continue;
+        }
+        let point = vertices[i];
+
+        // Remove every triangle whose circumcircle contains the new point
+        let mut cavity_edges = Vec::new();
+        triangles.retain(|triangle| {
+            if triangle.circumcircle_contains(point) {
+                cavity_edges.extend(triangle.edges());
+                false
+            } else {
+                true
+            }
+        });
+
+        // Edges shared by two removed triangles are interior to the cavity
+        let is_boundary = |&(a, b): &(usize, usize)| {
+            cavity_edges
+                .iter()
+                .filter(|&&(c, d)| (a == c && b == d) || (a == d && b == c))
+                .count()
+                == 1
+        };
+        let boundary: Vec<(usize, usize)> =
+            cavity_edges.iter().copied().filter(is_boundary).collect();
+
+        for (a, b) in boundary {
+            triangles.push(Triangle::new([a, b, i], &vertices));
+        }
+    }
+
+    triangles
+        .into_iter()
+        .filter(|triangle| triangle.vertices.iter().all(|&v| v < n))
+        .map(|triangle| triangle.vertices)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_triangulate_square_grid() {
+        let mut points = Vec::new();
+        for y in 0..4 {
+            for x in 0..4 {
+                points.push((x as f32 * 10.0, y as f32 * 10.0));
+            }
+        }
+
+        let triangles = triangulate(&points);
+
+        // A 3x3 grid of cells splits into two triangles per cell
+        assert_eq!(triangles.len(), 18);
+
+        let total_area: f32 = triangles
+            .iter()
+            .map(|&[a, b, c]| {
+                let (ax, ay) = points[a];
+                let (bx, by) = points[b];
+                let (cx, cy) = points[c];
+                ((bx - ax) * (cy - ay) - (cx - ax) * (by - ay)).abs() / 2.0
+            })
+            .sum();
+        assert!((total_area - 900.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_triangulate_degenerate_inputs() {
+        assert!(triangulate(&[(0.0, 0.0), (1.0, 1.0)]).is_empty());
+        assert_eq!(
+            triangulate(&[(0.0, 0.0), (4.0, 0.0), (0.0, 3.0), (4.0, 0.0)]).len(),
+            1
+        );
+    }
+}
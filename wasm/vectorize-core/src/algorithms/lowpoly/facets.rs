@@ -0,0 +1,275 @@
+//! Low-poly rendering: Delaunay facets filled with their average source color
+//!
+//! Vertices are a mix of a jittered grid (for even coverage) and
+//! high-gradient pixels (so facet edges follow image edges). Border anchors
+//! keep the triangulation covering the full canvas.
+
+use super::delaunay::triangulate;
+use crate::algorithms::edges::calculate_gradient_magnitude;
+use crate::algorithms::visual::color_processing::rgba_to_hex;
+use crate::algorithms::{SvgElementType, SvgPath};
+use image::{imageops, Rgba, RgbaImage};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// Minimum Sobel magnitude for a pixel to be considered as an edge vertex
+const MIN_EDGE_GRADIENT: f32 = 32.0;
+
+/// Stroke width applied in the fill color to hide anti-aliasing seams
+const SEAM_STROKE_WIDTH: f32 = 0.5;
+
+/// Configuration for low-poly triangulation
+#[derive(Debug, Clone)]
+pub struct LowPolyConfig {
+    /// Approximate number of triangle vertices, including border anchors
+    pub vertex_count: u32,
+    /// Fraction of interior vertices placed on high-gradient pixels (0.0-1.0)
+    pub edge_vertex_ratio: f32,
+    /// Grid jitter as a fraction of the grid spacing (0.0-1.0)
+    pub jitter: f32,
+    /// Seed for reproducible jitter
+    pub random_seed: u64,
+}
+
+impl Default for LowPolyConfig {
+    fn default() -> Self {
+        Self {
+            vertex_count: 1000,
+            edge_vertex_ratio: 0.6,
+            jitter: 0.8,
+            random_seed: 42,
+        }
+    }
+}
+
+/// Triangulate the image and return one flat-colored `<polygon>` per facet
+pub fn generate_lowpoly_paths(image: &RgbaImage, config: &LowPolyConfig) -> Vec<SvgPath> {
+    let (width, height) = image.dimensions();
+    if width < 2 || height < 2 {
+        return Vec::new();
+    }
+
+    let vertices = place_vertices(image, config);
+    let triangles = triangulate(&vertices);
+
+    log::debug!(
+        "Low-poly triangulation: {} vertices -> {} triangles",
+        vertices.len(),
+        triangles.len()
+    );
+
+    triangles
+        .iter()
+        .filter_map(|triangle| {
+            let corners = triangle.map(|i| vertices[i]);
+            let color = rgba_to_hex(&average_triangle_color(image, corners)?);
+            let points = corners
+                .iter()
+                .map(|(x, y)| format!("{x:.2},{y:.2}"))
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            Some(SvgPath {
+                data: String::new(),
+                fill: color.clone(),
+                stroke: color,
+                stroke_width: SEAM_STROKE_WIDTH,
+                stroke_opacity: None,
+                source_color: None,
+                region_metadata: None,
+                element_type: SvgElementType::Polygon { points },
+            })
+        })
+        .collect()
+}
+
+/// Place border anchors, a jittered grid and high-gradient vertices
+fn place_vertices(image: &RgbaImage, config: &LowPolyConfig) -> Vec<(f32, f32)> {
+    let (w, h) = (image.width() as f32, image.height() as f32);
+    let total = config.vertex_count.max(4) as usize;
+    let mut rng = ChaCha8Rng::seed_from_u64(config.random_seed);
+    let mut points = Vec::with_capacity(total + 8);
+
+    // Border anchors so the facets cover the whole canvas
+    let border_steps = ((total as f32).sqrt() / 2.0).ceil().max(1.0) as usize;
+    for i in 0..=border_steps {
+        let t = i as f32 / border_steps as f32;
+        points.push((t * w, 0.0));
+        points.push((t * w, h));
+        if i > 0 && i < border_steps {
+            points.push((0.0, t * h));
+            points.push((w, t * h));
+        }
+    }
+
+    let interior = total.saturating_sub(points.len());
+    let edge_count = (interior as f32 * config.edge_vertex_ratio.clamp(0.0, 1.0)).round() as usize;
+    let grid_count = interior - edge_count;
+
+    if grid_count > 0 {
+        let spacing = (w * h / grid_count as f32).sqrt();
+        let cols = (w / spacing).ceil().max(1.0) as usize;
+        let rows = (h / spacing).ceil().max(1.0) as usize;
+        let jitter = config.jitter.clamp(0.0, 1.0) * spacing;
+
+        for row in 0..rows {
+            for col in 0..cols {
+                let jx = rng.gen_range(-0.5..=0.5) * jitter;
+                let jy = rng.gen_range(-0.5..=0.5) * jitter;
+                let x = ((col as f32 + 0.5) * spacing + jx).clamp(0.0, w);
+                let y = ((row as f32 + 0.5) * spacing + jy).clamp(0.0, h);
+                points.push((x, y));
+            }
+        }
+    }
+
+    points.extend(gradient_vertices(image, edge_count));
+    points
+}
+
+/// Pick the strongest gradient pixels, kept apart so they spread along edges
+fn gradient_vertices(image: &RgbaImage, count: usize) -> Vec<(f32, f32)> {
+    if count == 0 {
+        return Vec::new();
+    }
+
+    let gray = imageops::grayscale(image);
+    let (width, height) = gray.dimensions();
+
+    let mut candidates: Vec<(f32, u32, u32)> = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .filter_map(|(x, y)| {
+            let magnitude = calculate_gradient_magnitude(&gray, x, y);
+            (magnitude >= MIN_EDGE_GRADIENT).then_some((magnitude, x, y))
+        })
+        .collect();
+    candidates.sort_unstable_by(|a, b| b.0.total_cmp(&a.0));
+
+    // Spatial hash with cells of the minimum spacing
+    let min_distance = ((width * height) as f32 / count as f32).sqrt() * 0.5;
+    let cell = min_distance.max(1.0);
+    let cols = (width as f32 / cell).ceil() as usize + 1;
+    let rows = (height as f32 / cell).ceil() as usize + 1;
+    let mut grid: Vec<Vec<(f32, f32)>> = vec![Vec::new(); cols * rows];
+
+    let mut selected = Vec::with_capacity(count);
+    for (_, x, y) in candidates {
+        let point = (x as f32 + 0.5, y as f32 + 0.5);
+        let (cx, cy) = ((point.0 / cell) as usize, (point.1 / cell) as usize);
+
+        let too_close = (cy.saturating_sub(1)..=(cy + 1).min(rows - 1)).any(|gy| {
+            (cx.saturating_sub(1)..=(cx + 1).min(cols - 1)).any(|gx| {
+                grid[gy * cols + gx].iter().any(|&(px, py)| {
+                    (px - point.0).powi(2) + (py - point.1).powi(2) < min_distance * min_distance
+                })
+            })
+        });
+        if too_close {
+            continue;
+        }
+
+        grid[cy * cols + cx].push(point);
+        selected.push(point);
+        if selected.len() >= count {
+            break;
+        }
+    }
+
+    selected
+}
+
+/// Average color of the pixel centers inside a triangle (centroid sample for slivers)
+fn average_triangle_color(image: &RgbaImage, corners: [(f32, f32); 3]) -> Option<Rgba<u8>> {
+    let [(ax, ay), (bx, by), (cx, cy)] = corners;
+    let area = (bx - ax) * (cy - ay) - (cx - ax) * (by - ay);
+    if area.abs() < 1e-3 {
+        return None;
+    }
+
+    let (width, height) = image.dimensions();
+    let min_x = ax.min(bx).min(cx).floor().max(0.0) as u32;
+    let min_y = ay.min(by).min(cy).floor().max(0.0) as u32;
+    let max_x = (ax.max(bx).max(cx).ceil() as u32).min(width);
+    let max_y = (ay.max(by).max(cy).ceil() as u32).min(height);
+
+    let edge = |(x0, y0): (f32, f32), (x1, y1): (f32, f32), (px, py): (f32, f32)| {
+        ((x1 - x0) * (py - y0) - (px - x0) * (y1 - y0)) * area.signum()
+    };
+
+    let mut sum = [0u64; 4];
+    let mut count = 0u64;
+    for y in min_y..max_y {
+        for x in min_x..max_x {
+            let p = (x as f32 + 0.5, y as f32 + 0.5);
+            if edge(corners[0], corners[1], p) >= 0.0
+                && edge(corners[1], corners[2], p) >= 0.0
+                && edge(corners[2], corners[0], p) >= 0.0
+            {
+                let pixel = image.get_pixel(x, y).0;
+                for (total, &channel) in sum.iter_mut().zip(pixel.iter()) {
+                    *total += channel as u64;
+                }
+                count += 1;
+            }
+        }
+    }
+
+    if count == 0 {
+        let centroid_x = ((ax + bx + cx) / 3.0).clamp(0.0, (width - 1) as f32) as u32;
+        let centroid_y = ((ay + by + cy) / 3.0).clamp(0.0, (height - 1) as f32) as u32;
+        return Some(*image.get_pixel(centroid_x, centroid_y));
+    }
+
+    Some(Rgba(sum.map(|total| ((total + count / 2) / count) as u8)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn two_tone_image() -> RgbaImage {
+        RgbaImage::from_fn(64, 48, |x, _| {
+            if x < 32 {
+                Rgba([200, 40, 40, 255])
+            } else {
+                Rgba([40, 40, 200, 255])
+            }
+        })
+    }
+
+    #[test]
+    fn test_lowpoly_emits_flat_colored_triangles() {
+        let paths = generate_lowpoly_paths(&two_tone_image(), &LowPolyConfig::default());
+
+        assert!(!paths.is_empty());
+        for path in &paths {
+            match &path.element_type {
+                SvgElementType::Polygon { points } => {
+                    assert_eq!(points.split_whitespace().count(), 3)
+                }
+                other => panic!("expected polygon, got {other:?}"),
+            }
+            assert_eq!(path.fill, path.stroke);
+        }
+        assert!(paths.iter().any(|p| p.fill == "#C82828"));
+        assert!(paths.iter().any(|p| p.fill == "#2828C8"));
+    }
+
+    #[test]
+    fn test_lowpoly_triangle_count_tracks_vertex_count() {
+        let image = two_tone_image();
+        let sparse = LowPolyConfig {
+            vertex_count: 50,
+            ..LowPolyConfig::default()
+        };
+        let dense = LowPolyConfig {
+            vertex_count: 400,
+            ..LowPolyConfig::default()
+        };
+
+        let sparse_paths = generate_lowpoly_paths(&image, &sparse);
+        let dense_paths = generate_lowpoly_paths(&image, &dense);
+
+        assert!(dense_paths.len() > sparse_paths.len() * 3);
+    }
+}
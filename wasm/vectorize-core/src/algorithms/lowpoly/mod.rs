@@ -0,0 +1,11 @@
+//! Low-poly triangulation module
+//!
+//! This module contains Delaunay triangulation and the flat-shaded facet
+//! renderer used by the low-poly backend.
+
+pub mod delaunay;
+pub mod facets;
+
+// Re-export commonly used types
+pub use delaunay::triangulate;
+pub use facets::{generate_lowpoly_paths, LowPolyConfig};
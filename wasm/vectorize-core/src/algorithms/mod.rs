@@ -6,12 +6,14 @@
 //! - `centerline/` - Centerline extraction and distance transforms
 //! - `dots/` - Stippling, dot generation, and background analysis
 //! - `edges/` - Edge detection, ETF, and gradient analysis
+//! - `lowpoly/` - Delaunay triangulation and low-poly facet rendering
 //! - `tracing/` - Path tracing, curve fitting, and trace-low algorithm
 //! - `visual/` - Color processing, SIMD operations, and visual enhancements
 
 pub mod centerline;
 pub mod dots;
 pub mod edges;
+pub mod lowpoly;
 pub mod tracing;
 pub mod visual;
 
@@ -27,8 +29,8 @@ pub use dots::{
     add_artistic_jitter, add_opacity_variation, add_size_variation, analyze_image_regions,
     apply_adaptive_density, apply_artistic_effects, apply_grid_alignment, apply_style_preset,
     calculate_adaptive_density, calculate_color_similarity, detect_background_advanced,
-    detect_background_mask, dots_to_svg_elements, dots_to_svg_paths, dots_to_svg_with_config,
-    generate_adaptive_dots, generate_dot_svg_document, generate_dots,
+    detect_background_mask, dots_to_dash_paths, dots_to_svg_elements, dots_to_svg_paths,
+    dots_to_svg_with_config, generate_adaptive_dots, generate_dot_svg_document, generate_dots,
     generate_dots_auto_background, generate_dots_from_image, generate_dots_optimized_pipeline,
     get_style_parameters, optimize_dot_svg, poisson_disk_sampling, rgba_to_lab,
     smooth_density_transitions, AdaptiveConfig, BackgroundConfig, Dot, DotConfig, DotStyle,
@@ -39,12 +41,15 @@ pub use dots::{
 // Edges module re-exports
 pub use edges::{
     analyze_image_gradients, analyze_image_gradients_with_config, apply_nms,
-    calculate_gradient_magnitude, calculate_local_variance, compute_etf, compute_fdog,
-    compute_multi_direction_edges, compute_xdog, hysteresis_threshold, EdgeResponse, EtfConfig,
-    EtfField, FdogConfig, GradientAnalysis, GradientConfig, MultiDirectionEdges, NmsConfig,
-    XdogConfig,
+    calculate_gradient_components, calculate_gradient_magnitude, calculate_local_variance,
+    compute_etf, compute_fdog, compute_multi_direction_edges, compute_xdog, gradient_normal_map,
+    hysteresis_threshold, EdgeResponse, EtfConfig, EtfField, FdogConfig, GradientAnalysis,
+    GradientConfig, MultiDirectionEdges, NmsConfig, XdogConfig,
 };
 
+// Low-poly module re-exports
+pub use lowpoly::{generate_lowpoly_paths, triangulate, LowPolyConfig};
+
 // Tracing module re-exports
 pub use tracing::{
     fit_beziers, trace_polylines, vectorize_trace_low, vectorize_trace_low_with_gradients,
@@ -59,7 +64,7 @@ pub use visual::{
     reduce_color_palette, rgba_to_hex, simd_analyze_gradient_strength,
     simd_k_means_palette_reduction, ColorSample, ColorSamplingMethod, GradientDetectionAnalysis,
     GradientDetectionConfig, GradientPoint, GradientStop, GradientType, HandDrawnConfig,
-    HandDrawnPresets, PathColorInfo,
+    HandDrawnPresets, HandDrawnTarget, PathColorInfo,
 };
 
 /// 2D point representation
@@ -98,6 +103,15 @@ pub enum SvgElementType {
     Polygon { points: String },
 }
 
+/// Pixel statistics of a traced region
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RegionMetadata {
+    /// Mean position of the region's pixels
+    pub centroid: Point,
+    /// Number of pixels in the region
+    pub area: usize,
+}
+
 /// SVG path representation
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct SvgPath {
@@ -109,6 +123,16 @@ pub struct SvgPath {
     pub stroke: String,
     /// Stroke width in pixels
     pub stroke_width: f32,
+    /// Optional stroke opacity (0.0-1.0), omitted from output when `None`
+    #[serde(default)]
+    pub stroke_opacity: Option<f32>,
+    /// Average source-image color under the element (`#rrggbb`), independent of
+    /// the rendered colors
+    #[serde(default)]
+    pub source_color: Option<String>,
+    /// Geometry of the source region the element outlines, for region-based backends
+    #[serde(default)]
+    pub region_metadata: Option<RegionMetadata>,
     /// Element type (mostly Path for trace-low)
     pub element_type: SvgElementType,
 }
@@ -121,6 +145,9 @@ impl SvgPath {
             fill: "none".to_string(),
             stroke: "#000000".to_string(),
             stroke_width: 1.0,
+            stroke_opacity: None,
+            source_color: None,
+            region_metadata: None,
             element_type: SvgElementType::Path,
         }
     }
@@ -132,6 +159,9 @@ impl SvgPath {
             fill: "none".to_string(),
             stroke: stroke_color.to_string(),
             stroke_width,
+            stroke_opacity: None,
+            source_color: None,
+            region_metadata: None,
             element_type: SvgElementType::Path,
         }
     }
@@ -143,6 +173,9 @@ impl SvgPath {
             fill: fill_color.to_string(),
             stroke: "none".to_string(),
             stroke_width: 0.0,
+            stroke_opacity: None,
+            source_color: None,
+            region_metadata: None,
             element_type: SvgElementType::Path,
         }
     }
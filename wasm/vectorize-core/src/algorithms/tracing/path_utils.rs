@@ -1,6 +1,8 @@
 //! Path manipulation utilities for vectorization algorithms
 
 use crate::algorithms::Point;
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
 
 /// Calculate appropriate Douglas-Peucker epsilon based on image dimensions
 ///
@@ -41,39 +43,71 @@ pub fn douglas_peucker_simplify(points: &[Point], tolerance: f64) -> Vec<Point>
     }
 
     let tolerance_sq = tolerance * tolerance;
-    simplify_recursive(points, 0, points.len() - 1, tolerance_sq)
+    douglas_peucker_by(points, |start, end| {
+        let mut max_distance_sq = 0.0;
+        let mut max_index = start;
+        for i in start + 1..end {
+            let distance_sq =
+                perpendicular_distance_squared(&points[i], &points[start], &points[end]);
+            if distance_sq > max_distance_sq {
+                max_distance_sq = distance_sq;
+                max_index = i;
+            }
+        }
+        (max_distance_sq > tolerance_sq).then_some(max_index)
+    })
 }
 
-fn simplify_recursive(points: &[Point], start: usize, end: usize, tolerance_sq: f64) -> Vec<Point> {
-    if end <= start + 1 {
-        return vec![points[start], points[end]];
-    }
+/// Douglas-Peucker driver shared by the simplifiers that use their own distance metric
+///
+/// `split` gets the index range `(start, end)` of a chord and returns the interior
+/// point to keep, or `None` when every point is close enough to the chord. Ranges
+/// wait on an explicit stack instead of recursing, so very long zigzag paths cannot
+/// overflow the (small, on WASM) call stack. The endpoints are always kept.
+pub(crate) fn douglas_peucker_by(
+    points: &[Point],
+    split: impl FnMut(usize, usize) -> Option<usize>,
+) -> Vec<Point> {
+    douglas_peucker_within_depth(points, u32::MAX, split).unwrap_or_else(|| points.to_vec())
+}
 
-    // Find the point with maximum distance from line segment
-    let mut max_distance_sq = 0.0;
-    let mut max_index = start;
+/// [`douglas_peucker_by`] that gives up once a chord would be split more than
+/// `max_depth` levels deep, returning `None` so the caller can fall back to a
+/// simplifier better suited to such paths
+pub(crate) fn douglas_peucker_within_depth(
+    points: &[Point],
+    max_depth: u32,
+    mut split: impl FnMut(usize, usize) -> Option<usize>,
+) -> Option<Vec<Point>> {
+    if points.len() <= 2 {
+        return Some(points.to_vec());
+    }
 
-    for i in start + 1..end {
-        let distance_sq = perpendicular_distance_squared(&points[i], &points[start], &points[end]);
-        if distance_sq > max_distance_sq {
-            max_distance_sq = distance_sq;
-            max_index = i;
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+    let mut ranges = vec![(0, points.len() - 1, 0u32)];
+    while let Some((start, end, depth)) = ranges.pop() {
+        if end <= start + 1 {
+            continue;
+        }
+        if let Some(index) = split(start, end) {
+            if depth >= max_depth {
+                return None;
+            }
+            keep[index] = true;
+            ranges.push((start, index, depth + 1));
+            ranges.push((index, end, depth + 1));
         }
     }
 
-    if max_distance_sq <= tolerance_sq {
-        // All points are within tolerance, return line segment
-        vec![points[start], points[end]]
-    } else {
-        // Recursively simplify both segments
-        let mut left = simplify_recursive(points, start, max_index, tolerance_sq);
-        let right = simplify_recursive(points, max_index, end, tolerance_sq);
-
-        // Merge results (remove duplicate middle point)
-        left.pop();
-        left.extend(right);
-        left
-    }
+    Some(
+        points
+            .iter()
+            .zip(keep)
+            .filter_map(|(point, kept)| kept.then_some(*point))
+            .collect(),
+    )
 }
 
 /// Calculate squared perpendicular distance from point to line segment
@@ -114,7 +148,8 @@ fn perpendicular_distance_squared(point: &Point, line_start: &Point, line_end: &
 /// Visvalingam-Whyatt path simplification algorithm
 ///
 /// Alternative to Douglas-Peucker that removes points based on effective area
-/// of triangles formed by consecutive point triplets.
+/// of triangles formed by consecutive point triplets. Points with equal area
+/// are removed in order of their original index, so results are reproducible.
 ///
 /// # Arguments
 /// * `points` - Input path as a series of points
@@ -127,40 +162,362 @@ pub fn visvalingam_whyatt_simplify(points: &[Point], min_area: f64) -> Vec<Point
         return points.to_vec();
     }
 
-    let mut simplified = points.to_vec();
+    // Remaining points form a linked list; heap entries go stale when a neighbor
+    // is removed and are skipped once their area no longer matches
+    let last = points.len() - 1;
+    let mut prev: Vec<usize> = (0..points.len()).map(|i| i.saturating_sub(1)).collect();
+    let mut next: Vec<usize> = (1..=points.len()).collect();
+    let mut removed = vec![false; points.len()];
+    let area_of = |i: usize, prev: &[usize], next: &[usize]| {
+        triangle_area(&points[prev[i]], &points[i], &points[next[i]])
+    };
 
-    loop {
-        if simplified.len() <= 2 {
-            break;
+    // Endpoints never enter the heap, so their entries are unused
+    let mut areas: Vec<f64> = (0..points.len())
+        .map(|i| {
+            if i == 0 || i == last {
+                f64::NAN
+            } else {
+                area_of(i, &prev, &next)
+            }
+        })
+        .collect();
+    let mut heap: BinaryHeap<Reverse<TriangleArea>> = areas[1..last]
+        .iter()
+        .zip(1..)
+        .map(|(&area, index)| Reverse(TriangleArea { area, index }))
+        .collect();
+
+    // Endpoints are kept, so at least two points remain
+    while let Some(Reverse(smallest)) = heap.pop() {
+        let index = smallest.index;
+        if removed[index] || areas[index].total_cmp(&smallest.area).is_ne() {
+            continue;
+        }
+        if smallest.area.is_nan() || smallest.area > min_area {
+            break; // All remaining points have area above threshold
         }
 
-        let mut min_effective_area = f64::INFINITY;
-        let mut min_index = 1; // Can't remove first or last point
-
-        // Find point with minimum effective area
-        for i in 1..simplified.len() - 1 {
-            let area = triangle_area(&simplified[i - 1], &simplified[i], &simplified[i + 1]);
-            if area < min_effective_area {
-                min_effective_area = area;
-                min_index = i;
+        removed[index] = true;
+        let (before, after) = (prev[index], next[index]);
+        next[before] = after;
+        prev[after] = before;
+        for neighbor in [before, after] {
+            if neighbor != 0 && neighbor != last {
+                areas[neighbor] = area_of(neighbor, &prev, &next);
+                heap.push(Reverse(TriangleArea {
+                    area: areas[neighbor],
+                    index: neighbor,
+                }));
             }
         }
+    }
 
-        if min_effective_area > min_area {
-            break; // All remaining points have area above threshold
-        }
+    points
+        .iter()
+        .zip(removed)
+        .filter_map(|(point, removed)| (!removed).then_some(*point))
+        .collect()
+}
+
+/// Effective area of a point in Visvalingam-Whyatt simplification
+///
+/// Ordered by area, then by the point's index in the input path so ties never
+/// depend on scan order.
+#[derive(Debug, Clone, Copy)]
+struct TriangleArea {
+    area: f64,
+    index: usize,
+}
+
+impl Ord for TriangleArea {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.area
+            .total_cmp(&other.area)
+            .then(self.index.cmp(&other.index))
+    }
+}
 
-        // Remove the point with minimum effective area
-        simplified.remove(min_index);
+impl PartialOrd for TriangleArea {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
+}
 
-    simplified
+impl PartialEq for TriangleArea {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
 }
 
+impl Eq for TriangleArea {}
+
 /// Calculate area of triangle formed by three points
+///
+/// Computed in `f64`, where differences and products of `f32` coordinates are
+/// exact, so mirrored triangles get exactly equal areas.
 fn triangle_area(p1: &Point, p2: &Point, p3: &Point) -> f64 {
-    let area = 0.5 * ((p2.x - p1.x) * (p3.y - p1.y) - (p3.x - p1.x) * (p2.y - p1.y)).abs();
-    area as f64
+    let (x1, y1) = (p1.x as f64, p1.y as f64);
+    let (x2, y2) = (p2.x as f64, p2.y as f64);
+    let (x3, y3) = (p3.x as f64, p3.y as f64);
+    0.5 * ((x2 - x1) * (y3 - y1) - (x3 - x1) * (y2 - y1)).abs()
+}
+
+/// Remove interior vertices where the path doubles back on itself
+///
+/// A vertex is dropped when the angle between its incoming and outgoing
+/// segments is below `min_angle_deg` (0° is a full reversal, 180° straight on),
+/// or when it coincides with a neighbor. Removal repeats until no spike
+/// remains; endpoints are always kept.
+///
+/// # Arguments
+/// * `points` - Input path points
+/// * `min_angle_deg` - Smallest vertex angle kept, in degrees (0 = disabled)
+///
+/// # Returns
+/// * `Vec<Point>` - Path without spike vertices
+pub fn remove_spike_vertices(points: &[Point], min_angle_deg: f32) -> Vec<Point> {
+    if points.len() <= 2 || min_angle_deg <= 0.0 {
+        return points.to_vec();
+    }
+
+    let min_cos = min_angle_deg.to_radians().cos();
+    let is_spike = |a: &Point, b: &Point, c: &Point| {
+        let (ux, uy) = (a.x - b.x, a.y - b.y);
+        let (vx, vy) = (c.x - b.x, c.y - b.y);
+        let lengths = (ux * ux + uy * uy).sqrt() * (vx * vx + vy * vy).sqrt();
+        lengths <= f32::EPSILON || (ux * vx + uy * vy) / lengths > min_cos
+    };
+
+    let mut result: Vec<Point> = Vec::with_capacity(points.len());
+    for point in points {
+        while result.len() >= 2 && is_spike(&result[result.len() - 2], &result[result.len() - 1], point) {
+            result.pop();
+        }
+        result.push(*point);
+    }
+
+    result
+}
+
+/// Total least-squares line fit over a growing set of points
+#[derive(Debug, Clone, Copy, Default)]
+struct LineFit {
+    count: f64,
+    sum_x: f64,
+    sum_y: f64,
+    sum_xx: f64,
+    sum_yy: f64,
+    sum_xy: f64,
+}
+
+/// Infinite line through `origin` along the unit vector `direction`
+#[derive(Debug, Clone, Copy)]
+struct FittedLine {
+    origin: (f64, f64),
+    direction: (f64, f64),
+}
+
+impl LineFit {
+    fn add(&mut self, point: &Point) {
+        let (x, y) = (point.x as f64, point.y as f64);
+        self.count += 1.0;
+        self.sum_x += x;
+        self.sum_y += y;
+        self.sum_xx += x * x;
+        self.sum_yy += y * y;
+        self.sum_xy += x * y;
+    }
+
+    /// Centroid and covariance terms `(cxx, cyy, cxy)`
+    fn moments(&self) -> ((f64, f64), (f64, f64, f64)) {
+        let (mx, my) = (self.sum_x / self.count, self.sum_y / self.count);
+        let cxx = self.sum_xx / self.count - mx * mx;
+        let cyy = self.sum_yy / self.count - my * my;
+        let cxy = self.sum_xy / self.count - mx * my;
+        ((mx, my), (cxx, cyy, cxy))
+    }
+
+    /// Root-mean-square perpendicular distance of the points to the fitted line
+    fn rms_residual(&self) -> f64 {
+        let (_, (cxx, cyy, cxy)) = self.moments();
+        let half_diff = (cxx - cyy) / 2.0;
+        let smallest_eigenvalue = (cxx + cyy) / 2.0 - (half_diff * half_diff + cxy * cxy).sqrt();
+        smallest_eigenvalue.max(0.0).sqrt()
+    }
+
+    fn line(&self) -> FittedLine {
+        let (origin, (cxx, cyy, cxy)) = self.moments();
+        let angle = 0.5 * (2.0 * cxy).atan2(cxx - cyy);
+        FittedLine {
+            origin,
+            direction: (angle.cos(), angle.sin()),
+        }
+    }
+}
+
+impl FittedLine {
+    fn distance(&self, point: &Point) -> f64 {
+        let (dx, dy) = (point.x as f64 - self.origin.0, point.y as f64 - self.origin.1);
+        (dx * self.direction.1 - dy * self.direction.0).abs()
+    }
+
+    fn project(&self, point: &Point) -> Point {
+        let (dx, dy) = (point.x as f64 - self.origin.0, point.y as f64 - self.origin.1);
+        let along = dx * self.direction.0 + dy * self.direction.1;
+        Point {
+            x: (self.origin.0 + along * self.direction.0) as f32,
+            y: (self.origin.1 + along * self.direction.1) as f32,
+        }
+    }
+
+    /// Intersection with `other`, or `None` when the lines meet at less than `min_angle_deg`
+    fn intersect(&self, other: &FittedLine, min_angle_deg: f64) -> Option<Point> {
+        let cross = self.direction.0 * other.direction.1 - self.direction.1 * other.direction.0;
+        if cross.abs() < min_angle_deg.to_radians().sin() {
+            return None;
+        }
+        let (dx, dy) = (other.origin.0 - self.origin.0, other.origin.1 - self.origin.1);
+        let t = (dx * other.direction.1 - dy * other.direction.0) / cross;
+        Some(Point {
+            x: (self.origin.0 + t * self.direction.0) as f32,
+            y: (self.origin.1 + t * self.direction.1) as f32,
+        })
+    }
+
+    /// Turn the line exactly horizontal or vertical when within `max_angle_deg` of either axis
+    fn snapped_to_axis(self, max_angle_deg: f64) -> FittedLine {
+        let (ux, uy) = self.direction;
+        let angle = uy.abs().atan2(ux.abs()).to_degrees();
+        let direction = if angle <= max_angle_deg {
+            (1.0, 0.0)
+        } else if angle >= 90.0 - max_angle_deg {
+            (0.0, 1.0)
+        } else {
+            return self;
+        };
+        FittedLine { direction, ..self }
+    }
+}
+
+/// Vertices joining two consecutive runs at `point`
+///
+/// Two straight runs meet at the intersection of their lines when it lies within
+/// `max_corner_shift`; otherwise the point is projected onto each line so both
+/// stay exactly straight. A single straight run takes the projection.
+fn straight_run_junction(
+    previous: Option<&FittedLine>,
+    next: Option<&FittedLine>,
+    point: &Point,
+    max_corner_shift: f64,
+) -> Vec<Point> {
+    match (previous, next) {
+        (Some(a), Some(b)) => {
+            let corner = a.intersect(b, 10.0).filter(|corner| {
+                ((corner.x - point.x) as f64).hypot((corner.y - point.y) as f64) <= max_corner_shift
+            });
+            match corner {
+                Some(corner) => vec![corner],
+                None => vec![a.project(point), b.project(point)],
+            }
+        }
+        (Some(line), None) | (None, Some(line)) => vec![line.project(point)],
+        (None, None) => vec![*point],
+    }
+}
+
+/// Replace near-straight runs of a path with exactly straight segments
+///
+/// Runs are grown greedily while every added point lies within twice
+/// `max_residual` of the total least-squares line and the RMS residual stays
+/// within `max_residual`. Runs at least `min_length` long are replaced by their
+/// fitted line, snapped horizontal or vertical when within `axis_snap_deg` of an
+/// axis; neighboring straight runs meet at their lines' intersection so corners
+/// stay sharp. Shorter runs keep their points.
+///
+/// # Arguments
+/// * `points` - Input path points
+/// * `max_residual` - Largest RMS distance from the fitted line, in pixels
+/// * `min_length` - Shortest run replaced by a straight segment, in pixels
+/// * `axis_snap_deg` - Largest angle to the nearest axis that is snapped (0 = disabled)
+///
+/// # Returns
+/// * `Vec<Point>` - Path with straight runs reduced to their end points
+pub fn snap_straight_runs(
+    points: &[Point],
+    max_residual: f32,
+    min_length: f32,
+    axis_snap_deg: f32,
+) -> Vec<Point> {
+    if points.len() < 3 || max_residual <= 0.0 {
+        return points.to_vec();
+    }
+
+    let max_residual = max_residual as f64;
+    let max_shift = 2.0 * max_residual;
+    // Traced corners are rounded over a few pixels
+    let max_corner_shift = 4.0 * max_residual;
+    let mut runs: Vec<(usize, usize, Option<FittedLine>)> = Vec::new();
+    let mut start = 0;
+    while start + 1 < points.len() {
+        let mut fit = LineFit::default();
+        fit.add(&points[start]);
+        fit.add(&points[start + 1]);
+        let mut end = start + 1;
+        while end + 1 < points.len() {
+            let candidate = &points[end + 1];
+            if fit.line().distance(candidate) > max_shift {
+                break;
+            }
+            let mut extended = fit;
+            extended.add(candidate);
+            if extended.rms_residual() > max_residual {
+                break;
+            }
+            fit = extended;
+            end += 1;
+        }
+
+        let length = points[start].distance_to(&points[end]);
+        let line = (length >= min_length).then(|| fit.line().snapped_to_axis(axis_snap_deg as f64));
+        runs.push((start, end, line));
+        start = end;
+    }
+
+    let closed = points.len() > 3 && points[0] == points[points.len() - 1];
+    let first_line = runs.first().and_then(|(_, _, line)| line.as_ref());
+    let last_line = runs.last().and_then(|(_, _, line)| line.as_ref());
+    // A closed path starts and ends on the junction of its last and first runs
+    let closing = closed.then(|| {
+        straight_run_junction(last_line, first_line, &points[0], max_corner_shift)
+    });
+
+    let mut result = match &closing {
+        Some(junction) => vec![*junction.last().unwrap()],
+        None => straight_run_junction(None, first_line, &points[0], max_corner_shift),
+    };
+    for (index, (start, end, line)) in runs.iter().enumerate() {
+        if line.is_none() {
+            result.extend_from_slice(&points[start + 1..*end]);
+        }
+        match (runs.get(index + 1), &closing) {
+            (Some((_, _, next)), _) => result.extend(straight_run_junction(
+                line.as_ref(),
+                next.as_ref(),
+                &points[*end],
+                max_corner_shift,
+            )),
+            (None, Some(junction)) => result.extend(junction),
+            (None, None) => result.extend(straight_run_junction(
+                line.as_ref(),
+                None,
+                &points[*end],
+                max_corner_shift,
+            )),
+        }
+    }
+
+    result
 }
 
 /// Smooth a path using a simple moving average filter
@@ -969,6 +1326,92 @@ mod tests {
         assert_eq!(simplified[1], points[3]);
     }
 
+    #[test]
+    fn test_douglas_peucker_survives_deep_zigzag() {
+        // Shrinking zigzag: every split peels off one point, so a recursive
+        // implementation would nest as deep as the path is long
+        let n = 20_000;
+        let zigzag: Vec<Point> = (0..=n)
+            .map(|i| {
+                let amplitude = (n - i) as f32;
+                let y = if i % 2 == 0 { amplitude } else { -amplitude };
+                Point { x: i as f32, y }
+            })
+            .collect();
+
+        assert_eq!(douglas_peucker_simplify(&zigzag, 0.5), zigzag);
+
+        // The heap-based Visvalingam-Whyatt stays fast on the same input
+        let simplified = visvalingam_whyatt_simplify(&zigzag, f64::INFINITY);
+        assert_eq!(simplified, vec![zigzag[0], zigzag[n]]);
+    }
+
+    #[test]
+    fn test_remove_spike_vertices() {
+        // Straight run with a spike that shoots out to (5, 8) and back at 10°
+        let spike_tip = Point { x: 5.0, y: 8.0 };
+        let points = vec![
+            Point { x: 0.0, y: 0.0 },
+            Point { x: 4.0, y: 0.0 },
+            spike_tip,
+            Point { x: 5.4, y: 0.0 },
+            Point { x: 10.0, y: 0.0 },
+            Point { x: 10.0, y: 0.0 },
+            Point { x: 10.0, y: 5.0 },
+        ];
+
+        // A small angle leaves the spike and the right-angle corner alone
+        let kept = remove_spike_vertices(&points, 2.0);
+        assert!(kept.contains(&spike_tip));
+        assert_eq!(kept.len(), points.len() - 1); // Only the duplicate goes
+
+        let smoothed = remove_spike_vertices(&points, 20.0);
+        assert!(!smoothed.contains(&spike_tip));
+        assert_eq!(smoothed.first(), points.first());
+        assert_eq!(smoothed.last(), points.last());
+        assert!(smoothed.contains(&Point { x: 10.0, y: 0.0 }));
+
+        assert_eq!(remove_spike_vertices(&points, 0.0), points);
+    }
+
+    #[test]
+    fn test_snap_straight_runs() {
+        // Wobbly, slightly tilted top edge turning a corner into a straight right edge
+        let mut points: Vec<Point> = (0..=40)
+            .map(|x| Point {
+                x: x as f32,
+                y: 10.0 + 0.02 * x as f32 + if x % 3 == 0 { 0.6 } else { -0.3 },
+            })
+            .collect();
+        points.extend((1..=20).map(|y| Point {
+            x: 40.0,
+            y: 10.8 + y as f32,
+        }));
+
+        let snapped = snap_straight_runs(&points, 1.0, 8.0, 3.0);
+        assert_eq!(snapped.len(), 3);
+        // The top edge is exactly horizontal and meets the vertical edge at a sharp corner
+        assert_eq!(snapped[0].y, snapped[1].y);
+        assert_eq!(snapped[1].x, snapped[2].x);
+        assert!((snapped[1].x - 40.0).abs() < 0.5);
+
+        // Without axis snapping the fitted tilt is kept
+        let tilted = snap_straight_runs(&points, 1.0, 8.0, 0.0);
+        assert_ne!(tilted[0].y, tilted[1].y);
+
+        // Curves are left alone
+        let arc: Vec<Point> = (0..=20)
+            .map(|i| {
+                let angle = i as f32 * 0.15;
+                Point {
+                    x: 20.0 * angle.cos(),
+                    y: 20.0 * angle.sin(),
+                }
+            })
+            .collect();
+        assert_eq!(snap_straight_runs(&arc, 0.5, 20.0, 3.0), arc);
+    }
+
     #[test]
     fn test_triangle_area() {
         let p1 = Point { x: 0.0, y: 0.0 };
@@ -979,6 +1422,40 @@ mod tests {
         assert!((area - 0.5).abs() < 1e-6);
     }
 
+    #[test]
+    fn test_visvalingam_whyatt_breaks_ties_by_index() {
+        // Path symmetric about x = 1000.3 with two mirrored bumps of equal area
+        let point = |x: f32, y: f32| Point { x: 1000.3 + x, y };
+        let points = vec![
+            point(-3.0, 0.0),
+            point(-2.0, 0.0),
+            point(-1.1, 0.7),
+            point(-0.2, 0.0),
+            point(0.2, 0.0),
+            point(1.1, 0.7),
+            point(2.0, 0.0),
+            point(3.0, 0.0),
+        ];
+        let bump_area = |i: usize| triangle_area(&points[i - 1], &points[i], &points[i + 1]);
+        assert_eq!(bump_area(2), bump_area(5));
+
+        // The shoulders tie and both go; then the two middle points tie and only
+        // the one with the lower index is removed, as the other no longer qualifies
+        let simplified = visvalingam_whyatt_simplify(&points, 0.35);
+        let expected: Vec<Point> = [0, 2, 4, 5, 7].iter().map(|&i| points[i]).collect();
+        assert_eq!(simplified, expected);
+        for _ in 0..10 {
+            assert_eq!(visvalingam_whyatt_simplify(&points, 0.35), simplified);
+        }
+        let ranked = |area: f64, index: usize| TriangleArea { area, index };
+        assert!(ranked(1.0, 2) < ranked(1.0, 5));
+        assert!(ranked(1.0, 2) > ranked(0.5, 7));
+
+        // Removing everything still keeps the endpoints
+        let line = visvalingam_whyatt_simplify(&points, 10.0);
+        assert_eq!(line, vec![points[0], points[7]]);
+    }
+
     #[test]
     fn test_calculate_path_length() {
         let points = vec![
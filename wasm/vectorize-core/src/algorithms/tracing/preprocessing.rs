@@ -5,6 +5,7 @@
 //! across Edge, Centerline, Superpixel, and Dots algorithms.
 
 use image::{DynamicImage, GrayImage, ImageBuffer, Luma, Rgba};
+use std::collections::VecDeque;
 
 
 use crate::algorithms::dots::background::BackgroundConfig;
@@ -31,12 +32,22 @@ pub fn apply_preprocessing(
 ) -> Result<ImageBuffer<Rgba<u8>, Vec<u8>>, VectorizeError> {
     let mut processed = image.clone();
 
+    // Peel off dark frames and light mats before single-color background removal
+    if config.dual_background {
+        processed = apply_dual_background_removal(&processed, algorithm_name);
+    }
+
     // Apply background removal first if enabled (before noise filtering)
     // Background removal should come first to eliminate unwanted areas before smoothing
     if config.enable_background_removal {
         processed = apply_background_removal(&processed, config, algorithm_name)?;
     }
 
+    // Smooth quantization bands into ramps before segmentation sees them
+    if config.deband {
+        processed = apply_debanding(&processed, algorithm_name);
+    }
+
     // Apply noise filtering if enabled (after background removal)
     if config.noise_filtering {
         log::info!("{} noise filtering ENABLED - applying bilateral filter", algorithm_name);
@@ -329,6 +340,248 @@ fn apply_background_removal(
     Ok(result_image)
 }
 
+/// Neighborhood radius for debanding; bands narrower than the window become ramps
+const DEBAND_RADIUS: i32 = 4;
+
+/// Largest luminance step treated as a quantization band rather than an edge
+const DEBAND_MAX_STEP: f32 = 12.0;
+
+/// 4x4 Bayer matrix used to dither the smoothed luminance back to 8 bits
+const DEBAND_DITHER: [[f32; 4]; 4] = [
+    [0.0, 8.0, 2.0, 10.0],
+    [12.0, 4.0, 14.0, 6.0],
+    [3.0, 11.0, 1.0, 9.0],
+    [15.0, 7.0, 13.0, 5.0],
+];
+
+/// Clear pixels whose alpha is below `threshold` to transparent white
+///
+/// Tracing works on color alone, so faint semi-transparent pixels (soft edges,
+/// anti-aliased fringes) would otherwise trace with their full RGB value. Cleared
+/// pixels match what background removal produces. Returns `None` when no pixel
+/// needed changing.
+pub(crate) fn apply_alpha_threshold(
+    image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+    threshold: u8,
+) -> Option<ImageBuffer<Rgba<u8>, Vec<u8>>> {
+    const CLEARED: Rgba<u8> = Rgba([255, 255, 255, 0]);
+
+    if !image.pixels().any(|p| p.0[3] < threshold && *p != CLEARED) {
+        return None;
+    }
+
+    let mut result = image.clone();
+    let mut cleared = 0usize;
+    for pixel in result.pixels_mut() {
+        if pixel.0[3] < threshold {
+            *pixel = CLEARED;
+            cleared += 1;
+        }
+    }
+    log::debug!("Alpha threshold {threshold}: cleared {cleared} semi-transparent pixels");
+    Some(result)
+}
+
+/// Reduce luminance banding from compressed gradients
+///
+/// Each pixel's luminance is averaged with neighbors whose luminance lies
+/// within `DEBAND_MAX_STEP`, so small quantization steps blend into a ramp
+/// while real edges (larger steps) are left alone. The luminance change is
+/// added to every color channel with ordered dithering to avoid re-banding
+/// on rounding. Alpha is unchanged.
+pub(crate) fn apply_debanding(
+    image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+    algorithm_name: &str,
+) -> ImageBuffer<Rgba<u8>, Vec<u8>> {
+    let (width, height) = image.dimensions();
+    let (w, h) = (width as i32, height as i32);
+    let luma: Vec<f32> = image
+        .pixels()
+        .map(|p| 0.299 * p.0[0] as f32 + 0.587 * p.0[1] as f32 + 0.114 * p.0[2] as f32)
+        .collect();
+
+    let mut result = image.clone();
+    for y in 0..h {
+        for x in 0..w {
+            let center = luma[(y * w + x) as usize];
+            let (mut sum, mut count) = (0.0f32, 0u32);
+            for ny in (y - DEBAND_RADIUS).max(0)..=(y + DEBAND_RADIUS).min(h - 1) {
+                for nx in (x - DEBAND_RADIUS).max(0)..=(x + DEBAND_RADIUS).min(w - 1) {
+                    let value = luma[(ny * w + nx) as usize];
+                    if (value - center).abs() <= DEBAND_MAX_STEP {
+                        sum += value;
+                        count += 1;
+                    }
+                }
+            }
+
+            let delta = sum / count as f32 - center;
+            let dither = DEBAND_DITHER[(y % 4) as usize][(x % 4) as usize] / 16.0;
+            let pixel = result.get_pixel_mut(x as u32, y as u32);
+            for channel in &mut pixel.0[..3] {
+                *channel = (*channel as f32 + delta + dither).floor().clamp(0.0, 255.0) as u8;
+            }
+        }
+    }
+
+    log::debug!("{} debanding applied (radius {})", algorithm_name, DEBAND_RADIUS);
+    result
+}
+
+/// Minimum luminance gap between the darkest and lightest clusters for them to
+/// count as separate backgrounds
+const DUAL_BACKGROUND_MIN_CONTRAST: f32 = 64.0;
+
+/// Mask border-connected near-black and near-white regions (e.g. a black frame
+/// around a white mat) as background, keeping the mid-tone content inside
+///
+/// Luminance is split into dark/mid/light clusters. The outermost layer is the
+/// cluster most border pixels belong to, flooded inward from the border; the
+/// second layer is the other extreme cluster, flooded from where the first
+/// layer stops. Dark or light pixels inside the artwork are not reachable and
+/// are kept. Masked pixels are set to opaque white.
+pub(crate) fn apply_dual_background_removal(
+    image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+    algorithm_name: &str,
+) -> ImageBuffer<Rgba<u8>, Vec<u8>> {
+    let (width, height) = image.dimensions();
+    let (w, h) = (width as usize, height as usize);
+    if w == 0 || h == 0 {
+        return image.clone();
+    }
+
+    let luma: Vec<u8> = image
+        .pixels()
+        .map(|p| (0.299 * p[0] as f32 + 0.587 * p[1] as f32 + 0.114 * p[2] as f32) as u8)
+        .collect();
+    let [dark, _, light] = luminance_cluster_centers(&luma);
+    if light - dark < DUAL_BACKGROUND_MIN_CONTRAST {
+        log::debug!("{} dual background: not enough contrast, skipping", algorithm_name);
+        return image.clone();
+    }
+
+    // 0 = dark cluster, 1 = mid, 2 = light
+    let mid = (dark + light) / 2.0;
+    let cluster: Vec<u8> = luma
+        .iter()
+        .map(|&l| {
+            let l = l as f32;
+            if (l - dark).abs() <= (l - mid).abs() {
+                0
+            } else if (l - light).abs() <= (l - mid).abs() {
+                2
+            } else {
+                1
+            }
+        })
+        .collect();
+
+    let border: Vec<usize> = (0..w)
+        .flat_map(|x| [x, (h - 1) * w + x])
+        .chain((0..h).flat_map(|y| [y * w, y * w + w - 1]))
+        .collect();
+    let border_dark = border.iter().filter(|&&i| cluster[i] == 0).count();
+    let border_light = border.iter().filter(|&&i| cluster[i] == 2).count();
+    let (outer, inner) = if border_dark >= border_light { (0, 2) } else { (2, 0) };
+
+    let mut mask = vec![false; w * h];
+    flood_cluster(&cluster, &mut mask, w, h, outer, border.iter().copied());
+
+    // Second layer starts wherever the inner cluster touches the first layer or the border
+    let seeds: Vec<usize> = (0..w * h)
+        .filter(|&i| cluster[i] == inner && !mask[i])
+        .filter(|&i| {
+            let (x, y) = (i % w, i / w);
+            x == 0
+                || y == 0
+                || x == w - 1
+                || y == h - 1
+                || mask[i - 1]
+                || mask[i + 1]
+                || mask[i - w]
+                || mask[i + w]
+        })
+        .collect();
+    flood_cluster(&cluster, &mut mask, w, h, inner, seeds.into_iter());
+
+    let mut result = image.clone();
+    for (pixel, _) in result.pixels_mut().zip(&mask).filter(|(_, &m)| m) {
+        *pixel = Rgba([255, 255, 255, 255]);
+    }
+
+    log::debug!(
+        "{} dual background removal masked {} of {} pixels (dark {:.0}, light {:.0})",
+        algorithm_name,
+        mask.iter().filter(|&&m| m).count(),
+        w * h,
+        dark,
+        light
+    );
+    result
+}
+
+/// Three luminance cluster centers (dark, mid, light) via 1-D k-means on the histogram
+fn luminance_cluster_centers(luma: &[u8]) -> [f32; 3] {
+    let mut histogram = [0u64; 256];
+    for &l in luma {
+        histogram[l as usize] += 1;
+    }
+
+    let mut centers = [0.0f32, 128.0, 255.0];
+    for _ in 0..20 {
+        let mut sums = [0.0f64; 3];
+        let mut counts = [0u64; 3];
+        for (value, &count) in histogram.iter().enumerate() {
+            let v = value as f32;
+            let nearest = (0..3)
+                .min_by(|&a, &b| (v - centers[a]).abs().total_cmp(&(v - centers[b]).abs()))
+                .unwrap_or(1);
+            sums[nearest] += value as f64 * count as f64;
+            counts[nearest] += count;
+        }
+        for k in 0..3 {
+            if counts[k] > 0 {
+                centers[k] = (sums[k] / counts[k] as f64) as f32;
+            }
+        }
+    }
+    centers
+}
+
+/// 4-connected flood fill over pixels of `target` cluster, starting from `seeds`
+fn flood_cluster(
+    cluster: &[u8],
+    mask: &mut [bool],
+    w: usize,
+    h: usize,
+    target: u8,
+    seeds: impl Iterator<Item = usize>,
+) {
+    let mut queue: VecDeque<usize> = VecDeque::new();
+    for i in seeds {
+        if cluster[i] == target && !mask[i] {
+            mask[i] = true;
+            queue.push_back(i);
+        }
+    }
+
+    while let Some(i) = queue.pop_front() {
+        let (x, y) = (i % w, i / w);
+        let neighbors = [
+            (x > 0).then(|| i - 1),
+            (x + 1 < w).then(|| i + 1),
+            (y > 0).then(|| i - w),
+            (y + 1 < h).then(|| i + w),
+        ];
+        for n in neighbors.into_iter().flatten() {
+            if cluster[n] == target && !mask[n] {
+                mask[n] = true;
+                queue.push_back(n);
+            }
+        }
+    }
+}
+
 /// Apply OTSU-based background removal
 fn apply_otsu_background_removal(
     image: &mut ImageBuffer<Rgba<u8>, Vec<u8>>,
@@ -527,6 +780,66 @@ mod tests {
         assert_eq!(processed.dimensions(), image.dimensions());
     }
 
+    /// Black frame (8px), white mat (8px) and mid-tone artwork with dark ink lines
+    fn matted_scan() -> ImageBuffer<Rgba<u8>, Vec<u8>> {
+        ImageBuffer::from_fn(64, 64, |x, y| {
+            let ring = x.min(y).min(63 - x).min(63 - y);
+            if ring < 8 {
+                Rgba([12, 12, 12, 255])
+            } else if ring < 16 {
+                Rgba([245, 242, 236, 255])
+            } else if x == 32 || y == 32 {
+                Rgba([15, 15, 15, 255])
+            } else {
+                Rgba([120, 140, 110, 255])
+            }
+        })
+    }
+
+    #[test]
+    fn test_dual_background_masks_frame_and_mat() {
+        let image = matted_scan();
+        let config = TraceLowConfig {
+            dual_background: true,
+            ..Default::default()
+        };
+
+        let processed = apply_preprocessing(&image, &config, "test").unwrap();
+        let white = Rgba([255, 255, 255, 255]);
+        assert_eq!(*processed.get_pixel(2, 30), white); // frame
+        assert_eq!(*processed.get_pixel(12, 30), white); // mat
+        assert_eq!(processed.get_pixel(20, 20), image.get_pixel(20, 20)); // artwork
+        assert_eq!(processed.get_pixel(32, 40), image.get_pixel(32, 40)); // ink inside artwork
+    }
+
+    #[test]
+    fn test_debanding_smooths_bands_but_keeps_edges() {
+        // Sky gradient quantized into 8px bands of 6 levels, next to a hard edge
+        let image = ImageBuffer::from_fn(96, 16, |x, _| {
+            let value = if x < 64 { 100 + (x / 8) * 6 } else { 250 };
+            Rgba([value as u8, value as u8, value as u8, 255])
+        });
+        let config = TraceLowConfig {
+            deband: true,
+            ..Default::default()
+        };
+
+        let processed = apply_preprocessing(&image, &config, "test").unwrap();
+        let row_luma = |img: &ImageBuffer<Rgba<u8>, Vec<u8>>, x: u32| -> f32 {
+            (0..16).map(|y| img.get_pixel(x, y).0[0] as f32).sum::<f32>() / 16.0
+        };
+        let max_step = |img: &ImageBuffer<Rgba<u8>, Vec<u8>>| {
+            (8..56)
+                .map(|x| (row_luma(img, x + 1) - row_luma(img, x)).abs())
+                .fold(0.0f32, f32::max)
+        };
+
+        assert_eq!(max_step(&image), 6.0);
+        assert!(max_step(&processed) < 2.0, "bands should blend into a ramp");
+        assert!(row_luma(&processed, 64) - row_luma(&processed, 63) > 100.0);
+        assert_eq!(processed.get_pixel(80, 8), image.get_pixel(80, 8));
+    }
+
     #[test]
     fn test_noise_filtering_disabled() {
         let image = ImageBuffer::from_fn(50, 50, |x, y| {
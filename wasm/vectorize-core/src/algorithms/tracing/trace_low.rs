@@ -9,23 +9,36 @@
 //! All algorithms are controlled by a single detail parameter (0..1) that maps
 //! to appropriate thresholds for each backend.
 
+use crate::algorithms::centerline::preprocessing::remove_small_components;
 use crate::algorithms::centerline::{CenterlineAlgorithm, DistanceTransformCenterlineAlgorithm};
-use crate::algorithms::dots::background::{rgba_to_lab, BackgroundConfig, LabColor};
-use crate::algorithms::dots::dots::{generate_dots_from_image, DotConfig};
-use crate::algorithms::dots::svg_dots::dots_to_svg_paths;
+use crate::algorithms::dots::background::{lab_to_rgba, rgba_to_lab, BackgroundConfig, LabColor};
+use crate::algorithms::dots::dots::{
+    apply_grayscale_colors, generate_dots_from_image, DotColorMode, DotConfig,
+};
+use crate::algorithms::dots::halftone::{generate_halftone_dots, HalftoneConfig};
+use crate::algorithms::dots::svg_dots::{dots_to_dash_paths, dots_to_svg_paths};
 use crate::algorithms::visual::color_processing::PaletteMethod;
 use crate::algorithms::edges::edges::{
     apply_nms, compute_fdog, hysteresis_threshold, FdogConfig, NmsConfig,
 };
 use crate::algorithms::edges::etf::{compute_etf, EtfConfig};
+use crate::algorithms::edges::text_detection::{detect_text_regions, TextRegion};
 use crate::algorithms::edges::gradients::GradientConfig;
+use crate::algorithms::lowpoly::{generate_lowpoly_paths, LowPolyConfig};
 use crate::algorithms::tracing::fit::{fit_beziers, FitConfig};
-use crate::algorithms::tracing::path_utils::calculate_douglas_peucker_epsilon;
+use crate::algorithms::tracing::path_utils::{
+    calculate_douglas_peucker_epsilon, douglas_peucker_by, douglas_peucker_within_depth,
+    remove_spike_vertices, snap_straight_runs, visvalingam_whyatt_simplify,
+};
 use crate::algorithms::tracing::trace::{trace_polylines, TraceConfig};
-use crate::algorithms::tracing::preprocessing::apply_preprocessing;
-use crate::algorithms::{Point, SvgElementType, SvgPath};
+use crate::algorithms::tracing::preprocessing::{
+    apply_alpha_threshold, apply_dual_background_removal, apply_preprocessing,
+};
+use crate::algorithms::{Point, RegionMetadata, SvgElementType, SvgPath};
 use crate::error::VectorizeError;
-use crate::execution::{execute_parallel, execute_parallel_filter_map};
+use crate::execution::{
+    execute_parallel, execute_parallel_chunks, execute_parallel_filter_map, should_use_parallel,
+};
 use crate::svg_gradients::{ColorStop, GradientDefinition};
 use crate::utils::Instant;
 use image::{GrayImage, ImageBuffer, Luma, Rgba};
@@ -141,6 +154,24 @@ pub enum TraceBackend {
     Superpixel,
     /// Dot-based pixel mapping with gradient analysis (stippling/pointillism effects)
     Dots,
+    /// Delaunay triangulation with flat-colored facets (low-poly art)
+    LowPoly,
+}
+
+impl TraceBackend {
+    /// Processing time budget in milliseconds used when none is set explicitly
+    ///
+    /// SLIC and skeletonization are far slower than edge tracing, so each
+    /// backend gets a budget matching its realistic worst case.
+    pub fn default_time_budget_ms(self) -> u64 {
+        match self {
+            TraceBackend::Edge => 60_000,
+            TraceBackend::Centerline => 120_000,
+            TraceBackend::Superpixel => 180_000,
+            TraceBackend::Dots => 45_000,
+            TraceBackend::LowPoly => 90_000,
+        }
+    }
 }
 
 /// Processing directions for multi-directional edge detection
@@ -156,6 +187,23 @@ pub enum ProcessingDirection {
     DiagonalNE,
 }
 
+/// How edges found again by several directional passes are combined
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "generate-ts", derive(TS))]
+#[cfg_attr(
+    feature = "generate-ts",
+    ts(export, export_to = "../../../frontend/src/lib/types/generated/")
+)]
+pub enum MultipassMerge {
+    /// Keep the first detection of each edge alongside every distinct edge
+    #[default]
+    Union,
+    /// Average overlapping detections into one path, weighted by edge response
+    WeightedAverage,
+    /// Keep whichever overlapping detection has the strongest edge response
+    MaxResponse,
+}
+
 /// Available background removal algorithms for pre-processing
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[cfg_attr(feature = "generate-ts", derive(TS))]
@@ -222,6 +270,21 @@ pub struct TraceLowConfig {
     pub enable_diagonal_pass: bool,
     /// Threshold for directional strength - skip pass if not beneficial (0.0-1.0)
     pub directional_strength_threshold: f32,
+    /// Margin around the threshold a directional benefit must cross before a pass
+    /// switches on or off relative to `previous_directional_passes`, keeping pass
+    /// selection stable across similar frames (0.0-0.5, default: 0.0)
+    #[serde(default)]
+    pub directional_hysteresis: f32,
+    /// Directional passes selected for the previous frame: they keep running until
+    /// their benefit falls below the threshold minus `directional_hysteresis`, while
+    /// other passes need the threshold plus the margin. `None` compares every pass
+    /// against the plain threshold (default: None)
+    #[serde(default)]
+    pub previous_directional_passes: Option<Vec<ProcessingDirection>>,
+    /// How overlapping edges from the base and directional passes are combined
+    /// (default: Union)
+    #[serde(default)]
+    pub multipass_merge: MultipassMerge,
     /// Maximum total processing time budget in milliseconds
     #[cfg_attr(feature = "generate-ts", ts(type = "number"))]
     pub max_processing_time_ms: u64,
@@ -261,6 +324,18 @@ pub struct TraceLowConfig {
     pub fit_max_err: f32,
     /// Corner splitting angle threshold in degrees (default: 32.0)
     pub fit_split_angle: f32,
+    /// Drop interior vertices whose angle is below this many degrees before
+    /// fitting, removing spikes where a trace doubles back (0 = disabled, default: 2.0)
+    #[serde(default = "default_min_vertex_angle_deg")]
+    pub min_vertex_angle_deg: f32,
+    /// Deepest Douglas-Peucker split before a path is simplified with the
+    /// Visvalingam-Whyatt algorithm instead (default: 512)
+    #[serde(default = "default_max_simplify_depth")]
+    pub max_simplify_depth: u32,
+    /// Edge backend: replace near-straight runs of traced edges with exactly straight
+    /// segments, snapped horizontal/vertical when nearly axis-aligned (default: false)
+    #[serde(default)]
+    pub snap_straight_lines: bool,
     // Dot-specific configuration fields
     /// Dot density threshold - minimum gradient strength required to place a dot (0.0 to 1.0)
     pub dot_density_threshold: f32,
@@ -270,10 +345,21 @@ pub struct TraceLowConfig {
     pub dot_max_radius: f32,
     /// Whether to preserve original pixel colors in dot output
     pub dot_preserve_colors: bool,
+    /// Dot fill coloring (Black, Grayscale, SourceColor); overrides
+    /// `dot_preserve_colors` when set (default: None)
+    #[serde(default)]
+    pub dot_color_mode: Option<crate::algorithms::dots::dots::DotColorMode>,
     /// Whether to use adaptive sizing based on local variance
     pub dot_adaptive_sizing: bool,
     /// Background color tolerance for background detection (0.0 to 1.0)
     pub dot_background_tolerance: f32,
+    /// Exclude detected background pixels from dot placement entirely (default: false)
+    #[serde(default)]
+    pub dots_skip_background: bool,
+    /// Merge runs of nearly-collinear dots into short dashes, reducing pen-up/pen-down
+    /// cycles on pen plotters (default: false)
+    #[serde(default)]
+    pub dots_merge_to_dashes: bool,
     /// Enable gradient-based sizing for dot scaling based on local image gradients (default: false)
     pub dot_gradient_based_sizing: bool,
     /// Amount of random variation in dot sizes (0.0 = no variation, 1.0 = maximum variation, default: 0.3)
@@ -282,6 +368,10 @@ pub struct TraceLowConfig {
     pub dot_shape: crate::algorithms::dots::dots::DotShape,
     /// Grid pattern for dot placement (Grid, Hexagonal, Random)
     pub dot_grid_pattern: crate::algorithms::dots::dots::GridPattern,
+    // Low-poly specific configuration
+    /// Approximate number of triangle vertices for the low-poly backend (16-5000, default: 1000)
+    #[serde(default = "default_lowpoly_vertex_count")]
+    pub lowpoly_vertex_count: u32,
     /// Enable adaptive thresholding for centerline backend (default: true)
     pub enable_adaptive_threshold: bool,
     /// Window size for adaptive thresholding (default: 31, auto-computed from detail level: 35px at detail=0.1 to 25px at detail=1.0)
@@ -300,6 +390,15 @@ pub struct TraceLowConfig {
     pub douglas_peucker_epsilon: f32,
     /// Enable high-performance Distance Transform-based centerline algorithm (default: false)
     pub enable_distance_transform_centerline: bool,
+    /// Remove connected components smaller than this many pixels from the binarized image
+    /// before tracing (0 = disabled, default: 0)
+    #[serde(default)]
+    pub prebinary_despeckle_px: u32,
+    /// Per-pixel detail weights matching the input dimensions; bright pixels lower the
+    /// edge backend's Canny thresholds (more detail), dark pixels raise them (default: None)
+    #[serde(skip)]
+    #[cfg_attr(feature = "generate-ts", ts(skip))]
+    pub detail_map: Option<GrayImage>,
     // Superpixel-specific configuration fields
     /// Number of superpixels to generate (20-1000, default: computed from detail level)
     pub num_superpixels: u32,
@@ -307,12 +406,33 @@ pub struct TraceLowConfig {
     pub superpixel_compactness: f32,
     /// SLIC iterations for convergence (5-15, default: 10)
     pub superpixel_slic_iterations: u32,
+    /// Stop SLIC early once no cluster center moves more than this many pixels in an
+    /// iteration (0.0 = disabled, default: 0.0)
+    #[serde(default)]
+    pub slic_convergence_threshold: f32,
     /// Superpixel cluster initialization pattern (default: hexagonal)
     pub superpixel_initialization_pattern: SuperpixelInitPattern,
+    /// Seed superpixels more densely in high-gradient, textured areas and more
+    /// sparsely on flat background; overrides the initialization pattern (default: false)
+    #[serde(default)]
+    pub superpixel_saliency_seeding: bool,
+    /// Average superpixel region colors in LAB space and convert back to sRGB, which
+    /// keeps regions spanning gradients from turning muddy (default: false)
+    #[serde(default)]
+    pub region_average_in_lab: bool,
+    /// Blend each superpixel region's color towards similar adjacent regions to reduce
+    /// banding, without merging region shapes (0.0 = off, 1.0 = strongest, default: 0.0)
+    #[serde(default)]
+    pub region_color_smoothing: f32,
     /// Whether to fill superpixel regions with solid color (default: true)
     pub superpixel_fill_regions: bool,
     /// Whether to stroke superpixel region boundaries (default: true)
     pub superpixel_stroke_regions: bool,
+    /// Stroke the region adjacency edge network, drawing each boundary shared by two
+    /// regions once instead of outlining every region separately; sets
+    /// `SvgConfig::shared_region_edges` for superpixel output (default: false)
+    #[serde(default)]
+    pub shared_region_edges: bool,
     /// Whether to simplify superpixel boundaries using Douglas-Peucker (default: true)
     pub superpixel_simplify_boundaries: bool,
     /// Boundary simplification tolerance (0.5-3.0, default: 1.0)
@@ -329,6 +449,9 @@ pub struct TraceLowConfig {
     pub superpixel_merge_threshold: f32,
     /// Enable advanced merging algorithms for improved region quality (default: false)
     pub enable_advanced_merging: bool,
+    /// Fill superpixel regions with a distinct debug palette instead of their colors (default: false)
+    #[serde(default)]
+    pub superpixel_debug_colors: bool,
     // Line tracing color configuration fields
     /// Whether to preserve original pixel colors in line tracing output (edge/centerline backends)
     pub line_preserve_colors: bool,
@@ -362,6 +485,190 @@ pub struct TraceLowConfig {
     pub max_image_size: u32,
     /// SVG coordinate precision in decimal places (0-4, default: 2)
     pub svg_precision: u8,
+    /// Emit deterministic `id` attributes on output paths for CSS/JS targeting (default: false)
+    #[serde(default)]
+    pub emit_path_ids: bool,
+    /// Resample polyline output to evenly spaced points at this interval for stroke animation (default: None)
+    #[serde(default)]
+    pub resample_even_spacing: Option<f32>,
+    /// Fade line paths by edge strength: weak edges get lower `stroke-opacity` (default: false)
+    #[serde(default)]
+    pub edge_strength_opacity: bool,
+    /// Record each path's average source-image color for `data-source-color` attributes (default: false)
+    #[serde(default)]
+    pub emit_source_color: bool,
+    /// Write each superpixel region's pixel centroid and area as `data-centroid` and
+    /// `data-area` attributes (default: false)
+    #[serde(default)]
+    pub emit_region_metadata: bool,
+    /// Group stroked paths into thin/medium/thick `<g>` layers by stroke width for
+    /// multi-pen plotting (default: false)
+    #[serde(default)]
+    pub group_by_thickness: bool,
+    /// Append a legend of swatches listing each output color with its hex value and
+    /// coverage percentage (default: false)
+    #[serde(default)]
+    pub emit_palette_legend: bool,
+    /// Color space for output fill/stroke colors (default: sRGB)
+    #[serde(default)]
+    pub color_space: crate::config::ColorSpace,
+    /// Edge backend: relax thresholds until at least this many paths are produced
+    /// (0 = disabled, default: 0)
+    #[serde(default)]
+    pub guarantee_min_paths: usize,
+    /// Edge backend: when the output is dominated by short noise paths, raise
+    /// thresholds and re-run once (default: false)
+    #[serde(default)]
+    pub auto_denoise_escalate: bool,
+    /// Halo color and width in pixels drawn behind strokes for legibility (default: None)
+    #[serde(default)]
+    pub stroke_halo: Option<(String, f32)>,
+    /// Outline color and width in pixels added to every filled region, e.g. for
+    /// sticker/label output (default: None)
+    #[serde(default)]
+    pub fill_and_stroke: Option<(String, f32)>,
+    /// Expand strokes into filled outlines at their width, e.g. for glyph-like
+    /// icon output (default: false)
+    #[serde(default)]
+    pub stroke_to_outline: bool,
+    /// Cap on gradient definitions emitted when `line_preserve_colors` samples
+    /// gradients; the weakest gradients past the cap fall back to solid colors (default: None)
+    #[serde(default)]
+    pub max_gradients: Option<usize>,
+    /// Split self-intersecting filled region outlines into simple polygons (default: false)
+    #[serde(default)]
+    pub repair_self_intersections: bool,
+    /// Close polyline paths whose end points coincide at `svg_precision` with an
+    /// explicit `Z` (default: false)
+    #[serde(default)]
+    pub auto_close_coincident: bool,
+    /// Keep vertices shared at junctions fixed during simplification so line
+    /// networks stay connected (default: false)
+    #[serde(default)]
+    pub preserve_junction_topology: bool,
+    /// Prepend an `<?xml version="1.0" encoding="UTF-8"?>` declaration to the SVG (default: false)
+    #[serde(default)]
+    pub emit_xml_declaration: bool,
+    /// Declare `pathLength="100"` on every stroke for dash draw-on animations (default: false)
+    #[serde(default)]
+    pub emit_path_length: bool,
+    /// Mirror or rotate the whole output (default: None)
+    #[serde(default)]
+    pub output_transform: Option<crate::config::Transform>,
+    /// Origin of emitted coordinates, e.g. bottom-left for CAD tools; ignored when
+    /// `output_transform` is set (default: top-left)
+    #[serde(default)]
+    pub coordinate_origin: crate::config::CoordinateOrigin,
+    /// Drop or clamp NaN/infinite coordinates during SVG assembly (default: drop)
+    #[serde(default)]
+    pub non_finite_handling: crate::config::NonFiniteHandling,
+    /// Animate strokes drawing on in path order over this many seconds (default: None)
+    #[serde(default)]
+    pub draw_on_animation: Option<f32>,
+    /// Exact stroke width in output pixels, overriding the resolution-relative
+    /// `stroke_px_at_1080p` scaling (default: None)
+    #[serde(default)]
+    pub absolute_stroke_width: Option<f32>,
+    /// Random per-path stroke width variation as a fraction of each width, applied
+    /// reproducibly and independently of hand-drawn effects (0.0-0.5, default: 0.0)
+    #[serde(default)]
+    pub stroke_width_jitter: f32,
+    /// Embed a hash of the source image and configuration as an SVG comment
+    /// for provenance and caching (default: false)
+    #[serde(default)]
+    pub embed_source_hash: bool,
+    /// Place one darkness-sized dot per cell of a rotated grid instead of
+    /// stippling (dots backend, default: false)
+    #[serde(default)]
+    pub halftone_mode: bool,
+    /// Halftone screen angle in degrees (default: 45.0)
+    #[serde(default = "default_halftone_angle")]
+    pub halftone_angle: f32,
+    /// Halftone grid cell size in pixels (default: 8.0)
+    #[serde(default = "default_halftone_cell_px")]
+    pub halftone_cell_px: f32,
+    /// Convert images with an embedded ICC profile (Adobe RGB, ProPhoto) to
+    /// sRGB when decoding (default: true)
+    #[serde(default = "default_apply_icc_profile")]
+    pub apply_icc_profile: bool,
+    /// Largest encoded image, in megapixels, the byte entry points decode; larger
+    /// sources are reduced to `max_image_size` while decoding (default: 400)
+    #[serde(default = "default_max_decode_megapixels")]
+    pub max_decode_megapixels: u32,
+    /// Write SVG width/height in inches at this many source pixels per inch,
+    /// keeping the viewBox in pixels (default: None)
+    #[serde(default)]
+    pub output_dpi: Option<f32>,
+    /// Take `output_dpi` from PNG `pHYs` or JPEG JFIF density metadata when
+    /// decoding, unless it was set explicitly (default: false)
+    #[serde(default)]
+    pub use_embedded_dpi: bool,
+    /// Treat border-connected near-black and near-white regions (frame and mat
+    /// of a scan) as background, keeping mid-tone content (default: false)
+    #[serde(default)]
+    pub dual_background: bool,
+    /// Blend luminance banding from compressed gradients into smooth ramps
+    /// before tracing (default: false)
+    #[serde(default)]
+    pub deband: bool,
+    /// Pixels with alpha below this are treated as empty background by content
+    /// detection and tracing (default: 10)
+    #[serde(default = "default_alpha_threshold")]
+    pub alpha_threshold: u8,
+    /// Images with fewer pixels at or above `alpha_threshold` are treated as empty
+    /// and produce a minimal SVG (default: 10)
+    #[serde(default = "default_min_content_pixels")]
+    pub min_content_pixels: usize,
+    /// Detect lines of text and trace them as centerlines, using the selected
+    /// backend for the rest of the image (default: false)
+    #[serde(default)]
+    pub text_aware: bool,
+}
+
+/// Default vertex budget for the low-poly backend (also used when deserializing older configs)
+fn default_lowpoly_vertex_count() -> u32 {
+    1000
+}
+
+/// Default spike filter angle: only near-reversals are removed
+fn default_min_vertex_angle_deg() -> f32 {
+    2.0
+}
+
+/// Default simplification depth: far beyond real traces, so only pathological
+/// zigzags fall back to Visvalingam-Whyatt
+fn default_max_simplify_depth() -> u32 {
+    512
+}
+
+/// Default alpha cutoff: only nearly invisible pixels are ignored
+fn default_alpha_threshold() -> u8 {
+    10
+}
+
+/// Default content minimum: a handful of stray pixels is not worth tracing
+fn default_min_content_pixels() -> usize {
+    10
+}
+
+/// Default halftone screen angle (classic 45° screen)
+fn default_halftone_angle() -> f32 {
+    45.0
+}
+
+/// Default halftone grid cell size
+fn default_halftone_cell_px() -> f32 {
+    8.0
+}
+
+/// Embedded ICC profiles are honored unless explicitly disabled
+fn default_apply_icc_profile() -> bool {
+    true
+}
+
+/// Default decode limit, enough for a 20000×20000 scan
+fn default_max_decode_megapixels() -> u32 {
+    400
 }
 
 impl Default for TraceLowConfig {
@@ -380,7 +687,10 @@ impl Default for TraceLowConfig {
             enable_reverse_pass: false,
             enable_diagonal_pass: false,
             directional_strength_threshold: 0.3,
-            max_processing_time_ms: 300000, // 5 minute safety timeout (will be overridden by frontend)
+            directional_hysteresis: 0.0, // Plain threshold comparison
+            previous_directional_passes: None, // No previous frame
+            multipass_merge: MultipassMerge::Union, // Keep the first detection of each edge
+            max_processing_time_ms: 300000, // 5 minute safety timeout (ConfigBuilder applies per-backend budgets)
             // ETF/FDoG parameters (disabled by default for compatibility)
             enable_etf_fdog: false,
             etf_radius: 4,
@@ -402,17 +712,24 @@ impl Default for TraceLowConfig {
             fit_lambda_curv: 0.01, // Less restrictive (was 0.02)
             fit_max_err: 2.0,      // Allow more error (was 0.8)
             fit_split_angle: 32.0,
+            min_vertex_angle_deg: default_min_vertex_angle_deg(),
+            max_simplify_depth: default_max_simplify_depth(),
+            snap_straight_lines: false, // Keep traced edges as detected
             // Dot-specific defaults (following DotConfig::default())
             dot_density_threshold: 0.1,
             dot_min_radius: 0.5,
             dot_max_radius: 3.0,
             dot_preserve_colors: true,
+            dot_color_mode: None, // Follow dot_preserve_colors
             dot_adaptive_sizing: true,
             dot_background_tolerance: 0.1,
+            dots_skip_background: false, // Allow faint background dots for texture
+            dots_merge_to_dashes: false, // Keep individual dots by default
             dot_gradient_based_sizing: false,
             dot_size_variation: 0.0, // Default to no size variation (uniform dots)
             dot_shape: crate::algorithms::dots::dots::DotShape::default(),
             dot_grid_pattern: crate::algorithms::dots::dots::GridPattern::default(),
+            lowpoly_vertex_count: default_lowpoly_vertex_count(), // Balanced facet density
             // Adaptive thresholding defaults
             enable_adaptive_threshold: true,
             adaptive_threshold_window_size: 31, // Default value, auto-calculated from detail level if not explicitly set
@@ -424,13 +741,20 @@ impl Default for TraceLowConfig {
             min_branch_length: 12.0, // Will be adjusted based on detail level
             douglas_peucker_epsilon: 1.5, // Will be adjusted based on detail level
             enable_distance_transform_centerline: false, // Default to traditional skeleton approach
+            prebinary_despeckle_px: 0, // Pre-trace despeckle disabled by default
+            detail_map: None, // Uniform detail across the image
             // Superpixel defaults
             num_superpixels: 150, // Default region complexity for balanced detail
             superpixel_compactness: 10.0, // Balanced shape vs color similarity
             superpixel_slic_iterations: 5, // Good quality/performance balance
+            slic_convergence_threshold: 0.0, // Always run the configured iteration count
             superpixel_initialization_pattern: SuperpixelInitPattern::Poisson, // Default to best artifact-reducing pattern
+            superpixel_saliency_seeding: false, // Even seed density
+            region_average_in_lab: false, // Per-channel RGB averaging
+            region_color_smoothing: 0.0, // Keep each region's own average color
             superpixel_fill_regions: true, // Default to filled poster-style look
             superpixel_stroke_regions: true, // Include boundaries for definition
+            shared_region_edges: false, // Outline each region separately
             superpixel_simplify_boundaries: true, // Simplified paths for cleaner output
             superpixel_boundary_epsilon: 1.0, // Moderate simplification
             superpixel_preserve_colors: true, // Default to color for interesting output
@@ -439,6 +763,7 @@ impl Default for TraceLowConfig {
             superpixel_enhance_edges: false, // Advanced feature, disabled by default
             superpixel_merge_threshold: 0.15, // Moderate color merging
             enable_advanced_merging: false, // Advanced features disabled by default
+            superpixel_debug_colors: false, // Debug palette only used for tuning segmentation
             // Line tracing color defaults
             line_preserve_colors: false, // Default to monochrome for backward compatibility
             line_color_sampling: crate::algorithms::ColorSamplingMethod::DominantColor, // Default to simple method
@@ -457,12 +782,50 @@ impl Default for TraceLowConfig {
             // Safety and optimization defaults
             max_image_size: 4096, // 4K maximum dimension before resizing
             svg_precision: 2,     // 2 decimal places for balanced file size/quality
+            emit_path_ids: false, // No path IDs by default to keep output compact
+            resample_even_spacing: None, // Keep the traced point distribution
+            edge_strength_opacity: false, // Uniform opacity unless explicitly requested
+            emit_source_color: false, // No data attributes by default to keep output compact
+            emit_region_metadata: false, // Region statistics are opt-in like source colors
+            group_by_thickness: false, // Keep paths in trace order
+            emit_palette_legend: false, // Artwork only, no annotations
+            color_space: crate::config::ColorSpace::Srgb, // Hex colors for maximum compatibility
+            guarantee_min_paths: 0, // Accept whatever the detail level produces
+            auto_denoise_escalate: false, // Keep the first result even if it is speckled
+            stroke_halo: None, // No underlay behind strokes
+            fill_and_stroke: None, // Fills are drawn without an outline
+            stroke_to_outline: false, // Keep strokes as stroked paths
+            max_gradients: None, // Emit every detected gradient
+            repair_self_intersections: false, // Emit simplified outlines as traced
+            auto_close_coincident: false, // Leave near-closed paths open
+            preserve_junction_topology: false, // Simplify each path independently
+            emit_xml_declaration: false, // Browsers don't need the XML declaration
+            emit_path_length: false, // No animation attributes by default
+            output_transform: None,  // Output in source orientation
+            coordinate_origin: crate::config::CoordinateOrigin::TopLeft, // SVG convention
+            non_finite_handling: crate::config::NonFiniteHandling::Drop, // Remove bad segments
+            draw_on_animation: None, // Static output
+            absolute_stroke_width: None, // Resolution-relative stroke width
+            stroke_width_jitter: 0.0, // Uniform widths
+            embed_source_hash: false, // No provenance comment
+            halftone_mode: false, // Stippled placement by default
+            halftone_angle: default_halftone_angle(),
+            halftone_cell_px: default_halftone_cell_px(),
+            apply_icc_profile: true, // Color-manage wide-gamut uploads
+            max_decode_megapixels: default_max_decode_megapixels(), // Large scans, bounded memory
+            output_dpi: None, // Sizes in pixels
+            use_embedded_dpi: false, // Ignore density metadata
+            dual_background: false, // Only the regular single-color background removal
+            deband: false, // Trace gradients exactly as quantized
+            alpha_threshold: default_alpha_threshold(),
+            min_content_pixels: default_min_content_pixels(),
+            text_aware: false, // Trace text like any other content
         }
     }
 }
 
 /// Global threshold mapping from detail parameter
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ThresholdMapping {
     /// Douglas-Peucker epsilon in pixels
     pub dp_epsilon_px: f32,
@@ -569,8 +932,23 @@ pub fn vectorize_trace_low(
     config: &TraceLowConfig,
     hand_drawn_config: Option<&crate::algorithms::visual::hand_drawn::HandDrawnConfig>,
 ) -> Result<Vec<SvgPath>, VectorizeError> {
+    // Faint semi-transparent pixels are background for every backend
+    let masked = apply_alpha_threshold(image, config.alpha_threshold);
+    let image = masked.as_ref().unwrap_or(image);
+
+    if config.detail_map.is_some() && config.backend != TraceBackend::Edge {
+        log::warn!(
+            "Detail map is only used by the edge backend; ignoring it for {:?}",
+            config.backend
+        );
+    }
+
+    if config.text_aware {
+        return vectorize_trace_low_text_aware(image, config, hand_drawn_config);
+    }
+
     // Check if directional passes are enabled (independent of multipass setting)
-    if config.backend == TraceBackend::Edge
+    let mut paths = if config.backend == TraceBackend::Edge
         && (config.enable_reverse_pass || config.enable_diagonal_pass)
     {
         log::info!(
@@ -588,7 +966,157 @@ pub fn vectorize_trace_low(
         log::info!("⚡ Using single-pass processing");
         // Use single-pass processing (original implementation)
         vectorize_trace_low_single_pass(image, config, hand_drawn_config)
+    }?;
+
+    if config.edge_strength_opacity
+        && matches!(config.backend, TraceBackend::Edge | TraceBackend::Centerline)
+    {
+        apply_edge_strength_opacity(&mut paths, image);
+    }
+    if config.emit_source_color {
+        apply_source_colors(&mut paths, image);
+    }
+
+    Ok(paths)
+}
+
+/// Branch pruning length for text strokes, short enough to keep serifs and dots
+const TEXT_MIN_BRANCH_LENGTH_PX: f32 = 3.0;
+
+/// Trace detected text regions as centerlines and the rest with the selected backend
+///
+/// Text regions are painted over with their surrounding color before the main
+/// trace, then traced on their own with the centerline backend. Text paths are
+/// drawn on top in the average ink color.
+fn vectorize_trace_low_text_aware(
+    image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+    config: &TraceLowConfig,
+    hand_drawn_config: Option<&crate::algorithms::visual::hand_drawn::HandDrawnConfig>,
+) -> Result<Vec<SvgPath>, VectorizeError> {
+    let base_config = TraceLowConfig {
+        text_aware: false,
+        ..config.clone()
+    };
+    let gray = rgba_to_gray(image);
+    let regions = detect_text_regions(&gray);
+    log::info!("📝 Text-aware tracing: {} text regions", regions.len());
+    if regions.is_empty() {
+        return vectorize_trace_low(image, &base_config, hand_drawn_config);
+    }
+
+    let mut rest = image.clone();
+    for region in &regions {
+        let surround = text_region_surround_color(image, region);
+        for y in region.y..region.y + region.height {
+            for x in region.x..region.x + region.width {
+                rest.put_pixel(x, y, surround);
+            }
+        }
+    }
+    let mut paths = vectorize_trace_low(&rest, &base_config, hand_drawn_config)?;
+
+    // The centerline backend skeletonizes bright foreground, so the ink is inverted
+    let in_text = |x: u32, y: u32| regions.iter().any(|region| region.contains(x, y));
+    let text = ImageBuffer::from_fn(image.width(), image.height(), |x, y| {
+        let ink = if in_text(x, y) {
+            255 - gray.get_pixel(x, y).0[0]
+        } else {
+            0
+        };
+        Rgba([ink, ink, ink, 255])
+    });
+    let text_config = TraceLowConfig {
+        backend: TraceBackend::Centerline,
+        enable_adaptive_threshold: false,
+        noise_filtering: false,
+        line_preserve_colors: false,
+        enable_multipass: false,
+        enable_reverse_pass: false,
+        enable_diagonal_pass: false,
+        enable_background_removal: false,
+        dual_background: false,
+        guarantee_min_paths: 0,
+        auto_denoise_escalate: false,
+        min_branch_length: TEXT_MIN_BRANCH_LENGTH_PX,
+        emit_source_color: false,
+        ..base_config
+    };
+    let mut text_paths = vectorize_trace_low(&text, &text_config, hand_drawn_config)?;
+
+    let ink_color = text_ink_color(image, &gray, &regions);
+    for path in &mut text_paths {
+        path.stroke = ink_color.clone();
+    }
+    // Sample the original ink rather than the inverted text image
+    if config.emit_source_color {
+        apply_source_colors(&mut text_paths, image);
+    }
+    log::info!(
+        "Text-aware tracing: {} background paths, {} text centerlines",
+        paths.len(),
+        text_paths.len()
+    );
+    paths.extend(text_paths);
+    Ok(paths)
+}
+
+/// Average color of the one-pixel ring just outside a text region
+fn text_region_surround_color(
+    image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+    region: &TextRegion,
+) -> Rgba<u8> {
+    let (width, height) = image.dimensions();
+    let (x0, y0) = (region.x.saturating_sub(1), region.y.saturating_sub(1));
+    let x1 = (region.x + region.width).min(width - 1);
+    let y1 = (region.y + region.height).min(height - 1);
+
+    let mut sum = [0u64; 4];
+    let mut count = 0u64;
+    for y in y0..=y1 {
+        for x in x0..=x1 {
+            if region.contains(x, y) {
+                continue;
+            }
+            for (total, &channel) in sum.iter_mut().zip(&image.get_pixel(x, y).0) {
+                *total += channel as u64;
+            }
+            count += 1;
+        }
+    }
+    if count == 0 {
+        return Rgba([255, 255, 255, 255]);
+    }
+    Rgba(sum.map(|total| (total / count) as u8))
+}
+
+/// Hex color of the text ink: the average of pixels darker than their region's mean
+fn text_ink_color(
+    image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+    gray: &GrayImage,
+    regions: &[TextRegion],
+) -> String {
+    let mut sum = [0u64; 3];
+    let mut count = 0u64;
+    for region in regions {
+        let pixels = || {
+            (region.y..region.y + region.height)
+                .flat_map(move |y| (region.x..region.x + region.width).map(move |x| (x, y)))
+        };
+        let area = (region.width * region.height).max(1) as u64;
+        let mean = pixels()
+            .map(|(x, y)| gray.get_pixel(x, y).0[0] as u64)
+            .sum::<u64>()
+            / area;
+        for (x, y) in pixels().filter(|&(x, y)| (gray.get_pixel(x, y).0[0] as u64) < mean) {
+            let [r, g, b, _] = image.get_pixel(x, y).0;
+            sum[0] += r as u64;
+            sum[1] += g as u64;
+            sum[2] += b as u64;
+            count += 1;
+        }
     }
+    let [r, g, b] = sum.map(|total| (total / count.max(1)) as u8);
+    format!("#{r:02x}{g:02x}{b:02x}")
 }
 
 /// Single-pass trace-low vectorization (original implementation)
@@ -607,10 +1135,11 @@ pub fn vectorize_trace_low_single_pass(
     log::debug!("Threshold mapping: {thresholds:?}");
 
     let paths = match config.backend {
-        TraceBackend::Edge => trace_edge(image, &thresholds, config),
+        TraceBackend::Edge => trace_edge_with_denoise_escalation(image, &thresholds, config),
         TraceBackend::Centerline => trace_centerline(image, &thresholds, config),
         TraceBackend::Superpixel => trace_superpixel(image, &thresholds, config),
         TraceBackend::Dots => trace_dots(image, &thresholds, config),
+        TraceBackend::LowPoly => trace_lowpoly(image, &thresholds, config),
     }?;
 
     // Apply artistic enhancements if hand-drawn effects are configured
@@ -866,6 +1395,9 @@ pub fn vectorize_trace_low_directional(
     let remaining_budget_ms = budget.total_budget_ms.saturating_sub(budget.consumed_ms);
     budget.pass_priority =
         schedule_directional_passes(&directional_analysis, remaining_budget_ms, config);
+    crate::telemetry::record(|record| {
+        record.resolved.directional_passes = Some(budget.pass_priority.clone());
+    });
 
     if budget.pass_priority.is_empty() {
         log::info!("No beneficial directional passes identified");
@@ -914,8 +1446,13 @@ pub fn vectorize_trace_low_directional(
     log::info!("Phase 4: Merging directional results");
     let phase_start = Instant::now();
     let base_path_count = base_paths.len();
-    let final_paths =
-        merge_directional_results(base_paths, all_directional_paths, config, hand_drawn_config);
+    let final_paths = merge_directional_results(
+        image,
+        base_paths,
+        all_directional_paths,
+        config,
+        hand_drawn_config,
+    );
     let merge_time = phase_start.elapsed();
 
     let total_time = total_start.elapsed();
@@ -936,6 +1473,181 @@ pub fn vectorize_trace_low_directional(
     Ok(final_paths)
 }
 
+/// Factor applied to edge thresholds on each `guarantee_min_paths` retry
+const MIN_PATHS_RELAXATION: f32 = 0.6;
+
+/// Lowest Canny high threshold reached while relaxing for `guarantee_min_paths`
+const MIN_PATHS_CANNY_HIGH_FLOOR: f32 = 0.02;
+
+/// Shortest stroke kept while relaxing for `guarantee_min_paths`
+const MIN_PATHS_STROKE_LENGTH_FLOOR: f32 = 4.0;
+
+/// Edge backend that relaxes thresholds until `config.guarantee_min_paths` is met
+///
+/// Canny thresholds and the minimum stroke length are lowered step by step; the
+/// search stops once enough paths are found or both hit their floors.
+fn trace_edge_with_min_paths(
+    image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+    thresholds: &ThresholdMapping,
+    config: &TraceLowConfig,
+) -> Result<Vec<SvgPath>, VectorizeError> {
+    let mut paths = trace_edge(image, thresholds, config)?;
+    let mut relaxed = thresholds.clone();
+
+    while paths.len() < config.guarantee_min_paths {
+        if relaxed.canny_high_threshold <= MIN_PATHS_CANNY_HIGH_FLOOR
+            && relaxed.min_stroke_length_px <= MIN_PATHS_STROKE_LENGTH_FLOOR
+        {
+            log::warn!(
+                "Edge thresholds bottomed out with {} of {} guaranteed paths",
+                paths.len(),
+                config.guarantee_min_paths
+            );
+            break;
+        }
+
+        relaxed.canny_high_threshold = (relaxed.canny_high_threshold * MIN_PATHS_RELAXATION)
+            .max(MIN_PATHS_CANNY_HIGH_FLOOR);
+        relaxed.canny_low_threshold = 0.4 * relaxed.canny_high_threshold;
+        relaxed.min_stroke_length_px = (relaxed.min_stroke_length_px * MIN_PATHS_RELAXATION)
+            .max(MIN_PATHS_STROKE_LENGTH_FLOOR);
+
+        log::info!(
+            "Only {} of {} guaranteed paths; retrying edge backend with canny_high={:.3}, min_stroke={:.1}px",
+            paths.len(),
+            config.guarantee_min_paths,
+            relaxed.canny_high_threshold,
+            relaxed.min_stroke_length_px
+        );
+        paths = trace_edge(image, &relaxed, config)?;
+    }
+
+    Ok(paths)
+}
+
+/// Largest RMS distance of linked edge pixels from a line still treated as straight
+const STRAIGHT_LINE_MAX_RESIDUAL_PX: f32 = 1.0;
+
+/// Shortest edge run replaced by an exactly straight segment
+const STRAIGHT_LINE_MIN_LENGTH_PX: f32 = 16.0;
+
+/// Straight runs within this angle of horizontal or vertical are snapped to the axis
+const STRAIGHT_LINE_AXIS_SNAP_DEG: f32 = 3.0;
+
+/// Replace near-straight runs of linked edge polylines with exactly straight
+/// segments when `config.snap_straight_lines` is set
+///
+/// Runs on the pixel-accurate polylines before simplification, so the residual
+/// test sees the raw edge wobble rather than simplification error.
+fn snap_straight_polylines(polylines: Vec<Vec<Point>>, config: &TraceLowConfig) -> Vec<Vec<Point>> {
+    if !config.snap_straight_lines {
+        return polylines;
+    }
+    polylines
+        .iter()
+        .map(|polyline| {
+            snap_straight_runs(
+                polyline,
+                STRAIGHT_LINE_MAX_RESIDUAL_PX,
+                STRAIGHT_LINE_MIN_LENGTH_PX,
+                STRAIGHT_LINE_AXIS_SNAP_DEG,
+            )
+        })
+        .collect()
+}
+
+/// Fewest paths before an edge result can count as noise dominated
+const NOISE_DOMINATED_MIN_PATHS: usize = 40;
+
+/// Mean path length, as a multiple of the minimum stroke length, below which
+/// an edge result counts as noise dominated
+const NOISE_DOMINATED_LENGTH_RATIO: f32 = 3.0;
+
+/// Factor applied to edge thresholds when escalating past a noisy result
+const NOISE_ESCALATION_FACTOR: f32 = 2.0;
+
+/// Highest Canny high threshold reached while escalating past noise
+const NOISE_ESCALATION_CANNY_HIGH_CEILING: f32 = 0.9;
+
+/// Whether edge output is mostly short specks: many paths with a mean length
+/// close to the minimum stroke length
+fn is_noise_dominated(paths: &[SvgPath], min_stroke_length_px: f32) -> bool {
+    if paths.len() < NOISE_DOMINATED_MIN_PATHS {
+        return false;
+    }
+    let total_length: f32 = paths
+        .iter()
+        .map(|p| crate::svg::path_data_length(&p.data).unwrap_or(0.0))
+        .sum();
+    let mean_length = total_length / paths.len() as f32;
+    mean_length < NOISE_DOMINATED_LENGTH_RATIO * min_stroke_length_px
+}
+
+/// Edge backend that re-runs once with raised thresholds when
+/// `config.auto_denoise_escalate` is set and the first result is noise dominated
+fn trace_edge_with_denoise_escalation(
+    image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+    thresholds: &ThresholdMapping,
+    config: &TraceLowConfig,
+) -> Result<Vec<SvgPath>, VectorizeError> {
+    let paths = trace_edge_with_min_paths(image, thresholds, config)?;
+    escalate_if_noise_dominated(paths, thresholds, config, |escalated| {
+        trace_edge_with_min_paths(image, escalated, config)
+    })
+}
+
+/// Re-run `retrace` once with raised thresholds when `config.auto_denoise_escalate`
+/// is set and `paths` are noise dominated, counting the escalation in telemetry
+fn escalate_if_noise_dominated(
+    paths: Vec<SvgPath>,
+    thresholds: &ThresholdMapping,
+    config: &TraceLowConfig,
+    retrace: impl FnOnce(&ThresholdMapping) -> Result<Vec<SvgPath>, VectorizeError>,
+) -> Result<Vec<SvgPath>, VectorizeError> {
+    if !config.auto_denoise_escalate || !is_noise_dominated(&paths, thresholds.min_stroke_length_px)
+    {
+        return Ok(paths);
+    }
+
+    let mut escalated = thresholds.clone();
+    escalated.canny_high_threshold = (escalated.canny_high_threshold * NOISE_ESCALATION_FACTOR)
+        .min(NOISE_ESCALATION_CANNY_HIGH_CEILING);
+    escalated.canny_low_threshold = 0.4 * escalated.canny_high_threshold;
+    escalated.min_stroke_length_px *= NOISE_ESCALATION_FACTOR;
+
+    log::info!(
+        "Edge output dominated by noise ({} short paths); re-running with canny_high {:.3} -> {:.3}, min_stroke {:.1}px -> {:.1}px",
+        paths.len(),
+        thresholds.canny_high_threshold,
+        escalated.canny_high_threshold,
+        thresholds.min_stroke_length_px,
+        escalated.min_stroke_length_px
+    );
+    crate::telemetry::record(|record| record.guards.denoise_escalations += 1);
+    retrace(&escalated)
+}
+
+/// Reject a detail map whose size differs from the image being traced
+fn validate_detail_map(
+    image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+    config: &TraceLowConfig,
+) -> Result<(), VectorizeError> {
+    match &config.detail_map {
+        Some(map) if map.dimensions() != image.dimensions() => {
+            Err(VectorizeError::InvalidDimensions {
+                width: map.width(),
+                height: map.height(),
+                details: format!(
+                    "Detail map must match the {}x{} input image",
+                    image.width(),
+                    image.height()
+                ),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
 /// Edge backend: Canny edge detection + contour following with performance profiling
 fn trace_edge(
     image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
@@ -952,8 +1664,18 @@ fn trace_edge(
             details: "Image must have non-zero dimensions".to_string(),
         });
     }
+    validate_detail_map(image, config)?;
     let total_start = Instant::now();
 
+    // The edge backend preprocesses inline rather than via apply_preprocessing
+    let dual_masked;
+    let image = if config.dual_background {
+        dual_masked = apply_dual_background_removal(image, "edge");
+        &dual_masked
+    } else {
+        image
+    };
+
     // Convert to grayscale with optional color preservation
     let phase_start = Instant::now();
     let (gray, color_map) = rgba_to_gray_with_colors(image, config.line_preserve_colors);
@@ -1081,12 +1803,18 @@ fn trace_edge(
     } else {
         // Traditional Canny edge detection
         log::debug!("Using traditional Canny edge detection");
-        canny_edge_detection(
+        canny_edge_detection_with_detail_map(
             &blurred,
             thresholds.canny_low_threshold,
             thresholds.canny_high_threshold,
+            config.detail_map.as_ref(),
         )
     };
+    let edges = if config.prebinary_despeckle_px > 0 {
+        remove_small_components(&edges, config.prebinary_despeckle_px)
+    } else {
+        edges
+    };
     let edge_time = phase_start.elapsed();
     log::debug!(
         "Edge detection: {:.3}ms ({})",
@@ -1142,7 +1870,8 @@ fn trace_edge(
             // Fit Bézier curves to each polyline
             let mut all_curves = Vec::new();
             for polyline in &traced_polylines {
-                let curves = fit_beziers(polyline, &fit_config);
+                let polyline = remove_spike_vertices(polyline, config.min_vertex_angle_deg);
+                let curves = fit_beziers(&polyline, &fit_config);
                 all_curves.extend(curves);
             }
 
@@ -1216,8 +1945,17 @@ fn trace_edge(
     } else {
         // Traditional polyline processing with Douglas-Peucker simplification
         let polyline_count = polylines.len();
-        let simplified_polylines = execute_parallel_filter_map(polylines, |polyline| {
-            let simplified = douglas_peucker_simplify(&polyline, thresholds.dp_epsilon_px);
+        let polylines = snap_straight_polylines(polylines, config);
+        let fixed_vertices = junction_vertex_indices(&polylines, config.preserve_junction_topology);
+        let polylines = polylines.into_iter().zip(fixed_vertices);
+        let simplified_polylines = execute_parallel_filter_map(polylines, |(polyline, fixed)| {
+            let simplified = simplify_with_fixed_vertices(
+                &polyline,
+                thresholds.dp_epsilon_px,
+                &fixed,
+                config.max_simplify_depth,
+            );
+            let simplified = remove_spike_vertices(&simplified, config.min_vertex_angle_deg);
             let length = calculate_polyline_length(&simplified);
 
             // Use more lenient length filtering for flow-traced polylines
@@ -1292,12 +2030,15 @@ fn trace_edge(
     Ok(svg_paths)
 }
 
-/// Apply background removal preprocessing to image
-fn apply_background_removal(
+/// Detect the background mask used by background removal (`true` = background pixel)
+///
+/// Row-major, one entry per pixel. Uses the same detection settings as the
+/// background removal pre-processing step so callers can preview what it will affect.
+pub fn detect_background_removal_mask(
     image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
     config: &TraceLowConfig,
-) -> Result<ImageBuffer<Rgba<u8>, Vec<u8>>, VectorizeError> {
-    use crate::algorithms::dots::background::{detect_background_advanced, BackgroundConfig};
+) -> Vec<bool> {
+    use crate::algorithms::dots::background::detect_background_advanced;
 
     // Create background detection config
     let bg_config = BackgroundConfig {
@@ -1309,8 +2050,16 @@ fn apply_background_removal(
         random_seed: 42,
     };
 
+    detect_background_advanced(image, &bg_config)
+}
+
+/// Apply background removal preprocessing to image
+fn apply_background_removal(
+    image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+    config: &TraceLowConfig,
+) -> Result<ImageBuffer<Rgba<u8>, Vec<u8>>, VectorizeError> {
     // Detect background mask
-    let background_mask = detect_background_advanced(image, &bg_config);
+    let background_mask = detect_background_removal_mask(image, config);
 
     // Apply background removal based on algorithm
     let mut result_image = image.clone();
@@ -1969,6 +2718,11 @@ fn trace_centerline_skeleton_based(
     } else {
         binary
     };
+    let processed_binary = if config.prebinary_despeckle_px > 0 {
+        remove_small_components(&processed_binary, config.prebinary_despeckle_px)
+    } else {
+        processed_binary
+    };
     let morphology_time = phase_start.elapsed();
 
     // Health metrics: foreground pixels after morphology
@@ -2483,20 +3237,37 @@ fn trace_superpixel(
 
     // 2. Initialize SLIC superpixel segmentation
     let phase_start = Instant::now();
-    let superpixel_labels = slic_segmentation(
+    let (mut superpixel_labels, slic_iterations_used) = slic_segmentation(
         &lab_image,
         width,
         height,
         superpixel_count,
         superpixel_compactness,
         config.superpixel_slic_iterations as usize, // Use configured iterations
-        config.superpixel_initialization_pattern,   // Pass the pattern
+        config.slic_convergence_threshold,
+        config.superpixel_initialization_pattern, // Pass the pattern
+        config.superpixel_saliency_seeding,
+    );
+    log::debug!(
+        "SLIC segmentation: {} of {} iterations in {:?}",
+        slic_iterations_used,
+        config.superpixel_slic_iterations,
+        phase_start.elapsed()
     );
-    log::debug!("SLIC segmentation: {:?}", phase_start.elapsed());
+    crate::telemetry::record(|record| {
+        record.resolved.slic_iters = Some(config.superpixel_slic_iterations);
+        record.resolved.slic_iters_used = Some(slic_iterations_used as u32);
+    });
 
     // 3. Extract superpixel regions and calculate average colors
     let phase_start = Instant::now();
-    let mut regions = extract_superpixel_regions(&superpixel_labels, &lab_image, image, width, height);
+    let mut regions = extract_superpixel_regions(
+        &superpixel_labels,
+        &lab_image,
+        image,
+        (width, height),
+        config.region_average_in_lab,
+    );
     log::debug!("Region extraction: {:?}", phase_start.elapsed());
 
     // 3.5. Apply merge budget system to control region count
@@ -2504,6 +3275,7 @@ fn trace_superpixel(
         let phase_start = Instant::now();
         log::info!("🎯 Starting merge budget system:");
         regions = apply_merge_budget_system(regions, config, image);
+        relabel_merged_regions(&regions, &mut superpixel_labels);
         log::debug!("Merge budget system: {:?}", phase_start.elapsed());
     }
 
@@ -2528,6 +3300,22 @@ fn trace_superpixel(
         log::info!("🎨 Color processing SKIPPED - superpixel_preserve_colors is false");
     }
 
+    // 3.65. Smooth colors across similar neighbouring regions to reduce banding
+    if config.region_color_smoothing > 0.0 {
+        smooth_superpixel_region_colors(
+            &mut regions,
+            &superpixel_labels,
+            width,
+            height,
+            config.region_color_smoothing,
+        );
+    }
+
+    // 3.7. Override region colors with a distinct debug palette if requested
+    if config.superpixel_debug_colors {
+        assign_superpixel_debug_colors(&mut regions, &superpixel_labels, width, height);
+    }
+
     // 4. Generate SVG paths based on artistic mode
     let phase_start = Instant::now();
     let dp_epsilon = if config.superpixel_simplify_boundaries {
@@ -2536,18 +3324,9 @@ fn trace_superpixel(
         calculate_douglas_peucker_epsilon(width as u32, height as u32, 0.005) as f32
     };
 
-    let svg_paths = generate_superpixel_svg_paths(
-        &regions,
-        &superpixel_labels,
-        width,
-        height,
-        config.stroke_px_at_1080p,
-        dp_epsilon,
-        config.detail,
-        config.superpixel_fill_regions,
-        config.superpixel_stroke_regions,
-        config.superpixel_preserve_colors,
-    )?;
+    let options = SuperpixelSvgOptions::from_config(config, dp_epsilon);
+    let svg_paths =
+        generate_superpixel_svg_paths(&regions, &superpixel_labels, width, height, &options)?;
     log::debug!("SVG generation: {:?}", phase_start.elapsed());
 
     log::info!(
@@ -2596,7 +3375,6 @@ impl SlicCluster {
 #[derive(Debug, Clone)]
 struct SuperpixelRegion {
     /// Region label/ID
-    #[allow(dead_code)]
     label: usize,
     /// Average LAB color of the region
     #[allow(dead_code)]
@@ -2609,8 +3387,11 @@ struct SuperpixelRegion {
     #[allow(dead_code)]
     bbox: (u32, u32, u32, u32),
     /// Area in pixels
-    #[allow(dead_code)]
     area: usize,
+    /// Mean pixel position, in the same coordinates as `boundary_points`
+    centroid: Point,
+    /// SLIC labels of the regions absorbed into this one by merging
+    merged_labels: Vec<usize>,
 }
 
 /// Refine cluster centers to positions with lowest gradient magnitude
@@ -2855,10 +3636,114 @@ fn initialize_cluster_centers(
     clusters
 }
 
+/// Bounds on a saliency cell's seed density relative to the image average
+///
+/// The floor keeps background seeds within reach of SLIC's fixed 2S search window.
+const SALIENCY_SEED_DENSITY_RANGE: (f32, f32) = (0.5, 4.0);
+
+/// Place cluster centers with density proportional to local saliency
+///
+/// The image is split into cells of S/2 pixels scored by mean LAB gradient
+/// magnitude (texture and edges score high, flat areas low). Each cell's share
+/// of `num_superpixels` follows its score relative to the average, clamped to
+/// `SALIENCY_SEED_DENSITY_RANGE`. Seeds are handed out by accumulating fractional
+/// shares along a serpentine walk and spread on a small grid within each cell.
+fn initialize_saliency_seeded_centers(
+    lab_image: &[LabColor],
+    width: usize,
+    height: usize,
+    num_superpixels: usize,
+    s: usize,
+) -> Vec<SlicCluster> {
+    let cell = (s / 2).max(2);
+    let (cells_x, cells_y) = (width.div_ceil(cell), height.div_ceil(cell));
+
+    // Mean gradient magnitude per cell
+    let mut scores = vec![0.0f32; cells_x * cells_y];
+    let mut counts = vec![0usize; cells_x * cells_y];
+    for y in 0..height {
+        for x in 0..width {
+            let here = &lab_image[y * width + x];
+            let right = &lab_image[y * width + (x + 1).min(width - 1)];
+            let below = &lab_image[(y + 1).min(height - 1) * width + x];
+            let cell_idx = (y / cell) * cells_x + x / cell;
+            scores[cell_idx] += here.distance_to(right) + here.distance_to(below);
+            counts[cell_idx] += 1;
+        }
+    }
+    for (score, &count) in scores.iter_mut().zip(&counts) {
+        *score /= count.max(1) as f32;
+    }
+
+    // Relative densities, clamped then renormalized a few times so both bounds hold
+    let (min_density, max_density) = SALIENCY_SEED_DENSITY_RANGE;
+    let mean_score = scores.iter().sum::<f32>() / scores.len() as f32;
+    let mut densities: Vec<f32> = if mean_score > f32::EPSILON {
+        scores.iter().map(|score| score / mean_score).collect()
+    } else {
+        vec![1.0; scores.len()]
+    };
+    for _ in 0..4 {
+        densities
+            .iter_mut()
+            .for_each(|d| *d = d.clamp(min_density, max_density));
+        let mean = densities.iter().sum::<f32>() / densities.len() as f32;
+        densities.iter_mut().for_each(|d| *d /= mean);
+    }
+
+    // Cells hold different pixel counts at the right/bottom edges
+    let weights: Vec<f32> = densities
+        .iter()
+        .zip(&counts)
+        .map(|(d, &count)| d * count as f32)
+        .collect();
+    let total_weight: f32 = weights.iter().sum();
+    let seeds_per_weight = num_superpixels as f32 / total_weight.max(f32::EPSILON);
+
+    let mut clusters = Vec::with_capacity(num_superpixels);
+    let mut accumulated = 0.0f32;
+    for cy in 0..cells_y {
+        for step in 0..cells_x {
+            let cx = if cy % 2 == 0 { step } else { cells_x - 1 - step };
+            let cell_idx = cy * cells_x + cx;
+            accumulated += weights[cell_idx] * seeds_per_weight;
+            let seeds = accumulated.round().max(0.0) as usize;
+            accumulated -= seeds as f32;
+
+            let (x0, y0) = (cx * cell, cy * cell);
+            let (cell_w, cell_h) = ((width - x0).min(cell), (height - y0).min(cell));
+            let grid = (seeds as f32).sqrt().ceil().max(1.0) as usize;
+            for seed in 0..seeds {
+                if clusters.len() >= num_superpixels {
+                    break;
+                }
+                let x = x0 + ((seed % grid) * 2 + 1) * cell_w / (2 * grid);
+                let y = y0 + ((seed / grid) * 2 + 1) * cell_h / (2 * grid);
+                let (x, y) = (x.min(width - 1), y.min(height - 1));
+                clusters.push(SlicCluster::new(lab_image[y * width + x], x as f32, y as f32));
+            }
+        }
+    }
+
+    log::info!(
+        "🎯 Saliency seeding placed {} of {} cluster centers over {}×{} cells",
+        clusters.len(),
+        num_superpixels,
+        cells_x,
+        cells_y
+    );
+    clusters
+}
+
 /// SLIC superpixel segmentation algorithm
 ///
 /// Implements Simple Linear Iterative Clustering (SLIC) for superpixel segmentation.
 /// This pure Rust implementation avoids external dependencies while maintaining performance.
+/// Returns the pixel labels and the number of iterations actually run; iteration stops
+/// early when no center moves more than `convergence_threshold` pixels (0.0 disables).
+/// With `saliency_seeding`, centers are placed by local saliency instead of
+/// `initialization_pattern`.
+#[allow(clippy::too_many_arguments)]
 fn slic_segmentation(
     lab_image: &[LabColor],
     width: usize,
@@ -2866,8 +3751,10 @@ fn slic_segmentation(
     num_superpixels: usize,
     compactness: f32,
     max_iterations: usize,
+    convergence_threshold: f32,
     initialization_pattern: SuperpixelInitPattern,
-) -> Vec<usize> {
+    saliency_seeding: bool,
+) -> (Vec<usize>, usize) {
     // DEBUG: Log the initialization pattern being used
     log::info!("🎯 SLIC Segmentation: Using initialization pattern: {:?} for {}x{} image with {} superpixels",
                initialization_pattern, width, height, num_superpixels);
@@ -2878,14 +3765,18 @@ fn slic_segmentation(
     let s = s.max(1); // Ensure minimum spacing of 1
 
     // Initialize cluster centers using the specified pattern
-    let mut clusters = initialize_cluster_centers(
-        lab_image,
-        width,
-        height,
-        num_superpixels,
-        s,
-        initialization_pattern,
-    );
+    let mut clusters = if saliency_seeding {
+        initialize_saliency_seeded_centers(lab_image, width, height, num_superpixels, s)
+    } else {
+        initialize_cluster_centers(
+            lab_image,
+            width,
+            height,
+            num_superpixels,
+            s,
+            initialization_pattern,
+        )
+    };
 
     // Refine cluster centers to lowest gradient positions (avoids edges)
     // This is a crucial SLIC step that prevents clusters from starting on edges
@@ -2894,38 +3785,25 @@ fn slic_segmentation(
     // Initialize labels and distances
     let mut labels = vec![0; total_pixels];
     let mut distances = vec![f32::INFINITY; total_pixels];
+    let parallel = should_use_parallel(total_pixels, SLIC_PARALLEL_MIN_PIXELS);
 
     // K-means iteration
+    let mut iterations_used = 0;
     for iteration in 0..max_iterations {
-        let mut cluster_changed = false;
+        iterations_used = iteration + 1;
 
         // Assign pixels to nearest cluster
-        for (cluster_idx, cluster) in clusters.iter().enumerate() {
-            // Search in 2S×2S region around cluster center
-            let search_size = 2 * s;
-            let min_x = (cluster.x as isize - search_size as isize / 2).max(0) as usize;
-            let max_x = (cluster.x as usize + search_size / 2).min(width - 1);
-            let min_y = (cluster.y as isize - search_size as isize / 2).max(0) as usize;
-            let max_y = (cluster.y as usize + search_size / 2).min(height - 1);
-
-            for y in min_y..=max_y {
-                for x in min_x..=max_x {
-                    let idx = y * width + x;
-                    if idx < lab_image.len() {
-                        let dist =
-                            cluster.distance(&lab_image[idx], x as f32, y as f32, compactness);
-
-                        if dist < distances[idx] {
-                            distances[idx] = dist;
-                            if labels[idx] != cluster_idx {
-                                labels[idx] = cluster_idx;
-                                cluster_changed = true;
-                            }
-                        }
-                    }
-                }
-            }
-        }
+        let cluster_changed = slic_assign_pixels(
+            &clusters,
+            lab_image,
+            width,
+            height,
+            s,
+            compactness,
+            &mut labels,
+            &mut distances,
+            parallel,
+        );
 
         // Update cluster centers
         let mut cluster_sums: Vec<(LabColor, f32, f32, usize)> =
@@ -2963,35 +3841,164 @@ fn slic_segmentation(
             }
         }
 
+        let mut max_center_shift = 0.0f32;
         for (cluster_idx, cluster) in clusters.iter_mut().enumerate() {
             if cluster_sums[cluster_idx].3 > 0 {
                 let count = cluster_sums[cluster_idx].3 as f32;
+                let (new_x, new_y) = (
+                    cluster_sums[cluster_idx].1 / count,
+                    cluster_sums[cluster_idx].2 / count,
+                );
+                max_center_shift =
+                    max_center_shift.max((new_x - cluster.x).hypot(new_y - cluster.y));
                 cluster.lab.l = cluster_sums[cluster_idx].0.l / count;
                 cluster.lab.a = cluster_sums[cluster_idx].0.a / count;
                 cluster.lab.b = cluster_sums[cluster_idx].0.b / count;
-                cluster.x = cluster_sums[cluster_idx].1 / count;
-                cluster.y = cluster_sums[cluster_idx].2 / count;
+                cluster.x = new_x;
+                cluster.y = new_y;
                 cluster.pixel_count = cluster_sums[cluster_idx].3;
             }
         }
 
-        // Early termination if no significant changes
+        // Early termination once cluster centers have settled
+        if convergence_threshold > 0.0 && max_center_shift < convergence_threshold {
+            log::debug!(
+                "SLIC centers settled after {} iterations (max shift {:.3}px)",
+                iteration + 1,
+                max_center_shift
+            );
+            break;
+        }
+
+        // Early termination if no significant changes
         if !cluster_changed && iteration > 2 {
             log::debug!("SLIC converged after {} iterations", iteration + 1);
             break;
         }
     }
 
-    labels
+    (labels, iterations_used)
+}
+
+/// Minimum pixel count before SLIC assignment is split across threads
+const SLIC_PARALLEL_MIN_PIXELS: usize = 250_000;
+
+/// Image rows per work item in parallel SLIC assignment
+const SLIC_PARALLEL_ROWS_PER_CHUNK: usize = 16;
+
+/// Assign pixels to the nearest cluster center within each center's 2S×2S search window
+///
+/// Updates `labels` and `distances` in place and returns whether any label changed.
+/// The parallel path processes row chunks independently, visiting clusters in index
+/// order for every pixel, so it makes exactly the comparisons of the serial
+/// cluster-by-cluster loop and yields identical labels.
+#[allow(clippy::too_many_arguments)]
+fn slic_assign_pixels(
+    clusters: &[SlicCluster],
+    lab_image: &[LabColor],
+    width: usize,
+    height: usize,
+    s: usize,
+    compactness: f32,
+    labels: &mut [usize],
+    distances: &mut [f32],
+    parallel: bool,
+) -> bool {
+    // Search bounds (min_x, max_x, min_y, max_y) per cluster
+    let search_size = 2 * s;
+    let windows: Vec<(usize, usize, usize, usize)> = clusters
+        .iter()
+        .map(|cluster| {
+            (
+                (cluster.x as isize - search_size as isize / 2).max(0) as usize,
+                (cluster.x as usize + search_size / 2).min(width - 1),
+                (cluster.y as isize - search_size as isize / 2).max(0) as usize,
+                (cluster.y as usize + search_size / 2).min(height - 1),
+            )
+        })
+        .collect();
+
+    if !parallel {
+        let mut cluster_changed = false;
+        for (cluster_idx, (cluster, &(min_x, max_x, min_y, max_y))) in
+            clusters.iter().zip(&windows).enumerate()
+        {
+            for y in min_y..=max_y {
+                for x in min_x..=max_x {
+                    let idx = y * width + x;
+                    if idx < lab_image.len() {
+                        let dist =
+                            cluster.distance(&lab_image[idx], x as f32, y as f32, compactness);
+
+                        if dist < distances[idx] {
+                            distances[idx] = dist;
+                            if labels[idx] != cluster_idx {
+                                labels[idx] = cluster_idx;
+                                cluster_changed = true;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        return cluster_changed;
+    }
+
+    let (current_labels, current_distances) = (&*labels, &*distances);
+    let chunk_results = execute_parallel_chunks(0..height, SLIC_PARALLEL_ROWS_PER_CHUNK, |rows| {
+        let start = rows[0] * width;
+        let end = (rows[rows.len() - 1] + 1) * width;
+        let mut chunk_labels = current_labels[start..end].to_vec();
+        let mut chunk_distances = current_distances[start..end].to_vec();
+        let mut chunk_changed = false;
+
+        for &y in rows {
+            for (cluster_idx, (cluster, &(min_x, max_x, min_y, max_y))) in
+                clusters.iter().zip(&windows).enumerate()
+            {
+                if y < min_y || y > max_y {
+                    continue;
+                }
+                for x in min_x..=max_x {
+                    let idx = y * width + x;
+                    if idx < lab_image.len() {
+                        let dist =
+                            cluster.distance(&lab_image[idx], x as f32, y as f32, compactness);
+
+                        let local = idx - start;
+                        if dist < chunk_distances[local] {
+                            chunk_distances[local] = dist;
+                            if chunk_labels[local] != cluster_idx {
+                                chunk_labels[local] = cluster_idx;
+                                chunk_changed = true;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        (start, chunk_labels, chunk_distances, chunk_changed)
+    });
+
+    let mut cluster_changed = false;
+    for (start, chunk_labels, chunk_distances, chunk_changed) in chunk_results {
+        labels[start..start + chunk_labels.len()].copy_from_slice(&chunk_labels);
+        distances[start..start + chunk_distances.len()].copy_from_slice(&chunk_distances);
+        cluster_changed |= chunk_changed;
+    }
+    cluster_changed
 }
 
 /// Extract superpixel regions with boundaries and average colors
+///
+/// With `average_in_lab` the fill color is the mean LAB color converted back to
+/// sRGB instead of the per-channel RGB mean.
 fn extract_superpixel_regions(
     labels: &[usize],
     lab_image: &[LabColor],
     rgba_image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
-    width: usize,
-    height: usize,
+    (width, height): (usize, usize),
+    average_in_lab: bool,
 ) -> Vec<SuperpixelRegion> {
     use std::collections::HashMap;
 
@@ -3040,10 +4047,17 @@ fn extract_superpixel_regions(
         );
 
         // Calculate average RGB color
-        let rgb_sum = region_rgb_sums[label];
-        let avg_r = (rgb_sum.0 / rgb_sum.3 as u64) as u8;
-        let avg_g = (rgb_sum.1 / rgb_sum.3 as u64) as u8;
-        let avg_b = (rgb_sum.2 / rgb_sum.3 as u64) as u8;
+        let (avg_r, avg_g, avg_b) = if average_in_lab {
+            let [r, g, b, _] = lab_to_rgba(&avg_lab).0;
+            (r, g, b)
+        } else {
+            let rgb_sum = region_rgb_sums[label];
+            (
+                (rgb_sum.0 / rgb_sum.3 as u64) as u8,
+                (rgb_sum.1 / rgb_sum.3 as u64) as u8,
+                (rgb_sum.2 / rgb_sum.3 as u64) as u8,
+            )
+        };
         let avg_rgb_hex = format!("#{avg_r:02x}{avg_g:02x}{avg_b:02x}");
 
         // Calculate bounding box
@@ -3052,6 +4066,14 @@ fn extract_superpixel_regions(
         let min_y = pixels.iter().map(|(_, y)| *y).min().unwrap_or(0) as u32;
         let max_y = pixels.iter().map(|(_, y)| *y).max().unwrap_or(0) as u32;
 
+        let (sum_x, sum_y) = pixels.iter().fold((0u64, 0u64), |(sx, sy), &(x, y)| {
+            (sx + x as u64, sy + y as u64)
+        });
+        let centroid = Point::new(
+            sum_x as f32 / pixels.len() as f32,
+            sum_y as f32 / pixels.len() as f32,
+        );
+
         // Extract boundary using 4-connectivity
         let boundary_points = extract_region_boundary(pixels, labels, *label, width, height);
 
@@ -3062,6 +4084,8 @@ fn extract_superpixel_regions(
             boundary_points,
             bbox: (min_x, min_y, max_x - min_x + 1, max_y - min_y + 1),
             area: pixels.len(),
+            centroid,
+            merged_labels: Vec::new(),
         });
     }
 
@@ -3226,19 +4250,334 @@ fn trace_boundary_contour(boundary_points: &[Point]) -> Vec<Point> {
     traced_path
 }
 
-/// Generate SVG paths for superpixel regions with different artistic modes
-fn generate_superpixel_svg_paths(
+/// Replace region colors with a high-contrast debug palette
+///
+/// Regions are visited in label order and greedily given the first palette entry not used
+/// by any neighbour in the label map, so touching regions never share a color and the
+/// assignment is identical across runs.
+fn assign_superpixel_debug_colors(
+    regions: &mut [SuperpixelRegion],
+    labels: &[usize],
+    width: usize,
+    height: usize,
+) {
+    use std::collections::HashSet;
+
+    let neighbors = superpixel_region_adjacency(regions, labels, width, height);
+
+    let mut order: Vec<usize> = (0..regions.len()).collect();
+    order.sort_by_key(|&idx| regions[idx].label);
+
+    let mut palette_indices: Vec<Option<usize>> = vec![None; regions.len()];
+    for idx in order {
+        let used: HashSet<usize> = neighbors[idx]
+            .keys()
+            .filter_map(|&other| palette_indices[other])
+            .collect();
+        let palette_index = (0..).find(|c| !used.contains(c)).unwrap_or(0);
+        palette_indices[idx] = Some(palette_index);
+        regions[idx].avg_rgb_hex = debug_palette_color(palette_index);
+    }
+}
+
+/// Region adjacency graph from 4-connected label transitions
+///
+/// Entry `i` maps each region index touching region `i` to the number of pixel
+/// edges along their shared boundary.
+fn superpixel_region_adjacency(
     regions: &[SuperpixelRegion],
-    _labels: &[usize],
-    _width: usize,
-    _height: usize,
+    labels: &[usize],
+    width: usize,
+    height: usize,
+) -> Vec<HashMap<usize, usize>> {
+    let label_to_region: HashMap<usize, usize> = regions
+        .iter()
+        .enumerate()
+        .map(|(idx, region)| (region.label, idx))
+        .collect();
+
+    let mut neighbors: Vec<HashMap<usize, usize>> = vec![HashMap::new(); regions.len()];
+    for y in 0..height {
+        for x in 0..width {
+            let idx = y * width + x;
+            let Some(&region) = label_to_region.get(&labels[idx]) else {
+                continue;
+            };
+            let right = (x + 1 < width).then(|| labels[idx + 1]);
+            let down = (y + 1 < height).then(|| labels[idx + width]);
+            for other_label in [right, down].into_iter().flatten() {
+                if let Some(&other) = label_to_region.get(&other_label) {
+                    if other != region {
+                        *neighbors[region].entry(other).or_insert(0) += 1;
+                        *neighbors[other].entry(region).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+    }
+    neighbors
+}
+
+/// ΔE (CIE76) scale of the similarity weight in region color smoothing; neighbours
+/// a few times further apart than this are treated as real edges and left alone
+const REGION_SMOOTHING_DELTA_E: f32 = 10.0;
+
+/// Blend region colors towards their similar neighbours, bilateral-filter style
+///
+/// Each neighbour is weighted by its share of the region's boundary and by a Gaussian
+/// of the LAB color difference, so gentle steps between regions (banding on gradients)
+/// are evened out while strong edges keep their contrast. At `strength` 1.0 a region
+/// moves halfway towards the weighted mean of fully similar neighbours. All colors are
+/// updated from the original values; region shapes are not changed.
+fn smooth_superpixel_region_colors(
+    regions: &mut [SuperpixelRegion],
+    labels: &[usize],
+    width: usize,
+    height: usize,
+    strength: f32,
+) {
+    let neighbors = superpixel_region_adjacency(regions, labels, width, height);
+    let colors: Vec<LabColor> = regions
+        .iter()
+        .map(|region| rgba_to_lab(&hex_to_rgba(&region.avg_rgb_hex)))
+        .collect();
+
+    let step = strength.clamp(0.0, 1.0) * 0.5;
+    for (idx, region) in regions.iter_mut().enumerate() {
+        let color = colors[idx];
+        let boundary: usize = neighbors[idx].values().sum();
+        if boundary == 0 {
+            continue;
+        }
+
+        let (mut dl, mut da, mut db) = (0.0f32, 0.0f32, 0.0f32);
+        for (&other, &shared) in &neighbors[idx] {
+            let other = colors[other];
+            let similarity =
+                (-0.5 * (color.distance_to(&other) / REGION_SMOOTHING_DELTA_E).powi(2)).exp();
+            let weight = similarity * shared as f32 / boundary as f32;
+            dl += weight * (other.l - color.l);
+            da += weight * (other.a - color.a);
+            db += weight * (other.b - color.b);
+        }
+
+        let smoothed = LabColor::new(
+            color.l + step * dl,
+            color.a + step * da,
+            color.b + step * db,
+        );
+        region.avg_rgb_hex = rgba_to_hex(&lab_to_rgba(&smoothed));
+        region.avg_lab = smoothed;
+    }
+    log::debug!(
+        "Smoothed colors of {} superpixel regions (strength {strength:.2})",
+        regions.len()
+    );
+}
+
+/// Distinct saturated color for a palette index, stepping hue by the golden angle
+fn debug_palette_color(index: usize) -> String {
+    let hue = (index as f32 * 137.508) % 360.0;
+    // Alternate brightness so hues that end up close still differ in value
+    let value = if index % 2 == 0 { 0.95 } else { 0.7 };
+    let saturation = 0.85;
+
+    let chroma = value * saturation;
+    let h_prime = hue / 60.0;
+    let x = chroma * (1.0 - (h_prime % 2.0 - 1.0).abs());
+    let (r1, g1, b1) = match h_prime as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = value - chroma;
+    let to_u8 = |c: f32| ((c + m) * 255.0).round() as u8;
+
+    format!("#{:02x}{:02x}{:02x}", to_u8(r1), to_u8(g1), to_u8(b1))
+}
+
+/// Boundary between two superpixel labels, as a chain of pixel corners
+#[derive(Debug, Clone)]
+struct SharedRegionEdge {
+    /// Labels on either side, smaller first
+    labels: (usize, usize),
+    points: Vec<Point>,
+    /// Whether the chain loops back to its start (a region enclosed by one neighbor)
+    closed: bool,
+}
+
+/// Extract the region adjacency edge network from a label map
+///
+/// Boundaries follow the cracks between differently labelled pixels, so every
+/// boundary shared by two regions appears exactly once. Chains end at junctions
+/// where three or more regions meet, which neighboring chains share as endpoints.
+/// The image border is not a shared boundary and is left out.
+fn extract_shared_region_edges(
+    labels: &[usize],
+    width: usize,
+    height: usize,
+) -> Vec<SharedRegionEdge> {
+    let corner = |x: usize, y: usize| y * (width + 1) + x;
+    let corner_point = |c: usize| Point {
+        x: (c % (width + 1)) as f32,
+        y: (c / (width + 1)) as f32,
+    };
+
+    // Unit cracks (corner, corner, label pair) between 4-connected pixels
+    let mut cracks: Vec<(usize, usize, (usize, usize))> = Vec::new();
+    for y in 0..height {
+        for x in 0..width {
+            let label = labels[y * width + x];
+            if x > 0 {
+                let left = labels[y * width + x - 1];
+                if left != label {
+                    let pair = (left.min(label), left.max(label));
+                    cracks.push((corner(x, y), corner(x, y + 1), pair));
+                }
+            }
+            if y > 0 {
+                let up = labels[(y - 1) * width + x];
+                if up != label {
+                    let pair = (up.min(label), up.max(label));
+                    cracks.push((corner(x, y), corner(x + 1, y), pair));
+                }
+            }
+        }
+    }
+
+    let mut incident: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (i, &(a, b, _)) in cracks.iter().enumerate() {
+        incident.entry(a).or_default().push(i);
+        incident.entry(b).or_default().push(i);
+    }
+    // Chains stop at junctions and where the pair of regions changes
+    let is_stop = |c: usize| match incident[&c].as_slice() {
+        [first, second] => cracks[*first].2 != cracks[*second].2,
+        _ => true,
+    };
+
+    // Open chains start from stop corners; the cracks left over form closed loops
+    let starts: Vec<(usize, usize)> = cracks
+        .iter()
+        .enumerate()
+        .flat_map(|(i, &(a, b, _))| [(a, i), (b, i)])
+        .filter(|&(c, _)| is_stop(c))
+        .chain(cracks.iter().enumerate().map(|(i, &(a, _, _))| (a, i)))
+        .collect();
+
+    let mut used = vec![false; cracks.len()];
+    let mut edges = Vec::new();
+    for (start, first_crack) in starts {
+        if used[first_crack] {
+            continue;
+        }
+        let mut points = vec![corner_point(start)];
+        let (mut at, mut crack) = (start, first_crack);
+        loop {
+            used[crack] = true;
+            let (a, b, _) = cracks[crack];
+            at = if a == at { b } else { a };
+            points.push(corner_point(at));
+            if at == start || is_stop(at) {
+                break;
+            }
+            match incident[&at].iter().find(|&&next| !used[next]) {
+                Some(&next) => crack = next,
+                None => break,
+            }
+        }
+        edges.push(SharedRegionEdge {
+            labels: cracks[first_crack].2,
+            points,
+            closed: at == start,
+        });
+    }
+    edges
+}
+
+/// Stroke color for a shared boundary in strokes-only mode: the mix of both region colors
+fn shared_edge_color(
+    (first, second): (usize, usize),
+    region_colors: &HashMap<usize, &str>,
+) -> String {
+    match (region_colors.get(&first), region_colors.get(&second)) {
+        (Some(a), Some(b)) => {
+            let (a, b) = (hex_to_rgba(a), hex_to_rgba(b));
+            let mix = |channel: usize| ((a[channel] as u16 + b[channel] as u16) / 2) as u8;
+            rgba_to_hex(&Rgba([mix(0), mix(1), mix(2), 255]))
+        }
+        (Some(color), None) | (None, Some(color)) => color.to_string(),
+        (None, None) => "#000000".to_string(),
+    }
+}
+
+/// Output settings for [`generate_superpixel_svg_paths`]
+#[derive(Debug, Clone, Copy)]
+struct SuperpixelSvgOptions {
+    /// Stroke width for region borders and shared edges
     stroke_width: f32,
+    /// Douglas-Peucker tolerance for region boundaries and shared edges
     dp_epsilon: f32,
+    /// Deepest Douglas-Peucker split before falling back to Visvalingam-Whyatt
+    max_simplify_depth: u32,
+    /// Picks the artistic mode when neither fills nor strokes are enabled
     detail: f32,
+    /// Fill each region with its color
     fill_regions: bool,
+    /// Outline each region
     stroke_regions: bool,
+    /// Draw borders once from the shared edge network instead of per region
+    shared_edges: bool,
+    /// Paint with region colors instead of black and white
     preserve_colors: bool,
+    /// Record each region's centroid and area on its elements
+    region_metadata: bool,
+}
+
+impl SuperpixelSvgOptions {
+    /// Options for the superpixel backend of `config`, simplifying with `dp_epsilon`
+    fn from_config(config: &TraceLowConfig, dp_epsilon: f32) -> Self {
+        Self {
+            stroke_width: config.stroke_px_at_1080p,
+            dp_epsilon,
+            max_simplify_depth: config.max_simplify_depth,
+            detail: config.detail,
+            fill_regions: config.superpixel_fill_regions,
+            stroke_regions: config.superpixel_stroke_regions,
+            shared_edges: config.shared_region_edges,
+            preserve_colors: config.superpixel_preserve_colors || config.superpixel_debug_colors,
+            region_metadata: config.emit_region_metadata,
+        }
+    }
+}
+
+/// Generate SVG paths for superpixel regions with different artistic modes
+///
+/// With `shared_edges`, boundary strokes come from the region adjacency edge
+/// network of `labels` and are drawn once, on top of all fills. With
+/// `region_metadata`, every per-region element records its region's centroid
+/// and area.
+fn generate_superpixel_svg_paths(
+    regions: &[SuperpixelRegion],
+    labels: &[usize],
+    width: usize,
+    height: usize,
+    options: &SuperpixelSvgOptions,
 ) -> Result<Vec<SvgPath>, VectorizeError> {
+    let SuperpixelSvgOptions {
+        stroke_width,
+        dp_epsilon,
+        max_simplify_depth,
+        detail,
+        fill_regions,
+        stroke_regions,
+        shared_edges,
+        preserve_colors,
+        region_metadata,
+    } = *options;
     let mut svg_paths = Vec::new();
 
     // Determine artistic mode based on configuration
@@ -3266,7 +4605,8 @@ fn generate_superpixel_svg_paths(
         }
 
         // Simplify boundary path using Douglas-Peucker
-        let simplified_points = douglas_peucker_simplify(&region.boundary_points, dp_epsilon);
+        let simplified_points =
+            douglas_peucker_simplify(&region.boundary_points, dp_epsilon, max_simplify_depth);
 
         if simplified_points.len() < 3 {
             continue; // Skip if simplification resulted in too few points
@@ -3296,6 +4636,7 @@ fn generate_superpixel_svg_paths(
 
         let fill_color = &fill_color_str;
         let stroke_color = &stroke_color_str;
+        let region_start = svg_paths.len();
 
         match mode {
             "filled_only" => {
@@ -3303,6 +4644,10 @@ fn generate_superpixel_svg_paths(
                 let svg_path = SvgPath::new_fill(path_data, fill_color);
                 svg_paths.push(svg_path);
             }
+            "filled_with_borders" if shared_edges => {
+                // Borders come from the shared edge network below
+                svg_paths.push(SvgPath::new_fill(path_data, fill_color));
+            }
             "filled_with_borders" => {
                 // Comic book style - filled regions with black borders
                 let filled_path = SvgPath::new_fill(path_data.clone(), fill_color);
@@ -3312,6 +4657,7 @@ fn generate_superpixel_svg_paths(
                 let stroke_path = SvgPath::new_stroke(path_data, "#000000", stroke_width);
                 svg_paths.push(stroke_path);
             }
+            "strokes_only" if shared_edges => {}
             "strokes_only" => {
                 // Cell animation style - strokes only with region color
                 let stroke_path = SvgPath::new_stroke(path_data, stroke_color, stroke_width * 1.5);
@@ -3326,6 +4672,55 @@ fn generate_superpixel_svg_paths(
                 svg_paths.push(svg_path);
             }
         }
+
+        if region_metadata {
+            let metadata = RegionMetadata {
+                centroid: region.centroid,
+                area: region.area,
+            };
+            for path in &mut svg_paths[region_start..] {
+                path.region_metadata = Some(metadata);
+            }
+        }
+    }
+
+    if shared_edges && matches!(mode, "filled_with_borders" | "strokes_only") {
+        let region_colors: HashMap<usize, &str> = regions
+            .iter()
+            .map(|region| (region.label, region.avg_rgb_hex.as_str()))
+            .collect();
+        let edges = extract_shared_region_edges(labels, width, height);
+        log::debug!("Shared region edge network: {} boundaries", edges.len());
+
+        for edge in edges {
+            let mut points = douglas_peucker_simplify(&edge.points, dp_epsilon, max_simplify_depth);
+            if edge.closed {
+                points.pop();
+                if points.len() < 3 {
+                    continue;
+                }
+            }
+            let mut path_data = format!("M {:.1},{:.1}", points[0].x, points[0].y);
+            for point in points.iter().skip(1) {
+                path_data.push_str(&format!(" L {:.1},{:.1}", point.x, point.y));
+            }
+            if edge.closed {
+                path_data.push_str(" Z");
+            }
+
+            let stroke_path = if mode == "filled_with_borders" {
+                SvgPath::new_stroke(path_data, "#000000", stroke_width)
+            } else {
+                let color = shared_edge_color(edge.labels, &region_colors);
+                let color = if preserve_colors {
+                    color
+                } else {
+                    hex_to_grayscale_hex(&color)
+                };
+                SvgPath::new_stroke(path_data, &color, stroke_width * 1.5)
+            };
+            svg_paths.push(stroke_path);
+        }
     }
 
     log::debug!(
@@ -3345,13 +4740,16 @@ fn trace_dots(
 ) -> Result<Vec<SvgPath>, VectorizeError> {
     log::info!("Running dots backend");
     let total_start = Instant::now();
+    let color_mode = config
+        .dot_color_mode
+        .unwrap_or_else(|| DotColorMode::from_preserve_colors(config.dot_preserve_colors));
 
     // Create DotConfig from TraceLowConfig - trust parameter validation
     let dot_config = DotConfig {
         min_radius: config.dot_min_radius,
         max_radius: config.dot_max_radius,
         density_threshold: config.dot_density_threshold,
-        preserve_colors: config.dot_preserve_colors,
+        preserve_colors: color_mode.samples_source(),
         adaptive_sizing: config.dot_adaptive_sizing,
         spacing_factor: 1.5, // Fixed reasonable default
         default_color: "#000000".to_string(),
@@ -3362,6 +4760,7 @@ fn trace_dots(
         size_variation: config.dot_size_variation,
         shape: config.dot_shape,
         grid_pattern: config.dot_grid_pattern,
+        skip_background: config.dots_skip_background,
     };
 
     // Create GradientConfig - can use defaults for now
@@ -3391,6 +4790,31 @@ fn trace_dots(
 
     // Apply unified preprocessing (background removal + noise filtering)    let processed_image = apply_preprocessing(image, config, "dots")?;
 
+    if config.halftone_mode {
+        let halftone_config = HalftoneConfig {
+            angle_deg: config.halftone_angle,
+            cell_px: config.halftone_cell_px,
+            preserve_colors: color_mode.samples_source(),
+            default_color: dot_config.default_color.clone(),
+        };
+        let mut dots = generate_halftone_dots(&processed_image, &halftone_config);
+        if color_mode == DotColorMode::Grayscale {
+            apply_grayscale_colors(&mut dots);
+        }
+        let svg_paths = if config.dots_merge_to_dashes {
+            dots_to_dash_paths(&dots)
+        } else {
+            dots_to_svg_paths(&dots)
+        };
+
+        log::info!(
+            "Dots backend (halftone) completed: {:.3}ms total ({} SVG paths)",
+            total_start.elapsed().as_secs_f64() * 1000.0,
+            svg_paths.len()
+        );
+        return Ok(svg_paths);
+    }
+
     // Generate dots using the complete pipeline
     let phase_start = Instant::now();
     let dots = generate_dots_from_image(
@@ -3431,9 +4855,17 @@ fn trace_dots(
         );
     }
 
+    if color_mode == DotColorMode::Grayscale {
+        apply_grayscale_colors(&mut processed_dots);
+    }
+
     // Convert dots to SVG paths
     let phase_start = Instant::now();
-    let svg_paths = dots_to_svg_paths(&processed_dots);
+    let svg_paths = if config.dots_merge_to_dashes {
+        dots_to_dash_paths(&processed_dots)
+    } else {
+        dots_to_svg_paths(&processed_dots)
+    };
     let svg_conversion_time = phase_start.elapsed();
 
     log::debug!(
@@ -3452,6 +4884,36 @@ fn trace_dots(
     Ok(svg_paths)
 }
 
+/// Low-poly backend: Delaunay triangulation with flat-colored facets
+fn trace_lowpoly(
+    image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+    _thresholds: &ThresholdMapping,
+    config: &TraceLowConfig,
+) -> Result<Vec<SvgPath>, VectorizeError> {
+    log::info!(
+        "Running low-poly backend with {} target vertices",
+        config.lowpoly_vertex_count
+    );
+    let total_start = Instant::now();
+
+    // Apply unified preprocessing (background removal + noise filtering)
+    let processed_image = apply_preprocessing(image, config, "lowpoly")?;
+
+    let lowpoly_config = LowPolyConfig {
+        vertex_count: config.lowpoly_vertex_count,
+        ..LowPolyConfig::default()
+    };
+    let svg_paths = generate_lowpoly_paths(&processed_image, &lowpoly_config);
+
+    log::info!(
+        "Low-poly backend completed: {:.3}ms total ({} triangles)",
+        total_start.elapsed().as_secs_f64() * 1000.0,
+        svg_paths.len()
+    );
+
+    Ok(svg_paths)
+}
+
 // ============================================================================
 // Spatial Indexing and Performance Optimization
 // ============================================================================
@@ -3828,6 +5290,31 @@ fn apply_color_based_merging(
     regions
 }
 
+/// Point the labels of pixels in absorbed regions at the region that absorbed them
+///
+/// Keeps the label map in step with the merged regions, so label-based steps
+/// (color smoothing, debug colors and the shared edge network) see merged regions
+/// as one and draw no seams through them.
+fn relabel_merged_regions(regions: &[SuperpixelRegion], labels: &mut [usize]) {
+    let survivors: HashMap<usize, usize> = regions
+        .iter()
+        .flat_map(|region| {
+            region
+                .merged_labels
+                .iter()
+                .map(|&absorbed| (absorbed, region.label))
+        })
+        .collect();
+    if survivors.is_empty() {
+        return;
+    }
+    for label in labels {
+        if let Some(&survivor) = survivors.get(label) {
+            *label = survivor;
+        }
+    }
+}
+
 /// Find the closest neighboring region for merging
 fn find_closest_neighbor(regions: &[SuperpixelRegion], target_idx: usize) -> Option<usize> {
     let target_region = &regions[target_idx];
@@ -3880,8 +5367,12 @@ fn merge_regions(mut region1: SuperpixelRegion, region2: SuperpixelRegion) -> Su
         ((region1.avg_lab.b + 128.0).max(0.0).min(255.0) as u8)
     );
 
-    // Combine areas
+    // Combine areas and area-weighted centroids
     region1.area = total_area;
+    region1.centroid = Point::new(
+        region1.centroid.x * weight1 + region2.centroid.x * weight2,
+        region1.centroid.y * weight1 + region2.centroid.y * weight2,
+    );
 
     // Merge bounding boxes
     let min_x = region1.bbox.0.min(region2.bbox.0);
@@ -3892,6 +5383,8 @@ fn merge_regions(mut region1: SuperpixelRegion, region2: SuperpixelRegion) -> Su
 
     // Combine boundary points (simplified - just concatenate)
     region1.boundary_points.extend(region2.boundary_points);
+    region1.merged_labels.push(region2.label);
+    region1.merged_labels.extend(region2.merged_labels);
 
     region1
 }
@@ -4493,8 +5986,25 @@ fn gaussian_blur(image: &GrayImage, sigma: f32) -> GrayImage {
     blurred
 }
 
-/// Optimized Canny edge detection with parallel processing and fast approximations
-fn canny_edge_detection(image: &GrayImage, low_threshold: f32, high_threshold: f32) -> GrayImage {
+/// Largest factor by which a detail map raises or lowers the Canny thresholds
+const DETAIL_MAP_MAX_THRESHOLD_SCALE: f32 = 2.0;
+
+/// Canny threshold multiplier for a detail map weight
+///
+/// Mid-gray (128) leaves thresholds unchanged; white halves them (more detail) and
+/// black doubles them (less detail), scaling geometrically in between.
+fn detail_map_threshold_scale(weight: u8) -> f32 {
+    let centered = 1.0 - 2.0 * weight as f32 / 255.0;
+    DETAIL_MAP_MAX_THRESHOLD_SCALE.powf(centered)
+}
+
+/// Canny edge detection with thresholds scaled per pixel by an optional detail map
+fn canny_edge_detection_with_detail_map(
+    image: &GrayImage,
+    low_threshold: f32,
+    high_threshold: f32,
+    detail_map: Option<&GrayImage>,
+) -> GrayImage {
     let (width, height) = image.dimensions();
     let total_pixels = (width * height) as usize;
 
@@ -4688,10 +6198,11 @@ fn canny_edge_detection(image: &GrayImage, low_threshold: f32, high_threshold: f
     // Sequential processing for thresholding (can't easily parallelize mutable access)
     for idx in 0..total_pixels {
         let magnitude = suppressed[idx];
+        let scale = detail_map.map_or(1.0, |map| detail_map_threshold_scale(map.as_raw()[idx]));
 
-        if magnitude >= high_threshold {
+        if magnitude >= high_threshold * scale {
             strong_edges[idx] = true;
-        } else if magnitude >= low_threshold {
+        } else if magnitude >= low_threshold * scale {
             weak_edges[idx] = true;
         }
     }
@@ -4860,29 +6371,34 @@ fn simplify_adaptive(polyline: &[Point], base_epsilon: f32) -> Vec<Point> {
     // Find maximum curvature for normalization
     let max_curvature = curvatures.iter().fold(0.0f32, |acc, &c| acc.max(c));
 
-    // Recursive simplification with adaptive epsilon
-    fn simplify_recursive(
-        points: &[Point],
-        curvatures: &[f32],
-        max_curvature: f32,
-        base_epsilon: f32,
-        start: usize,
-        end: usize,
-        result: &mut Vec<Point>,
-    ) {
-        if end <= start + 1 {
-            return;
+    // Helper function for perpendicular distance calculation
+    fn perpendicular_distance(point: &Point, line_start: &Point, line_end: &Point) -> f32 {
+        let dx = line_end.x - line_start.x;
+        let dy = line_end.y - line_start.y;
+
+        if dx == 0.0 && dy == 0.0 {
+            return ((point.x - line_start.x).powi(2) + (point.y - line_start.y).powi(2)).sqrt();
         }
 
-        let first = &points[start];
-        let last = &points[end];
+        let numerator = (dy * point.x - dx * point.y + line_end.x * line_start.y
+            - line_end.y * line_start.x)
+            .abs();
+        let denominator = (dx * dx + dy * dy).sqrt();
+
+        numerator / denominator
+    }
+
+    // Douglas-Peucker with the epsilon adapted to the worst point's curvature
+    douglas_peucker_by(polyline, |start, end| {
+        let first = &polyline[start];
+        let last = &polyline[end];
 
         let mut max_distance = 0.0;
         let mut max_index = start;
 
         // Find point with maximum deviation
         for i in (start + 1)..end {
-            let distance = perpendicular_distance(&points[i], first, last);
+            let distance = perpendicular_distance(&polyline[i], first, last);
             if distance > max_distance {
                 max_distance = distance;
                 max_index = i;
@@ -4901,68 +6417,107 @@ fn simplify_adaptive(polyline: &[Point], base_epsilon: f32) -> Vec<Point> {
         let curvature_factor = 0.2 + 0.8 * normalized_curvature; // Range: 0.2 to 1.0
         let adaptive_epsilon = base_epsilon * curvature_factor;
 
-        if max_distance > adaptive_epsilon {
-            // Point is significant, recurse on both sides
-            simplify_recursive(
-                points,
-                curvatures,
-                max_curvature,
-                base_epsilon,
-                start,
-                max_index,
-                result,
-            );
-            result.push(points[max_index]);
-            simplify_recursive(
-                points,
-                curvatures,
-                max_curvature,
-                base_epsilon,
-                max_index,
-                end,
-                result,
-            );
-        }
-    }
+        (max_distance > adaptive_epsilon).then_some(max_index)
+    })
+}
 
-    // Helper function for perpendicular distance calculation
-    fn perpendicular_distance(point: &Point, line_start: &Point, line_end: &Point) -> f32 {
-        let dx = line_end.x - line_start.x;
-        let dy = line_end.y - line_start.y;
+/// Integer pixel key used to match junction points between polylines
+fn junction_key(point: &Point) -> (i32, i32) {
+    (point.x.round() as i32, point.y.round() as i32)
+}
 
-        if dx == 0.0 && dy == 0.0 {
-            return ((point.x - line_start.x).powi(2) + (point.y - line_start.y).powi(2)).sqrt();
+/// Indices of vertices that must survive simplification to keep junctions connected
+///
+/// A vertex is fixed when another polyline passes through the same pixel (a
+/// crossing) or ends within one pixel of it (a T-junction). Endpoints are always
+/// kept by Douglas-Peucker, so only interior vertices are reported. Returns empty
+/// lists when `enabled` is false.
+fn junction_vertex_indices(polylines: &[Vec<Point>], enabled: bool) -> Vec<Vec<usize>> {
+    if !enabled {
+        return vec![Vec::new(); polylines.len()];
+    }
+
+    // Which polylines pass through each pixel, and which end there
+    let mut owners: HashMap<(i32, i32), Vec<usize>> = HashMap::new();
+    let mut endpoints: HashMap<(i32, i32), Vec<usize>> = HashMap::new();
+    for (index, polyline) in polylines.iter().enumerate() {
+        for point in polyline {
+            let entry = owners.entry(junction_key(point)).or_default();
+            if entry.last() != Some(&index) {
+                entry.push(index);
+            }
         }
+        if let (Some(first), Some(last)) = (polyline.first(), polyline.last()) {
+            endpoints.entry(junction_key(first)).or_default().push(index);
+            endpoints.entry(junction_key(last)).or_default().push(index);
+        }
+    }
 
-        let numerator = (dy * point.x - dx * point.y + line_end.x * line_start.y
-            - line_end.y * line_start.x)
-            .abs();
-        let denominator = (dx * dx + dy * dy).sqrt();
+    polylines
+        .iter()
+        .enumerate()
+        .map(|(index, polyline)| {
+            let interior = 1..polyline.len().saturating_sub(1);
+            interior
+                .filter(|&i| {
+                    let (x, y) = junction_key(&polyline[i]);
+                    let crossed = owners
+                        .get(&(x, y))
+                        .is_some_and(|owners| owners.iter().any(|&o| o != index));
+                    let touched = (-1..=1).any(|dy| {
+                        (-1..=1).any(|dx| {
+                            endpoints
+                                .get(&(x + dx, y + dy))
+                                .is_some_and(|ends| ends.iter().any(|&o| o != index))
+                        })
+                    });
+                    crossed || touched
+                })
+                .collect()
+        })
+        .collect()
+}
 
-        numerator / denominator
+/// Douglas-Peucker simplification that keeps the vertices at `fixed` indices
+///
+/// The polyline is split at each fixed vertex and every piece is simplified on
+/// its own, so shared junction points never move.
+fn simplify_with_fixed_vertices(
+    polyline: &[Point],
+    epsilon: f32,
+    fixed: &[usize],
+    max_depth: u32,
+) -> Vec<Point> {
+    if fixed.is_empty() {
+        return douglas_peucker_simplify(polyline, epsilon, max_depth);
     }
 
-    let mut result = vec![polyline[0]];
-    simplify_recursive(
-        polyline,
-        &curvatures,
-        max_curvature,
-        base_epsilon,
-        0,
-        polyline.len() - 1,
-        &mut result,
-    );
-    result.push(polyline[polyline.len() - 1]);
+    let mut result: Vec<Point> = Vec::with_capacity(polyline.len());
+    let mut start = 0;
+    for end in fixed
+        .iter()
+        .copied()
+        .filter(|&i| i > 0 && i < polyline.len())
+        .chain(std::iter::once(polyline.len() - 1))
+    {
+        if end <= start {
+            continue;
+        }
+        let piece = douglas_peucker_simplify(&polyline[start..=end], epsilon, max_depth);
+        let skip = usize::from(!result.is_empty());
+        result.extend(piece.into_iter().skip(skip));
+        start = end;
+    }
 
     result
 }
 
 /// Douglas-Peucker polyline simplification
-fn douglas_peucker_simplify(polyline: &[Point], epsilon: f32) -> Vec<Point> {
-    if polyline.len() <= 2 {
-        return polyline.to_vec();
-    }
-
+///
+/// Splitting stops at `max_depth`; deeper paths (long zigzags that split one
+/// point at a time) are simplified with Visvalingam-Whyatt instead, using the
+/// area of a triangle `epsilon` high over a `2 * epsilon` base as the threshold.
+fn douglas_peucker_simplify(polyline: &[Point], epsilon: f32, max_depth: u32) -> Vec<Point> {
     fn perpendicular_distance(point: &Point, line_start: &Point, line_end: &Point) -> f32 {
         let dx = line_end.x - line_start.x;
         let dy = line_end.y - line_start.y;
@@ -4980,55 +6535,28 @@ fn douglas_peucker_simplify(polyline: &[Point], epsilon: f32) -> Vec<Point> {
         numerator / denominator
     }
 
-    fn dp_recursive(points: &[Point], epsilon: f32, result: &mut Vec<Point>) {
-        // CRITICAL FIX: Enhanced bounds checking to prevent "unreachable executed" in multipass processing
-        if points.len() < 2 {
-            return;
-        }
-
-        // Additional safety check to prevent corruption-induced panics
-        if points.is_empty() {
-            return;
-        }
-
-        let first = &points[0];
-        let last = &points[points.len() - 1];
-
+    let simplified = douglas_peucker_within_depth(polyline, max_depth, |start, end| {
+        let (first, last) = (&polyline[start], &polyline[end]);
         let mut max_distance = 0.0;
-        let mut max_index = 0;
-
-        // CRITICAL FIX: More robust loop bounds to prevent integer underflow
-        if points.len() > 2 {
-            for (i, point) in points.iter().enumerate().skip(1).take(points.len() - 2) {
-                let distance = perpendicular_distance(point, first, last);
-                if distance > max_distance {
-                    max_distance = distance;
-                    max_index = i;
-                }
-            }
-        }
-
-        if max_distance > epsilon && points.len() > 2 {
-            // CRITICAL FIX: Validate indices before slicing to prevent bounds panic
-            if max_index < points.len() && max_index > 0 {
-                dp_recursive(&points[0..=max_index], epsilon, result);
-                result.pop(); // Remove duplicate point
-                dp_recursive(&points[max_index..], epsilon, result);
-            } else {
-                // Fallback: just add endpoints if indices are invalid
-                result.push(*first);
-                result.push(*last);
+        let mut max_index = start;
+        for (i, point) in polyline.iter().enumerate().take(end).skip(start + 1) {
+            let distance = perpendicular_distance(point, first, last);
+            if distance > max_distance {
+                max_distance = distance;
+                max_index = i;
             }
-        } else {
-            // Keep only first and last points
-            result.push(*first);
-            result.push(*last);
         }
+        (max_distance > epsilon).then_some(max_index)
+    });
+    if let Some(simplified) = simplified {
+        return simplified;
     }
 
-    let mut result = Vec::new();
-    dp_recursive(polyline, epsilon, &mut result);
-    result
+    log::warn!(
+        "Douglas-Peucker exceeded depth {max_depth} on a {}-point path, using Visvalingam-Whyatt",
+        polyline.len()
+    );
+    visvalingam_whyatt_simplify(polyline, (epsilon * epsilon) as f64)
 }
 
 /// Calculate total length of a polyline
@@ -5492,6 +7020,9 @@ fn create_stroke_path_with_color(
         fill: "none".to_string(),
         stroke: stroke_color,
         stroke_width,
+        stroke_opacity: None,
+        source_color: None,
+        region_metadata: None,
     }
 }
 
@@ -5979,32 +7510,44 @@ fn schedule_directional_passes(
     config: &TraceLowConfig,
 ) -> Vec<ProcessingDirection> {
     let mut candidates = Vec::new();
+    // A pass only switches relative to the previous frame once its benefit crosses
+    // the threshold by the hysteresis margin, so borderline images don't flip passes
+    // on and off between similar frames
+    let margin = config.directional_hysteresis.max(0.0);
+    let required =
+        |direction: ProcessingDirection| match config.previous_directional_passes.as_deref() {
+            Some(previous) if previous.contains(&direction) => {
+                config.directional_strength_threshold - margin
+            }
+            Some(_) => config.directional_strength_threshold + margin,
+            None => config.directional_strength_threshold,
+        };
 
     // Add reverse pass if enabled and beneficial
     if config.enable_reverse_pass {
-        if analysis.direction_benefits[1] >= config.directional_strength_threshold {
+        if analysis.direction_benefits[1] >= required(ProcessingDirection::Reverse) {
             log::info!(
                 "✓ Reverse pass scheduled (benefit: {:.2} >= threshold: {:.2})",
                 analysis.direction_benefits[1],
-                config.directional_strength_threshold
+                required(ProcessingDirection::Reverse)
             );
             candidates.push((ProcessingDirection::Reverse, analysis.direction_benefits[1]));
         } else {
             log::info!(
                 "⏭ Reverse pass skipped (benefit: {:.2} < threshold: {:.2})",
                 analysis.direction_benefits[1],
-                config.directional_strength_threshold
+                required(ProcessingDirection::Reverse)
             );
         }
     }
 
     // Add diagonal passes if enabled and beneficial
     if config.enable_diagonal_pass {
-        if analysis.direction_benefits[2] >= config.directional_strength_threshold {
+        if analysis.direction_benefits[2] >= required(ProcessingDirection::DiagonalNW) {
             log::info!(
                 "✓ Diagonal NW pass scheduled (benefit: {:.2} >= threshold: {:.2})",
                 analysis.direction_benefits[2],
-                config.directional_strength_threshold
+                required(ProcessingDirection::DiagonalNW)
             );
             candidates.push((
                 ProcessingDirection::DiagonalNW,
@@ -6014,15 +7557,15 @@ fn schedule_directional_passes(
             log::info!(
                 "⏭ Diagonal NW pass skipped (benefit: {:.2} < threshold: {:.2})",
                 analysis.direction_benefits[2],
-                config.directional_strength_threshold
+                required(ProcessingDirection::DiagonalNW)
             );
         }
 
-        if analysis.direction_benefits[3] >= config.directional_strength_threshold {
+        if analysis.direction_benefits[3] >= required(ProcessingDirection::DiagonalNE) {
             log::info!(
                 "✓ Diagonal NE pass scheduled (benefit: {:.2} >= threshold: {:.2})",
                 analysis.direction_benefits[3],
-                config.directional_strength_threshold
+                required(ProcessingDirection::DiagonalNE)
             );
             candidates.push((
                 ProcessingDirection::DiagonalNE,
@@ -6032,7 +7575,7 @@ fn schedule_directional_passes(
             log::info!(
                 "⏭ Diagonal NE pass skipped (benefit: {:.2} < threshold: {:.2})",
                 analysis.direction_benefits[3],
-                config.directional_strength_threshold
+                required(ProcessingDirection::DiagonalNE)
             );
         }
     }
@@ -6069,13 +7612,15 @@ fn execute_directional_pass(
             // This shouldn't be called directly, but handle gracefully
             trace_edge(image, &thresholds, config)
         }
-        ProcessingDirection::Reverse => {
-            // Implement reverse processing by modifying edge detection sensitivity
-            trace_edge_directional(image, direction, &thresholds, config)
-        }
-        ProcessingDirection::DiagonalNW | ProcessingDirection::DiagonalNE => {
-            // Implement diagonal processing with adjusted parameters
-            trace_edge_directional(image, direction, &thresholds, config)
+        ProcessingDirection::Reverse
+        | ProcessingDirection::DiagonalNW
+        | ProcessingDirection::DiagonalNE => {
+            // Reverse passes adjust edge sensitivity, diagonal passes their blur; both
+            // escalate like the base pass so noise can't return through them
+            let paths = trace_edge_directional(image, direction, &thresholds, config)?;
+            escalate_if_noise_dominated(paths, &thresholds, config, |escalated| {
+                trace_edge_directional(image, direction, escalated, config)
+            })
         }
     }
 }
@@ -6088,6 +7633,15 @@ fn trace_edge_directional(
     config: &TraceLowConfig,
 ) -> Result<Vec<SvgPath>, VectorizeError> {
     log::debug!("Running directional edge backend: {direction:?}");
+    validate_detail_map(image, config)?;
+
+    let dual_masked;
+    let image = if config.dual_background {
+        dual_masked = apply_dual_background_removal(image, "edge");
+        &dual_masked
+    } else {
+        image
+    };
 
     // Convert to grayscale
     let gray = rgba_to_gray(image);
@@ -6103,15 +7657,28 @@ fn trace_edge_directional(
     let blurred = gaussian_blur(&gray, sigma);
 
     // Apply directional Canny edge detection
-    let edges = canny_edge_detection_directional(&blurred, direction, thresholds);
+    let edges = canny_edge_detection_directional(
+        &blurred,
+        direction,
+        thresholds,
+        config.detail_map.as_ref(),
+    );
 
     // Link edges with direction-aware processing
     let polylines = link_edges_to_polylines_directional(&edges, direction);
 
     // Simplify and filter using execution abstraction
     let stroke_width = calculate_stroke_width(image, config.stroke_px_at_1080p);
-    let svg_paths: Vec<SvgPath> = execute_parallel_filter_map(polylines, |polyline| {
-        let simplified = douglas_peucker_simplify(&polyline, thresholds.dp_epsilon_px);
+    let polylines = snap_straight_polylines(polylines, config);
+    let fixed_vertices = junction_vertex_indices(&polylines, config.preserve_junction_topology);
+    let polylines = polylines.into_iter().zip(fixed_vertices);
+    let svg_paths: Vec<SvgPath> = execute_parallel_filter_map(polylines, |(polyline, fixed)| {
+        let simplified = simplify_with_fixed_vertices(
+            &polyline,
+            thresholds.dp_epsilon_px,
+            &fixed,
+            config.max_simplify_depth,
+        );
         let length = calculate_polyline_length(&simplified);
 
         if length >= thresholds.min_stroke_length_px * 1.2 {
@@ -6135,6 +7702,7 @@ fn canny_edge_detection_directional(
     image: &GrayImage,
     direction: ProcessingDirection,
     thresholds: &ThresholdMapping,
+    detail_map: Option<&GrayImage>,
 ) -> GrayImage {
     // Adjust thresholds based on direction
     let (low_threshold, high_threshold) = match direction {
@@ -6161,7 +7729,7 @@ fn canny_edge_detection_directional(
     // For now, use standard Canny with adjusted thresholds
     // Full directional implementation would modify the non-maximum suppression
     // and hysteresis stages to favor certain orientations
-    canny_edge_detection(image, low_threshold, high_threshold)
+    canny_edge_detection_with_detail_map(image, low_threshold, high_threshold, detail_map)
 }
 
 /// Direction-aware edge linking
@@ -6232,10 +7800,14 @@ fn is_diagonal_oriented(polyline: &[Point]) -> bool {
 }
 
 /// Optimized merge directional results with spatial indexing
+///
+/// Paths duplicating an already kept path are combined according to
+/// `config.multipass_merge`.
 fn merge_directional_results(
+    image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
     base_paths: Vec<SvgPath>,
     directional_paths: Vec<Vec<SvgPath>>,
-    _config: &TraceLowConfig,
+    config: &TraceLowConfig,
     hand_drawn_config: Option<&crate::algorithms::visual::hand_drawn::HandDrawnConfig>,
 ) -> Vec<SvgPath> {
     log::debug!(
@@ -6268,9 +7840,18 @@ fn merge_directional_results(
     // Create spatial index with appropriate cell size (50 pixels works well)
     let mut spatial_index = SpatialIndex::new(bounds, 50.0);
 
+    // Edge responses weigh duplicates against each other; union mode never needs them
+    let gray = (config.multipass_merge != MultipassMerge::Union).then(|| rgba_to_gray(image));
+    let response = |path: &SvgPath| {
+        gray.as_ref()
+            .and_then(|gray| mean_edge_response(path, gray))
+            .unwrap_or(0.0)
+    };
+
     // Build spatial index - O(n) operation
     let mut final_paths = Vec::new();
     let mut final_cached_data = Vec::new();
+    let mut final_responses = Vec::new();
 
     let all_paths_len = all_paths.len();
     for (path, cached) in all_paths.into_iter().zip(cached_data.into_iter()) {
@@ -6282,23 +7863,48 @@ fn merge_directional_results(
         // Find potentially overlapping paths using spatial index - O(k) where k << n
         let candidates = spatial_index.find_overlapping(cached.bbox);
 
-        let mut is_duplicate = false;
-
         // Only check against spatially nearby paths
-        for &candidate_idx in &candidates {
-            if candidate_idx < final_cached_data.len()
+        let duplicate_of = candidates.into_iter().find(|&candidate_idx| {
+            candidate_idx < final_cached_data.len()
                 && cached.is_geometrically_similar(&final_cached_data[candidate_idx], 8.0)
-            {
-                is_duplicate = true;
-                break;
-            }
-        }
+        });
 
-        if !is_duplicate {
+        let Some(kept_idx) = duplicate_of else {
             // Add to spatial index and final results
             spatial_index.insert(final_paths.len(), cached.bbox);
+            final_responses.push(response(&path));
             final_paths.push(path);
             final_cached_data.push(cached);
+            continue;
+        };
+
+        let replacement = match config.multipass_merge {
+            MultipassMerge::Union => None,
+            MultipassMerge::MaxResponse => {
+                let path_response = response(&path);
+                (path_response > final_responses[kept_idx]).then(|| {
+                    final_responses[kept_idx] = path_response;
+                    path
+                })
+            }
+            MultipassMerge::WeightedAverage => {
+                let path_response = response(&path);
+                let averaged = average_duplicate_paths(
+                    &final_paths[kept_idx],
+                    &path,
+                    (final_responses[kept_idx], path_response),
+                    config.svg_precision,
+                );
+                // Later duplicates weigh against everything merged so far
+                final_responses[kept_idx] += path_response;
+                averaged
+            }
+        };
+        if let Some(replacement) = replacement {
+            let replacement_cached = CachedPathData::from_svg_path(&replacement);
+            spatial_index.insert(kept_idx, replacement_cached.bbox);
+            final_paths[kept_idx] = replacement;
+            final_cached_data[kept_idx] = replacement_cached;
         }
     }
 
@@ -6317,6 +7923,70 @@ fn merge_directional_results(
     apply_artistic_enhancements(final_paths, hand_drawn_config)
 }
 
+/// Weight given to each detection when neither has a measurable edge response
+const MIN_MERGE_WEIGHT: f32 = 1e-3;
+
+/// Average two detections of the same edge into one stroke
+///
+/// Every point of `kept` moves towards its closest point on `other`, in proportion
+/// to `other`'s share of the combined weights. Returns `None` when either path
+/// data cannot be flattened into a single polyline.
+fn average_duplicate_paths(
+    kept: &SvgPath,
+    other: &SvgPath,
+    (kept_weight, other_weight): (f32, f32),
+    precision: u8,
+) -> Option<SvgPath> {
+    let single_polyline = |data: &str| match crate::svg::flatten_path_data(data)?.as_slice() {
+        [(points, _)] if points.len() >= 2 => Some(points.clone()),
+        _ => None,
+    };
+    let kept_points = single_polyline(&kept.data)?;
+    let other_points = single_polyline(&other.data)?;
+
+    let (kept_weight, other_weight) = (
+        kept_weight.max(MIN_MERGE_WEIGHT),
+        other_weight.max(MIN_MERGE_WEIGHT),
+    );
+    let share = other_weight / (kept_weight + other_weight);
+    let averaged: Vec<String> = kept_points
+        .iter()
+        .map(|&(x, y)| {
+            let (cx, cy) = closest_point_on_polyline((x, y), &other_points);
+            format!(
+                "{:.prec$} {:.prec$}",
+                x + (cx - x) * share,
+                y + (cy - y) * share,
+                prec = precision as usize
+            )
+        })
+        .collect();
+
+    Some(SvgPath {
+        data: format!("M {}", averaged.join(" L ")),
+        ..kept.clone()
+    })
+}
+
+/// Closest point to `point` on any segment of `polyline`
+fn closest_point_on_polyline(point: (f32, f32), polyline: &[(f32, f32)]) -> (f32, f32) {
+    polyline
+        .windows(2)
+        .map(|segment| {
+            let ((ax, ay), (bx, by)) = (segment[0], segment[1]);
+            let (dx, dy) = (bx - ax, by - ay);
+            let length_sq = dx * dx + dy * dy;
+            let t = if length_sq > 0.0 {
+                (((point.0 - ax) * dx + (point.1 - ay) * dy) / length_sq).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            (ax + t * dx, ay + t * dy)
+        })
+        .min_by(|a, b| point_distance(*a, point).total_cmp(&point_distance(*b, point)))
+        .unwrap_or(polyline[0])
+}
+
 /// Apply artistic enhancements for hand-drawn aesthetic
 fn apply_artistic_enhancements(
     paths: Vec<SvgPath>,
@@ -6347,6 +8017,146 @@ fn apply_artistic_enhancements(
     enhanced_paths
 }
 
+/// Lowest stroke opacity assigned to the weakest edge when fading by edge strength
+const MIN_EDGE_STRENGTH_OPACITY: f32 = 0.2;
+
+/// Set `stroke_opacity` on stroked paths from the average gradient magnitude along them
+///
+/// Strengths are normalized against the strongest path, so the most prominent line
+/// stays fully opaque and weaker lines fade towards `MIN_EDGE_STRENGTH_OPACITY`.
+fn apply_edge_strength_opacity(paths: &mut [SvgPath], image: &ImageBuffer<Rgba<u8>, Vec<u8>>) {
+    if paths.is_empty() || image.width() == 0 || image.height() == 0 {
+        return;
+    }
+
+    let gray = image::imageops::grayscale(image);
+
+    let strengths: Vec<Option<f32>> = paths
+        .iter()
+        .map(|path| {
+            if path.stroke == "none" || path.fill != "none" {
+                return None;
+            }
+            mean_edge_response(path, &gray)
+        })
+        .collect();
+
+    let strongest = strengths.iter().flatten().copied().fold(0.0f32, f32::max);
+    if strongest <= 0.0 {
+        return;
+    }
+
+    for (path, strength) in paths.iter_mut().zip(strengths) {
+        if let Some(strength) = strength {
+            let ratio = (strength / strongest).clamp(0.0, 1.0);
+            path.stroke_opacity =
+                Some(MIN_EDGE_STRENGTH_OPACITY + (1.0 - MIN_EDGE_STRENGTH_OPACITY) * ratio);
+        }
+    }
+
+    log::debug!("Applied edge-strength opacity to {} paths", paths.len());
+}
+
+/// Average gradient magnitude under a path's vertices, or `None` when its data
+/// has no parseable points
+fn mean_edge_response(path: &SvgPath, gray: &GrayImage) -> Option<f32> {
+    if gray.width() == 0 || gray.height() == 0 {
+        return None;
+    }
+    let (max_x, max_y) = (gray.width() - 1, gray.height() - 1);
+    let points = crate::algorithms::visual::hand_drawn::parse_polyline_points(&path.data)?;
+    if points.is_empty() {
+        return None;
+    }
+    let total: f32 = points
+        .iter()
+        .map(|&(x, y)| {
+            let px = (x.round().max(0.0) as u32).min(max_x);
+            let py = (y.round().max(0.0) as u32).min(max_y);
+            crate::algorithms::edges::gradients::calculate_gradient_magnitude(gray, px, py)
+        })
+        .sum();
+    Some(total / points.len() as f32)
+}
+
+/// Set `source_color` on every path to the average image color it covers
+///
+/// Filled paths average the pixels inside their outline (even-odd rule); strokes,
+/// shapes and outlines enclosing no pixel centers average the pixels under their
+/// sample points. Rendered colors are left untouched.
+fn apply_source_colors(paths: &mut [SvgPath], image: &ImageBuffer<Rgba<u8>, Vec<u8>>) {
+    if image.width() == 0 || image.height() == 0 {
+        return;
+    }
+    for path in paths.iter_mut() {
+        path.source_color = source_region_color(path, image);
+    }
+    log::debug!("Sampled source colors for {} paths", paths.len());
+}
+
+/// Average color under a single path, or `None` when it covers no pixels
+fn source_region_color(path: &SvgPath, image: &ImageBuffer<Rgba<u8>, Vec<u8>>) -> Option<String> {
+    let (width, height) = image.dimensions();
+    let mut pixels = Vec::new();
+    if path.fill != "none" && path.element_type == SvgElementType::Path {
+        let rings = crate::svg::flatten_path_data(&path.data).unwrap_or_default();
+        let ys = rings.iter().flat_map(|(points, _)| points.iter().map(|&(_, y)| y));
+        let (min_y, max_y) = ys.fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), y| {
+            (lo.min(y), hi.max(y))
+        });
+        let first_row = (min_y - 0.5).ceil().max(0.0) as u32;
+        let end_row = ((max_y - 0.5).floor() + 1.0).clamp(0.0, height as f32) as u32;
+        for y in first_row..end_row {
+            // Scanline through pixel centers; every ring is implicitly closed
+            let scan_y = y as f32 + 0.5;
+            let mut crossings: Vec<f32> = rings
+                .iter()
+                .flat_map(|(points, _)| {
+                    let next = points.iter().cycle().skip(1);
+                    points
+                        .iter()
+                        .zip(next)
+                        .filter(move |(&(_, y0), &(_, y1))| (y0 <= scan_y) != (y1 <= scan_y))
+                        .map(move |(&(x0, y0), &(x1, y1))| {
+                            x0 + (scan_y - y0) / (y1 - y0) * (x1 - x0)
+                        })
+                })
+                .collect();
+            crossings.sort_by(f32::total_cmp);
+            for span in crossings.as_chunks::<2>().0 {
+                let start = (span[0] - 0.5).ceil().clamp(0.0, width as f32) as u32;
+                let end = (span[1] - 0.5).ceil().clamp(0.0, width as f32) as u32;
+                pixels.extend((start..end).map(|x| (x, y)));
+            }
+        }
+    }
+    if pixels.is_empty() {
+        pixels = crate::svg::element_sample_points(path)
+            .into_iter()
+            .filter(|&(x, y)| x.is_finite() && y.is_finite())
+            .map(|(x, y)| {
+                let px = (x.round().max(0.0) as u32).min(width - 1);
+                let py = (y.round().max(0.0) as u32).min(height - 1);
+                (px, py)
+            })
+            .collect();
+    }
+    if pixels.is_empty() {
+        return None;
+    }
+
+    let mut sum = [0u64; 3];
+    for &(x, y) in &pixels {
+        let pixel = image.get_pixel(x, y);
+        for (total, &channel) in sum.iter_mut().zip(&pixel.0[..3]) {
+            *total += channel as u64;
+        }
+    }
+    let count = pixels.len() as u64;
+    let average = sum.map(|total| ((total + count / 2) / count) as u8);
+    Some(rgba_to_hex_string(&Rgba([average[0], average[1], average[2], 255])))
+}
+
 /// Comprehensive performance profiling for multipass processing
 #[derive(Debug)]
 #[allow(dead_code)]
@@ -6477,7 +8287,7 @@ fn profile_multipass_performance(
     // Merge phase
     let merge_start = Instant::now();
     let final_paths =
-        merge_directional_results(conservative_paths, directional_paths, config, None);
+        merge_directional_results(image, conservative_paths, directional_paths, config, None);
     profile.merge_phase = merge_start.elapsed();
     profile.path_counts.final_paths = final_paths.len();
 
@@ -7530,7 +9340,7 @@ mod tests {
             Point { x: 6.0, y: 0.0 },
         ];
 
-        let simplified = douglas_peucker_simplify(&polyline, 0.5);
+        let simplified = douglas_peucker_simplify(&polyline, 0.5, 512);
 
         // Should remove some intermediate points but keep the significant deviation
         assert!(simplified.len() < polyline.len());
@@ -7544,6 +9354,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_douglas_peucker_depth_guard_falls_back_to_visvalingam() {
+        // Zigzag with shrinking amplitude: every split peels off a single point,
+        // so unguarded recursion would be as deep as the path is long
+        let zigzag = |n: usize| -> Vec<Point> {
+            (0..=n)
+                .map(|i| {
+                    let amplitude = (n - i) as f32;
+                    let y = if i % 2 == 0 { amplitude } else { -amplitude };
+                    Point::new(i as f32, y)
+                })
+                .collect()
+        };
+
+        let short = zigzag(64);
+        assert_eq!(douglas_peucker_simplify(&short, 0.5, 512), short);
+
+        // Splitting runs on an explicit stack, so even an unbounded depth is safe
+        let deep = zigzag(10_000);
+        assert_eq!(douglas_peucker_simplify(&deep, 0.5, u32::MAX), deep);
+
+        let long = zigzag(50_000);
+        let simplified = douglas_peucker_simplify(&long, 0.5, 512);
+        assert_eq!(simplified, visvalingam_whyatt_simplify(&long, 0.25));
+        assert_eq!(simplified.first(), long.first());
+        assert_eq!(simplified.last(), long.last());
+        assert!(simplified
+            .iter()
+            .all(|p| p.x.is_finite() && p.y.is_finite()));
+    }
+
     #[test]
     fn test_trace_dots_backend() {
         use image::RgbaImage;
@@ -7694,6 +9535,1244 @@ mod tests {
             "Main vectorize_trace_low should work with dots backend"
         );
     }
+
+    #[test]
+    fn test_prebinary_despeckle_reduces_paths() {
+        use image::RgbaImage;
+
+        // Bright bar on a dark background, sprinkled with short dashes of the same stroke
+        // weight that survive branch pruning unless removed before thinning
+        let img = RgbaImage::from_fn(160, 160, |x, y| {
+            let in_bar = (77..83).contains(&y) && (20..140).contains(&x);
+            let in_speck = (x % 30 < 20) && (y % 20 < 6) && !(60..100).contains(&y);
+            if in_bar || in_speck {
+                Rgba([255, 255, 255, 255])
+            } else {
+                Rgba([0, 0, 0, 255])
+            }
+        });
+
+        let base_config = TraceLowConfig {
+            backend: TraceBackend::Centerline,
+            enable_adaptive_threshold: false,
+            ..Default::default()
+        };
+        let despeckle_config = TraceLowConfig {
+            prebinary_despeckle_px: 200,
+            ..base_config.clone()
+        };
+
+        // Less work reaches thinning and skeleton extraction once the specks are gone
+        let mask = GrayImage::from_fn(160, 160, |x, y| Luma([img.get_pixel(x, y)[0]]));
+        let despeckled = remove_small_components(&mask, despeckle_config.prebinary_despeckle_px);
+        let noisy_skeleton = guo_hall_thinning(&mask);
+        let clean_skeleton = guo_hall_thinning(&despeckled);
+        assert!(
+            count_skeleton_pixels(&clean_skeleton) < count_skeleton_pixels(&noisy_skeleton),
+            "Despeckle should shrink the skeleton ({} vs {} pixels)",
+            count_skeleton_pixels(&clean_skeleton),
+            count_skeleton_pixels(&noisy_skeleton)
+        );
+        assert!(
+            extract_skeleton_polylines_improved(&clean_skeleton).len()
+                < extract_skeleton_polylines_improved(&noisy_skeleton).len(),
+            "Despeckle should leave fewer polylines to prune and simplify"
+        );
+
+        let noisy_paths = vectorize_trace_low(&img, &base_config, None).unwrap();
+        let clean_paths = vectorize_trace_low(&img, &despeckle_config, None).unwrap();
+
+        assert!(!clean_paths.is_empty(), "Main bar should still be traced");
+        assert!(
+            clean_paths.len() < noisy_paths.len(),
+            "Despeckle should reduce path count ({} vs {})",
+            clean_paths.len(),
+            noisy_paths.len()
+        );
+    }
+
+    #[test]
+    fn test_edge_strength_opacity_fades_weak_edges() {
+        // White canvas with a black bar (strong edge) and a light gray bar (weak edge)
+        let image = ImageBuffer::from_fn(40, 40, |x, _| {
+            if (10..15).contains(&x) {
+                Rgba([0, 0, 0, 255])
+            } else if (25..30).contains(&x) {
+                Rgba([220, 220, 220, 255])
+            } else {
+                Rgba([255, 255, 255, 255])
+            }
+        });
+
+        let mut paths = vec![
+            SvgPath::new_stroke("M 10 5 L 10 35".to_string(), "#000000", 1.0),
+            SvgPath::new_stroke("M 25 5 L 25 35".to_string(), "#000000", 1.0),
+            SvgPath::new_fill("M 0 0 L 5 0 L 5 5 Z".to_string(), "#000000"),
+        ];
+        apply_edge_strength_opacity(&mut paths, &image);
+
+        let strong = paths[0].stroke_opacity.expect("strong edge should get an opacity");
+        let weak = paths[1].stroke_opacity.expect("weak edge should get an opacity");
+        assert!((strong - 1.0).abs() < 1e-6);
+        assert!(weak < strong, "weak edge opacity {weak} should be below {strong}");
+        assert!(weak >= MIN_EDGE_STRENGTH_OPACITY);
+        assert_eq!(paths[2].stroke_opacity, None, "filled paths are left untouched");
+    }
+
+    #[test]
+    fn test_source_colors_sample_strokes_and_fill_interiors() {
+        // Green bar on the left, red block on the right of a white canvas
+        let image = ImageBuffer::from_fn(40, 40, |x, _| {
+            if (10..13).contains(&x) {
+                Rgba([20, 140, 40, 255])
+            } else if x >= 20 {
+                Rgba([200, 30, 30, 255])
+            } else {
+                Rgba([255, 255, 255, 255])
+            }
+        });
+
+        let mut paths = vec![
+            SvgPath::new_stroke("M 11 5 L 11 35".to_string(), "#000000", 1.0),
+            // Outline straddles the white/red boundary but encloses only red pixels
+            SvgPath::new_fill("M 20 10 L 30 10 L 30 20 L 20 20 Z".to_string(), "#000000"),
+            SvgPath::new_fill("M 1 1 L 1.2 1 L 1.2 1.2 Z".to_string(), "#000000"),
+        ];
+        apply_source_colors(&mut paths, &image);
+
+        assert_eq!(paths[0].source_color.as_deref(), Some("#148c28"));
+        assert_eq!(paths[1].source_color.as_deref(), Some("#c81e1e"));
+        // Slivers without interior pixels fall back to their outline points
+        assert_eq!(paths[2].source_color.as_deref(), Some("#ffffff"));
+        assert!(paths
+            .iter()
+            .all(|p| p.fill == "#000000" || p.stroke == "#000000"));
+    }
+
+    #[test]
+    fn test_monochrome_trace_emits_source_color_attributes() {
+        let image = ImageBuffer::from_fn(64, 64, |x, y| {
+            if (16..48).contains(&x) && (16..48).contains(&y) {
+                Rgba([30, 60, 200, 255])
+            } else {
+                Rgba([255, 255, 255, 255])
+            }
+        });
+        let config = TraceLowConfig {
+            emit_source_color: true,
+            ..TraceLowConfig::default()
+        };
+        let paths = vectorize_trace_low(&image, &config, None).unwrap();
+        assert!(!paths.is_empty());
+        assert!(
+            paths.iter().all(|p| p.stroke == "#000000"),
+            "rendering stays monochrome"
+        );
+        assert!(paths.iter().all(|p| p.source_color.is_some()));
+
+        let svg = crate::vectorize_trace_low_rgba(&image, &config, None).unwrap();
+        assert_eq!(
+            svg.matches("data-source-color=").count(),
+            svg.matches("<path").count()
+        );
+        let plain =
+            crate::vectorize_trace_low_rgba(&image, &TraceLowConfig::default(), None).unwrap();
+        assert!(!plain.contains("data-source-color"));
+    }
+
+    #[test]
+    fn test_detect_background_removal_mask_matches_border() {
+        // Light border around a dark centre square
+        let image = ImageBuffer::from_fn(30, 30, |x, y| {
+            if (10..20).contains(&x) && (10..20).contains(&y) {
+                Rgba([20, 20, 20, 255])
+            } else {
+                Rgba([240, 240, 240, 255])
+            }
+        });
+
+        let mask = detect_background_removal_mask(&image, &TraceLowConfig::default());
+        assert_eq!(mask.len(), 30 * 30);
+        assert!(mask[0], "corner should be background");
+        assert!(mask[5 * 30 + 25], "border region should be background");
+        assert!(!mask[15 * 30 + 15], "centre square should be foreground");
+    }
+
+    #[test]
+    fn test_slic_convergence_threshold_exits_early() {
+        let (width, height) = (60, 60);
+        let flat = vec![LabColor::new(50.0, 0.0, 0.0); width * height];
+        let max_iterations = 10;
+
+        let (labels, iterations) = slic_segmentation(
+            &flat,
+            width,
+            height,
+            16,
+            10.0,
+            max_iterations,
+            0.5,
+            SuperpixelInitPattern::Square,
+            false,
+        );
+        assert_eq!(labels.len(), width * height);
+        assert!(
+            iterations < max_iterations,
+            "expected early exit, ran {iterations} iterations"
+        );
+
+        let (_, iterations_without_threshold) = slic_segmentation(
+            &flat,
+            width,
+            height,
+            16,
+            10.0,
+            max_iterations,
+            0.0,
+            SuperpixelInitPattern::Square,
+            false,
+        );
+        assert!(iterations < iterations_without_threshold);
+    }
+
+    #[test]
+    fn test_slic_iterations_used_reach_telemetry() {
+        let image = ImageBuffer::from_pixel(60, 60, Rgba([120, 120, 120, 255]));
+        let config = TraceLowConfig {
+            backend: TraceBackend::Superpixel,
+            superpixel_slic_iterations: 10,
+            slic_convergence_threshold: 0.5,
+            ..TraceLowConfig::default()
+        };
+
+        let (result, record) =
+            crate::telemetry::capture(|| vectorize_trace_low(&image, &config, None));
+        assert!(result.is_ok());
+        assert_eq!(record.resolved.slic_iters, Some(10));
+        let used = record.resolved.slic_iters_used.unwrap();
+        assert!((1..10).contains(&used), "ran {used} iterations");
+
+        // Nothing is recorded outside a capture
+        let (_, idle) = crate::telemetry::capture(|| ());
+        assert_eq!(idle.resolved.slic_iters_used, None);
+    }
+
+    #[test]
+    fn test_saliency_seeding_concentrates_superpixels_on_subject() {
+        // Flat background with a textured subject in the central quarter
+        let (width, height) = (120, 120);
+        let in_subject = |x: usize, y: usize| (30..90).contains(&x) && (30..90).contains(&y);
+        let lab_image: Vec<LabColor> = (0..width * height)
+            .map(|i| {
+                let (x, y) = (i % width, i / width);
+                if in_subject(x, y) {
+                    let texture = ((x * 7 + y * 13) % 11) as f32 * 6.0;
+                    LabColor::new(20.0 + texture, texture - 30.0, 15.0)
+                } else {
+                    LabColor::new(80.0, 0.0, 0.0)
+                }
+            })
+            .collect();
+        let count = 60;
+        let subject_regions = |saliency_seeding: bool| {
+            let (labels, _) = slic_segmentation(
+                &lab_image,
+                width,
+                height,
+                count,
+                10.0,
+                10,
+                0.0,
+                SuperpixelInitPattern::Square,
+                saliency_seeding,
+            );
+            let mut subject_labels: Vec<usize> = labels
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| in_subject(i % width, i / width))
+                .map(|(_, &label)| label)
+                .collect();
+            subject_labels.sort_unstable();
+            subject_labels.dedup();
+            subject_labels.len()
+        };
+
+        let s = ((width * height) as f32 / count as f32).sqrt() as usize;
+        let seeds = initialize_saliency_seeded_centers(&lab_image, width, height, count, s);
+        assert!(seeds.len().abs_diff(count) <= 2, "placed {} seeds", seeds.len());
+        assert!(seeds.iter().any(|c| !in_subject(c.x as usize, c.y as usize)));
+
+        let uniform = subject_regions(false);
+        let salient = subject_regions(true);
+        assert!(
+            salient as f32 >= uniform as f32 * 1.5,
+            "saliency seeding gave {salient} subject regions vs {uniform} uniform"
+        );
+    }
+
+    #[test]
+    fn test_slic_parallel_assignment_matches_serial() {
+        let (width, height) = (90, 70);
+        let lab_image: Vec<LabColor> = (0..width * height)
+            .map(|i| {
+                let (x, y) = ((i % width) as f32, (i / width) as f32);
+                LabColor::new((x * 0.7 + y * 0.3) % 100.0, (x - y) * 0.4, (x * y) % 37.0 - 18.0)
+            })
+            .collect();
+        let s = ((width * height) as f32 / 30.0).sqrt() as usize;
+        let mut clusters = initialize_cluster_centers(
+            &lab_image,
+            width,
+            height,
+            30,
+            s,
+            SuperpixelInitPattern::Square,
+        );
+
+        let mut serial = (vec![0; width * height], vec![f32::INFINITY; width * height]);
+        let mut parallel = serial.clone();
+        for _ in 0..3 {
+            let serial_changed = slic_assign_pixels(
+                &clusters, &lab_image, width, height, s, 10.0, &mut serial.0, &mut serial.1, false,
+            );
+            let parallel_changed = slic_assign_pixels(
+                &clusters, &lab_image, width, height, s, 10.0, &mut parallel.0, &mut parallel.1, true,
+            );
+            assert_eq!(serial_changed, parallel_changed);
+            assert_eq!(serial.0, parallel.0);
+            assert_eq!(serial.1, parallel.1);
+
+            // Nudge centers so the next round exercises different windows
+            for (i, cluster) in clusters.iter_mut().enumerate() {
+                cluster.x = (cluster.x + (i % 5) as f32 - 2.0).clamp(0.0, (width - 1) as f32);
+                cluster.y = (cluster.y + (i % 3) as f32 - 1.0).clamp(0.0, (height - 1) as f32);
+            }
+        }
+    }
+
+    #[test]
+    fn test_junction_topology_survives_aggressive_simplification() {
+        // 3x3 grid of slightly wavy lines crossing at multiples of 10px
+        let wave = |t: usize| if t % 10 == 5 { 1.0 } else { 0.0 };
+        let mut polylines: Vec<Vec<Point>> = Vec::new();
+        for k in 0..3 {
+            let offset = (k * 10) as f32;
+            polylines.push((0..=20).map(|t| Point::new(t as f32, offset + wave(t))).collect());
+            polylines.push((0..=20).map(|t| Point::new(offset + wave(t), t as f32)).collect());
+        }
+        let intersections: Vec<Point> = (0..3)
+            .flat_map(|i| (0..3).map(move |j| Point::new((i * 10) as f32, (j * 10) as f32)))
+            .collect();
+
+        let simplify = |enabled: bool| -> Vec<Vec<Point>> {
+            let fixed = junction_vertex_indices(&polylines, enabled);
+            polylines
+                .iter()
+                .zip(&fixed)
+                .map(|(polyline, fixed)| simplify_with_fixed_vertices(polyline, 5.0, fixed, 512))
+                .collect()
+        };
+        let incident_vertices = |simplified: &[Vec<Point>], p: &Point| {
+            simplified
+                .iter()
+                .filter(|polyline| polyline.contains(p))
+                .count()
+        };
+
+        let preserved = simplify(true);
+        for p in &intersections {
+            assert_eq!(incident_vertices(&preserved, p), 2, "junction {p:?} lost");
+        }
+
+        let plain = simplify(false);
+        assert!(intersections
+            .iter()
+            .any(|p| incident_vertices(&plain, p) < 2));
+    }
+
+    #[test]
+    fn test_guarantee_min_paths_recovers_dominant_contour() {
+        // Small dark square whose outline is shorter than the low-detail minimum stroke
+        let image = ImageBuffer::from_fn(64, 64, |x, y| {
+            if (28..36).contains(&x) && (28..36).contains(&y) {
+                Rgba([40, 40, 40, 255])
+            } else {
+                Rgba([255, 255, 255, 255])
+            }
+        });
+        let config = TraceLowConfig {
+            detail: 0.05,
+            ..TraceLowConfig::default()
+        };
+
+        let plain = vectorize_trace_low(&image, &config, None).unwrap();
+        assert!(plain.is_empty());
+
+        let guaranteed = TraceLowConfig {
+            guarantee_min_paths: 1,
+            ..config
+        };
+        let paths = vectorize_trace_low(&image, &guaranteed, None).unwrap();
+        assert!(!paths.is_empty());
+    }
+
+    #[test]
+    fn test_auto_denoise_escalate_cleans_speckled_edges() {
+        // Grainy photo stand-in: speckle blobs scattered around one large dark square
+        let mut state = 0x2545_f491_u32;
+        let mut specks = Vec::new();
+        for _ in 0..160 {
+            state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            let x = (state >> 8) % 252;
+            state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            let y = (state >> 8) % 252;
+            specks.push((x, y));
+        }
+        let image = ImageBuffer::from_fn(256, 256, |x, y| {
+            let in_square = (96..160).contains(&x) && (96..160).contains(&y);
+            let in_speck = specks
+                .iter()
+                .any(|&(sx, sy)| (sx..sx + 4).contains(&x) && (sy..sy + 4).contains(&y));
+            if in_square {
+                Rgba([30, 30, 30, 255])
+            } else if in_speck {
+                Rgba([150, 150, 150, 255])
+            } else {
+                Rgba([255, 255, 255, 255])
+            }
+        });
+        let config = TraceLowConfig {
+            detail: 0.9,
+            ..TraceLowConfig::default()
+        };
+        let mean_length = |paths: &[SvgPath]| {
+            paths
+                .iter()
+                .map(|p| crate::svg::path_data_length(&p.data).unwrap_or(0.0))
+                .sum::<f32>()
+                / paths.len() as f32
+        };
+
+        let plain = vectorize_trace_low(&image, &config, None).unwrap();
+        let escalated_config = TraceLowConfig {
+            auto_denoise_escalate: true,
+            ..config
+        };
+        let escalated = vectorize_trace_low(&image, &escalated_config, None).unwrap();
+
+        assert!(!escalated.is_empty());
+        assert!(escalated.len() * 4 < plain.len());
+        assert!(mean_length(&escalated) > mean_length(&plain));
+
+        let (_, record) =
+            crate::telemetry::capture(|| vectorize_trace_low(&image, &escalated_config, None));
+        assert_eq!(record.guards.denoise_escalations, 1);
+    }
+
+    #[test]
+    fn test_auto_denoise_escalate_covers_directional_passes() {
+        // Blotches large enough to survive the directional passes' stricter length filter
+        let mut state = 0x1b87_3593_u32;
+        let mut blotches = Vec::new();
+        for _ in 0..250 {
+            state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            let x = (state >> 8) % 244;
+            state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            let y = (state >> 8) % 244;
+            blotches.push((x, y));
+        }
+        let image = ImageBuffer::from_fn(256, 256, |x, y| {
+            let in_blotch = blotches
+                .iter()
+                .any(|&(bx, by)| (bx..bx + 6).contains(&x) && (by..by + 6).contains(&y));
+            if in_blotch {
+                Rgba([120, 120, 120, 255])
+            } else {
+                Rgba([255, 255, 255, 255])
+            }
+        });
+        let directional = |auto_denoise_escalate| TraceLowConfig {
+            detail: 0.9,
+            enable_reverse_pass: true,
+            enable_diagonal_pass: true,
+            directional_strength_threshold: 0.0,
+            auto_denoise_escalate,
+            ..TraceLowConfig::default()
+        };
+
+        let plain = vectorize_trace_low(&image, &directional(false), None).unwrap();
+        let (escalated, record) =
+            crate::telemetry::capture(|| vectorize_trace_low(&image, &directional(true), None));
+        let escalated = escalated.unwrap();
+        let passes = record.resolved.directional_passes.unwrap_or_default();
+
+        assert!(!passes.is_empty());
+        // The base pass and at least one directional pass were re-run
+        let escalations = record.guards.denoise_escalations as usize;
+        assert!(
+            (2..=1 + passes.len()).contains(&escalations),
+            "{escalations} escalations for {passes:?}"
+        );
+        assert!(escalated.len() * 2 < plain.len());
+    }
+
+    #[test]
+    fn test_snap_straight_lines_straightens_wobbly_edges() {
+        // Building facade whose roof line wobbles by a pixel
+        let image = ImageBuffer::from_fn(128, 96, |x, y| {
+            let roof = 30 + [0, 1, 1, 0, 1][(x / 4 % 5) as usize];
+            if (20..108).contains(&x) && (roof..80).contains(&y) {
+                Rgba([50, 50, 60, 255])
+            } else {
+                Rgba([255, 255, 255, 255])
+            }
+        });
+        let config = TraceLowConfig {
+            detail: 0.9,
+            ..TraceLowConfig::default()
+        };
+        // Longest exactly vertical segment among the traced paths
+        let longest_vertical = |paths: &[SvgPath]| {
+            paths
+                .iter()
+                .map(|p| {
+                    crate::svg::element_sample_points(p)
+                        .windows(2)
+                        .filter(|w| w[0].0 == w[1].0)
+                        .map(|w| (w[1].1 - w[0].1).abs())
+                        .fold(0.0f32, f32::max)
+                })
+                .fold(0.0f32, f32::max)
+        };
+
+        let plain = vectorize_trace_low(&image, &config, None).unwrap();
+        let snapped_config = TraceLowConfig {
+            snap_straight_lines: true,
+            ..config
+        };
+        let snapped = vectorize_trace_low(&image, &snapped_config, None).unwrap();
+
+        // Blurred corners leave the traced walls jogging by a pixel
+        assert!(longest_vertical(&plain) < 45.0);
+        assert!(longest_vertical(&snapped) > 50.0);
+    }
+
+    #[test]
+    fn test_lowpoly_backend_emits_polygons() {
+        let image = ImageBuffer::from_fn(64, 64, |x, y| {
+            if (x / 16 + y / 16) % 2 == 0 {
+                Rgba([230, 200, 40, 255])
+            } else {
+                Rgba([20, 60, 140, 255])
+            }
+        });
+        let config = TraceLowConfig {
+            backend: TraceBackend::LowPoly,
+            lowpoly_vertex_count: 120,
+            ..TraceLowConfig::default()
+        };
+
+        let paths = vectorize_trace_low(&image, &config, None).unwrap();
+
+        assert!(!paths.is_empty());
+        assert!(paths
+            .iter()
+            .all(|p| matches!(p.element_type, SvgElementType::Polygon { .. })));
+    }
+
+    #[test]
+    fn test_dual_background_traces_only_artwork() {
+        // Black frame and white mat around a mid-tone square with a dark inner block
+        let image = ImageBuffer::from_fn(96, 96, |x, y| {
+            let ring = x.min(y).min(95 - x).min(95 - y);
+            if ring < 12 {
+                Rgba([10, 10, 10, 255])
+            } else if ring < 28 {
+                Rgba([246, 244, 238, 255])
+            } else if (40..56).contains(&x) && (40..56).contains(&y) {
+                Rgba([30, 60, 30, 255])
+            } else {
+                Rgba([130, 150, 120, 255])
+            }
+        });
+        let coordinates = |paths: &[SvgPath]| -> Vec<f32> {
+            paths
+                .iter()
+                .flat_map(|p| {
+                    p.data
+                        .split(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-'))
+                        .filter_map(|t| t.parse::<f32>().ok())
+                        .collect::<Vec<_>>()
+                })
+                .collect()
+        };
+        let config = TraceLowConfig {
+            detail: 0.5,
+            ..TraceLowConfig::default()
+        };
+
+        let plain = vectorize_trace_low(&image, &config, None).unwrap();
+        assert!(coordinates(&plain).iter().any(|&c| c < 20.0 || c > 76.0));
+
+        let config = TraceLowConfig {
+            dual_background: true,
+            ..config
+        };
+        let paths = vectorize_trace_low(&image, &config, None).unwrap();
+        let coords = coordinates(&paths);
+        assert!(!coords.is_empty());
+        assert!(coords.iter().all(|&c| (20.0..=76.0).contains(&c)));
+    }
+
+    #[test]
+    fn test_dots_halftone_mode_grids_dots_by_darkness() {
+        // Horizontal gradient from white (left) to black (right)
+        let image = ImageBuffer::from_fn(96, 64, |x, _| {
+            let value = 255 - (x * 255 / 95) as u8;
+            Rgba([value, value, value, 255])
+        });
+        let config = TraceLowConfig {
+            backend: TraceBackend::Dots,
+            halftone_mode: true,
+            halftone_angle: 0.0,
+            halftone_cell_px: 8.0,
+            ..TraceLowConfig::default()
+        };
+
+        let paths = vectorize_trace_low(&image, &config, None).unwrap();
+        let circles: Vec<(f32, f32, f32)> = paths
+            .iter()
+            .filter_map(|p| match p.element_type {
+                SvgElementType::Circle { cx, cy, r } => Some((cx, cy, r)),
+                _ => None,
+            })
+            .collect();
+        assert!(!circles.is_empty());
+        assert!(circles
+            .iter()
+            .all(|(cx, cy, _)| (cx % 8.0 - 4.0).abs() < 1e-3 && (cy % 8.0 - 4.0).abs() < 1e-3));
+
+        let mut row: Vec<_> = circles.iter().filter(|(_, cy, _)| (cy - 36.0).abs() < 1e-3).collect();
+        row.sort_by(|a, b| a.0.total_cmp(&b.0));
+        assert!(row.len() > 1);
+        assert!(row.windows(2).all(|pair| pair[1].2 > pair[0].2));
+    }
+
+    #[test]
+    fn test_multipass_merge_combines_offset_detections() {
+        // Edge between rows 21 and 22, found one pixel off by each pass
+        let image = ImageBuffer::from_fn(100, 40, |_, y| {
+            if y >= 22 {
+                Rgba([40, 40, 40, 255])
+            } else {
+                Rgba([220, 220, 220, 255])
+            }
+        });
+        let base = vec![SvgPath::new_stroke("M 10 20 L 90 20".to_string(), "#000000", 1.0)];
+        let directional = vec![vec![SvgPath::new_stroke(
+            "M 90 22 L 10 22".to_string(),
+            "#000000",
+            1.0,
+        )]];
+        let merge =
+            |mode: MultipassMerge, image: &ImageBuffer<Rgba<u8>, Vec<u8>>, base: &[SvgPath]| {
+                let config = TraceLowConfig {
+                    multipass_merge: mode,
+                    ..TraceLowConfig::default()
+                };
+                merge_directional_results(image, base.to_vec(), directional.clone(), &config, None)
+            };
+
+        let union = merge(MultipassMerge::Union, &image, &base);
+        assert_eq!(union.len(), 1);
+        assert_eq!(union[0].data, "M 10 20 L 90 20");
+
+        let strongest = merge(MultipassMerge::MaxResponse, &image, &base);
+        assert_eq!(strongest.len(), 1);
+        assert_eq!(strongest[0].data, "M 90 22 L 10 22");
+
+        // Without any edge response both detections weigh the same
+        let flat = ImageBuffer::from_pixel(100, 40, Rgba([128, 128, 128, 255]));
+        let averaged = merge(MultipassMerge::WeightedAverage, &flat, &base);
+        assert_eq!(averaged.len(), 1);
+        assert_eq!(averaged[0].data, "M 10.00 21.00 L 90.00 21.00");
+
+        // Detections on either side of the edge respond alike and meet in between
+        let straddling = vec![SvgPath::new_stroke(
+            "M 10 21 L 90 21".to_string(),
+            "#000000",
+            1.0,
+        )];
+        let weighted = merge(MultipassMerge::WeightedAverage, &image, &straddling);
+        assert_eq!(weighted.len(), 1);
+        let y = crate::svg::element_sample_points(&weighted[0])[0].1;
+        assert!(y > 21.0 && y < 22.0, "y = {y}");
+    }
+
+    #[test]
+    fn test_directional_hysteresis_stabilizes_pass_selection() {
+        let analysis = |diagonal_benefit: f32| DirectionalAnalysis {
+            has_diagonal_content: false,
+            _dominant_lighting_direction: None,
+            has_architectural_elements: false,
+            texture_directionality: 0.0,
+            direction_benefits: [1.0, 0.2, diagonal_benefit, diagonal_benefit],
+        };
+        // Two nearly identical frames straddling the threshold
+        let (frame_a, frame_b) = (analysis(0.31), analysis(0.29));
+        let mut config = TraceLowConfig {
+            enable_diagonal_pass: true,
+            directional_strength_threshold: 0.3,
+            ..TraceLowConfig::default()
+        };
+
+        let passes_a = schedule_directional_passes(&frame_a, 10_000, &config);
+        let passes_b = schedule_directional_passes(&frame_b, 10_000, &config);
+        assert_ne!(passes_a, passes_b);
+        assert_eq!(passes_a.len(), 2);
+
+        // Each frame carries the previous selection, which only changes once a
+        // benefit crosses the threshold by the margin
+        config.directional_hysteresis = 0.1;
+        let next_frame = |previous: &[ProcessingDirection], frame: &DirectionalAnalysis| {
+            let config = TraceLowConfig {
+                previous_directional_passes: Some(previous.to_vec()),
+                ..config.clone()
+            };
+            schedule_directional_passes(frame, 10_000, &config)
+        };
+        assert_eq!(next_frame(&passes_a, &frame_b), passes_a);
+        assert!(next_frame(&[], &frame_a).is_empty());
+        assert!(next_frame(&passes_a, &analysis(0.15)).is_empty());
+        assert_eq!(next_frame(&[], &analysis(0.45)).len(), 2);
+    }
+
+    #[test]
+    fn test_dots_grayscale_color_mode_tracks_source_darkness() {
+        use crate::algorithms::dots::dots::DotColorMode;
+
+        // Horizontal gradient from pale to deep blue
+        let image = ImageBuffer::from_fn(96, 64, |x, _| {
+            let value = 255 - (x * 255 / 95) as u8;
+            Rgba([value, value, 255, 255])
+        });
+        let config = TraceLowConfig {
+            backend: TraceBackend::Dots,
+            halftone_mode: true,
+            halftone_angle: 0.0,
+            dot_preserve_colors: true,
+            dot_color_mode: Some(DotColorMode::Grayscale),
+            ..TraceLowConfig::default()
+        };
+
+        let paths = vectorize_trace_low(&image, &config, None).unwrap();
+        let mut row: Vec<(f32, u8)> = paths
+            .iter()
+            .filter_map(|p| match p.element_type {
+                SvgElementType::Circle { cx, cy, .. } if (cy - 36.0).abs() < 1e-3 => {
+                    let hex = p.fill.trim_start_matches('#');
+                    assert!(hex[0..2] == hex[2..4] && hex[2..4] == hex[4..6], "not gray: {}", p.fill);
+                    Some((cx, u8::from_str_radix(&hex[0..2], 16).unwrap()))
+                }
+                _ => None,
+            })
+            .collect();
+        row.sort_by(|a, b| a.0.total_cmp(&b.0));
+        assert!(row.len() > 1);
+        assert!(row.windows(2).all(|pair| pair[1].1 < pair[0].1));
+
+        let black = TraceLowConfig {
+            dot_color_mode: Some(DotColorMode::Black),
+            ..config
+        };
+        let paths = vectorize_trace_low(&image, &black, None).unwrap();
+        assert!(!paths.is_empty());
+        assert!(paths.iter().all(|p| p.fill == "#000000"));
+    }
+
+    #[test]
+    fn test_alpha_threshold_excludes_faint_regions() {
+        // Opaque black on the left, faint semi-transparent black on the right
+        let image = ImageBuffer::from_fn(96, 64, |x, _| {
+            Rgba([0, 0, 0, if x < 48 { 255 } else { 60 }])
+        });
+        let config = TraceLowConfig {
+            backend: TraceBackend::Dots,
+            halftone_mode: true,
+            ..TraceLowConfig::default()
+        };
+        let dots_right_of = |config: &TraceLowConfig, x: f32| {
+            vectorize_trace_low(&image, config, None)
+                .unwrap()
+                .iter()
+                .filter(|p| matches!(p.element_type, SvgElementType::Circle { cx, .. } if cx > x))
+                .count()
+        };
+
+        assert!(dots_right_of(&config, 52.0) > 0);
+        let raised = TraceLowConfig {
+            alpha_threshold: 128,
+            ..config
+        };
+        assert_eq!(dots_right_of(&raised, 52.0), 0);
+        assert!(dots_right_of(&raised, 0.0) > 0);
+    }
+
+    #[test]
+    fn test_text_aware_traces_label_as_centerlines() {
+        // Diagram: a filled box with the label "HILT" beside it, in 2px block letters
+        let mut image = ImageBuffer::from_pixel(160, 100, Rgba([245, 245, 245, 255]));
+        let mut fill = |x0: u32, y0: u32, w: u32, h: u32, color: Rgba<u8>| {
+            for y in y0..y0 + h {
+                for x in x0..x0 + w {
+                    image.put_pixel(x, y, color);
+                }
+            }
+        };
+        let ink = Rgba([30, 30, 120, 255]);
+        let strokes: [&[(u32, u32, u32, u32)]; 4] = [
+            &[(0, 0, 2, 14), (8, 0, 2, 14), (0, 6, 10, 2)],
+            &[(4, 0, 2, 14)],
+            &[(0, 0, 2, 14), (0, 12, 8, 2)],
+            &[(0, 0, 10, 2), (4, 0, 2, 14)],
+        ];
+        for (i, letter) in strokes.iter().enumerate() {
+            for &(x, y, w, h) in letter.iter() {
+                fill(20 + i as u32 * 14 + x, 20 + y, w, h, ink);
+            }
+        }
+        fill(90, 40, 50, 40, Rgba([200, 60, 40, 255]));
+
+        let config = TraceLowConfig {
+            backend: TraceBackend::Superpixel,
+            superpixel_stroke_regions: false,
+            ..TraceLowConfig::default()
+        };
+        let plain = vectorize_trace_low(&image, &config, None).unwrap();
+        assert!(plain.iter().all(|p| p.fill != "none"));
+
+        let text_aware = TraceLowConfig {
+            text_aware: true,
+            ..config
+        };
+        let paths = vectorize_trace_low(&image, &text_aware, None).unwrap();
+        let (text, rest): (Vec<_>, Vec<_>) = paths.iter().partition(|p| p.fill == "none");
+        assert!(
+            text.len() >= 4,
+            "expected a centerline per letter, got {}",
+            text.len()
+        );
+        assert!(text.iter().all(|p| p.stroke == "#1e1e78"));
+        for path in &text {
+            let points = crate::svg::element_sample_points(path);
+            assert!(points
+                .iter()
+                .all(|&(x, y)| (14.0..82.0).contains(&x) && (14.0..40.0).contains(&y)));
+        }
+        // The box is still traced by the superpixel backend
+        assert!(!rest.is_empty());
+        assert!(rest.iter().any(|p| p.fill == "#c83c28"));
+    }
+
+    #[test]
+    fn test_detail_map_concentrates_edges_in_bright_regions() {
+        // Vertical stripes whose contrast fades from top to bottom, identical in both halves
+        let (width, height) = (64u32, 64u32);
+        let image = GrayImage::from_fn(width, height, |x, y| {
+            let contrast = 120.0 * (1.0 - y as f32 / height as f32);
+            let stripe = if (x / 4) % 2 == 0 { 1.0 } else { -1.0 };
+            Luma([(128.0 + stripe * contrast) as u8])
+        });
+        let edge_count = |edges: &GrayImage, columns: std::ops::Range<u32>| {
+            edges
+                .enumerate_pixels()
+                .filter(|(x, _, p)| columns.contains(x) && p.0[0] > 0)
+                .count()
+        };
+
+        let uniform = canny_edge_detection_with_detail_map(&image, 0.15, 0.35, None);
+        assert_eq!(
+            edge_count(&uniform, 0..32),
+            edge_count(&uniform, 32..64),
+            "Without a map both halves trace alike"
+        );
+
+        // Left half painted high, right half painted low
+        let map = GrayImage::from_fn(width, height, |x, _| Luma([if x < 32 { 255 } else { 0 }]));
+        let weighted = canny_edge_detection_with_detail_map(&image, 0.15, 0.35, Some(&map));
+        let (high, low) = (edge_count(&weighted, 0..32), edge_count(&weighted, 32..64));
+        assert!(
+            high > low,
+            "High-weight half should trace more finely: {high} vs {low}"
+        );
+        assert!(high > edge_count(&uniform, 0..32));
+
+        // Mismatched map dimensions are rejected
+        let config = TraceLowConfig {
+            detail_map: Some(GrayImage::new(8, 8)),
+            ..TraceLowConfig::default()
+        };
+        let rgba = ImageBuffer::from_pixel(width, height, Rgba([255, 255, 255, 255]));
+        assert!(matches!(
+            vectorize_trace_low(&rgba, &config, None),
+            Err(VectorizeError::InvalidDimensions { .. })
+        ));
+    }
+
+    #[test]
+    fn test_region_average_in_lab_is_perceptually_central() {
+        // Single region spanning a red-to-green gradient swatch
+        let (width, height) = (32usize, 8usize);
+        let image = ImageBuffer::from_fn(width as u32, height as u32, |x, _| {
+            let t = x as f32 / (width - 1) as f32;
+            Rgba([(255.0 * (1.0 - t)) as u8, (255.0 * t) as u8, 0, 255])
+        });
+        let lab_image: Vec<LabColor> = image.pixels().map(rgba_to_lab).collect();
+        let labels = vec![0usize; width * height];
+
+        let mean_squared_delta_e = |average_in_lab: bool| {
+            let regions = extract_superpixel_regions(
+                &labels,
+                &lab_image,
+                &image,
+                (width, height),
+                average_in_lab,
+            );
+            let fill = rgba_to_lab(&hex_to_rgba(&regions[0].avg_rgb_hex));
+            let total: f32 = lab_image.iter().map(|lab| fill.distance_to(lab).powi(2)).sum();
+            total / lab_image.len() as f32
+        };
+
+        let rgb_error = mean_squared_delta_e(false);
+        let lab_error = mean_squared_delta_e(true);
+        assert!(
+            lab_error < rgb_error,
+            "LAB average should sit closer to the swatch: {lab_error} vs {rgb_error}"
+        );
+    }
+
+    #[test]
+    fn test_shared_region_edges_meet_at_junctions() {
+        // Left half is region 0, the right half splits into 1 (top) and 2 (bottom),
+        // and region 3 is a 2x2 island inside region 0
+        let (width, height) = (8usize, 8usize);
+        let labels: Vec<usize> = (0..width * height)
+            .map(|idx| match (idx % width, idx / width) {
+                (1..=2, 1..=2) => 3,
+                (0..=3, _) => 0,
+                (_, 0..=3) => 1,
+                _ => 2,
+            })
+            .collect();
+
+        let mut edges = extract_shared_region_edges(&labels, width, height);
+        edges.sort_by_key(|edge| edge.labels);
+        let pairs: Vec<(usize, usize)> = edges.iter().map(|edge| edge.labels).collect();
+        assert_eq!(pairs, vec![(0, 1), (0, 2), (0, 3), (1, 2)]);
+
+        // Each boundary appears once, and the three open ones share the junction (4, 4)
+        let junction = Point { x: 4.0, y: 4.0 };
+        for edge in edges.iter().filter(|edge| edge.labels != (0, 3)) {
+            assert!(!edge.closed);
+            assert_eq!(edge.points.len(), 5);
+            let ends = [edge.points[0], edge.points[4]];
+            assert!(ends.contains(&junction), "{:?}", edge.points);
+        }
+        let island = &edges[2];
+        assert!(island.closed);
+        assert_eq!(island.points.len(), 9);
+        assert_eq!(island.points.first(), island.points.last());
+    }
+
+    #[test]
+    fn test_region_color_smoothing_evens_out_banding() {
+        // Gray columns rising in uneven steps, ending in a strongly different red column
+        let grays = [100u8, 104, 120, 124, 140, 144, 160, 164];
+        let (width, height) = (36usize, 8usize);
+        let column = |x: usize| x / 4;
+        let image = ImageBuffer::from_fn(width as u32, height as u32, |x, _| {
+            match grays.get(column(x as usize)) {
+                Some(&gray) => Rgba([gray, gray, gray, 255]),
+                None => Rgba([220, 30, 30, 255]),
+            }
+        });
+        let labels: Vec<usize> = (0..width * height).map(|idx| column(idx % width)).collect();
+        let lab_image: Vec<LabColor> = image.pixels().map(rgba_to_lab).collect();
+        let extract = || {
+            let mut regions =
+                extract_superpixel_regions(&labels, &lab_image, &image, (width, height), false);
+            regions.sort_by_key(|region| region.label);
+            regions
+        };
+        let lab_colors = |regions: &[SuperpixelRegion]| -> Vec<LabColor> {
+            regions
+                .iter()
+                .map(|region| rgba_to_lab(&hex_to_rgba(&region.avg_rgb_hex)))
+                .collect()
+        };
+        let max_gray_step = |colors: &[LabColor]| {
+            colors[..grays.len()]
+                .windows(2)
+                .map(|pair| pair[0].distance_to(&pair[1]))
+                .fold(0.0f32, f32::max)
+        };
+
+        let original = extract();
+        let mut smoothed = extract();
+        smooth_superpixel_region_colors(&mut smoothed, &labels, width, height, 1.0);
+        let (before, after) = (lab_colors(&original), lab_colors(&smoothed));
+
+        assert!(
+            max_gray_step(&after) < 0.8 * max_gray_step(&before),
+            "steps {} -> {}",
+            max_gray_step(&before),
+            max_gray_step(&after)
+        );
+        // The red column is an edge, not banding, and keeps its color
+        assert!(before[8].distance_to(&after[8]) < 0.5);
+        // Only colors change
+        for (a, b) in original.iter().zip(&smoothed) {
+            assert_eq!(a.label, b.label);
+            assert_eq!(a.boundary_points, b.boundary_points);
+        }
+
+        let mut unchanged = extract();
+        smooth_superpixel_region_colors(&mut unchanged, &labels, width, height, 0.0);
+        let hexes = |regions: &[SuperpixelRegion]| -> Vec<String> {
+            regions.iter().map(|region| region.avg_rgb_hex.clone()).collect()
+        };
+        assert_eq!(hexes(&unchanged), hexes(&original));
+    }
+
+    #[test]
+    fn test_region_color_smoothing_keeps_region_boundaries() {
+        let image = ImageBuffer::from_fn(96, 32, |x, _| {
+            let shade = 60 + (x * 3 / 2) as u8;
+            Rgba([shade, shade / 2 + 40, 200, 255])
+        });
+        let config = TraceLowConfig {
+            backend: TraceBackend::Superpixel,
+            num_superpixels: 12,
+            superpixel_stroke_regions: false,
+            ..TraceLowConfig::default()
+        };
+        let trace = |config: &TraceLowConfig| -> Vec<(String, String)> {
+            let mut paths: Vec<(String, String)> = vectorize_trace_low(&image, config, None)
+                .unwrap()
+                .into_iter()
+                .map(|path| (path.data, path.fill))
+                .collect();
+            paths.sort();
+            paths
+        };
+
+        let plain = trace(&config);
+        let smoothed = trace(&TraceLowConfig {
+            region_color_smoothing: 1.0,
+            ..config
+        });
+        let shapes = |paths: &[(String, String)]| -> Vec<String> {
+            paths.iter().map(|(data, _)| data.clone()).collect()
+        };
+        assert_eq!(shapes(&plain), shapes(&smoothed));
+        assert_ne!(plain, smoothed, "some region colors should be blended");
+    }
+
+    #[test]
+    fn test_shared_region_edges_draw_each_seam_once() {
+        // Four flat quadrants, so every region borders two others
+        let image = ImageBuffer::from_fn(64, 64, |x, y| match (x < 32, y < 32) {
+            (true, true) => Rgba([220, 40, 40, 255]),
+            (false, true) => Rgba([40, 160, 40, 255]),
+            (true, false) => Rgba([40, 40, 200, 255]),
+            (false, false) => Rgba([230, 200, 40, 255]),
+        });
+        let config = TraceLowConfig {
+            backend: TraceBackend::Superpixel,
+            num_superpixels: 4,
+            ..TraceLowConfig::default()
+        };
+        let strokes = |config: &TraceLowConfig| -> Vec<SvgPath> {
+            vectorize_trace_low(&image, config, None)
+                .unwrap()
+                .into_iter()
+                .filter(|path| path.fill == "none")
+                .collect()
+        };
+
+        let total_length = |paths: &[SvgPath]| -> f32 {
+            paths
+                .iter()
+                .map(|path| {
+                    let points = crate::svg::element_sample_points(path);
+                    points
+                        .windows(2)
+                        .map(|pair| (pair[1].0 - pair[0].0).hypot(pair[1].1 - pair[0].1))
+                        .sum::<f32>()
+                })
+                .sum()
+        };
+
+        // Outlines trace both sides of every seam plus the image border; the
+        // network is just the two 64px seams through the center
+        let outlined = strokes(&config);
+        let shared = strokes(&TraceLowConfig {
+            shared_region_edges: true,
+            ..config
+        });
+        assert_eq!(shared.len(), 4, "one boundary per pair of neighbors");
+        let (shared_length, outlined_length) = (total_length(&shared), total_length(&outlined));
+        assert!((shared_length - 128.0).abs() < 1.0, "{shared_length}");
+        assert!(outlined_length > 2.0 * shared_length, "{outlined_length}");
+        let width = shared[0].stroke_width;
+        assert!(shared.iter().all(|path| path.stroke_width == width));
+        // Seams run along the pixel cracks between quadrants
+        assert!(shared.iter().any(|path| path.data.contains("32.0,")));
+    }
+
+    #[test]
+    fn test_shared_region_edges_follow_merged_regions() {
+        // Two flat top quadrants in the same color merge across their weak seam
+        let image = ImageBuffer::from_fn(64, 64, |x, y| match (x < 32, y < 32) {
+            (_, true) => Rgba([220, 40, 40, 255]),
+            (true, false) => Rgba([40, 40, 200, 255]),
+            (false, false) => Rgba([230, 200, 40, 255]),
+        });
+        let config = TraceLowConfig {
+            backend: TraceBackend::Superpixel,
+            num_superpixels: 4,
+            enable_advanced_merging: true,
+            superpixel_enhance_edges: true,
+            shared_region_edges: true,
+            ..TraceLowConfig::default()
+        };
+        let paths = vectorize_trace_low(&image, &config, None).unwrap();
+        let (strokes, fills): (Vec<SvgPath>, Vec<SvgPath>) =
+            paths.into_iter().partition(|path| path.fill == "none");
+        assert_eq!(fills.len(), 3);
+
+        // The network keeps the horizontal seam and the lower half of the vertical
+        // one, but nothing runs between the merged top quadrants
+        assert_eq!(strokes.len(), 3, "one boundary per pair of neighbors");
+        for path in &strokes {
+            let points = crate::svg::element_sample_points(path);
+            assert!(
+                !points.iter().any(|&(x, y)| x == 32.0 && y < 31.0),
+                "seam through a merged region: {}",
+                path.data
+            );
+        }
+    }
+
+    #[test]
+    fn test_superpixel_region_metadata_matches_region_geometry() {
+        // Quadrant labels: 0 and 1 on top, 2 and 3 below
+        let (width, height) = (64usize, 64usize);
+        let labels: Vec<usize> = (0..width * height)
+            .map(|idx| (idx / width / 32) * 2 + (idx % width) / 32)
+            .collect();
+        let image = ImageBuffer::from_pixel(width as u32, height as u32, Rgba([128, 128, 128, 255]));
+        let lab_image: Vec<LabColor> = image.pixels().map(rgba_to_lab).collect();
+
+        let mut regions = extract_superpixel_regions(&labels, &lab_image, &image, (width, height), false);
+        regions.sort_by_key(|region| region.label);
+        let expected = [(15.5, 15.5), (47.5, 15.5), (15.5, 47.5), (47.5, 47.5)];
+        for (region, (x, y)) in regions.iter().zip(expected) {
+            assert_eq!(region.area, 32 * 32);
+            assert_eq!(region.centroid, Point::new(x, y));
+        }
+        let merged = merge_regions(regions[0].clone(), regions[1].clone());
+        assert_eq!(merged.area, 2 * 32 * 32);
+        assert_eq!(merged.centroid, Point::new(31.5, 15.5));
+
+        let options = SuperpixelSvgOptions {
+            stroke_width: 1.0,
+            dp_epsilon: 1.0,
+            max_simplify_depth: 8,
+            detail: 0.5,
+            fill_regions: true,
+            stroke_regions: true,
+            shared_edges: false,
+            preserve_colors: false,
+            region_metadata: true,
+        };
+        let paths =
+            generate_superpixel_svg_paths(&regions, &labels, width, height, &options).unwrap();
+        assert_eq!(paths.len(), 8, "a fill and an outline per region");
+        for (pair, region) in paths.chunks(2).zip(&regions) {
+            let expected = Some(RegionMetadata {
+                centroid: region.centroid,
+                area: region.area,
+            });
+            assert!(pair.iter().all(|path| path.region_metadata == expected));
+        }
+
+        let svg_config = crate::config::SvgConfig {
+            emit_region_metadata: true,
+            ..Default::default()
+        };
+        let svg = crate::svg::generate_svg_document(&paths, 64, 64, &svg_config);
+        assert!(svg.contains(r#"data-centroid="47.50,15.50" data-area="1024""#));
+        // The centroid follows the emitted coordinates
+        for (origin, centroid) in [
+            (crate::config::CoordinateOrigin::Center, "15.50,-16.50"),
+            (crate::config::CoordinateOrigin::BottomLeft, "47.50,48.50"),
+        ] {
+            let svg_config = crate::config::SvgConfig {
+                emit_region_metadata: true,
+                coordinate_origin: origin,
+                ..Default::default()
+            };
+            let svg = crate::svg::generate_svg_document(&paths, 64, 64, &svg_config);
+            assert!(svg.contains(&format!(r#"data-centroid="{centroid}" data-area="1024""#)));
+        }
+        let plain = crate::svg::generate_svg_document(&paths, 64, 64, &Default::default());
+        assert!(!plain.contains("data-area"));
+    }
+
+    #[test]
+    fn test_superpixel_debug_colors_distinct_for_neighbors() {
+        // 4x4 grid of 8px label blocks over a uniform gray image
+        let (width, height) = (32usize, 32usize);
+        let labels: Vec<usize> = (0..width * height)
+            .map(|idx| (idx / width / 8) * 4 + (idx % width) / 8)
+            .collect();
+        let image = ImageBuffer::from_pixel(width as u32, height as u32, Rgba([128, 128, 128, 255]));
+        let lab_image: Vec<LabColor> = image.pixels().map(rgba_to_lab).collect();
+
+        let mut regions = extract_superpixel_regions(&labels, &lab_image, &image, (width, height), false);
+        assert_eq!(regions.len(), 16);
+        assert!(regions.iter().all(|r| r.avg_rgb_hex == regions[0].avg_rgb_hex));
+
+        assign_superpixel_debug_colors(&mut regions, &labels, width, height);
+
+        let color_of = |label: usize| {
+            regions
+                .iter()
+                .find(|r| r.label == label)
+                .map(|r| r.avg_rgb_hex.clone())
+                .unwrap()
+        };
+        for row in 0..4 {
+            for col in 0..4 {
+                let label = row * 4 + col;
+                if col + 1 < 4 {
+                    assert_ne!(color_of(label), color_of(label + 1));
+                }
+                if row + 1 < 4 {
+                    assert_ne!(color_of(label), color_of(label + 4));
+                }
+            }
+        }
+
+        // Deterministic regardless of region order
+        let mut reversed = extract_superpixel_regions(&labels, &lab_image, &image, (width, height), false);
+        reversed.reverse();
+        assign_superpixel_debug_colors(&mut reversed, &labels, width, height);
+        for region in &reversed {
+            assert_eq!(region.avg_rgb_hex, color_of(region.label));
+        }
+    }
 }
 
 // =============================================================================
@@ -7945,6 +11024,9 @@ fn polyline_to_svg_path_with_edt_width(
             fill: "none".to_string(),
             stroke: "#000000".to_string(),
             stroke_width: base_width,
+            stroke_opacity: None,
+            source_color: None,
+            region_metadata: None,
             element_type: SvgElementType::Path,
         };
     }
@@ -7969,6 +11051,9 @@ fn polyline_to_svg_path_with_edt_width(
         fill: "none".to_string(),
         stroke: "#000000".to_string(),
         stroke_width: modulated_width,
+        stroke_opacity: None,
+        source_color: None,
+        region_metadata: None,
         element_type: SvgElementType::Path,
     }
 }
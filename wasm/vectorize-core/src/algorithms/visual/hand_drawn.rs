@@ -4,10 +4,35 @@
 //! line art feel more organic and hand-drawn, including variable line weights,
 //! subtle tremor, line tapering, and pressure simulation.
 
-use crate::algorithms::SvgPath;
+use crate::algorithms::{SvgElementType, SvgPath};
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
 
+/// Which elements receive hand-drawn effects
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HandDrawnTarget {
+    /// Only stroked lines; filled regions keep crisp boundaries
+    StrokesOnly,
+    /// Only filled regions
+    FillsOnly,
+    /// Every element
+    #[default]
+    Both,
+}
+
+impl HandDrawnTarget {
+    /// Whether `path` gets the treatment; elements with a fill count as fills
+    pub fn includes(self, path: &SvgPath) -> bool {
+        let is_fill = path.fill != "none";
+        match self {
+            Self::StrokesOnly => !is_fill,
+            Self::FillsOnly => is_fill,
+            Self::Both => true,
+        }
+    }
+}
+
 /// Configuration for hand-drawn aesthetic enhancements
 #[derive(Debug, Clone)]
 pub struct HandDrawnConfig {
@@ -29,6 +54,11 @@ pub struct HandDrawnConfig {
     pub seed: u64,
     /// Base stroke width multiplier
     pub base_width_multiplier: f32,
+    /// Pressure profile as width multipliers spaced evenly along each stroke, e.g.
+    /// `[0.2, 1.0, 0.2]` for thin-thick-thin (empty = disabled)
+    pub pressure_profile: Vec<f32>,
+    /// Which elements the effects apply to
+    pub apply_to: HandDrawnTarget,
 }
 
 impl Default for HandDrawnConfig {
@@ -43,6 +73,8 @@ impl Default for HandDrawnConfig {
             image_resolution: (800, 600),
             seed: 42,
             base_width_multiplier: 1.0,
+            pressure_profile: Vec::new(),
+            apply_to: HandDrawnTarget::Both,
         }
     }
 }
@@ -54,6 +86,7 @@ pub fn apply_hand_drawn_aesthetics(paths: Vec<SvgPath>, config: &HandDrawnConfig
         && config.tapering == 0.0
         && config.pressure_variation == 0.0
         && config.multi_pass_intensity == 0.0
+        && config.pressure_profile.is_empty()
     {
         return paths; // No enhancements requested
     }
@@ -70,6 +103,11 @@ pub fn apply_hand_drawn_aesthetics(paths: Vec<SvgPath>, config: &HandDrawnConfig
     };
 
     for path in paths {
+        if !config.apply_to.includes(&path) {
+            enhanced_paths.push(path);
+            continue;
+        }
+
         // Create primary path with enhancements
         let enhanced_path = apply_path_aesthetics(path.clone(), config, &mut rng, resolution_scale);
         enhanced_paths.push(enhanced_path.clone());
@@ -123,6 +161,11 @@ fn apply_path_aesthetics(
         path = apply_pressure_variation(path, config, rng);
     }
 
+    // Apply pressure profile last so it sees the final stroke width
+    if !config.pressure_profile.is_empty() {
+        path = apply_pressure_profile(path, &config.pressure_profile);
+    }
+
     path
 }
 
@@ -261,6 +304,98 @@ fn apply_pressure_variation(
     path
 }
 
+/// Convert a stroked path into a filled outline whose width follows the pressure profile
+fn apply_pressure_profile(path: SvgPath, profile: &[f32]) -> SvgPath {
+    if !matches!(path.element_type, SvgElementType::Path)
+        || path.stroke == "none"
+        || path.fill != "none"
+        || path.stroke_width <= 0.0
+    {
+        return path;
+    }
+
+    let Some(points) = parse_polyline_points(&path.data) else {
+        return path; // Unsupported path commands - leave the stroke untouched
+    };
+    let total_length: f32 = points
+        .windows(2)
+        .map(|w| ((w[1].0 - w[0].0).powi(2) + (w[1].1 - w[0].1).powi(2)).sqrt())
+        .sum();
+    if points.len() < 2 || total_length < f32::EPSILON {
+        return path;
+    }
+
+    // Subdivide long segments so the profile can vary along straight runs
+    let max_step = total_length / (profile.len() * 8) as f32;
+    let mut samples = vec![points[0]];
+    for w in points.windows(2) {
+        let length = ((w[1].0 - w[0].0).powi(2) + (w[1].1 - w[0].1).powi(2)).sqrt();
+        let steps = (length / max_step).ceil().max(1.0) as usize;
+        for step in 1..=steps {
+            let t = step as f32 / steps as f32;
+            samples.push((w[0].0 + (w[1].0 - w[0].0) * t, w[0].1 + (w[1].1 - w[0].1) * t));
+        }
+    }
+
+    let mut left = Vec::with_capacity(samples.len());
+    let mut right = Vec::with_capacity(samples.len());
+    let mut travelled = 0.0;
+    for i in 0..samples.len() {
+        if i > 0 {
+            let (dx, dy) = (samples[i].0 - samples[i - 1].0, samples[i].1 - samples[i - 1].1);
+            travelled += (dx * dx + dy * dy).sqrt();
+        }
+        let prev = samples[i.saturating_sub(1)];
+        let next = samples[(i + 1).min(samples.len() - 1)];
+        let normal = calculate_perpendicular(prev, next);
+        let half_width = 0.5 * path.stroke_width * sample_pressure_profile(profile, travelled / total_length);
+
+        left.push((samples[i].0 + normal.0 * half_width, samples[i].1 + normal.1 * half_width));
+        right.push((samples[i].0 - normal.0 * half_width, samples[i].1 - normal.1 * half_width));
+    }
+
+    let mut data = format!("M {:.2} {:.2}", left[0].0, left[0].1);
+    for point in left.iter().skip(1).chain(right.iter().rev()) {
+        data.push_str(&format!(" L {:.2} {:.2}", point.0, point.1));
+    }
+    data.push_str(" Z");
+
+    SvgPath::new_fill(data, &path.stroke)
+}
+
+/// Linearly interpolate the pressure profile at normalized position `t` (0.0-1.0)
+fn sample_pressure_profile(profile: &[f32], t: f32) -> f32 {
+    if profile.len() == 1 {
+        return profile[0].max(0.0);
+    }
+
+    let position = t.clamp(0.0, 1.0) * (profile.len() - 1) as f32;
+    let index = (position.floor() as usize).min(profile.len() - 2);
+    let frac = position - index as f32;
+    (profile[index] + (profile[index + 1] - profile[index]) * frac).max(0.0)
+}
+
+/// Flatten SVG path data into a single polyline, repeating the start point of
+/// closed subpaths
+///
+/// Shares the SVG module's path tokenizer; returns `None` for unsupported commands.
+pub(crate) fn parse_polyline_points(path_data: &str) -> Option<Vec<(f32, f32)>> {
+    let subpaths = crate::svg::flatten_path_data(path_data)?;
+    Some(
+        subpaths
+            .into_iter()
+            .flat_map(|(mut points, closed)| {
+                if closed {
+                    if let Some(&first) = points.first() {
+                        points.push(first);
+                    }
+                }
+                points
+            })
+            .collect(),
+    )
+}
+
 /// Apply slight offset for multi-pass sketchy effect
 fn apply_slight_offset(mut path: SvgPath, offset_amount: f32, rng: &mut ChaCha8Rng) -> SvgPath {
     // Add small random offset to entire path for sketchy overlap
@@ -275,37 +410,7 @@ fn apply_slight_offset(mut path: SvgPath, offset_amount: f32, rng: &mut ChaCha8R
 
 /// Estimate path length from SVG path data
 fn estimate_path_length(path_data: &str) -> f32 {
-    let points = parse_path_to_points_simple(path_data);
-    if points.len() < 2 {
-        return 0.0;
-    }
-
-    let mut total_length = 0.0;
-    for i in 1..points.len() {
-        let dx = points[i].0 - points[i - 1].0;
-        let dy = points[i].1 - points[i - 1].1;
-        total_length += (dx * dx + dy * dy).sqrt();
-    }
-    total_length
-}
-
-/// Parse SVG path data to extract coordinate points (simplified)
-fn parse_path_to_points_simple(path_data: &str) -> Vec<(f32, f32)> {
-    let mut points = Vec::new();
-    let tokens: Vec<&str> = path_data.split_whitespace().collect();
-
-    let mut i = 0;
-    while i + 1 < tokens.len() {
-        if let Ok(x) = tokens[i].parse::<f32>() {
-            if let Ok(y) = tokens[i + 1].parse::<f32>() {
-                points.push((x, y));
-                i += 2;
-                continue;
-            }
-        }
-        i += 1;
-    }
-    points
+    crate::svg::path_data_length(path_data).unwrap_or(0.0)
 }
 
 /// Offset all coordinates in a path by given amounts
@@ -346,38 +451,6 @@ fn offset_path_coordinates(path_data: &str, offset_x: f32, offset_y: f32) -> Str
     result
 }
 
-/// Parse SVG path data to get coordinate points (test helper function)
-#[cfg(test)]
-fn parse_path_to_points(path_data: &str) -> Vec<(f32, f32)> {
-    let mut points = Vec::new();
-    let tokens: Vec<&str> = path_data.split_whitespace().collect();
-
-    let mut i = 0;
-    while i < tokens.len() {
-        let token = tokens[i];
-
-        // Skip command letters and look for coordinate pairs
-        if token == "M" || token == "L" || token == "C" {
-            i += 1;
-            continue;
-        }
-
-        // Try to parse as coordinate pair
-        if let Ok(x) = token.parse::<f32>() {
-            if let Some(y_token) = tokens.get(i + 1) {
-                if let Ok(y) = y_token.parse::<f32>() {
-                    points.push((x, y));
-                    i += 2; // Skip the y coordinate
-                    continue;
-                }
-            }
-        }
-        i += 1;
-    }
-
-    points
-}
-
 /// Calculate width factor for tapering effect (test helper function)
 #[cfg(test)]
 fn calculate_width_factor(
@@ -414,8 +487,7 @@ fn calculate_width_factor(
     }
 }
 
-/// Calculate perpendicular vector for stroke outline (test helper function)
-#[cfg(test)]
+/// Calculate perpendicular vector for stroke outline
 fn calculate_perpendicular(p1: (f32, f32), p2: (f32, f32)) -> (f32, f32) {
     let dx = p2.0 - p1.0;
     let dy = p2.1 - p1.1;
@@ -436,7 +508,7 @@ fn apply_geometric_tapering(
     _stroke_width: f32,
     _tapering_strength: f32,
 ) -> String {
-    let points = parse_path_to_points(path_data);
+    let points = parse_polyline_points(path_data).unwrap_or_default();
     if points.len() < 2 {
         return String::from("M 0 0"); // Empty path
     }
@@ -565,24 +637,6 @@ mod tests {
         assert!(strong.tapering > subtle.tapering);
     }
 
-    #[test]
-    fn test_parse_path_to_points() {
-        // Test simple line path
-        let path_data = "M 10.0 20.0 L 30.0 40.0";
-        let points = parse_path_to_points(path_data);
-        assert_eq!(points.len(), 2);
-        assert_eq!(points[0], (10.0, 20.0));
-        assert_eq!(points[1], (30.0, 40.0));
-
-        // Test path with multiple segments
-        let path_data = "M 0 0 L 10 10 L 20 0";
-        let points = parse_path_to_points(path_data);
-        assert_eq!(points.len(), 3);
-        assert_eq!(points[0], (0.0, 0.0));
-        assert_eq!(points[1], (10.0, 10.0));
-        assert_eq!(points[2], (20.0, 0.0));
-    }
-
     #[test]
     fn test_calculate_width_factor() {
         let total_points = 10;
@@ -607,6 +661,93 @@ mod tests {
         assert!(factor_end >= 0.5); // Should not be below min width
     }
 
+    #[test]
+    fn test_pressure_profile_bulges_straight_stroke() {
+        let config = HandDrawnConfig {
+            variable_weights: 0.0,
+            tremor_strength: 0.0,
+            tapering: 0.0,
+            pressure_variation: 0.0,
+            multi_pass_intensity: 0.0,
+            pressure_profile: vec![0.2, 1.0, 0.2],
+            ..Default::default()
+        };
+        let stroke = SvgPath::new_stroke("M 0 50 L 100 50".to_string(), "#123456", 4.0);
+
+        let result = apply_hand_drawn_aesthetics(vec![stroke], &config);
+        assert_eq!(result.len(), 1);
+        let outline = &result[0];
+        assert_eq!(outline.fill, "#123456");
+        assert_eq!(outline.stroke, "none");
+
+        let half_width_near = |x_range: std::ops::Range<f32>| {
+            parse_polyline_points(&outline.data)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|(x, _)| x_range.contains(x))
+                .map(|(_, y)| (y - 50.0).abs())
+                .fold(0.0f32, f32::max)
+        };
+        let middle = half_width_near(45.0..55.0);
+        let start = half_width_near(0.0..2.0);
+        let end = half_width_near(98.0..100.5);
+
+        assert!((middle - 2.0).abs() < 0.1, "Middle should reach full width, got {middle}");
+        assert!(start < middle * 0.5, "Start should be thin ({start} vs {middle})");
+        assert!(end < middle * 0.5, "End should be thin ({end} vs {middle})");
+    }
+
+    #[test]
+    fn test_strokes_only_keeps_fill_boundaries_crisp() {
+        let config = HandDrawnConfig {
+            apply_to: HandDrawnTarget::StrokesOnly,
+            ..HandDrawnPresets::strong()
+        };
+        let fill_data = "M 10 10 L 90 10 L 90 90 L 10 90 Z";
+        let stroke_data = "M 0 50 L 25 52 L 50 48 L 75 51 L 100 50";
+        let paths = vec![
+            SvgPath::new_fill(fill_data.to_string(), "#336699"),
+            SvgPath::new_stroke(stroke_data.to_string(), "#000000", 2.0),
+        ];
+
+        let result = apply_hand_drawn_aesthetics(paths, &config);
+        let fills: Vec<_> = result.iter().filter(|p| p.fill == "#336699").collect();
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].data, fill_data);
+        assert!(result
+            .iter()
+            .filter(|p| p.fill != "#336699")
+            .all(|p| p.data != stroke_data));
+
+        let fills_only = HandDrawnConfig {
+            apply_to: HandDrawnTarget::FillsOnly,
+            ..HandDrawnPresets::strong()
+        };
+        let stroke = SvgPath::new_stroke(stroke_data.to_string(), "#000000", 2.0);
+        let result = apply_hand_drawn_aesthetics(vec![stroke], &fills_only);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].data, stroke_data);
+    }
+
+    #[test]
+    fn test_parse_polyline_points() {
+        let points = parse_polyline_points("M10,20 L30,40 L50-60").unwrap();
+        assert_eq!(points, vec![(10.0, 20.0), (30.0, 40.0), (50.0, -60.0)]);
+
+        let curve = parse_polyline_points("M 0 0 C 10 0 20 10 30 10").unwrap();
+        assert_eq!(curve.len(), 17);
+        assert_eq!(*curve.last().unwrap(), (30.0, 10.0));
+
+        let relative = parse_polyline_points("m 5 5 l 10 10 h -10 z").unwrap();
+        assert_eq!(
+            relative,
+            vec![(5.0, 5.0), (15.0, 15.0), (5.0, 15.0), (5.0, 5.0)]
+        );
+        assert_eq!(estimate_path_length("M 0 0 L 3 4"), 5.0);
+
+        assert!(parse_polyline_points("M 0 0 A 5 5 0 0 1 10 0").is_none());
+    }
+
     #[test]
     fn test_calculate_perpendicular() {
         // Test horizontal line
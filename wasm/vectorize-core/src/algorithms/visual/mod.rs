@@ -20,7 +20,9 @@ pub use gradient_detection::{
 
 // Re-export GradientAnalysis as GradientDetectionAnalysis for backward compatibility
 pub use gradient_detection::GradientAnalysis as GradientDetectionAnalysis;
-pub use hand_drawn::{apply_hand_drawn_aesthetics, HandDrawnConfig, HandDrawnPresets};
+pub use hand_drawn::{
+    apply_hand_drawn_aesthetics, HandDrawnConfig, HandDrawnPresets, HandDrawnTarget,
+};
 pub use simd_color::{
     get_simd_info, is_simd_available, simd_analyze_gradient_strength,
     simd_k_means_palette_reduction,
@@ -61,6 +61,121 @@ impl Default for FitDefaultConfig {
     }
 }
 
+/// How SVG assembly treats NaN/infinite coordinates produced upstream
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "generate-ts", derive(ts_rs::TS))]
+#[cfg_attr(
+    feature = "generate-ts",
+    ts(export, export_to = "../../../frontend/src/lib/types/generated/")
+)]
+pub enum NonFiniteHandling {
+    /// Drop the offending path segment, or the whole element for shapes
+    #[default]
+    Drop,
+    /// Replace NaN with 0 and infinities with the nearest viewport edge
+    Clamp,
+}
+
+/// Color space used for fill and stroke colors in SVG output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "generate-ts", derive(ts_rs::TS))]
+#[cfg_attr(
+    feature = "generate-ts",
+    ts(export, export_to = "../../../frontend/src/lib/types/generated/")
+)]
+pub enum ColorSpace {
+    /// Plain sRGB hex colors (`#RRGGBB`)
+    #[default]
+    Srgb,
+    /// CSS `color(display-p3 r g b)` with an inherited sRGB hex fallback
+    DisplayP3,
+}
+
+/// Fixed mirror or rotation applied to the whole SVG output
+///
+/// Rotations are clockwise; 90° and 270° swap the output width and height.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "generate-ts", derive(ts_rs::TS))]
+#[cfg_attr(
+    feature = "generate-ts",
+    ts(export, export_to = "../../../frontend/src/lib/types/generated/")
+)]
+pub enum Transform {
+    /// Mirror left-to-right
+    FlipHorizontal,
+    /// Mirror top-to-bottom
+    FlipVertical,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+}
+
+impl Transform {
+    /// Output canvas size for a `width` x `height` source
+    pub fn output_dimensions(self, width: u32, height: u32) -> (u32, u32) {
+        match self {
+            Transform::Rotate90 | Transform::Rotate270 => (height, width),
+            _ => (width, height),
+        }
+    }
+
+    /// SVG `transform` attribute value mapping source coordinates onto the output canvas
+    pub fn svg_transform(self, width: u32, height: u32) -> String {
+        match self {
+            Transform::FlipHorizontal => format!("translate({width} 0) scale(-1 1)"),
+            Transform::FlipVertical => format!("translate(0 {height}) scale(1 -1)"),
+            Transform::Rotate90 => format!("translate({height} 0) rotate(90)"),
+            Transform::Rotate180 => format!("translate({width} {height}) rotate(180)"),
+            Transform::Rotate270 => format!("translate(0 {width}) rotate(270)"),
+        }
+    }
+}
+
+/// Where (0,0) sits in emitted SVG coordinates
+///
+/// The viewBox is shifted to match, so the rendered image is unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "generate-ts", derive(ts_rs::TS))]
+#[cfg_attr(
+    feature = "generate-ts",
+    ts(export, export_to = "../../../frontend/src/lib/types/generated/")
+)]
+pub enum CoordinateOrigin {
+    /// SVG convention: top-left corner with y pointing down
+    #[default]
+    TopLeft,
+    /// Image center with y pointing down
+    Center,
+    /// CAD/plotter convention: bottom-left corner with y pointing up
+    BottomLeft,
+}
+
+impl CoordinateOrigin {
+    /// Map a top-left-origin point on a `width` x `height` canvas to this origin
+    pub fn map_point(self, (x, y): (f32, f32), width: f32, height: f32) -> (f32, f32) {
+        match self {
+            CoordinateOrigin::TopLeft => (x, y),
+            CoordinateOrigin::Center => (x - width / 2.0, y - height / 2.0),
+            CoordinateOrigin::BottomLeft => (x, height - y),
+        }
+    }
+
+    /// Whether the y axis points up, which needs a `scale(1 -1)` group to render
+    pub fn flips_y(self) -> bool {
+        self == CoordinateOrigin::BottomLeft
+    }
+
+    /// `min-x min-y` of the viewBox showing the whole `width` x `height` canvas
+    pub fn view_box_origin(self, width: u32, height: u32) -> (f32, f32) {
+        match self {
+            CoordinateOrigin::TopLeft => (0.0, 0.0),
+            CoordinateOrigin::Center => (-(width as f32) / 2.0, -(height as f32) / 2.0),
+            // Drawn through `scale(1 -1)`, so y-up coordinates 0..height land on -height..0
+            CoordinateOrigin::BottomLeft => (0.0, -(height as f32)),
+        }
+    }
+}
+
 /// SVG output configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SvgConfig {
@@ -70,6 +185,99 @@ pub struct SvgConfig {
     pub optimize: bool,
     /// Whether to include metadata comments
     pub include_metadata: bool,
+    /// Whether to emit deterministic `id` attributes (`path-<index>`) on each element
+    #[serde(default)]
+    pub emit_path_ids: bool,
+    /// Resample polyline paths to evenly spaced points at this interval (pixels)
+    #[serde(default)]
+    pub resample_even_spacing: Option<f32>,
+    /// Treatment of non-finite coordinates so output always parses
+    #[serde(default)]
+    pub non_finite_handling: NonFiniteHandling,
+    /// Color space for fill and stroke colors
+    #[serde(default)]
+    pub color_space: ColorSpace,
+    /// Halo color and width in pixels drawn as a wider underlay behind every stroke
+    #[serde(default)]
+    pub stroke_halo: Option<(String, f32)>,
+    /// Outline color and width in pixels added to every filled shape (sticker-style borders)
+    #[serde(default)]
+    pub fill_and_stroke: Option<(String, f32)>,
+    /// Replace every stroke-only element with a fill of its stroke outline, so line
+    /// art scales as plain filled shapes
+    #[serde(default)]
+    pub stroke_to_outline: bool,
+    /// Most gradient definitions to emit; the least visible gradients beyond it
+    /// are written as solid colors
+    #[serde(default)]
+    pub max_gradients: Option<usize>,
+    /// Split self-intersecting filled outlines into simple loops so folded
+    /// regions don't render with holes
+    #[serde(default)]
+    pub repair_self_intersections: bool,
+    /// Close polyline paths whose end points coincide at the output precision
+    /// with an explicit `Z`, avoiding hairline gaps in filled shapes
+    #[serde(default)]
+    pub auto_close_coincident: bool,
+    /// Prepend an `<?xml ...?>` declaration for strict XML consumers
+    #[serde(default)]
+    pub emit_xml_declaration: bool,
+    /// Declare a normalized `pathLength` on stroked paths for draw-on animations
+    #[serde(default)]
+    pub emit_path_length: bool,
+    /// Mirror or rotate the output by wrapping all elements in a transformed group
+    #[serde(default)]
+    pub output_transform: Option<Transform>,
+    /// Animate stroked paths drawing on in path order over this many seconds
+    #[serde(default)]
+    pub draw_on_animation: Option<f32>,
+    /// Literal stroke width in output pixels for every stroked path, overriding the
+    /// resolution-relative width chosen during tracing
+    #[serde(default)]
+    pub absolute_stroke_width: Option<f32>,
+    /// Random per-path stroke width variation as a fraction of each width
+    /// (0.0 disables, up to 0.5)
+    #[serde(default)]
+    pub stroke_width_jitter: f32,
+    /// Seed for `stroke_width_jitter`, so the same seed reproduces the same widths
+    #[serde(default = "default_stroke_width_jitter_seed")]
+    pub stroke_width_jitter_seed: u64,
+    /// Record `source_hash` in a metadata comment for provenance and caching
+    #[serde(default)]
+    pub embed_source_hash: bool,
+    /// Hash of the source image and configuration, filled in by the pipeline
+    #[serde(default)]
+    pub source_hash: Option<String>,
+    /// Pixels per inch of the coordinate space; when set, `width`/`height` are
+    /// written in inches so the document prints at physical size
+    #[serde(default)]
+    pub physical_dpi: Option<f32>,
+    /// Whether to write each element's `source_color` as a `data-source-color` attribute
+    #[serde(default)]
+    pub emit_source_color: bool,
+    /// Whether to write each element's `region_metadata` as `data-centroid="x,y"` and
+    /// `data-area="n"` attributes
+    #[serde(default)]
+    pub emit_region_metadata: bool,
+    /// Wrap strokes in `thickness-thin`/`-medium`/`-thick` groups by stroke width,
+    /// e.g. to map each tier to a plotter pen
+    #[serde(default)]
+    pub group_by_thickness: bool,
+    /// Origin of emitted coordinates; ignored when `output_transform` is set
+    #[serde(default)]
+    pub coordinate_origin: CoordinateOrigin,
+    /// Append a `<g class="legend">` row of swatches listing each fill/stroke color
+    /// with its canvas coverage, e.g. to document screen-printing inks
+    #[serde(default)]
+    pub emit_palette_legend: bool,
+    /// Region boundaries form one shared edge network, each boundary drawn once; strokes
+    /// get round caps and joins so chains meeting at junctions close without notches
+    #[serde(default)]
+    pub shared_region_edges: bool,
+}
+
+fn default_stroke_width_jitter_seed() -> u64 {
+    42
 }
 
 impl Default for SvgConfig {
@@ -78,6 +286,32 @@ impl Default for SvgConfig {
             precision: 2,
             optimize: true,
             include_metadata: false,
+            emit_path_ids: false,
+            resample_even_spacing: None,
+            non_finite_handling: NonFiniteHandling::Drop,
+            color_space: ColorSpace::Srgb,
+            stroke_halo: None,
+            fill_and_stroke: None,
+            stroke_to_outline: false,
+            max_gradients: None,
+            repair_self_intersections: false,
+            auto_close_coincident: false,
+            emit_xml_declaration: false,
+            emit_path_length: false,
+            output_transform: None,
+            draw_on_animation: None,
+            absolute_stroke_width: None,
+            stroke_width_jitter: 0.0,
+            stroke_width_jitter_seed: default_stroke_width_jitter_seed(),
+            embed_source_hash: false,
+            source_hash: None,
+            physical_dpi: None,
+            emit_source_color: false,
+            emit_region_metadata: false,
+            group_by_thickness: false,
+            coordinate_origin: CoordinateOrigin::TopLeft,
+            emit_palette_legend: false,
+            shared_region_edges: false,
         }
     }
 }
@@ -5,7 +5,12 @@
 //! optimizations. It serves as the shared configuration interface for both the CLI and WASM
 //! implementations.
 
-use crate::algorithms::{HandDrawnConfig, HandDrawnPresets, TraceBackend, TraceLowConfig};
+use crate::algorithms::{
+    HandDrawnConfig, HandDrawnPresets, HandDrawnTarget, TraceBackend, TraceLowConfig,
+};
+use crate::config::ColorSpace;
+use crate::utils::normalize_color;
+use image::GrayImage;
 use std::collections::HashMap;
 
 /// Error type for configuration validation
@@ -37,6 +42,41 @@ impl std::error::Error for ConfigBuilderError {}
 /// Result type for configuration operations
 pub type ConfigBuilderResult<T> = Result<T, ConfigBuilderError>;
 
+/// Hand-drawn preset and overrides recorded on a builder
+///
+/// These live outside `TraceLowConfig`; the serialized keys match the ones read
+/// by the WASM unified config JSON.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct HandDrawnSettings {
+    #[serde(rename = "handDrawnPreset", skip_serializing_if = "Option::is_none")]
+    pub preset: Option<String>,
+    #[serde(rename = "handDrawnTremorStrength", skip_serializing_if = "Option::is_none")]
+    pub tremor: Option<f32>,
+    #[serde(rename = "handDrawnVariableWeights", skip_serializing_if = "Option::is_none")]
+    pub variable_weights: Option<f32>,
+    #[serde(rename = "handDrawnTapering", skip_serializing_if = "Option::is_none")]
+    pub tapering: Option<f32>,
+    #[serde(rename = "handDrawnPressureProfile", skip_serializing_if = "Option::is_none")]
+    pub pressure_profile: Option<Vec<f32>>,
+    #[serde(rename = "handDrawnApplyTo", skip_serializing_if = "Option::is_none")]
+    pub apply_to: Option<HandDrawnTarget>,
+}
+
+/// A setting that has no effect because a prerequisite setting is off
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct DependencyWarning {
+    /// The setting that was enabled
+    pub setting: String,
+    /// The prerequisite it depends on
+    pub requires: String,
+}
+
+impl std::fmt::Display for DependencyWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} has no effect without {}", self.setting, self.requires)
+    }
+}
+
 /// Builder pattern for constructing TraceLowConfig instances with validation
 #[derive(Debug, Clone)]
 pub struct ConfigBuilder {
@@ -45,10 +85,13 @@ pub struct ConfigBuilder {
     custom_tremor: Option<f32>,
     custom_variable_weights: Option<f32>,
     custom_tapering: Option<f32>,
+    custom_pressure_profile: Option<Vec<f32>>,
+    hand_drawn_target: Option<HandDrawnTarget>,
     // Superpixel-specific configuration
     num_superpixels: Option<u32>,
     compactness: Option<f32>,
     slic_iterations: Option<u32>,
+    slic_convergence_threshold: Option<f32>,
     superpixel_initialization_pattern: Option<String>,
     fill_regions: Option<bool>,
     stroke_regions: Option<bool>,
@@ -61,6 +104,9 @@ pub struct ConfigBuilder {
     superpixel_enhance_edges: Option<bool>,
     superpixel_merge_threshold: Option<f32>,
     enable_advanced_merging: Option<bool>,
+    superpixel_debug_colors: Option<bool>,
+    // Time budget override; the backend's default budget applies otherwise
+    max_processing_time_ms: Option<u64>,
 }
 
 impl Default for ConfigBuilder {
@@ -78,10 +124,13 @@ impl ConfigBuilder {
             custom_tremor: None,
             custom_variable_weights: None,
             custom_tapering: None,
+            custom_pressure_profile: None,
+            hand_drawn_target: None,
             // Initialize superpixel fields
             num_superpixels: None,
             compactness: None,
             slic_iterations: None,
+            slic_convergence_threshold: None,
             superpixel_initialization_pattern: None,
             fill_regions: None,
             stroke_regions: None,
@@ -94,6 +143,8 @@ impl ConfigBuilder {
             superpixel_enhance_edges: None,
             superpixel_merge_threshold: None,
             enable_advanced_merging: None,
+            superpixel_debug_colors: None,
+            max_processing_time_ms: None,
         }
     }
 
@@ -103,6 +154,33 @@ impl ConfigBuilder {
         self
     }
 
+    /// Switch to another tracing backend, keeping every setting the new backend accepts
+    ///
+    /// Unlike [`Self::backend`], settings the new backend rejects during validation
+    /// (ETF/FDoG, flow tracing, Bézier fitting, directional passes) are cleared, so the
+    /// result builds whenever the previous configuration did. Settings that only draw a
+    /// warning, such as halftone mode, are kept for switching back.
+    pub fn switch_backend(mut self, backend: TraceBackend) -> Self {
+        let clear = |enabled: &mut bool, setting: &str| {
+            if *enabled {
+                log::info!("ConfigBuilder: clearing {setting}, unsupported by {backend:?} backend");
+                *enabled = false;
+            }
+        };
+        let config = &mut self.config;
+        if !matches!(backend, TraceBackend::Edge | TraceBackend::Centerline) {
+            clear(&mut config.enable_etf_fdog, "enable_etf_fdog");
+            clear(&mut config.enable_flow_tracing, "enable_flow_tracing");
+            clear(&mut config.enable_bezier_fitting, "enable_bezier_fitting");
+        }
+        if matches!(backend, TraceBackend::Dots | TraceBackend::Superpixel) {
+            clear(&mut config.enable_reverse_pass, "enable_reverse_pass");
+            clear(&mut config.enable_diagonal_pass, "enable_diagonal_pass");
+        }
+        self.config.backend = backend;
+        self
+    }
+
     /// Set the tracing backend by string name
     pub fn backend_by_name(mut self, backend: &str) -> ConfigBuilderResult<Self> {
         self.config.backend = self.parse_backend(backend)?;
@@ -188,10 +266,36 @@ impl ConfigBuilder {
         Ok(self)
     }
 
-    /// Set maximum processing time in milliseconds
+    /// Set the margin directional benefits must clear the threshold by (0.0-0.5)
+    pub fn directional_hysteresis(mut self, margin: f32) -> ConfigBuilderResult<Self> {
+        self.validate_directional_hysteresis(margin)?;
+        self.config.directional_hysteresis = margin;
+        Ok(self)
+    }
+
+    /// Set the directional passes selected for the previous frame, which
+    /// `directional_hysteresis` keeps stable
+    pub fn previous_directional_passes(
+        mut self,
+        passes: Option<Vec<crate::algorithms::tracing::trace_low::ProcessingDirection>>,
+    ) -> Self {
+        self.config.previous_directional_passes = passes;
+        self
+    }
+
+    /// Set how overlapping edges from the base and directional passes are combined
+    pub fn multipass_merge(
+        mut self,
+        mode: crate::algorithms::tracing::trace_low::MultipassMerge,
+    ) -> Self {
+        self.config.multipass_merge = mode;
+        self
+    }
+
+    /// Set maximum processing time in milliseconds, overriding the backend's default budget
     pub fn max_processing_time_ms(mut self, time_ms: u64) -> ConfigBuilderResult<Self> {
         self.validate_processing_time(time_ms)?;
-        self.config.max_processing_time_ms = time_ms;
+        self.max_processing_time_ms = Some(time_ms);
         Ok(self)
     }
 
@@ -225,12 +329,30 @@ impl ConfigBuilder {
         Ok(self)
     }
 
+    /// Never place dots on detected background pixels
+    pub fn dots_skip_background(mut self, enabled: bool) -> Self {
+        self.config.dots_skip_background = enabled;
+        self
+    }
+
+    /// Merge runs of collinear dots into short dashes for pen plotters
+    pub fn dots_merge_to_dashes(mut self, enabled: bool) -> Self {
+        self.config.dots_merge_to_dashes = enabled;
+        self
+    }
+
     /// Enable or disable color preservation in dots
     pub fn preserve_colors(mut self, enabled: bool) -> Self {
         self.config.dot_preserve_colors = enabled;
         self
     }
 
+    /// Choose dot fill coloring independently of `preserve_colors`
+    pub fn dot_color_mode(mut self, mode: crate::algorithms::dots::dots::DotColorMode) -> Self {
+        self.config.dot_color_mode = Some(mode);
+        self
+    }
+
     /// Enable or disable color preservation in line tracing (edge/centerline backends)
     pub fn line_preserve_colors(mut self, enabled: bool) -> Self {
         self.config.line_preserve_colors = enabled;
@@ -327,6 +449,33 @@ impl ConfigBuilder {
         self
     }
 
+    /// Enable halftone placement: one darkness-sized dot per rotated grid cell
+    pub fn halftone_mode(mut self, enabled: bool) -> Self {
+        self.config.halftone_mode = enabled;
+        self
+    }
+
+    /// Set the halftone screen angle in degrees (0-180)
+    pub fn halftone_angle(mut self, degrees: f32) -> ConfigBuilderResult<Self> {
+        self.validate_halftone_angle(degrees)?;
+        self.config.halftone_angle = degrees;
+        Ok(self)
+    }
+
+    /// Set the halftone grid cell size in pixels (2-64)
+    pub fn halftone_cell_px(mut self, cell_px: f32) -> ConfigBuilderResult<Self> {
+        self.validate_halftone_cell_px(cell_px)?;
+        self.config.halftone_cell_px = cell_px;
+        Ok(self)
+    }
+
+    /// Set the approximate vertex count for the low-poly backend (16-5000)
+    pub fn lowpoly_vertex_count(mut self, count: u32) -> ConfigBuilderResult<Self> {
+        self.validate_lowpoly_vertex_count(count)?;
+        self.config.lowpoly_vertex_count = count;
+        Ok(self)
+    }
+
 
     /// Enable or disable gradient-based sizing for dot scaling based on local image gradients
     pub fn set_gradient_based_sizing(mut self, enabled: bool) -> Self {
@@ -371,6 +520,19 @@ impl ConfigBuilder {
         Ok(self)
     }
 
+    /// Set custom pressure profile as width multipliers along each stroke (overrides preset)
+    pub fn custom_pressure_profile(mut self, profile: Vec<f32>) -> ConfigBuilderResult<Self> {
+        self.validate_pressure_profile(&profile)?;
+        self.custom_pressure_profile = Some(profile);
+        Ok(self)
+    }
+
+    /// Limit hand-drawn effects to strokes or fills (overrides preset)
+    pub fn hand_drawn_apply_to(mut self, target: HandDrawnTarget) -> Self {
+        self.hand_drawn_target = Some(target);
+        self
+    }
+
     // Advanced parameters for ETF/FDoG
 
     /// Enable ETF/FDoG advanced edge detection
@@ -436,6 +598,20 @@ impl ConfigBuilder {
         self
     }
 
+    /// Set the minimum connected-component area (in pixels) kept in the binarized image
+    /// before tracing. Components smaller than this are removed (0 = disabled)
+    pub fn prebinary_despeckle_px(mut self, min_area_px: u32) -> Self {
+        self.config.prebinary_despeckle_px = min_area_px;
+        self
+    }
+
+    /// Set a per-pixel detail weight map (must match the input image dimensions).
+    /// Bright pixels trace more finely, dark pixels more coarsely (edge backend)
+    pub fn detail_map(mut self, map: Option<GrayImage>) -> Self {
+        self.config.detail_map = map;
+        self
+    }
+
     /// Set minimum branch length for centerline tracing (4-24 pixels)
     pub fn min_branch_length(mut self, length: f32) -> ConfigBuilderResult<Self> {
         self.validate_min_branch_length(length)?;
@@ -496,6 +672,13 @@ impl ConfigBuilder {
         Ok(self)
     }
 
+    /// Set the SLIC early-exit threshold in pixels of cluster center movement (0.0 disables)
+    pub fn slic_convergence_threshold(mut self, threshold: f32) -> ConfigBuilderResult<Self> {
+        self.validate_slic_convergence_threshold(threshold)?;
+        self.slic_convergence_threshold = Some(threshold);
+        Ok(self)
+    }
+
     /// Set superpixel initialization pattern: "square", "hexagonal", or "poisson"
     pub fn superpixel_initialization_pattern(mut self, pattern: &str) -> ConfigBuilderResult<Self> {
         log::info!(
@@ -508,6 +691,31 @@ impl ConfigBuilder {
         Ok(self)
     }
 
+    /// Seed superpixels by saliency so textured subjects get more regions than flat background
+    pub fn superpixel_saliency_seeding(mut self, enabled: bool) -> Self {
+        self.config.superpixel_saliency_seeding = enabled;
+        self
+    }
+
+    /// Average region colors in LAB space instead of RGB for perceptually central fills
+    pub fn region_average_in_lab(mut self, enabled: bool) -> Self {
+        self.config.region_average_in_lab = enabled;
+        self
+    }
+
+    /// Blend superpixel region colors towards similar neighbours to reduce banding (0.0-1.0, 0 disables)
+    pub fn region_color_smoothing(mut self, strength: f32) -> ConfigBuilderResult<Self> {
+        self.validate_region_color_smoothing(strength)?;
+        self.config.region_color_smoothing = strength;
+        Ok(self)
+    }
+
+    /// Stroke each boundary shared by two superpixel regions once instead of outlining every region
+    pub fn shared_region_edges(mut self, enabled: bool) -> Self {
+        self.config.shared_region_edges = enabled;
+        self
+    }
+
     /// Deprecated: Use superpixel_initialization_pattern instead
     #[deprecated(
         since = "0.1.0",
@@ -580,6 +788,12 @@ impl ConfigBuilder {
         self
     }
 
+    /// Enable or disable the distinct-color debug palette for superpixel regions
+    pub fn superpixel_debug_colors(mut self, enabled: bool) -> Self {
+        self.superpixel_debug_colors = Some(enabled);
+        self
+    }
+
     // Safety and optimization parameters
 
     /// Set maximum image size before automatic resizing (512-8192 pixels)
@@ -596,6 +810,266 @@ impl ConfigBuilder {
         Ok(self)
     }
 
+    /// Enable or disable deterministic `id` attributes on output paths
+    pub fn emit_path_ids(mut self, enabled: bool) -> Self {
+        self.config.emit_path_ids = enabled;
+        self
+    }
+
+    /// Enable or disable the `<?xml ...?>` declaration at the top of the SVG
+    pub fn emit_xml_declaration(mut self, enabled: bool) -> Self {
+        self.config.emit_xml_declaration = enabled;
+        self
+    }
+
+    /// Emit a normalized `pathLength` on stroked paths for draw-on animations
+    pub fn emit_path_length(mut self, enabled: bool) -> Self {
+        self.config.emit_path_length = enabled;
+        self
+    }
+
+    /// Embed a hash of the source image and configuration as an SVG metadata comment
+    pub fn embed_source_hash(mut self, enabled: bool) -> Self {
+        self.config.embed_source_hash = enabled;
+        self
+    }
+
+    /// Animate strokes drawing on in path order over `duration` seconds (None disables)
+    pub fn draw_on_animation(mut self, duration: Option<f32>) -> ConfigBuilderResult<Self> {
+        if let Some(seconds) = duration {
+            self.validate_draw_on_duration(seconds)?;
+        }
+        self.config.draw_on_animation = duration;
+        Ok(self)
+    }
+
+    /// Use an exact stroke width in output pixels instead of resolution-relative scaling
+    /// (None restores `stroke_width` scaling)
+    pub fn absolute_stroke_width(mut self, width: Option<f32>) -> ConfigBuilderResult<Self> {
+        if let Some(width) = width {
+            self.validate_absolute_stroke_width(width)?;
+        }
+        self.config.absolute_stroke_width = width;
+        Ok(self)
+    }
+
+    /// Vary each stroke's width randomly by up to this fraction (0.0-0.5, 0.0 disables)
+    pub fn stroke_width_jitter(mut self, jitter: f32) -> ConfigBuilderResult<Self> {
+        self.validate_stroke_width_jitter(jitter)?;
+        self.config.stroke_width_jitter = jitter;
+        Ok(self)
+    }
+
+    /// Mirror or rotate the output (None keeps the source orientation)
+    pub fn output_transform(mut self, transform: Option<crate::config::Transform>) -> Self {
+        self.config.output_transform = transform;
+        self
+    }
+
+    /// Place the origin of emitted coordinates at the top-left, center or bottom-left
+    pub fn coordinate_origin(mut self, origin: crate::config::CoordinateOrigin) -> Self {
+        self.config.coordinate_origin = origin;
+        self
+    }
+
+    /// Drop the offending segment or clamp NaN/infinite coordinates to the viewport
+    pub fn non_finite_handling(mut self, handling: crate::config::NonFiniteHandling) -> Self {
+        self.config.non_finite_handling = handling;
+        self
+    }
+
+    /// Mask border-connected near-black and near-white regions (frame and mat) as background
+    pub fn dual_background(mut self, enabled: bool) -> Self {
+        self.config.dual_background = enabled;
+        self
+    }
+
+    /// Smooth luminance banding in compressed gradients before tracing
+    pub fn deband(mut self, enabled: bool) -> Self {
+        self.config.deband = enabled;
+        self
+    }
+
+    /// Treat pixels with alpha below `threshold` as empty background (0 keeps every pixel)
+    pub fn alpha_threshold(mut self, threshold: u8) -> Self {
+        self.config.alpha_threshold = threshold;
+        self
+    }
+
+    /// Treat images with fewer content pixels than `count` as empty (lower it for tiny icons)
+    pub fn min_content_pixels(mut self, count: usize) -> Self {
+        self.config.min_content_pixels = count;
+        self
+    }
+
+    /// Trace detected text as centerlines, using the selected backend elsewhere
+    pub fn text_aware(mut self, enabled: bool) -> Self {
+        self.config.text_aware = enabled;
+        self
+    }
+
+    /// Convert images with an embedded ICC profile to sRGB when decoding
+    pub fn apply_icc_profile(mut self, enabled: bool) -> Self {
+        self.config.apply_icc_profile = enabled;
+        self
+    }
+
+    /// Set the largest encoded image, in megapixels, that will be decoded (1-1000)
+    pub fn max_decode_megapixels(mut self, megapixels: u32) -> ConfigBuilderResult<Self> {
+        self.validate_max_decode_megapixels(megapixels)?;
+        self.config.max_decode_megapixels = megapixels;
+        Ok(self)
+    }
+
+    /// Write the SVG size in inches at this pixel density (None keeps pixel units)
+    pub fn output_dpi(mut self, dpi: Option<f32>) -> ConfigBuilderResult<Self> {
+        if let Some(dpi) = dpi {
+            self.validate_output_dpi(dpi)?;
+        }
+        self.config.output_dpi = dpi;
+        Ok(self)
+    }
+
+    /// Use the pixel density embedded in PNG/JPEG files as the output DPI when decoding
+    pub fn use_embedded_dpi(mut self, enabled: bool) -> Self {
+        self.config.use_embedded_dpi = enabled;
+        self
+    }
+
+    /// Set the interval for evenly resampling polyline output points (None disables)
+    pub fn resample_even_spacing(mut self, spacing: Option<f32>) -> ConfigBuilderResult<Self> {
+        if let Some(s) = spacing {
+            self.validate_resample_spacing(s)?;
+        }
+        self.config.resample_even_spacing = spacing;
+        Ok(self)
+    }
+
+    /// Enable or disable fading line paths by the strength of the edge they trace
+    pub fn edge_strength_opacity(mut self, enabled: bool) -> Self {
+        self.config.edge_strength_opacity = enabled;
+        self
+    }
+
+    /// Attach each path's average source-image color as a `data-source-color`
+    /// attribute, without changing the rendered colors
+    pub fn emit_source_color(mut self, enabled: bool) -> Self {
+        self.config.emit_source_color = enabled;
+        self
+    }
+
+    /// Attach each superpixel region's centroid and pixel area as `data-centroid`
+    /// and `data-area` attributes, e.g. for recoloring or analytics
+    pub fn emit_region_metadata(mut self, enabled: bool) -> Self {
+        self.config.emit_region_metadata = enabled;
+        self
+    }
+
+    /// Group strokes into thin/medium/thick layers by stroke width, e.g. one per plotter pen
+    pub fn group_by_thickness(mut self, enabled: bool) -> Self {
+        self.config.group_by_thickness = enabled;
+        self
+    }
+
+    /// Append a legend of the colors used with their coverage, e.g. to document print inks
+    pub fn emit_palette_legend(mut self, enabled: bool) -> Self {
+        self.config.emit_palette_legend = enabled;
+        self
+    }
+
+    /// Set the color space for output fill/stroke colors
+    pub fn color_space(mut self, color_space: ColorSpace) -> Self {
+        self.config.color_space = color_space;
+        self
+    }
+
+    /// Guarantee at least this many paths from the edge backend by relaxing thresholds (0 disables)
+    pub fn guarantee_min_paths(mut self, count: usize) -> Self {
+        self.config.guarantee_min_paths = count;
+        self
+    }
+
+    /// Re-run the edge backend with raised thresholds when output is dominated by short noise paths
+    pub fn auto_denoise_escalate(mut self, enabled: bool) -> Self {
+        self.config.auto_denoise_escalate = enabled;
+        self
+    }
+
+    /// Keep junction vertices fixed during simplification so line networks stay connected
+    pub fn preserve_junction_topology(mut self, enabled: bool) -> Self {
+        self.config.preserve_junction_topology = enabled;
+        self
+    }
+
+    /// Drop spike vertices sharper than this angle in degrees before fitting (0-90, 0 disables)
+    pub fn min_vertex_angle(mut self, degrees: f32) -> ConfigBuilderResult<Self> {
+        self.validate_min_vertex_angle(degrees)?;
+        self.config.min_vertex_angle_deg = degrees;
+        Ok(self)
+    }
+
+    /// Straighten near-straight traced edges exactly, snapping nearly axis-aligned ones
+    pub fn snap_straight_lines(mut self, enabled: bool) -> Self {
+        self.config.snap_straight_lines = enabled;
+        self
+    }
+
+    /// Limit Douglas-Peucker split depth; deeper paths fall back to Visvalingam-Whyatt
+    pub fn max_simplify_depth(mut self, depth: u32) -> ConfigBuilderResult<Self> {
+        self.validate_max_simplify_depth(depth)?;
+        self.config.max_simplify_depth = depth;
+        Ok(self)
+    }
+
+    /// Set a halo (color, width in pixels) drawn as a wider underlay behind strokes (None disables)
+    ///
+    /// The color may be any form accepted by [`parse_color`](crate::utils::parse_color)
+    /// and is stored normalized.
+    pub fn stroke_halo(mut self, halo: Option<(String, f32)>) -> ConfigBuilderResult<Self> {
+        self.config.stroke_halo = match halo {
+            Some((color, width)) => Some((self.validate_stroke_halo(&color, width)?, width)),
+            None => None,
+        };
+        Ok(self)
+    }
+
+    /// Expand strokes into filled outlines at their width before output
+    pub fn stroke_to_outline(mut self, enabled: bool) -> Self {
+        self.config.stroke_to_outline = enabled;
+        self
+    }
+
+    /// Emit at most this many gradient definitions, converting the weakest to
+    /// solid colors (None keeps every gradient)
+    pub fn max_gradients(mut self, max: Option<usize>) -> Self {
+        self.config.max_gradients = max;
+        self
+    }
+
+    /// Split self-intersecting filled outlines into simple polygons before output
+    pub fn repair_self_intersections(mut self, enabled: bool) -> Self {
+        self.config.repair_self_intersections = enabled;
+        self
+    }
+
+    /// Close paths whose end points coincide at the output precision with an explicit `Z`
+    pub fn auto_close_coincident(mut self, enabled: bool) -> Self {
+        self.config.auto_close_coincident = enabled;
+        self
+    }
+
+    /// Set an outline (color, width in pixels) drawn around every filled shape (None disables)
+    ///
+    /// The color may be any form accepted by [`parse_color`](crate::utils::parse_color)
+    /// and is stored normalized.
+    pub fn fill_and_stroke(mut self, outline: Option<(String, f32)>) -> ConfigBuilderResult<Self> {
+        self.config.fill_and_stroke = match outline {
+            Some((color, width)) => Some((self.validate_fill_outline(&color, width)?, width)),
+            None => None,
+        };
+        Ok(self)
+    }
+
     // Preset configurations for common use cases
 
     /// Configure for standard line art with clean lines
@@ -727,6 +1201,62 @@ impl ConfigBuilder {
             .preserve_colors(true)
     }
 
+    /// Report every enabled setting whose prerequisite is off
+    ///
+    /// Unlike `build()`, which stops at the first inconsistency, this collects
+    /// all of them so callers can explain the whole feature chain at once.
+    pub fn validate_dependencies(&self) -> Vec<DependencyWarning> {
+        let config = &self.config;
+
+        let checks = [
+            (
+                config.enable_flow_tracing && !config.enable_etf_fdog,
+                "enable_flow_tracing",
+                "enable_etf_fdog",
+            ),
+            (
+                config.enable_bezier_fitting && !config.enable_flow_tracing,
+                "enable_bezier_fitting",
+                "enable_flow_tracing",
+            ),
+            (
+                config.enable_reverse_pass && !config.enable_multipass,
+                "enable_reverse_pass",
+                "enable_multipass",
+            ),
+            (
+                config.enable_diagonal_pass && !config.enable_multipass,
+                "enable_diagonal_pass",
+                "enable_multipass",
+            ),
+            (
+                (config.conservative_detail.is_some() || config.aggressive_detail.is_some())
+                    && !config.enable_multipass,
+                "conservative_detail/aggressive_detail",
+                "enable_multipass",
+            ),
+            (
+                config.halftone_mode && config.backend != TraceBackend::Dots,
+                "halftone_mode",
+                "backend=dots",
+            ),
+            (
+                self.has_hand_drawn_overrides() && !self.has_hand_drawn_preset(),
+                "hand-drawn custom overrides",
+                "hand_drawn_preset",
+            ),
+        ];
+
+        checks
+            .into_iter()
+            .filter(|(missing, _, _)| *missing)
+            .map(|(_, setting, requires)| DependencyWarning {
+                setting: setting.to_string(),
+                requires: requires.to_string(),
+            })
+            .collect()
+    }
+
     /// Build the final TraceLowConfig with validation
     pub fn build(self) -> ConfigBuilderResult<TraceLowConfig> {
         // Validate the complete configuration
@@ -743,6 +1273,9 @@ impl ConfigBuilder {
         if let Some(iterations) = self.slic_iterations {
             config.superpixel_slic_iterations = iterations;
         }
+        if let Some(threshold) = self.slic_convergence_threshold {
+            config.slic_convergence_threshold = threshold;
+        }
         if let Some(pattern) = &self.superpixel_initialization_pattern {
             let enum_pattern = match pattern.as_str() {
                 "square" => crate::algorithms::tracing::trace_low::SuperpixelInitPattern::Square,
@@ -796,6 +1329,12 @@ impl ConfigBuilder {
         if let Some(advanced_merging) = self.enable_advanced_merging {
             config.enable_advanced_merging = advanced_merging;
         }
+        if let Some(debug_colors) = self.superpixel_debug_colors {
+            config.superpixel_debug_colors = debug_colors;
+        }
+        config.max_processing_time_ms = self
+            .max_processing_time_ms
+            .unwrap_or_else(|| config.backend.default_time_budget_ms());
 
         Ok(config)
     }
@@ -821,6 +1360,9 @@ impl ConfigBuilder {
         if let Some(iterations) = self.slic_iterations {
             config.superpixel_slic_iterations = iterations;
         }
+        if let Some(threshold) = self.slic_convergence_threshold {
+            config.slic_convergence_threshold = threshold;
+        }
         if let Some(pattern) = &self.superpixel_initialization_pattern {
             let enum_pattern = match pattern.as_str() {
                 "square" => crate::algorithms::tracing::trace_low::SuperpixelInitPattern::Square,
@@ -874,13 +1416,31 @@ impl ConfigBuilder {
         if let Some(advanced_merging) = self.enable_advanced_merging {
             config.enable_advanced_merging = advanced_merging;
         }
+        if let Some(debug_colors) = self.superpixel_debug_colors {
+            config.superpixel_debug_colors = debug_colors;
+        }
+        config.max_processing_time_ms = self
+            .max_processing_time_ms
+            .unwrap_or_else(|| config.backend.default_time_budget_ms());
 
         Ok((config, hand_drawn_config))
     }
 
     /// Get available backend names
     pub fn available_backends() -> Vec<&'static str> {
-        vec!["edge", "centerline", "superpixel", "dots"]
+        vec!["edge", "centerline", "superpixel", "dots", "lowpoly"]
+    }
+
+    /// Hand-drawn preset and custom overrides set on this builder
+    pub fn hand_drawn_settings(&self) -> HandDrawnSettings {
+        HandDrawnSettings {
+            preset: self.hand_drawn_preset.clone(),
+            tremor: self.custom_tremor,
+            variable_weights: self.custom_variable_weights,
+            tapering: self.custom_tapering,
+            pressure_profile: self.custom_pressure_profile.clone(),
+            apply_to: self.hand_drawn_target,
+        }
     }
 
     /// Get available hand-drawn presets
@@ -933,6 +1493,13 @@ impl ConfigBuilder {
                     "recommended for natural look".to_string(),
                 );
             }
+            TraceBackend::LowPoly => {
+                recommendations.insert(
+                    "lowpoly_vertex_count",
+                    "200-500 for bold facets, 1000-3000 for detailed".to_string(),
+                );
+                recommendations.insert("multipass", "false - single pass sufficient".to_string());
+            }
         }
 
         recommendations
@@ -981,6 +1548,21 @@ impl ConfigBuilder {
         Ok(())
     }
 
+    fn validate_pressure_profile(&self, profile: &[f32]) -> ConfigBuilderResult<()> {
+        if profile.is_empty() || profile.len() > 16 {
+            return Err(ConfigBuilderError::InvalidParameter(format!(
+                "Pressure profile must have between 1 and 16 values, got: {}",
+                profile.len()
+            )));
+        }
+        if let Some(value) = profile.iter().find(|v| !(0.0..=3.0).contains(*v)) {
+            return Err(ConfigBuilderError::InvalidParameter(format!(
+                "Pressure profile values must be between 0.0 and 3.0, got: {value}"
+            )));
+        }
+        Ok(())
+    }
+
     fn validate_dot_size_range(&self, min_radius: f32, max_radius: f32) -> ConfigBuilderResult<()> {
         if min_radius <= 0.0 || max_radius <= 0.0 {
             return Err(ConfigBuilderError::InvalidParameter(
@@ -1029,8 +1611,9 @@ impl ConfigBuilder {
             "centerline" => Ok(TraceBackend::Centerline),
             "superpixel" => Ok(TraceBackend::Superpixel),
             "dots" => Ok(TraceBackend::Dots),
+            "lowpoly" => Ok(TraceBackend::LowPoly),
             _ => Err(ConfigBuilderError::InvalidBackend(format!(
-                "Invalid backend: {backend}. Must be one of: edge, centerline, superpixel, dots"
+                "Invalid backend: {backend}. Must be one of: edge, centerline, superpixel, dots, lowpoly"
             ))),
         }
     }
@@ -1112,6 +1695,69 @@ impl ConfigBuilder {
         Ok(())
     }
 
+    fn validate_slic_convergence_threshold(&self, threshold: f32) -> ConfigBuilderResult<()> {
+        if !(0.0..=10.0).contains(&threshold) {
+            return Err(ConfigBuilderError::InvalidParameter(format!(
+                "SLIC convergence threshold must be between 0.0 and 10.0 pixels, got: {threshold}"
+            )));
+        }
+        Ok(())
+    }
+
+    fn validate_directional_hysteresis(&self, margin: f32) -> ConfigBuilderResult<()> {
+        if !(0.0..=0.5).contains(&margin) {
+            return Err(ConfigBuilderError::InvalidParameter(format!(
+                "Directional hysteresis must be between 0.0 and 0.5, got: {margin}"
+            )));
+        }
+        Ok(())
+    }
+
+    fn validate_min_vertex_angle(&self, degrees: f32) -> ConfigBuilderResult<()> {
+        if !(0.0..=90.0).contains(&degrees) {
+            return Err(ConfigBuilderError::InvalidParameter(format!(
+                "Minimum vertex angle must be between 0 and 90 degrees, got: {degrees}"
+            )));
+        }
+        Ok(())
+    }
+
+    fn validate_max_simplify_depth(&self, depth: u32) -> ConfigBuilderResult<()> {
+        if depth == 0 {
+            return Err(ConfigBuilderError::InvalidParameter(
+                "Maximum simplification depth must be at least 1".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn validate_lowpoly_vertex_count(&self, count: u32) -> ConfigBuilderResult<()> {
+        if !(16..=5000).contains(&count) {
+            return Err(ConfigBuilderError::InvalidParameter(format!(
+                "Low-poly vertex count must be between 16 and 5000, got: {count}"
+            )));
+        }
+        Ok(())
+    }
+
+    fn validate_halftone_angle(&self, degrees: f32) -> ConfigBuilderResult<()> {
+        if !(0.0..=180.0).contains(&degrees) {
+            return Err(ConfigBuilderError::InvalidParameter(format!(
+                "Halftone angle must be between 0 and 180 degrees, got: {degrees}"
+            )));
+        }
+        Ok(())
+    }
+
+    fn validate_halftone_cell_px(&self, cell_px: f32) -> ConfigBuilderResult<()> {
+        if !(2.0..=64.0).contains(&cell_px) {
+            return Err(ConfigBuilderError::InvalidParameter(format!(
+                "Halftone cell size must be between 2 and 64 pixels, got: {cell_px}"
+            )));
+        }
+        Ok(())
+    }
+
     fn validate_boundary_epsilon(&self, epsilon: f32) -> ConfigBuilderResult<()> {
         if !(0.5..=3.0).contains(&epsilon) {
             return Err(ConfigBuilderError::InvalidParameter(format!(
@@ -1140,6 +1786,15 @@ impl ConfigBuilder {
         Ok(())
     }
 
+    fn validate_region_color_smoothing(&self, strength: f32) -> ConfigBuilderResult<()> {
+        if !(0.0..=1.0).contains(&strength) {
+            return Err(ConfigBuilderError::InvalidParameter(format!(
+                "Region color smoothing must be between 0.0 and 1.0, got: {strength}"
+            )));
+        }
+        Ok(())
+    }
+
     fn validate_superpixel_merge_threshold(&self, threshold: f32) -> ConfigBuilderResult<()> {
         if !(0.01..=1.0).contains(&threshold) {
             return Err(ConfigBuilderError::InvalidParameter(format!(
@@ -1149,6 +1804,15 @@ impl ConfigBuilder {
         Ok(())
     }
 
+    fn validate_max_decode_megapixels(&self, megapixels: u32) -> ConfigBuilderResult<()> {
+        if !(1..=1000).contains(&megapixels) {
+            return Err(ConfigBuilderError::InvalidParameter(format!(
+                "Maximum decode size must be between 1 and 1000 megapixels, got: {megapixels}"
+            )));
+        }
+        Ok(())
+    }
+
     fn validate_max_image_size(&self, size: u32) -> ConfigBuilderResult<()> {
         if !(512..=8192).contains(&size) {
             return Err(ConfigBuilderError::InvalidParameter(format!(
@@ -1167,6 +1831,77 @@ impl ConfigBuilder {
         Ok(())
     }
 
+    fn validate_resample_spacing(&self, spacing: f32) -> ConfigBuilderResult<()> {
+        if !spacing.is_finite() || spacing <= 0.0 {
+            return Err(ConfigBuilderError::InvalidParameter(format!(
+                "Resample spacing must be a positive number of pixels, got: {spacing}"
+            )));
+        }
+        Ok(())
+    }
+
+    fn validate_draw_on_duration(&self, seconds: f32) -> ConfigBuilderResult<()> {
+        if !seconds.is_finite() || seconds <= 0.0 {
+            return Err(ConfigBuilderError::InvalidParameter(format!(
+                "Draw-on animation duration must be a positive number of seconds, got: {seconds}"
+            )));
+        }
+        Ok(())
+    }
+
+    fn validate_absolute_stroke_width(&self, width: f32) -> ConfigBuilderResult<()> {
+        if !width.is_finite() || width <= 0.0 {
+            return Err(ConfigBuilderError::InvalidParameter(format!(
+                "Absolute stroke width must be a positive number of pixels, got: {width}"
+            )));
+        }
+        Ok(())
+    }
+
+    fn validate_stroke_width_jitter(&self, jitter: f32) -> ConfigBuilderResult<()> {
+        if !(0.0..=0.5).contains(&jitter) {
+            return Err(ConfigBuilderError::InvalidParameter(format!(
+                "Stroke width jitter must be between 0.0 and 0.5, got: {jitter}"
+            )));
+        }
+        Ok(())
+    }
+
+    fn validate_output_dpi(&self, dpi: f32) -> ConfigBuilderResult<()> {
+        if !dpi.is_finite() || dpi <= 0.0 {
+            return Err(ConfigBuilderError::InvalidParameter(format!(
+                "Output DPI must be a positive number of pixels per inch, got: {dpi}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Validate a color setting, returning it normalized
+    fn validate_color(&self, color: &str, setting: &str) -> ConfigBuilderResult<String> {
+        normalize_color(color)
+            .map_err(|e| ConfigBuilderError::InvalidParameter(format!("{setting} color: {e}")))
+    }
+
+    fn validate_stroke_halo(&self, color: &str, width: f32) -> ConfigBuilderResult<String> {
+        let color = self.validate_color(color, "Stroke halo")?;
+        if !width.is_finite() || width <= 0.0 {
+            return Err(ConfigBuilderError::InvalidParameter(format!(
+                "Stroke halo width must be a positive number of pixels, got: {width}"
+            )));
+        }
+        Ok(color)
+    }
+
+    fn validate_fill_outline(&self, color: &str, width: f32) -> ConfigBuilderResult<String> {
+        let color = self.validate_color(color, "Fill outline")?;
+        if !width.is_finite() || width <= 0.0 {
+            return Err(ConfigBuilderError::InvalidParameter(format!(
+                "Fill outline width must be a positive number of pixels, got: {width}"
+            )));
+        }
+        Ok(color)
+    }
+
     fn validate_processing_time(&self, time_ms: u64) -> ConfigBuilderResult<()> {
         if time_ms == 0 {
             return Err(ConfigBuilderError::InvalidParameter(
@@ -1288,6 +2023,17 @@ impl ConfigBuilder {
                     // Settings will be ignored but not an error
                 }
             }
+            TraceBackend::LowPoly => {
+                // ETF/FDoG settings don't apply to the low-poly backend
+                if self.config.enable_etf_fdog
+                    || self.config.enable_flow_tracing
+                    || self.config.enable_bezier_fitting
+                {
+                    return Err(ConfigBuilderError::ValidationFailed(
+                        "ETF/FDoG, flow tracing, and Bézier fitting are not supported by the LowPoly backend".to_string(),
+                    ));
+                }
+            }
         }
 
         Ok(())
@@ -1448,15 +2194,25 @@ impl ConfigBuilder {
         Ok(())
     }
 
+    /// Whether any hand-drawn custom override is set
+    fn has_hand_drawn_overrides(&self) -> bool {
+        self.custom_tremor.is_some()
+            || self.custom_variable_weights.is_some()
+            || self.custom_tapering.is_some()
+            || self.custom_pressure_profile.is_some()
+            || self.hand_drawn_target.is_some()
+    }
+
+    /// Whether a hand-drawn preset is selected; "none" counts as no preset
+    fn has_hand_drawn_preset(&self) -> bool {
+        !matches!(self.hand_drawn_preset.as_deref(), None | Some("none"))
+    }
+
     /// Validate hand-drawn custom overrides
     fn validate_hand_drawn_overrides(&self) -> ConfigBuilderResult<()> {
-        if (self.custom_tremor.is_some()
-            || self.custom_variable_weights.is_some()
-            || self.custom_tapering.is_some())
-            && matches!(self.hand_drawn_preset.as_deref(), None | Some("none"))
-        {
+        if self.has_hand_drawn_overrides() && !self.has_hand_drawn_preset() {
             return Err(ConfigBuilderError::ValidationFailed(
-                "Hand-drawn preset must be specified when using custom tremor, variable weights, or tapering"
+                "Hand-drawn preset must be specified when using custom tremor, variable weights, tapering, pressure profile, or target elements"
                     .to_string(),
             ));
         }
@@ -1491,6 +2247,12 @@ impl ConfigBuilder {
         if let Some(tapering) = self.custom_tapering {
             config.tapering = tapering;
         }
+        if let Some(profile) = &self.custom_pressure_profile {
+            config.pressure_profile = profile.clone();
+        }
+        if let Some(target) = self.hand_drawn_target {
+            config.apply_to = target;
+        }
 
         Ok(Some(config))
     }
@@ -1568,6 +2330,43 @@ mod tests {
         assert_eq!(config.stroke_px_at_1080p, 2.0);
     }
 
+    #[test]
+    fn test_switch_backend_preserves_compatible_settings() {
+        let builder = ConfigBuilder::new()
+            .backend(TraceBackend::Edge)
+            .detail(0.7)
+            .unwrap()
+            .stroke_width(2.5)
+            .unwrap()
+            .enable_etf_fdog(true)
+            .enable_flow_tracing(true);
+
+        // Plain backend() keeps the Edge-only settings and fails validation
+        assert!(builder.clone().backend(TraceBackend::Dots).build().is_err());
+
+        let config = builder
+            .switch_backend(TraceBackend::Dots)
+            .build()
+            .expect("switching backend should keep a valid configuration");
+        assert_eq!(config.backend, TraceBackend::Dots);
+        assert_eq!(config.detail, 0.7);
+        assert_eq!(config.stroke_px_at_1080p, 2.5);
+        assert!(!config.enable_etf_fdog);
+        assert!(!config.enable_flow_tracing);
+
+        // Settings that only warn on other backends survive a round trip
+        let round_trip = ConfigBuilder::new()
+            .backend(TraceBackend::Dots)
+            .halftone_mode(true)
+            .switch_backend(TraceBackend::Edge);
+        assert!(round_trip.clone().build().is_ok());
+        let config = round_trip
+            .switch_backend(TraceBackend::Dots)
+            .build()
+            .unwrap();
+        assert!(config.halftone_mode);
+    }
+
     #[test]
     fn test_backend_by_name() {
         let builder = ConfigBuilder::new().backend_by_name("dots").unwrap();
@@ -1682,6 +2481,68 @@ mod tests {
         assert_eq!(hd.tapering, 0.6);
     }
 
+    #[test]
+    fn test_hand_drawn_pressure_profile() {
+        let (_, hand_drawn) = ConfigBuilder::new()
+            .hand_drawn_preset("subtle")
+            .unwrap()
+            .custom_pressure_profile(vec![0.2, 1.0, 0.2])
+            .unwrap()
+            .build_with_hand_drawn()
+            .unwrap();
+        assert_eq!(hand_drawn.unwrap().pressure_profile, vec![0.2, 1.0, 0.2]);
+
+        assert!(ConfigBuilder::new().custom_pressure_profile(vec![]).is_err());
+        assert!(ConfigBuilder::new().custom_pressure_profile(vec![0.5, 4.0]).is_err());
+        assert!(ConfigBuilder::new()
+            .custom_pressure_profile(vec![1.0])
+            .unwrap()
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn test_validate_dependencies_names_missing_prerequisite() {
+        assert!(ConfigBuilder::new().validate_dependencies().is_empty());
+
+        let warnings = ConfigBuilder::new()
+            .enable_bezier_fitting(true)
+            .validate_dependencies();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(
+            warnings[0].to_string(),
+            "enable_bezier_fitting has no effect without enable_flow_tracing"
+        );
+
+        let warnings = ConfigBuilder::new()
+            .enable_etf_fdog(true)
+            .enable_flow_tracing(true)
+            .enable_bezier_fitting(true)
+            .validate_dependencies();
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_hand_drawn_preset_none_counts_as_missing() {
+        let builder = ConfigBuilder::new()
+            .hand_drawn_preset("none")
+            .unwrap()
+            .custom_tremor(0.2)
+            .unwrap();
+        let warnings = builder.validate_dependencies();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].requires, "hand_drawn_preset");
+        assert!(builder.build().is_err());
+
+        let builder = ConfigBuilder::new()
+            .hand_drawn_preset("subtle")
+            .unwrap()
+            .custom_tremor(0.2)
+            .unwrap();
+        assert!(builder.validate_dependencies().is_empty());
+        assert!(builder.build().is_ok());
+    }
+
     #[test]
     fn test_validation_failures() {
         // ETF/FDoG dependency validation
@@ -1962,4 +2823,51 @@ mod tests {
         assert!(ConfigBuilder::new().superpixel_merge_threshold(0.005).is_err()); // Too low
         assert!(ConfigBuilder::new().superpixel_merge_threshold(1.1).is_err()); // Too high
     }
+
+    #[test]
+    fn test_color_settings_are_normalized() {
+        let config = ConfigBuilder::new()
+            .stroke_halo(Some(("white".to_string(), 2.0)))
+            .unwrap()
+            .fill_and_stroke(Some(("#F00".to_string(), 1.0)))
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(config.stroke_halo, Some(("#ffffff".to_string(), 2.0)));
+        assert_eq!(config.fill_and_stroke, Some(("#ff0000".to_string(), 1.0)));
+
+        let error = ConfigBuilder::new()
+            .stroke_halo(Some(("#ff00zz".to_string(), 2.0)))
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("Stroke halo color") && error.contains("#ff00zz"), "{error}");
+        assert!(ConfigBuilder::new()
+            .fill_and_stroke(Some(("blurple".to_string(), 1.0)))
+            .is_err());
+    }
+
+    #[test]
+    fn test_time_budget_defaults_per_backend() {
+        let edge = ConfigBuilder::new().build().unwrap();
+        let superpixel = ConfigBuilder::new()
+            .backend(TraceBackend::Superpixel)
+            .build()
+            .unwrap();
+        assert_eq!(
+            edge.max_processing_time_ms,
+            TraceBackend::Edge.default_time_budget_ms()
+        );
+        assert_eq!(
+            superpixel.max_processing_time_ms,
+            TraceBackend::Superpixel.default_time_budget_ms()
+        );
+
+        let overridden = ConfigBuilder::new()
+            .max_processing_time_ms(5000)
+            .unwrap()
+            .backend(TraceBackend::Superpixel)
+            .build()
+            .unwrap();
+        assert_eq!(overridden.max_processing_time_ms, 5000);
+    }
 }
\ No newline at end of file
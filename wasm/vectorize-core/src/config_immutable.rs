@@ -581,6 +581,9 @@ impl VectorizerConfig {
                     .superpixel_preserve_colors
                     .unwrap_or(false);
             }
+            TraceBackend::LowPoly => {
+                // Low-poly only needs its vertex budget, which keeps the TraceLowConfig default
+            }
         }
 
         // Build hand-drawn config if specified
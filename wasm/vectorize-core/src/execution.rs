@@ -9,6 +9,13 @@
 //! - **Single-threaded WASM**: All processing runs single-threaded in WASM module
 //! - **Web Worker parallelism**: JavaScript-level parallelism through Web Workers
 //! - **Stable & reliable**: No complex threading or SharedArrayBuffer requirements
+//!
+//! ## Native parallelism
+//! Native builds can enable the `parallel` feature, which makes `execute_parallel`,
+//! `execute_parallel_filter_map` and `execute_parallel_chunks` split work across scoped
+//! OS threads, and lets `should_use_parallel` report true for large inputs. Results keep
+//! their input order, so output is identical to sequential execution. `with_thread_pool` with
+//! `num_threads: Some(1)` forces sequential execution for the duration of a call.
 
 use std::cmp::Ord;
 use std::marker::PhantomData;
@@ -65,6 +72,73 @@ impl ThreadingConfig {
 /// Simple threading state tracking
 static THREADING_FAILED: AtomicBool = AtomicBool::new(false);
 
+#[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+thread_local! {
+    /// Per-thread cap on worker threads set by `with_thread_pool`
+    static THREAD_LIMIT: std::cell::Cell<Option<usize>> = const { std::cell::Cell::new(None) };
+}
+
+/// Number of worker threads the current call may use
+#[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+fn available_threads() -> usize {
+    THREAD_LIMIT.with(|limit| limit.get()).unwrap_or_else(|| {
+        std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+    })
+}
+
+/// Split `items` into ordered chunks and run `process` on each chunk in its own thread
+#[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+fn run_chunked<T, R, P>(items: Vec<T>, process: P) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+    P: Fn(Vec<T>) -> Vec<R> + Send + Sync,
+{
+    let threads = available_threads().min(items.len());
+    if threads <= 1 || has_threading_failed() {
+        return process(items);
+    }
+
+    let chunk_size = items.len().div_ceil(threads);
+    let mut chunks = Vec::with_capacity(threads);
+    let mut items = items.into_iter();
+    loop {
+        let chunk: Vec<T> = items.by_ref().take(chunk_size).collect();
+        if chunk.is_empty() {
+            break;
+        }
+        chunks.push(chunk);
+    }
+
+    let process = &process;
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = chunks
+            .into_iter()
+            .map(|chunk| {
+                scope.spawn(move || {
+                    // Nested calls inside a worker stay on that worker
+                    THREAD_LIMIT.with(|limit| limit.set(Some(1)));
+                    process(chunk)
+                })
+            })
+            .collect();
+
+        let mut results = Vec::new();
+        for handle in handles {
+            match handle.join() {
+                Ok(chunk_results) => results.extend(chunk_results),
+                Err(panic) => {
+                    mark_threading_failed();
+                    std::panic::resume_unwind(panic);
+                }
+            }
+        }
+        results
+    })
+}
+
 /// Threading errors (simplified)
 #[derive(Debug, Clone)]
 pub enum ThreadingError {
@@ -92,7 +166,8 @@ pub fn mark_threading_failed() {
 
 /// Check if threading has failed (always false in single-threaded mode)
 pub fn has_threading_failed() -> bool {
-    false
+    cfg!(all(feature = "parallel", not(target_arch = "wasm32")))
+        && THREADING_FAILED.load(Ordering::Relaxed)
 }
 
 /// Confirm threading success (no-op in single-threaded mode)
@@ -147,13 +222,21 @@ where
 }
 
 /// Check if threading is supported in the current environment
-/// Always returns false for single-threaded WASM + Web Worker architecture
+/// Always returns false for single-threaded WASM + Web Worker architecture; native
+/// builds with the `parallel` feature report true when more than one core is available
 pub fn is_threading_supported() -> bool {
-    false
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    {
+        std::thread::available_parallelism().is_ok_and(|n| n.get() > 1)
+    }
+    #[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+    {
+        false
+    }
 }
 
 /// Execute processing using single-threaded approach (WASM + Web Worker architecture)
-/// Simple sequential processing for optimal stability
+/// Simple sequential processing for optimal stability; threaded with the `parallel` feature
 pub fn execute_parallel<I, F, R>(items: I, func: F) -> Vec<R>
 where
     I: IntoIterator,
@@ -161,7 +244,15 @@ where
     F: Fn(I::Item) -> R + Send + Sync,
     R: Send,
 {
-    items.into_iter().map(func).collect()
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    {
+        let items: Vec<I::Item> = items.into_iter().collect();
+        run_chunked(items, |chunk| chunk.into_iter().map(&func).collect())
+    }
+    #[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+    {
+        items.into_iter().map(func).collect()
+    }
 }
 
 /// Execute filter_map using single-threaded approach (WASM + Web Worker architecture)
@@ -172,7 +263,15 @@ where
     F: Fn(I::Item) -> Option<R> + Send + Sync,
     R: Send,
 {
-    items.into_iter().filter_map(func).collect()
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    {
+        let items: Vec<I::Item> = items.into_iter().collect();
+        run_chunked(items, |chunk| chunk.into_iter().filter_map(&func).collect())
+    }
+    #[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+    {
+        items.into_iter().filter_map(func).collect()
+    }
 }
 
 /// Execute chunks processing using single-threaded approach (WASM + Web Worker architecture)
@@ -184,7 +283,15 @@ where
     R: Send,
 {
     let vec: Vec<_> = items.into_iter().collect();
-    vec.chunks(chunk_size).map(func).collect()
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    {
+        let chunks: Vec<&[I::Item]> = vec.chunks(chunk_size.max(1)).collect();
+        run_chunked(chunks, |group| group.into_iter().map(&func).collect())
+    }
+    #[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+    {
+        vec.chunks(chunk_size).map(func).collect()
+    }
 }
 
 /// Thread pool configuration abstraction
@@ -196,20 +303,38 @@ pub struct ThreadPoolConfig {
 }
 
 /// Execute operation directly (single-threaded WASM + Web Worker architecture)
-/// Thread pool config is ignored in single-threaded mode
+/// Thread pool config is ignored in single-threaded mode; with the `parallel` feature
+/// `num_threads` caps the worker threads used by calls made inside `f`
 pub fn with_thread_pool<F, R>(config: &ThreadPoolConfig, f: F) -> R
 where
     F: FnOnce() -> R + Send,
     R: Send,
 {
-    let _ = config; // Config ignored in single-threaded mode
-    f()
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    {
+        let previous = THREAD_LIMIT.with(|limit| limit.replace(config.num_threads));
+        let result = f();
+        THREAD_LIMIT.with(|limit| limit.set(previous));
+        result
+    }
+    #[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+    {
+        let _ = config; // Config ignored in single-threaded mode
+        f()
+    }
 }
 
 /// Get the current number of threads available
 /// Always returns 1 for single-threaded WASM + Web Worker architecture
 pub fn current_num_threads() -> usize {
-    1
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    {
+        available_threads().max(1)
+    }
+    #[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+    {
+        1
+    }
 }
 
 /// Configure thread pool (no-op for single-threaded WASM + Web Worker architecture)
@@ -491,7 +616,71 @@ pub fn par_zip<'a, T, U>(
     a.iter().zip(b.iter())
 }
 
-/// Check if parallel processing should be used (always false in single-threaded mode)
-pub fn should_use_parallel(_size: usize, _threshold: usize) -> bool {
-    false
+/// Check if parallel processing should be used (always false in single-threaded mode;
+/// with the `parallel` feature, true once `size` reaches `threshold` and several threads
+/// are available)
+pub fn should_use_parallel(size: usize, threshold: usize) -> bool {
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    {
+        size >= threshold && available_threads() > 1 && !has_threading_failed()
+    }
+    #[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+    {
+        let _ = (size, threshold);
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_execute_parallel_preserves_order() {
+        let config = ThreadPoolConfig {
+            num_threads: Some(4),
+            ..Default::default()
+        };
+
+        let doubled = with_thread_pool(&config, || execute_parallel(0..101, |x: i32| x * 2));
+        assert_eq!(doubled, (0..101).map(|x| x * 2).collect::<Vec<_>>());
+
+        let odd = with_thread_pool(&config, || {
+            execute_parallel_filter_map(0..101, |x: i32| (x % 2 == 1).then_some(x))
+        });
+        assert_eq!(odd, (0..101).filter(|x| x % 2 == 1).collect::<Vec<_>>());
+
+        let chunk_sums = with_thread_pool(&config, || {
+            execute_parallel_chunks(0..101, 10, |chunk: &[i32]| chunk.iter().sum::<i32>())
+        });
+        let expected: Vec<i32> = (0..101).collect::<Vec<_>>().chunks(10).map(|c| c.iter().sum()).collect();
+        assert_eq!(chunk_sums, expected);
+    }
+
+    #[test]
+    fn test_single_thread_limit_is_scoped() {
+        let single = ThreadPoolConfig {
+            num_threads: Some(1),
+            ..Default::default()
+        };
+
+        assert_eq!(with_thread_pool(&single, current_num_threads), 1);
+        assert!(current_num_threads() >= 1);
+    }
+
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    #[test]
+    fn test_execute_parallel_uses_worker_threads() {
+        let config = ThreadPoolConfig {
+            num_threads: Some(4),
+            ..Default::default()
+        };
+
+        let workers = with_thread_pool(&config, || {
+            execute_parallel(0..64, |_: i32| std::thread::current().id())
+        });
+        let distinct: std::collections::HashSet<_> = workers.into_iter().collect();
+        assert_eq!(distinct.len(), 4);
+        assert!(!distinct.contains(&std::thread::current().id()));
+    }
 }
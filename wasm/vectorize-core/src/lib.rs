@@ -17,6 +17,7 @@ pub mod performance;
 pub mod preprocessing;
 pub mod svg;
 pub mod svg_gradients;
+pub mod svg_raster;
 pub mod telemetry;
 pub mod utils;
 
@@ -25,8 +26,10 @@ pub use algorithms::{
     vectorize_trace_low, vectorize_trace_low_with_gradients, EnhancedSvgResult, TraceBackend,
     TraceLowConfig,
 };
-pub use config::SvgConfig;
-pub use config_builder::{ConfigBuilder, ConfigBuilderError, ConfigBuilderResult};
+pub use config::{ColorSpace, CoordinateOrigin, NonFiniteHandling, SvgConfig, Transform};
+pub use config_builder::{
+    ConfigBuilder, ConfigBuilderError, ConfigBuilderResult, DependencyWarning, HandDrawnSettings,
+};
 pub use config_immutable::{ConfigError, ConfigResult, VectorizerConfig};
 pub use error::*;
 pub use execution::{
@@ -40,11 +43,12 @@ pub use parameters::{
     ValidationError, ValidationResult, ValidationWarning, PARAMETER_REGISTRY,
 };
 pub use svg_gradients::{
-    generate_optimized_svg_document_with_gradients, generate_svg_document_with_gradients,
-    ColorStop, GradientDefinition,
+    generate_layered_svg_document_with_gradients, generate_optimized_svg_document_with_gradients,
+    generate_svg_document_with_gradients, limit_gradients, ColorStop, GradientDefinition,
 };
 
 use image::{ImageBuffer, Rgba};
+use std::sync::LazyLock;
 
 // Note: TraceLowConfig and TraceBackend are now imported from algorithms module
 
@@ -70,12 +74,51 @@ pub fn vectorize_trace_low_rgba(
     image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
     config: &TraceLowConfig,
     hand_drawn_config: Option<&crate::algorithms::visual::hand_drawn::HandDrawnConfig>,
+) -> Result<String, VectorizeError> {
+    vectorize_trace_low_document(
+        image,
+        config,
+        hand_drawn_config,
+        |result, processing_image, svg_config| {
+            let (width, height) = processing_image.dimensions();
+            if !result.has_gradients {
+                return svg::generate_svg_document(&result.paths, width, height, svg_config);
+            }
+            let (svg_document, converted) = generate_svg_document_with_gradients(
+                &result.paths,
+                &result.gradients,
+                width,
+                height,
+                svg_config,
+            );
+            log::info!(
+                "Emitted {} gradients ({} converted to solid colors)",
+                result.gradients.len() - converted,
+                converted
+            );
+            svg_document
+        },
+    )
+}
+
+/// Trace `image` through the shared trace-low pipeline and render the result
+///
+/// Validates the input, returns a minimal SVG for empty or single-color images and
+/// for traces without paths, reduces the image to `max_image_size`, applies
+/// background removal and scales the rendered document back to the source size.
+/// `render` gets the traced result, the processing-resolution image and the SVG
+/// settings, and writes the document at processing resolution.
+fn vectorize_trace_low_document(
+    image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+    config: &TraceLowConfig,
+    hand_drawn_config: Option<&crate::algorithms::visual::hand_drawn::HandDrawnConfig>,
+    render: impl FnOnce(EnhancedSvgResult, &ImageBuffer<Rgba<u8>, Vec<u8>>, &SvgConfig) -> String,
 ) -> Result<String, VectorizeError> {
     use input_validation::validate_image_input;
     use preprocessing::{
         adjust_trace_low_config, analyze_resolution_requirements, apply_background_removal,
-        apply_resolution_processing, scale_svg_coordinates, BackgroundRemovalConfig,
-        ResolutionConfig,
+        apply_resolution_processing, fit_detail_map, scale_svg_coordinates,
+        BackgroundRemovalConfig, ResolutionConfig,
     };
 
     log::info!("Starting trace-low vectorization with config: {config:?}");
@@ -85,7 +128,8 @@ pub fn vectorize_trace_low_rgba(
     validate_trace_low_config(config)?;
 
     // Check for edge cases that would make processing impossible
-    let single_color_result = is_empty_or_single_color_image(image);
+    let single_color_result =
+        is_empty_or_single_color_image(image, config.alpha_threshold, config.min_content_pixels);
     log::debug!(
         "Single color check result: {} for {}x{} image",
         single_color_result,
@@ -138,6 +182,7 @@ pub fn vectorize_trace_low_rgba(
     // Adjust configuration based on resolution scaling
     let mut adjusted_config =
         adjust_trace_low_config(config, &resolution_analysis.parameter_adjustments);
+    fit_detail_map(&mut adjusted_config, processing_image.dimensions());
 
     // CRITICAL FIX: Disable background removal in trace-low since we already applied it above
     // This prevents double processing which causes timeouts/hangs
@@ -155,36 +200,426 @@ pub fn vectorize_trace_low_rgba(
         resolution_analysis.scale_factor
     );
 
-    // Use the trace-low algorithm with optimized config
-    let svg_paths = vectorize_trace_low(&processing_image, &adjusted_config, hand_drawn_config)?;
+    // Use the trace-low algorithm with optimized config; gradients are only detected
+    // when color sampling asks for them
+    let result =
+        vectorize_trace_low_with_gradients(&processing_image, &adjusted_config, hand_drawn_config)?;
 
     // Handle case where no paths were generated
-    if svg_paths.is_empty() {
+    if result.paths.is_empty() {
         log::warn!("No paths generated, creating minimal SVG");
-        return Ok(generate_minimal_svg(
-            image.width(),
-            image.height(),
-            "trace-low",
-        ));
+        let minimal = generate_minimal_svg(image.width(), image.height(), "trace-low");
+        return Ok(if config.emit_xml_declaration {
+            format!("{}{minimal}", svg::XML_DECLARATION)
+        } else {
+            minimal
+        });
     }
 
     // Generate complete SVG document with precision from config
-    let svg_config = SvgConfig {
+    let svg_config = trace_low_svg_config(config, image, resolution_analysis.scale_factor);
+    let svg_document = render(result, &processing_image, &svg_config);
+
+    // Scale SVG back to original resolution if needed
+    let final_svg = scale_svg_coordinates(&svg_document, &resolution_analysis)?;
+
+    Ok(final_svg)
+}
+
+/// SVG output settings taken from a trace-low configuration
+///
+/// The source hash is only computed when `embed_source_hash` is enabled. `scale_factor`
+/// is the processing resolution relative to `image`, so physical sizes match the source.
+fn trace_low_svg_config(
+    config: &TraceLowConfig,
+    image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+    scale_factor: f32,
+) -> SvgConfig {
+    SvgConfig {
         precision: config.svg_precision,
         optimize: true,
         include_metadata: false,
+        emit_path_ids: config.emit_path_ids,
+        resample_even_spacing: config.resample_even_spacing,
+        color_space: config.color_space,
+        stroke_halo: config.stroke_halo.clone(),
+        fill_and_stroke: config.fill_and_stroke.clone(),
+        stroke_to_outline: config.stroke_to_outline,
+        max_gradients: config.max_gradients,
+        repair_self_intersections: config.repair_self_intersections,
+        auto_close_coincident: config.auto_close_coincident,
+        emit_xml_declaration: config.emit_xml_declaration,
+        emit_path_length: config.emit_path_length,
+        output_transform: config.output_transform,
+        draw_on_animation: config.draw_on_animation,
+        absolute_stroke_width: config.absolute_stroke_width,
+        stroke_width_jitter: config.stroke_width_jitter,
+        embed_source_hash: config.embed_source_hash,
+        source_hash: config.embed_source_hash.then(|| source_hash(image, config)),
+        physical_dpi: config.output_dpi.map(|dpi| dpi * scale_factor),
+        emit_source_color: config.emit_source_color,
+        emit_region_metadata: config.emit_region_metadata,
+        group_by_thickness: config.group_by_thickness,
+        emit_palette_legend: config.emit_palette_legend,
+        coordinate_origin: config.coordinate_origin,
+        non_finite_handling: config.non_finite_handling,
+        shared_region_edges: config.shared_region_edges
+            && config.backend == TraceBackend::Superpixel,
+        ..SvgConfig::default()
+    }
+}
+
+/// Stable 64-bit FNV-1a hash of a source image and the configuration used to trace it
+///
+/// Covers the image dimensions, every RGBA byte and the serialized configuration
+/// (fields skipped by serde, such as `detail_map`, are not included). The result is
+/// formatted as `fnv1a64:<16 hex digits>` and can be used as a cache key.
+pub fn source_hash(image: &ImageBuffer<Rgba<u8>, Vec<u8>>, config: &TraceLowConfig) -> String {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let recipe = serde_json::to_string(config).unwrap_or_default();
+    let hash = image
+        .width()
+        .to_le_bytes()
+        .iter()
+        .chain(&image.height().to_le_bytes())
+        .chain(image.as_raw())
+        .chain(recipe.as_bytes())
+        .fold(FNV_OFFSET_BASIS, |hash, &byte| {
+            (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+        });
+    format!("fnv1a64:{hash:016x}")
+}
+
+/// Longest side of the reduced-resolution preview used by `find_detail_for_size`
+const SIZE_ESTIMATE_MAX_DIMENSION: u32 = 256;
+
+/// Number of bisection steps used by `find_detail_for_size`
+const SIZE_SEARCH_ITERATIONS: usize = 8;
+
+/// Find the detail level whose SVG output lands closest to a target file size
+///
+/// Vectorizes a reduced-resolution preview of the image at candidate detail levels and
+/// scales each SVG size back up by the downscale factor, since path count and length grow
+/// roughly linearly with image dimension. Detail is then bisected on the assumption that
+/// output size grows with detail. All other settings are taken from `config`.
+///
+/// # Arguments
+/// * `image` - Input RGBA image buffer
+/// * `config` - Base configuration (its `detail` is ignored)
+/// * `hand_drawn_config` - Optional hand-drawn effects applied during estimation
+/// * `target_bytes` - Desired SVG size in bytes
+///
+/// # Returns
+/// * `Result<f32, VectorizeError>` - Detail level in 0.0..=1.0
+pub fn find_detail_for_size(
+    image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+    config: &TraceLowConfig,
+    hand_drawn_config: Option<&crate::algorithms::visual::hand_drawn::HandDrawnConfig>,
+    target_bytes: usize,
+) -> Result<f32, VectorizeError> {
+    input_validation::validate_image_input(image)?;
+
+    let (width, height) = image.dimensions();
+    let longest = width.max(height);
+    let (preview, scale) = if longest > SIZE_ESTIMATE_MAX_DIMENSION {
+        let scale = longest as f32 / SIZE_ESTIMATE_MAX_DIMENSION as f32;
+        let preview = image::imageops::resize(
+            image,
+            ((width as f32 / scale).round() as u32).max(1),
+            ((height as f32 / scale).round() as u32).max(1),
+            image::imageops::FilterType::Triangle,
+        );
+        (preview, scale)
+    } else {
+        (image.clone(), 1.0)
     };
-    let svg_document = svg::generate_svg_document(
-        &svg_paths,
-        processing_image.width(),
-        processing_image.height(),
-        &svg_config,
-    );
 
-    // Scale SVG back to original resolution if needed
-    let final_svg = scale_svg_coordinates(&svg_document, &resolution_analysis)?;
+    let estimate = |detail: f32| -> Result<usize, VectorizeError> {
+        let estimate_config = TraceLowConfig {
+            detail,
+            ..config.clone()
+        };
+        let svg = vectorize_trace_low_rgba(&preview, &estimate_config, hand_drawn_config)?;
+        Ok((svg::calculate_svg_size(&svg) as f32 * scale).round() as usize)
+    };
 
-    Ok(final_svg)
+    let (mut low, mut high) = (0.0f32, 1.0f32);
+    let mut low_size = estimate(low)?;
+    let mut high_size = estimate(high)?;
+    if target_bytes <= low_size {
+        return Ok(low);
+    }
+    if target_bytes >= high_size {
+        return Ok(high);
+    }
+
+    for _ in 0..SIZE_SEARCH_ITERATIONS {
+        let mid = (low + high) / 2.0;
+        let mid_size = estimate(mid)?;
+        log::debug!("Size search: detail {mid:.3} -> ~{mid_size} bytes (target {target_bytes})");
+        if mid_size > target_bytes {
+            high = mid;
+            high_size = mid_size;
+        } else {
+            low = mid;
+            low_size = mid_size;
+        }
+    }
+
+    // Pick whichever bracket end is closer to the target
+    let detail = if target_bytes - low_size <= high_size.saturating_sub(target_bytes) {
+        low
+    } else {
+        high
+    };
+    log::info!("Selected detail {detail:.3} for target size {target_bytes} bytes");
+    Ok(detail)
+}
+
+/// Longest side of the coarse preview emitted by `vectorize_trace_low_progressive`
+const PROGRESSIVE_PREVIEW_MAX_DIMENSION: u32 = 512;
+
+/// Fraction of the configured detail used for the coarse preview
+const PROGRESSIVE_PREVIEW_DETAIL_FACTOR: f32 = 0.5;
+
+/// Vectorize an image twice: a fast coarse preview first, then the full-quality result
+///
+/// The preview is traced at reduced resolution and detail with multipass and
+/// hand-drawn effects disabled, and handed to `on_preview` before the full run
+/// starts. Its SVG uses the reduced dimensions, so callers should scale it to fit.
+/// A failed preview is logged and skipped rather than aborting the full run.
+///
+/// # Arguments
+/// * `image` - Input RGBA image buffer, shared by both runs
+/// * `config` - Configuration for the full-quality run
+/// * `hand_drawn_config` - Optional hand-drawn effects for the full-quality run
+/// * `on_preview` - Receives the preview SVG
+///
+/// # Returns
+/// * `Result<String, VectorizeError>` - Full-quality SVG string or error
+pub fn vectorize_trace_low_progressive(
+    image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+    config: &TraceLowConfig,
+    hand_drawn_config: Option<&crate::algorithms::visual::hand_drawn::HandDrawnConfig>,
+    on_preview: impl FnOnce(&str),
+) -> Result<String, VectorizeError> {
+    let preview_config = TraceLowConfig {
+        detail: config.detail * PROGRESSIVE_PREVIEW_DETAIL_FACTOR,
+        max_image_size: config.max_image_size.min(PROGRESSIVE_PREVIEW_MAX_DIMENSION),
+        enable_multipass: false,
+        enable_reverse_pass: false,
+        enable_diagonal_pass: false,
+        draw_on_animation: None,
+        ..config.clone()
+    };
+
+    match vectorize_trace_low_rgba(image, &preview_config, None) {
+        Ok(preview) => on_preview(&preview),
+        Err(e) => log::warn!("Progressive preview failed, continuing with full run: {e}"),
+    }
+
+    vectorize_trace_low_rgba(image, config, hand_drawn_config)
+}
+
+/// Group id of paths on the in-focus subject in `vectorize_trace_low_layered_depth`
+pub const FOREGROUND_LAYER_ID: &str = "foreground";
+
+/// Group id of paths on the defocused background in `vectorize_trace_low_layered_depth`
+pub const BACKGROUND_LAYER_ID: &str = "background";
+
+/// Vectorize an image into separate foreground and background layers for parallax effects
+///
+/// Each traced path goes to the `foreground` group when most of its outline lies on
+/// sharp, non-background pixels (see [`preprocessing::foreground_depth_mask`]) and to
+/// the `background` group otherwise. The background group is drawn first.
+///
+/// # Arguments
+/// * `image` - Input RGBA image buffer
+/// * `config` - Configuration parameters for the trace-low process
+/// * `hand_drawn_config` - Optional hand-drawn effects
+///
+/// # Returns
+/// * `Result<String, VectorizeError>` - Layered SVG string or error
+pub fn vectorize_trace_low_layered_depth(
+    image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+    config: &TraceLowConfig,
+    hand_drawn_config: Option<&crate::algorithms::visual::hand_drawn::HandDrawnConfig>,
+) -> Result<String, VectorizeError> {
+    vectorize_trace_low_document(
+        image,
+        config,
+        hand_drawn_config,
+        |result, processing_image, svg_config| {
+            let (width, height) = processing_image.dimensions();
+            let mask = preprocessing::foreground_depth_mask(processing_image);
+            let in_foreground = |(x, y): (f32, f32)| {
+                let (x, y) = (x.round(), y.round());
+                (0.0..width as f32).contains(&x)
+                    && (0.0..height as f32).contains(&y)
+                    && mask[y as usize * width as usize + x as usize]
+            };
+
+            let (foreground, background): (Vec<algorithms::SvgPath>, Vec<_>) =
+                result.paths.into_iter().partition(|path| {
+                    let points = svg::element_sample_points(path);
+                    let sharp = points.iter().filter(|&&point| in_foreground(point)).count();
+                    !points.is_empty() && sharp * 2 >= points.len()
+                });
+            log::info!(
+                "Depth layers: {} foreground and {} background paths",
+                foreground.len(),
+                background.len()
+            );
+
+            let layers = [
+                (BACKGROUND_LAYER_ID, background.as_slice()),
+                (FOREGROUND_LAYER_ID, foreground.as_slice()),
+            ];
+            if result.has_gradients {
+                generate_layered_svg_document_with_gradients(
+                    &layers,
+                    &result.gradients,
+                    width,
+                    height,
+                    svg_config,
+                )
+                .0
+            } else {
+                svg::generate_layered_svg_document(&layers, width, height, svg_config)
+            }
+        },
+    )
+}
+
+/// Mask of the source pixels covered by one traced path, for "select by region" UIs
+///
+/// The image is vectorized with `config` and the element with id `path-<path_index>`
+/// (the index of the path in the SVG output) is rasterized at source resolution.
+/// Covered pixels are white and all others black.
+///
+/// # Arguments
+/// * `image` - Input RGBA image buffer
+/// * `config` - Configuration parameters for the trace-low process
+/// * `hand_drawn_config` - Optional hand-drawn effects
+/// * `path_index` - Index of the path in the traced output
+///
+/// # Returns
+/// * `Result<GrayImage, VectorizeError>` - Coverage mask, or an error if tracing
+///   fails or there is no path at `path_index`
+pub fn path_coverage_mask(
+    image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+    config: &TraceLowConfig,
+    hand_drawn_config: Option<&crate::algorithms::visual::hand_drawn::HandDrawnConfig>,
+    path_index: usize,
+) -> Result<image::GrayImage, VectorizeError> {
+    let config = TraceLowConfig {
+        emit_path_ids: true,
+        ..config.clone()
+    };
+    let svg = vectorize_trace_low_rgba(image, &config, hand_drawn_config)?;
+    svg_raster::rasterize_element_mask(
+        &svg,
+        &format!("path-{path_index}"),
+        image.width(),
+        image.height(),
+    )
+    .ok_or_else(|| {
+        VectorizeError::config_error(format!(
+            "Path index {path_index} is out of range for the traced output"
+        ))
+    })
+}
+
+/// Longest side of the rasters compared by `compare_configs`
+const COMPARE_RASTER_MAX_DIMENSION: u32 = 256;
+
+/// Gaussian blur applied to both rasters before SSIM in `compare_configs`
+const COMPARE_RASTER_BLUR_SIGMA: f32 = 1.5;
+
+/// Drawn elements counted by `compare_configs`
+static COMPARE_ELEMENT_REGEX: LazyLock<regex::Regex> = LazyLock::new(|| {
+    regex::Regex::new(r"<(?:path|circle|ellipse|line|rect|polygon|polyline)\b").unwrap()
+});
+
+/// Hex fill and stroke paints counted by `compare_configs`
+static COMPARE_COLOR_REGEX: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r##"(?:fill|stroke)="(#[0-9a-fA-F]{3,6})""##).unwrap());
+
+/// Side-by-side statistics for two configurations run on the same image
+///
+/// Each delta is `b - a`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ConfigComparison {
+    pub paths_a: usize,
+    pub paths_b: usize,
+    pub path_delta: i64,
+    pub colors_a: usize,
+    pub colors_b: usize,
+    pub color_delta: i64,
+    pub bytes_a: usize,
+    pub bytes_b: usize,
+    pub byte_delta: i64,
+    /// Structural similarity of the two rasterized outputs (1.0 = identical)
+    pub ssim: f32,
+}
+
+/// Vectorize an image with two configurations and compare the results
+///
+/// Counts drawn elements (paths and shapes of every type), distinct hex paint colors and bytes
+/// of each SVG, then rasterizes both at reduced resolution and reports their SSIM.
+///
+/// # Arguments
+/// * `image` - Input RGBA image buffer
+/// * `config_a` - First configuration
+/// * `config_b` - Second configuration
+/// * `hand_drawn_config` - Optional hand-drawn effects applied to both runs
+pub fn compare_configs(
+    image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+    config_a: &TraceLowConfig,
+    config_b: &TraceLowConfig,
+    hand_drawn_config: Option<&crate::algorithms::visual::hand_drawn::HandDrawnConfig>,
+) -> Result<ConfigComparison, VectorizeError> {
+    let svg_a = vectorize_trace_low_rgba(image, config_a, hand_drawn_config)?;
+    let svg_b = vectorize_trace_low_rgba(image, config_b, hand_drawn_config)?;
+
+    let count_colors = |svg: &str| {
+        COMPARE_COLOR_REGEX
+            .captures_iter(svg)
+            .map(|caps| caps[1].to_ascii_lowercase())
+            .collect::<std::collections::HashSet<_>>()
+            .len()
+    };
+
+    let raster_a = svg_raster::rasterize_svg_luma(&svg_a, COMPARE_RASTER_MAX_DIMENSION);
+    let raster_b = svg_raster::rasterize_svg_luma(&svg_b, COMPARE_RASTER_MAX_DIMENSION);
+    let ssim = match (&raster_a, &raster_b) {
+        // Soften aliasing so one-pixel stroke shifts don't dominate the score
+        (Some(a), Some(b)) => svg_raster::ssim(
+            &image::imageops::blur(a, COMPARE_RASTER_BLUR_SIGMA),
+            &image::imageops::blur(b, COMPARE_RASTER_BLUR_SIGMA),
+        ),
+        _ => None,
+    }
+    .ok_or_else(|| VectorizeError::svg_error("Failed to rasterize SVG output for comparison"))?;
+
+    let count_elements = |svg: &str| COMPARE_ELEMENT_REGEX.find_iter(svg).count();
+    let (paths_a, paths_b) = (count_elements(&svg_a), count_elements(&svg_b));
+    let (colors_a, colors_b) = (count_colors(&svg_a), count_colors(&svg_b));
+    let (bytes_a, bytes_b) = (svg_a.len(), svg_b.len());
+    Ok(ConfigComparison {
+        paths_a,
+        paths_b,
+        path_delta: paths_b as i64 - paths_a as i64,
+        colors_a,
+        colors_b,
+        color_delta: colors_b as i64 - colors_a as i64,
+        bytes_a,
+        bytes_b,
+        byte_delta: bytes_b as i64 - bytes_a as i64,
+        ssim,
+    })
 }
 
 // Helper functions for input validation and edge case handling
@@ -240,7 +675,14 @@ mod input_validation {
     }
 
     /// Check if image is effectively empty or single color
-    pub fn is_empty_or_single_color_image(image: &ImageBuffer<Rgba<u8>, Vec<u8>>) -> bool {
+    ///
+    /// Pixels with alpha below `alpha_threshold` don't count as content, and fewer
+    /// than `min_content_pixels` content pixels of a single color count as empty.
+    pub fn is_empty_or_single_color_image(
+        image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+        alpha_threshold: u8,
+        min_content_pixels: usize,
+    ) -> bool {
         if image.width() == 0 || image.height() == 0 {
             return true;
         }
@@ -250,7 +692,7 @@ mod input_validation {
         let mut opaque_pixel_count = 0;
 
         for pixel in image.pixels() {
-            if pixel.0[3] >= 10 {
+            if pixel.0[3] >= alpha_threshold {
                 // Only check opaque-ish pixels
                 opaque_pixel_count += 1;
 
@@ -271,7 +713,7 @@ mod input_validation {
 
         // If we have no opaque pixels, consider it empty
         // If we have very few opaque pixels, consider it effectively empty
-        opaque_pixel_count == 0 || opaque_pixel_count < 10
+        opaque_pixel_count == 0 || opaque_pixel_count < min_content_pixels
     }
 
     /// Generate minimal SVG for edge cases
@@ -330,6 +772,425 @@ mod tests {
         assert!(svg.contains("</svg>"));
     }
 
+    #[test]
+    fn test_absolute_stroke_width_ignores_resolution_scaling() {
+        let stroke_widths = |size: u32, config: &TraceLowConfig| {
+            let img = ImageBuffer::from_fn(size, size, |x, y| {
+                if (x * 8 / size) % 2 == (y * 8 / size) % 2 {
+                    Rgba([0, 0, 0, 255])
+                } else {
+                    Rgba([255, 255, 255, 255])
+                }
+            });
+            let svg = vectorize_trace_low_rgba(&img, config, None).unwrap();
+            svg.split("stroke-width=\"")
+                .skip(1)
+                .filter_map(|rest| rest.split('"').next().map(str::to_string))
+                .collect::<Vec<_>>()
+        };
+
+        // Relative widths differ between a small and a large image
+        let relative = TraceLowConfig::default();
+        assert_ne!(stroke_widths(64, &relative), stroke_widths(512, &relative));
+
+        let absolute = TraceLowConfig {
+            absolute_stroke_width: Some(2.0),
+            ..TraceLowConfig::default()
+        };
+        for size in [64, 512] {
+            let widths = stroke_widths(size, &absolute);
+            assert!(!widths.is_empty());
+            assert!(
+                widths.iter().all(|w| w == "2"),
+                "{size}px image: {widths:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_non_finite_handling_reaches_svg_config() {
+        let image = ImageBuffer::from_pixel(4, 4, Rgba([255, 255, 255, 255]));
+        let config = ConfigBuilder::new()
+            .non_finite_handling(NonFiniteHandling::Clamp)
+            .build()
+            .unwrap();
+        let svg_config = trace_low_svg_config(&config, &image, 1.0);
+        assert_eq!(svg_config.non_finite_handling, NonFiniteHandling::Clamp);
+    }
+
+    #[test]
+    fn test_shared_region_edges_join_without_notches() {
+        let image = ImageBuffer::from_fn(64, 64, |x, y| match (x < 32, y < 32) {
+            (true, true) => Rgba([220, 40, 40, 255]),
+            (false, true) => Rgba([40, 160, 40, 255]),
+            (true, false) => Rgba([40, 40, 200, 255]),
+            (false, false) => Rgba([230, 200, 40, 255]),
+        });
+        let config = TraceLowConfig {
+            backend: TraceBackend::Superpixel,
+            num_superpixels: 4,
+            shared_region_edges: true,
+            ..TraceLowConfig::default()
+        };
+
+        let svg = vectorize_trace_low_rgba(&image, &config, None).unwrap();
+        let seams = svg.matches(r#"stroke-linecap="round" stroke-linejoin="round""#);
+        assert_eq!(seams.count(), 4, "{svg}");
+        assert!(trace_low_svg_config(&config, &image, 1.0).shared_region_edges);
+    }
+
+    #[test]
+    fn test_detail_map_follows_resolution_downscale() {
+        let img = ImageBuffer::from_fn(128, 128, |x, y| {
+            if (x / 16) % 2 == (y / 16) % 2 {
+                Rgba([0, 0, 0, 255])
+            } else {
+                Rgba([255, 255, 255, 255])
+            }
+        });
+        // Map sized to the original image while tracing runs at half resolution
+        for directional in [false, true] {
+            let config = TraceLowConfig {
+                max_image_size: 64,
+                detail_map: Some(image::GrayImage::from_pixel(128, 128, image::Luma([200]))),
+                enable_reverse_pass: directional,
+                ..TraceLowConfig::default()
+            };
+            let svg = vectorize_trace_low_rgba(&img, &config, None).unwrap();
+            assert!(svg.contains("<path"), "directional: {directional}");
+        }
+    }
+
+    #[test]
+    fn test_gradient_output_respects_max_gradients() {
+        // Dark bars over a horizontal color ramp, so every traced edge sees a gradient
+        let img = ImageBuffer::from_fn(160, 160, |x, y| {
+            if (y / 20) % 2 == 0 && (20..140).contains(&x) {
+                Rgba([(x * 255 / 160) as u8, 40, (255 - x * 255 / 160) as u8, 255])
+            } else {
+                Rgba([255, 255, 255, 255])
+            }
+        });
+        let config = TraceLowConfig {
+            line_preserve_colors: true,
+            line_color_sampling: crate::algorithms::ColorSamplingMethod::GradientMapping,
+            ..TraceLowConfig::default()
+        };
+        let gradient_defs = |svg: &str| {
+            svg.matches("<linearGradient").count() + svg.matches("<radialGradient").count()
+        };
+
+        let uncapped = vectorize_trace_low_rgba(&img, &config, None).unwrap();
+        assert!(gradient_defs(&uncapped) > 1);
+        let capped = vectorize_trace_low_rgba(
+            &img,
+            &TraceLowConfig {
+                max_gradients: Some(1),
+                ..config.clone()
+            },
+            None,
+        )
+        .unwrap();
+        assert_eq!(gradient_defs(&capped), 1);
+        assert_eq!(capped.matches("url(#").count(), 1);
+    }
+
+    #[test]
+    fn test_find_detail_for_size_hits_target() {
+        // Scattered rings give edge output whose size grows steadily with detail
+        let img = ImageBuffer::from_fn(160, 160, |x, y| {
+            let (cx, cy) = ((x % 40) as f32 - 20.0, (y % 40) as f32 - 20.0);
+            let ring = ((cx * cx + cy * cy).sqrt() as u32 + x / 40 + y / 40) % 6 < 3;
+            let shade = ((x * 3 + y * 5) % 200) as u8;
+            if ring {
+                Rgba([shade, 40, 255 - shade, 255])
+            } else {
+                Rgba([255, 255, 255, 255])
+            }
+        });
+        let config = TraceLowConfig::default();
+
+        let size_at = |detail: f32| {
+            let config = TraceLowConfig {
+                detail,
+                ..config.clone()
+            };
+            vectorize_trace_low_rgba(&img, &config, None).unwrap().len()
+        };
+        let target = size_at(0.5);
+
+        let detail = find_detail_for_size(&img, &config, None, target).unwrap();
+        assert!((0.0..=1.0).contains(&detail));
+        let achieved = size_at(detail) as f32;
+        let error = (achieved - target as f32).abs() / target as f32;
+        assert!(error < 0.25, "size {achieved} too far from target {target}");
+
+        // Unreachable budgets clamp to the ends of the detail range
+        assert_eq!(find_detail_for_size(&img, &config, None, 1).unwrap(), 0.0);
+        assert_eq!(
+            find_detail_for_size(&img, &config, None, usize::MAX).unwrap(),
+            1.0
+        );
+    }
+
+    #[test]
+    fn test_progressive_emits_preview_before_full_result() {
+        let img = ImageBuffer::from_fn(160, 160, |x, y| {
+            let (cx, cy) = ((x % 40) as f32 - 20.0, (y % 40) as f32 - 20.0);
+            if (cx * cx + cy * cy).sqrt() < 12.0 {
+                Rgba([20, 40, 200, 255])
+            } else {
+                Rgba([255, 255, 255, 255])
+            }
+        });
+        let config = TraceLowConfig {
+            detail: 0.8,
+            ..TraceLowConfig::default()
+        };
+
+        let mut preview = None;
+        let full = vectorize_trace_low_progressive(&img, &config, None, |svg| {
+            preview = Some(svg.to_string());
+        })
+        .unwrap();
+
+        let preview = preview.expect("preview callback should fire");
+        assert!(preview.contains("<svg"));
+        assert!(preview.len() <= full.len());
+        assert_eq!(full, vectorize_trace_low_rgba(&img, &config, None).unwrap());
+    }
+
+    #[test]
+    fn test_layered_depth_puts_sharp_subject_in_foreground() {
+        // Striped subject over a smooth, defocused-looking gradient
+        let img = ImageBuffer::from_fn(96, 96, |x, y| {
+            if (28..68).contains(&x) && (28..68).contains(&y) {
+                if (x / 4) % 2 == 0 {
+                    Rgba([20, 20, 20, 255])
+                } else {
+                    Rgba([230, 60, 40, 255])
+                }
+            } else {
+                let shade = 120 + (x + y) as u8 / 3;
+                Rgba([shade, shade, 200, 255])
+            }
+        });
+
+        let config = TraceLowConfig {
+            backend: TraceBackend::Superpixel,
+            ..TraceLowConfig::default()
+        };
+        let svg = vectorize_trace_low_layered_depth(&img, &config, None).unwrap();
+        let background_start = svg.find(r#"<g id="background">"#).unwrap();
+        let foreground_start = svg.find(r#"<g id="foreground">"#).unwrap();
+        assert!(background_start < foreground_start);
+
+        // Classify each path by the mean of its coordinates
+        let centers = |layer: &str| -> Vec<(f32, f32)> {
+            layer
+                .split(r#" d=""#)
+                .skip(1)
+                .map(|rest| {
+                    let data = &rest[..rest.find('"').unwrap()];
+                    let numbers: Vec<f32> = data
+                        .split(|c: char| c.is_ascii_alphabetic() || c == ',' || c == ' ')
+                        .filter_map(|token| token.parse().ok())
+                        .collect();
+                    let n = (numbers.len() / 2) as f32;
+                    let x = numbers.iter().step_by(2).sum::<f32>() / n;
+                    let y = numbers.iter().skip(1).step_by(2).sum::<f32>() / n;
+                    (x, y)
+                })
+                .collect()
+        };
+        let on_subject =
+            |&(x, y): &(f32, f32)| (28.0..68.0).contains(&x) && (28.0..68.0).contains(&y);
+
+        let foreground = centers(&svg[foreground_start..]);
+        let background = centers(&svg[background_start..foreground_start]);
+        let subject_in_foreground = foreground.iter().filter(|c| on_subject(c)).count();
+        let subject_in_background = background.iter().filter(|c| on_subject(c)).count();
+        assert!(
+            subject_in_foreground > 0,
+            "subject paths should be in the foreground"
+        );
+        assert!(subject_in_foreground >= 9 * subject_in_background);
+        assert!(subject_in_foreground * 10 >= foreground.len() * 9);
+    }
+
+    #[test]
+    fn test_layered_depth_matches_flat_output_when_downscaled() {
+        // Twice the processing size, so both entry points trace at half resolution
+        let img = ImageBuffer::from_fn(192, 192, |x, y| {
+            if (56..136).contains(&x) && (56..136).contains(&y) && (x / 8) % 2 == 0 {
+                Rgba([20, 20, 20, 255])
+            } else {
+                Rgba([230, 230, 240, 255])
+            }
+        });
+        let config = TraceLowConfig {
+            max_image_size: 96,
+            ..TraceLowConfig::default()
+        };
+        let header = |svg: &str| svg[..svg.find('>').unwrap()].to_string();
+        let path_data = |svg: &str| -> Vec<String> {
+            let mut data: Vec<String> = svg
+                .split(r#" d=""#)
+                .skip(1)
+                .map(|rest| rest[..rest.find('"').unwrap()].to_string())
+                .collect();
+            data.sort();
+            data
+        };
+
+        let flat = vectorize_trace_low_rgba(&img, &config, None).unwrap();
+        let layered = vectorize_trace_low_layered_depth(&img, &config, None).unwrap();
+        assert_eq!(header(&layered), header(&flat));
+        assert!(!path_data(&flat).is_empty());
+        assert_eq!(path_data(&layered), path_data(&flat));
+
+        // Empty images short-circuit the same way
+        let blank = ImageBuffer::from_pixel(192, 192, Rgba([255, 255, 255, 255]));
+        assert_eq!(
+            vectorize_trace_low_layered_depth(&blank, &config, None).unwrap(),
+            vectorize_trace_low_rgba(&blank, &config, None).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_embed_source_hash_tracks_source_and_config() {
+        let img = ImageBuffer::from_fn(32, 32, |x, y| {
+            if (8..24).contains(&x) && (8..24).contains(&y) {
+                Rgba([30, 30, 30, 255])
+            } else {
+                Rgba([255, 255, 255, 255])
+            }
+        });
+        let config = TraceLowConfig {
+            embed_source_hash: true,
+            ..TraceLowConfig::default()
+        };
+        let embedded_hash = |svg: &str| {
+            let start = svg.find("<!-- source-hash: ").unwrap() + "<!-- source-hash: ".len();
+            svg[start..start + svg[start..].find(' ').unwrap()].to_string()
+        };
+
+        let hash = embedded_hash(&vectorize_trace_low_rgba(&img, &config, None).unwrap());
+        assert_eq!(hash, source_hash(&img, &config));
+        assert_eq!(
+            embedded_hash(&vectorize_trace_low_rgba(&img, &config, None).unwrap()),
+            hash
+        );
+
+        let mut changed = img.clone();
+        changed.put_pixel(16, 16, Rgba([200, 30, 30, 255]));
+        assert_ne!(
+            embedded_hash(&vectorize_trace_low_rgba(&changed, &config, None).unwrap()),
+            hash
+        );
+        let detailed = TraceLowConfig {
+            detail: 0.9,
+            ..config.clone()
+        };
+        assert_ne!(source_hash(&img, &detailed), hash);
+
+        let plain = vectorize_trace_low_rgba(&img, &TraceLowConfig::default(), None).unwrap();
+        assert!(!plain.contains("source-hash"));
+    }
+
+    #[test]
+    fn test_compare_configs_reports_deltas() {
+        let img = ImageBuffer::from_fn(96, 96, |x, y| {
+            let (cx, cy) = ((x % 32) as f32 - 16.0, (y % 32) as f32 - 16.0);
+            if ((cx * cx + cy * cy).sqrt() as u32 + x / 32) % 5 < 2 {
+                Rgba([30, 30, 30, 255])
+            } else {
+                Rgba([255, 255, 255, 255])
+            }
+        });
+        let config_at = |detail: f32| TraceLowConfig {
+            detail,
+            ..TraceLowConfig::default()
+        };
+
+        let same = compare_configs(&img, &config_at(0.5), &config_at(0.5), None).unwrap();
+        assert_eq!((same.path_delta, same.color_delta, same.byte_delta), (0, 0, 0));
+        assert!((same.ssim - 1.0).abs() < 1e-4);
+
+        let nearby = compare_configs(&img, &config_at(0.5), &config_at(0.55), None).unwrap();
+        assert!(nearby.ssim > 0.8, "ssim {} too low for a small change", nearby.ssim);
+
+        let far = compare_configs(&img, &config_at(0.05), &config_at(0.95), None).unwrap();
+        assert!(far.byte_delta != 0 || far.path_delta != 0);
+        assert_eq!(far.byte_delta, far.bytes_b as i64 - far.bytes_a as i64);
+        assert!(far.ssim < nearby.ssim);
+    }
+
+    #[test]
+    fn test_path_coverage_mask_follows_path_in_source_coordinates() {
+        // Dark square on white, traced at half resolution
+        let img = ImageBuffer::from_fn(128, 128, |x, y| {
+            if (40..88).contains(&x) && (40..88).contains(&y) {
+                Rgba([20, 20, 20, 255])
+            } else {
+                Rgba([255, 255, 255, 255])
+            }
+        });
+        let config = TraceLowConfig {
+            max_image_size: 64,
+            ..TraceLowConfig::default()
+        };
+
+        let mask = path_coverage_mask(&img, &config, None, 0).unwrap();
+        assert_eq!(mask.dimensions(), (128, 128));
+        let covered: Vec<(u32, u32)> = mask
+            .enumerate_pixels()
+            .filter(|(_, _, pixel)| pixel.0[0] == 255)
+            .map(|(x, y, _)| (x, y))
+            .collect();
+        assert!(!covered.is_empty());
+        // Stroked outlines hug the square's border, away from its center and the corners
+        let near_border = |v: u32| (32..96).contains(&v);
+        assert!(covered.iter().all(|&(x, y)| near_border(x) && near_border(y)));
+        assert_eq!(mask.get_pixel(64, 64).0[0], 0);
+        assert_eq!(mask.get_pixel(5, 5).0[0], 0);
+
+        let svg = vectorize_trace_low_rgba(&img, &config, None).unwrap();
+        let path_count = svg.matches("<path").count();
+        assert!(path_coverage_mask(&img, &config, None, path_count).is_err());
+    }
+
+    #[test]
+    fn test_path_coverage_mask_supports_lowpoly_polygons() {
+        let img = ImageBuffer::from_fn(48, 48, |x, y| {
+            Rgba([(x * 5) as u8, (y * 5) as u8, 128, 255])
+        });
+        let config = TraceLowConfig {
+            backend: TraceBackend::LowPoly,
+            lowpoly_vertex_count: 60,
+            emit_path_ids: true,
+            ..TraceLowConfig::default()
+        };
+        let svg = vectorize_trace_low_rgba(&img, &config, None).unwrap();
+        let polygon_count = svg.matches("<polygon").count();
+        assert!(polygon_count > 1);
+
+        // The triangles tile the image, so their masks together cover every pixel
+        let mut union = image::GrayImage::new(48, 48);
+        for index in 0..polygon_count {
+            let mask = svg_raster::rasterize_element_mask(&svg, &format!("path-{index}"), 48, 48)
+                .unwrap();
+            for (covered, pixel) in union.pixels_mut().zip(mask.pixels()) {
+                covered.0[0] |= pixel.0[0];
+            }
+        }
+        assert!(union.pixels().all(|p| p.0[0] == 255));
+
+        let first = path_coverage_mask(&img, &config, None, 0).unwrap();
+        assert!(first.pixels().any(|p| p.0[0] == 255));
+        assert!(path_coverage_mask(&img, &config, None, polygon_count).is_err());
+    }
+
     #[test]
     fn test_edge_case_empty_image() {
         // Test 0x0 image
@@ -357,6 +1218,31 @@ mod tests {
         assert!(svg.contains("no content found") || svg.contains("<rect"));
     }
 
+    #[test]
+    fn test_min_content_pixels_keeps_tiny_marks() {
+        // Five-pixel plus sign on a transparent background
+        let img = ImageBuffer::from_fn(32, 32, |x, y| {
+            let (dx, dy) = (x.abs_diff(16), y.abs_diff(16));
+            if dx + dy <= 1 {
+                Rgba([0, 0, 0, 255])
+            } else {
+                Rgba([0, 0, 0, 0])
+            }
+        });
+
+        let svg = vectorize_trace_low_rgba(&img, &TraceLowConfig::default(), None).unwrap();
+        assert!(svg.contains("no content found"));
+
+        let config = TraceLowConfig {
+            min_content_pixels: 5,
+            // The mark's outline is shorter than the default minimum stroke
+            guarantee_min_paths: 1,
+            ..TraceLowConfig::default()
+        };
+        let svg = vectorize_trace_low_rgba(&img, &config, None).unwrap();
+        assert!(!svg.contains("no content found") && svg.contains("<path"));
+    }
+
     #[test]
     fn test_edge_case_extreme_aspect_ratio() {
         // Test image with extreme aspect ratio
@@ -0,0 +1,93 @@
+//! Rough foreground/background separation for layered (parallax) output
+//!
+//! In-focus subjects have strong local gradients while defocused backgrounds are
+//! smooth, so sharpness separates the two. Pixels matching the border colors are
+//! additionally kept in the background, as in the dots background detection.
+
+use super::calculate_otsu_threshold;
+use crate::algorithms::{calculate_gradient_magnitude, detect_background_mask};
+use image::{GrayImage, Luma, RgbaImage};
+
+/// Blur applied to the gradient magnitude so sharpness describes a neighbourhood
+const SHARPNESS_BLUR_SIGMA: f32 = 3.0;
+
+/// Color tolerance used to match pixels against the detected background colors
+const BACKGROUND_COLOR_TOLERANCE: f32 = 0.1;
+
+/// Estimate which pixels belong to the in-focus foreground
+///
+/// Gradient magnitude is smoothed into a local sharpness map and split with an
+/// Otsu threshold; sharp pixels that don't match the background colors count as
+/// foreground. Flat images have no foreground.
+///
+/// # Returns
+/// Row-major mask where `true` = foreground, `false` = background
+pub fn foreground_depth_mask(image: &RgbaImage) -> Vec<bool> {
+    let (width, height) = image.dimensions();
+    let gray = image::imageops::grayscale(image);
+
+    let magnitudes: Vec<f32> = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .map(|(x, y)| calculate_gradient_magnitude(&gray, x, y))
+        .collect();
+    let max_magnitude = magnitudes.iter().copied().fold(0.0f32, f32::max);
+    if max_magnitude <= f32::EPSILON {
+        return vec![false; magnitudes.len()];
+    }
+
+    let normalized = GrayImage::from_fn(width, height, |x, y| {
+        let magnitude = magnitudes[(y * width + x) as usize];
+        Luma([(magnitude / max_magnitude * 255.0).round() as u8])
+    });
+    let sharpness = image::imageops::blur(&normalized, SHARPNESS_BLUR_SIGMA);
+    let threshold = calculate_otsu_threshold(sharpness.as_raw());
+
+    let background = detect_background_mask(image, BACKGROUND_COLOR_TOLERANCE);
+    let mask: Vec<bool> = sharpness
+        .as_raw()
+        .iter()
+        .zip(&background)
+        .map(|(&sharp, &is_background)| sharp > threshold && !is_background)
+        .collect();
+
+    log::debug!(
+        "Depth layers: {} of {} pixels in foreground (sharpness threshold {})",
+        mask.iter().filter(|&&foreground| foreground).count(),
+        mask.len(),
+        threshold
+    );
+    mask
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    #[test]
+    fn test_foreground_depth_mask_separates_sharp_subject() {
+        // Striped subject over a smooth, defocused-looking gradient
+        let image = RgbaImage::from_fn(96, 96, |x, y| {
+            if (28..68).contains(&x) && (28..68).contains(&y) {
+                if (x / 4) % 2 == 0 {
+                    Rgba([20, 20, 20, 255])
+                } else {
+                    Rgba([230, 60, 40, 255])
+                }
+            } else {
+                let shade = 120 + (x + y) as u8 / 3;
+                Rgba([shade, shade, 200, 255])
+            }
+        });
+
+        let mask = foreground_depth_mask(&image);
+        assert!(mask[48 * 96 + 48], "subject center should be foreground");
+        assert!(!mask[5 * 96 + 5], "blurred corner should be background");
+        assert!(!mask[90 * 96 + 10], "blurred corner should be background");
+
+        let flat = RgbaImage::from_pixel(16, 16, Rgba([90, 90, 90, 255]));
+        assert!(foreground_depth_mask(&flat)
+            .iter()
+            .all(|&foreground| !foreground));
+    }
+}
@@ -0,0 +1,166 @@
+//! Decoding of images too large to hold or paint at full size
+//!
+//! Browsers cap canvas dimensions, so huge scans never reach the pipeline as
+//! `ImageData`. Decoding the file bytes directly avoids the canvas, but an
+//! expanded RGBA copy of a 20000×20000 scan alone needs 1.6 GB. The fitted decode
+//! keeps the decoder's native pixel format and reduces it band by band to the
+//! processing size, so only one full-size buffer ever exists.
+
+use crate::error::{VectorizeError, VectorizeResult};
+use crate::preprocessing::convert_icc_to_srgb;
+use image::{DynamicImage, GenericImageView, ImageDecoder, ImageReader, RgbaImage};
+use std::io::Cursor;
+
+/// Decode an encoded image reduced to fit within `max_dimension` on its longer side
+///
+/// Images that already fit are decoded unchanged. Larger ones are area-averaged
+/// down to the size the trace pipeline would resize them to. Returns the image
+/// together with the source dimensions, so densities given per source pixel can
+/// be rescaled. Images over `max_megapixels` are rejected before any pixel data
+/// is decoded; the embedded ICC profile is applied as in `decode_image`.
+pub fn decode_image_fitted(
+    bytes: &[u8],
+    apply_icc_profile: bool,
+    max_dimension: u32,
+    max_megapixels: u32,
+) -> VectorizeResult<(RgbaImage, (u32, u32))> {
+    let mut reader = ImageReader::new(Cursor::new(bytes)).with_guessed_format()?;
+    // The default allocation limit rejects large scans; the pixel count check below
+    // bounds memory instead
+    reader.no_limits();
+    let mut decoder = reader.into_decoder()?;
+
+    let (width, height) = decoder.dimensions();
+    let pixels = width as u64 * height as u64;
+    if pixels > max_megapixels as u64 * 1_000_000 {
+        return Err(VectorizeError::memory_limit_exceeded(format!(
+            "{width}x{height} image is {:.1} megapixels, above the {max_megapixels} megapixel decode limit",
+            pixels as f64 / 1_000_000.0
+        )));
+    }
+
+    let icc_profile = if apply_icc_profile {
+        decoder.icc_profile()?
+    } else {
+        None
+    };
+    let decoded = DynamicImage::from_decoder(decoder)?;
+    let mut image = fit_within(&decoded, max_dimension);
+    drop(decoded);
+
+    if let Some(icc) = icc_profile {
+        if let Err(e) = convert_icc_to_srgb(&mut image, &icc) {
+            log::warn!("Ignoring embedded ICC profile: {e}");
+        }
+    }
+
+    log::info!(
+        "Decoded {}x{} image at {}x{}",
+        width,
+        height,
+        image.width(),
+        image.height()
+    );
+    Ok((image, (width, height)))
+}
+
+/// Area-average `image` down so its longer side is at most `max_dimension`
+///
+/// Output rows are built one band of source rows at a time, accumulating into a
+/// single row of sums. Colors are weighted by alpha so transparent pixels don't
+/// bleed into visible ones.
+fn fit_within(image: &DynamicImage, max_dimension: u32) -> RgbaImage {
+    let (width, height) = image.dimensions();
+    let longest = width.max(height);
+    if longest <= max_dimension || width == 0 || height == 0 {
+        return image.to_rgba8();
+    }
+
+    // Same target size as `analyze_resolution_requirements`, so no second resize follows
+    let scale = max_dimension as f32 / longest as f32;
+    let fitted_width = ((width as f32 * scale).round() as u32).max(1);
+    let fitted_height = ((height as f32 * scale).round() as u32).max(1);
+    let column_of: Vec<usize> = (0..width)
+        .map(|x| (x as u64 * fitted_width as u64 / width as u64) as usize)
+        .collect();
+
+    let mut fitted = RgbaImage::new(fitted_width, fitted_height);
+    // Per output column: alpha-weighted RGB, alpha and pixel count
+    let mut sums = vec![([0u64; 3], 0u64, 0u64); fitted_width as usize];
+    for fitted_y in 0..fitted_height {
+        let band_start = (fitted_y as u64 * height as u64 / fitted_height as u64) as u32;
+        let band_end = ((fitted_y as u64 + 1) * height as u64 / fitted_height as u64) as u32;
+        sums.fill(([0; 3], 0, 0));
+        for y in band_start..band_end {
+            for (x, &column) in column_of.iter().enumerate() {
+                let [r, g, b, a] = image.get_pixel(x as u32, y).0;
+                let (color, alpha, count) = &mut sums[column];
+                for (total, channel) in color.iter_mut().zip([r, g, b]) {
+                    *total += channel as u64 * a as u64;
+                }
+                *alpha += a as u64;
+                *count += 1;
+            }
+        }
+        for (fitted_x, &(color, alpha, count)) in sums.iter().enumerate() {
+            let pixel = if alpha > 0 {
+                let [r, g, b] = color.map(|total| ((total + alpha / 2) / alpha) as u8);
+                [r, g, b, ((alpha + count / 2) / count) as u8]
+            } else {
+                [0, 0, 0, 0]
+            };
+            fitted.put_pixel(fitted_x as u32, fitted_y, image::Rgba(pixel));
+        }
+    }
+    fitted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::codecs::png::PngEncoder;
+    use image::{ImageEncoder, Rgb, RgbImage};
+
+    fn encode_png(image: &RgbImage) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        PngEncoder::new(&mut bytes)
+            .write_image(
+                image.as_raw(),
+                image.width(),
+                image.height(),
+                image::ExtendedColorType::Rgb8,
+            )
+            .unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_fitted_decode_reduces_large_images() {
+        // Left half red, right half blue
+        let image = RgbImage::from_fn(1200, 800, |x, _| {
+            if x < 600 {
+                Rgb([200, 30, 30])
+            } else {
+                Rgb([30, 30, 200])
+            }
+        });
+        let png = encode_png(&image);
+
+        let (fitted, source) = decode_image_fitted(&png, true, 300, 1).unwrap();
+        assert_eq!(source, (1200, 800));
+        assert_eq!(fitted.dimensions(), (300, 200));
+        assert_eq!(fitted.get_pixel(10, 100).0, [200, 30, 30, 255]);
+        assert_eq!(fitted.get_pixel(290, 100).0, [30, 30, 200, 255]);
+
+        let (unchanged, _) = decode_image_fitted(&png, true, 4096, 1).unwrap();
+        assert_eq!(unchanged.dimensions(), (1200, 800));
+    }
+
+    #[test]
+    fn test_fitted_decode_rejects_images_over_the_pixel_limit() {
+        let png = encode_png(&RgbImage::new(1200, 900));
+
+        assert!(decode_image_fitted(&png, false, 512, 1).is_err());
+        assert!(decode_image_fitted(&png, false, 512, 2).is_ok());
+    }
+}
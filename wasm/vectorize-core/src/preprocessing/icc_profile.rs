@@ -0,0 +1,119 @@
+//! ICC-aware image decoding
+//!
+//! Photos exported in wide-gamut spaces (Adobe RGB, ProPhoto) carry an embedded
+//! ICC profile. Treating their pixels as sRGB shifts colors, so the decode path
+//! reads the profile and converts pixels to sRGB before tracing.
+
+use crate::error::{VectorizeError, VectorizeResult};
+use image::{DynamicImage, ImageDecoder, ImageReader, RgbaImage};
+use moxcms::{ColorProfile, Layout, TransformOptions};
+use std::io::Cursor;
+
+/// Decode an encoded image, converting it to sRGB using its embedded ICC
+/// profile when `apply_icc_profile` is set
+///
+/// Images without a profile are returned as-is. A profile that can't be parsed
+/// or applied (e.g. a grayscale or CMYK profile) is logged and ignored rather
+/// than failing the decode.
+pub fn decode_image(bytes: &[u8], apply_icc_profile: bool) -> VectorizeResult<RgbaImage> {
+    let mut decoder = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()?
+        .into_decoder()?;
+    let icc_profile = if apply_icc_profile {
+        decoder.icc_profile()?
+    } else {
+        None
+    };
+    let mut image = DynamicImage::from_decoder(decoder)?.to_rgba8();
+
+    if let Some(icc) = icc_profile {
+        if let Err(e) = convert_icc_to_srgb(&mut image, &icc) {
+            log::warn!("Ignoring embedded ICC profile: {e}");
+        }
+    }
+
+    Ok(image)
+}
+
+/// Convert RGBA pixels in place from the given ICC profile's space to sRGB
+pub fn convert_icc_to_srgb(image: &mut RgbaImage, icc: &[u8]) -> VectorizeResult<()> {
+    let source = ColorProfile::new_from_slice(icc)
+        .map_err(|e| VectorizeError::algorithm_error(format!("Invalid ICC profile: {e:?}")))?;
+    let transform = source
+        .create_transform_8bit(
+            Layout::Rgba,
+            &ColorProfile::new_srgb(),
+            Layout::Rgba,
+            TransformOptions::default(),
+        )
+        .map_err(|e| VectorizeError::algorithm_error(format!("Unsupported ICC profile: {e:?}")))?;
+
+    let source_pixels = image.as_raw().clone();
+    transform
+        .transform(&source_pixels, image.as_mut())
+        .map_err(|e| VectorizeError::algorithm_error(format!("ICC conversion failed: {e:?}")))?;
+
+    log::debug!(
+        "Converted {}x{} image from embedded ICC profile to sRGB",
+        image.width(),
+        image.height()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::codecs::png::PngEncoder;
+    use image::{ImageEncoder, Rgba};
+
+    fn encode_png(image: &RgbaImage, icc: Option<Vec<u8>>) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut encoder = PngEncoder::new(&mut bytes);
+        if let Some(icc) = icc {
+            encoder.set_icc_profile(icc).unwrap();
+        }
+        encoder
+            .write_image(
+                image.as_raw(),
+                image.width(),
+                image.height(),
+                image::ExtendedColorType::Rgba8,
+            )
+            .unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_adobe_rgb_png_is_converted_to_srgb() {
+        let adobe_green = Rgba([40, 160, 60, 255]);
+        let image = RgbaImage::from_pixel(4, 4, adobe_green);
+        let icc = ColorProfile::new_adobe_rgb().encode().unwrap();
+        let png = encode_png(&image, Some(icc));
+
+        let raw = decode_image(&png, false).unwrap();
+        assert_eq!(*raw.get_pixel(0, 0), adobe_green);
+
+        // Adobe RGB green is more saturated than sRGB green: the converted
+        // pixel pushes red down and green up, keeping alpha untouched
+        let converted = *decode_image(&png, true).unwrap().get_pixel(0, 0);
+        assert!(converted[0] < adobe_green[0]);
+        assert!(converted[1] > adobe_green[1]);
+        assert_eq!(converted[3], 255);
+    }
+
+    #[test]
+    fn test_images_without_profile_are_unchanged() {
+        let image = RgbaImage::from_fn(4, 4, |x, y| Rgba([x as u8 * 60, y as u8 * 60, 90, 200]));
+        let png = encode_png(&image, None);
+
+        assert_eq!(decode_image(&png, true).unwrap(), image);
+    }
+
+    #[test]
+    fn test_invalid_profile_is_rejected() {
+        let mut image = RgbaImage::from_pixel(2, 2, Rgba([10, 20, 30, 255]));
+        assert!(convert_icc_to_srgb(&mut image, b"not an icc profile").is_err());
+        assert_eq!(*image.get_pixel(0, 0), Rgba([10, 20, 30, 255]));
+    }
+}
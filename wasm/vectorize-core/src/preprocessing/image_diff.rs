@@ -0,0 +1,101 @@
+//! Difference maps between two images for change-detection tracing
+//!
+//! The result keeps only the pixels that changed between a before and an after
+//! image, on a white background, so any tracing backend outlines just the change.
+
+use crate::algorithms::centerline::preprocessing::remove_small_components;
+use crate::error::{VectorizeError, VectorizeResult};
+use image::{GrayImage, Luma, Rgba, RgbaImage};
+
+/// Default per-channel difference (0-255) a pixel needs to count as changed
+pub const DEFAULT_DIFF_THRESHOLD: u8 = 32;
+
+/// Changed regions smaller than this many pixels are treated as noise
+const MIN_CHANGED_REGION_PX: u32 = 16;
+
+/// Build an image containing only the significant changes from `before` to `after`
+///
+/// A pixel counts as changed when any RGBA channel differs by more than
+/// `threshold`. Changed regions keep their `after` colors; everything else,
+/// including specks smaller than a few pixels, becomes white.
+pub fn difference_image(
+    before: &RgbaImage,
+    after: &RgbaImage,
+    threshold: u8,
+) -> VectorizeResult<RgbaImage> {
+    if before.dimensions() != after.dimensions() {
+        let (width, height) = after.dimensions();
+        return Err(VectorizeError::invalid_dimensions(
+            width,
+            height,
+            format!(
+                "Diff images must match the before image ({}x{})",
+                before.width(),
+                before.height()
+            ),
+        ));
+    }
+
+    let (width, height) = before.dimensions();
+    let changed = GrayImage::from_fn(width, height, |x, y| {
+        let a = before.get_pixel(x, y).0;
+        let b = after.get_pixel(x, y).0;
+        let delta = a
+            .iter()
+            .zip(b.iter())
+            .map(|(&a, &b)| a.abs_diff(b))
+            .max()
+            .unwrap_or(0);
+        Luma([if delta > threshold { 255 } else { 0 }])
+    });
+    let changed = remove_small_components(&changed, MIN_CHANGED_REGION_PX);
+
+    let changed_px = changed.pixels().filter(|p| p.0[0] > 127).count();
+    log::debug!(
+        "Image diff: {} of {} pixels changed (threshold {})",
+        changed_px,
+        width * height,
+        threshold
+    );
+
+    Ok(RgbaImage::from_fn(width, height, |x, y| {
+        if changed.get_pixel(x, y).0[0] > 127 {
+            *after.get_pixel(x, y)
+        } else {
+            Rgba([255, 255, 255, 255])
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_difference_image_keeps_only_changed_object() {
+        let before = RgbaImage::from_pixel(40, 40, Rgba([200, 220, 240, 255]));
+        let mut after = before.clone();
+        for y in 10..20 {
+            for x in 10..20 {
+                after.put_pixel(x, y, Rgba([180, 20, 20, 255]));
+            }
+        }
+        // Slight noise and a lone changed pixel should both be ignored
+        after.put_pixel(30, 30, Rgba([210, 225, 245, 255]));
+        after.put_pixel(35, 5, Rgba([0, 0, 0, 255]));
+
+        let diff = difference_image(&before, &after, DEFAULT_DIFF_THRESHOLD).unwrap();
+
+        assert_eq!(diff.get_pixel(15, 15).0, [180, 20, 20, 255]);
+        assert_eq!(diff.get_pixel(30, 30).0, [255, 255, 255, 255]);
+        assert_eq!(diff.get_pixel(35, 5).0, [255, 255, 255, 255]);
+        assert_eq!(diff.get_pixel(0, 0).0, [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn test_difference_image_rejects_mismatched_sizes() {
+        let before = RgbaImage::new(4, 4);
+        let after = RgbaImage::new(4, 5);
+        assert!(difference_image(&before, &after, DEFAULT_DIFF_THRESHOLD).is_err());
+    }
+}
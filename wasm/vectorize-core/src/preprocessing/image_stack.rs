@@ -0,0 +1,193 @@
+//! Combining multiple source images (exposure or focus stacks) before tracing
+//!
+//! All images in a stack must share dimensions. The result is a single RGBA
+//! image that can be passed to any tracing backend.
+
+use crate::error::{VectorizeError, VectorizeResult};
+use image::{Rgba, RgbaImage};
+
+/// Per-pixel strategy for merging an image stack
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StackCombineMode {
+    /// Brightest value per channel (lighten blend)
+    Max,
+    /// Darkest value per channel (darken blend)
+    Min,
+    /// Mean value per channel
+    Average,
+    /// Pixel from the source with the strongest local gradient (focus stacking)
+    MaxGradient,
+}
+
+/// Window radius used to measure local sharpness for `MaxGradient`
+const SHARPNESS_RADIUS: i64 = 2;
+
+/// Combine a stack of same-sized images into one image
+pub fn combine_images(images: &[RgbaImage], mode: StackCombineMode) -> VectorizeResult<RgbaImage> {
+    let first = images
+        .first()
+        .ok_or_else(|| VectorizeError::config_error("Image stack must contain at least one image"))?;
+    let (width, height) = first.dimensions();
+
+    if let Some(mismatch) = images.iter().find(|img| img.dimensions() != (width, height)) {
+        return Err(VectorizeError::invalid_dimensions(
+            mismatch.width(),
+            mismatch.height(),
+            format!("All stacked images must be {width}x{height}"),
+        ));
+    }
+
+    log::debug!(
+        "Combining {} images ({}x{}) with mode {:?}",
+        images.len(),
+        width,
+        height,
+        mode
+    );
+
+    let combined = match mode {
+        StackCombineMode::Max => combine_channels(images, |values| {
+            values.iter().copied().max().unwrap_or(0)
+        }),
+        StackCombineMode::Min => combine_channels(images, |values| {
+            values.iter().copied().min().unwrap_or(0)
+        }),
+        StackCombineMode::Average => combine_channels(images, |values| {
+            let sum: u32 = values.iter().map(|&v| v as u32).sum();
+            ((sum + values.len() as u32 / 2) / values.len() as u32) as u8
+        }),
+        StackCombineMode::MaxGradient => combine_by_sharpness(images),
+    };
+
+    Ok(combined)
+}
+
+/// Apply a channel reducer across the stack for every pixel
+fn combine_channels(images: &[RgbaImage], reduce: impl Fn(&[u8]) -> u8) -> RgbaImage {
+    let (width, height) = images[0].dimensions();
+    let mut values = Vec::with_capacity(images.len());
+
+    RgbaImage::from_fn(width, height, |x, y| {
+        let mut out = [0u8; 4];
+        for (channel, slot) in out.iter_mut().enumerate() {
+            values.clear();
+            values.extend(images.iter().map(|img| img.get_pixel(x, y).0[channel]));
+            *slot = reduce(&values);
+        }
+        Rgba(out)
+    })
+}
+
+/// Pick each pixel from the source with the highest local gradient energy
+fn combine_by_sharpness(images: &[RgbaImage]) -> RgbaImage {
+    let (width, height) = images[0].dimensions();
+    let sharpness: Vec<Vec<f32>> = images.iter().map(local_sharpness).collect();
+
+    RgbaImage::from_fn(width, height, |x, y| {
+        let idx = (y * width + x) as usize;
+        let best = (0..images.len())
+            .max_by(|&a, &b| sharpness[a][idx].total_cmp(&sharpness[b][idx]))
+            .unwrap_or(0);
+        *images[best].get_pixel(x, y)
+    })
+}
+
+/// Gradient energy summed over a small window around each pixel
+fn local_sharpness(image: &RgbaImage) -> Vec<f32> {
+    let (width, height) = (image.width() as i64, image.height() as i64);
+    let luma: Vec<f32> = image
+        .pixels()
+        .map(|p| 0.299 * p.0[0] as f32 + 0.587 * p.0[1] as f32 + 0.114 * p.0[2] as f32)
+        .collect();
+    let at = |x: i64, y: i64| luma[(y.clamp(0, height - 1) * width + x.clamp(0, width - 1)) as usize];
+
+    let mut energy = vec![0.0f32; luma.len()];
+    for y in 0..height {
+        for x in 0..width {
+            let gx = at(x + 1, y) - at(x - 1, y);
+            let gy = at(x, y + 1) - at(x, y - 1);
+            energy[(y * width + x) as usize] = gx * gx + gy * gy;
+        }
+    }
+
+    // Box-sum the energy so the choice is made per neighbourhood rather than per pixel
+    let mut summed = vec![0.0f32; luma.len()];
+    for y in 0..height {
+        for x in 0..width {
+            let mut total = 0.0;
+            for dy in -SHARPNESS_RADIUS..=SHARPNESS_RADIUS {
+                for dx in -SHARPNESS_RADIUS..=SHARPNESS_RADIUS {
+                    let (nx, ny) = (x + dx, y + dy);
+                    if nx >= 0 && ny >= 0 && nx < width && ny < height {
+                        total += energy[(ny * width + nx) as usize];
+                    }
+                }
+            }
+            summed[(y * width + x) as usize] = total;
+        }
+    }
+
+    summed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gradient_energy(image: &RgbaImage) -> f32 {
+        local_sharpness(image).iter().sum()
+    }
+
+    /// Checkerboard that is crisp on one half and flattened to mid-gray on the other
+    fn half_sharp_checkerboard(sharp_left: bool) -> RgbaImage {
+        RgbaImage::from_fn(40, 20, |x, y| {
+            let sharp = (x < 20) == sharp_left;
+            let value = if !sharp {
+                128
+            } else if (x / 2 + y / 2) % 2 == 0 {
+                0
+            } else {
+                255
+            };
+            Rgba([value, value, value, 255])
+        })
+    }
+
+    #[test]
+    fn test_combine_channel_modes() {
+        let dark = RgbaImage::from_pixel(4, 4, Rgba([10, 20, 30, 255]));
+        let bright = RgbaImage::from_pixel(4, 4, Rgba([200, 100, 50, 255]));
+        let stack = [dark, bright];
+
+        let max = combine_images(&stack, StackCombineMode::Max).unwrap();
+        assert_eq!(max.get_pixel(0, 0).0, [200, 100, 50, 255]);
+
+        let min = combine_images(&stack, StackCombineMode::Min).unwrap();
+        assert_eq!(min.get_pixel(0, 0).0, [10, 20, 30, 255]);
+
+        let average = combine_images(&stack, StackCombineMode::Average).unwrap();
+        assert_eq!(average.get_pixel(3, 3).0, [105, 60, 40, 255]);
+    }
+
+    #[test]
+    fn test_max_gradient_focus_stack_is_sharper() {
+        let left_focus = half_sharp_checkerboard(true);
+        let right_focus = half_sharp_checkerboard(false);
+        let stack = [left_focus.clone(), right_focus.clone()];
+
+        let combined = combine_images(&stack, StackCombineMode::MaxGradient).unwrap();
+
+        assert_eq!(combined.get_pixel(5, 10), left_focus.get_pixel(5, 10));
+        assert_eq!(combined.get_pixel(35, 10), right_focus.get_pixel(35, 10));
+        assert!(gradient_energy(&combined) > gradient_energy(&left_focus));
+        assert!(gradient_energy(&combined) > gradient_energy(&right_focus));
+    }
+
+    #[test]
+    fn test_combine_rejects_invalid_stacks() {
+        assert!(combine_images(&[], StackCombineMode::Max).is_err());
+
+        let stack = [RgbaImage::new(4, 4), RgbaImage::new(5, 4)];
+        assert!(combine_images(&stack, StackCombineMode::Average).is_err());
+    }
+}
@@ -12,6 +12,30 @@ pub use old::*;
 mod background_removal;
 pub use background_removal::*;
 
+// Include multi-image stack combination
+mod image_stack;
+pub use image_stack::*;
+
+// Include before/after difference maps
+mod image_diff;
+pub use image_diff::*;
+
+// Include ICC-aware image decoding
+mod icc_profile;
+pub use icc_profile::*;
+
+// Include reduced-size decoding of images too large to process at full size
+mod fitted_decode;
+pub use fitted_decode::*;
+
+// Include embedded pixel density (DPI) detection
+mod pixel_density;
+pub use pixel_density::*;
+
+// Include sharpness-based foreground/background separation
+mod depth_layers;
+pub use depth_layers::*;
+
 // Include test module for debugging background removal
 pub mod test_background_removal;
 
@@ -105,6 +129,20 @@ pub fn adjust_trace_low_config(
     config.clone()
 }
 
+/// Resample the detail map to the dimensions the image is processed at
+pub fn fit_detail_map(config: &mut TraceLowConfig, dimensions: (u32, u32)) {
+    if let Some(map) = config.detail_map.as_mut() {
+        if map.dimensions() != dimensions {
+            *map = image::imageops::resize(
+                map,
+                dimensions.0,
+                dimensions.1,
+                image::imageops::FilterType::Triangle,
+            );
+        }
+    }
+}
+
 /// Simplified SVG coordinate scaling (no scaling needed)
 pub fn scale_svg_coordinates(
     svg_document: &str,
@@ -330,11 +330,11 @@ pub fn lab_to_rgb(l: f32, a: f32, b: f32) -> (u8, u8, u8) {
         *component = component.clamp(0.0, 1.0);
     }
 
-    // Convert to 0-255 range
+    // Convert to 0-255 range, rounding so RGB -> LAB -> RGB round-trips exactly
     (
-        (rgb[0] * 255.0) as u8,
-        (rgb[1] * 255.0) as u8,
-        (rgb[2] * 255.0) as u8,
+        (rgb[0] * 255.0).round() as u8,
+        (rgb[1] * 255.0).round() as u8,
+        (rgb[2] * 255.0).round() as u8,
     )
 }
 
@@ -0,0 +1,185 @@
+//! Physical pixel density embedded in encoded images
+//!
+//! Scanners and print tools record the resolution an image was captured at in
+//! PNG `pHYs` chunks and JPEG JFIF headers. Reading it lets physical-size SVG
+//! output match the original document without the user supplying a DPI.
+
+use crate::algorithms::TraceLowConfig;
+
+/// Centimeters per inch, for converting metric densities to DPI
+const CM_PER_INCH: f32 = 2.54;
+
+/// PNG file signature preceding the first chunk
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Read the horizontal pixel density (dots per inch) stored in PNG or JPEG bytes
+///
+/// Returns `None` for other formats, images without density metadata, and
+/// densities that only give an aspect ratio.
+pub fn embedded_dpi(bytes: &[u8]) -> Option<f32> {
+    let dpi = if bytes.starts_with(PNG_SIGNATURE) {
+        png_dpi(&bytes[PNG_SIGNATURE.len()..])
+    } else if bytes.starts_with(&[0xFF, 0xD8]) {
+        jfif_dpi(&bytes[2..])
+    } else {
+        None
+    }?;
+    (dpi.is_finite() && dpi > 0.0).then_some(dpi)
+}
+
+/// Fill in `output_dpi` from the image's embedded density when `use_embedded_dpi`
+/// is set and no DPI was configured explicitly
+pub fn apply_embedded_dpi(config: &mut TraceLowConfig, bytes: &[u8]) {
+    if !config.use_embedded_dpi || config.output_dpi.is_some() {
+        return;
+    }
+    if let Some(dpi) = embedded_dpi(bytes) {
+        log::info!("Using embedded pixel density of {dpi:.1} DPI for physical output size");
+        config.output_dpi = Some(dpi);
+    }
+}
+
+/// Density from a `pHYs` chunk, which must precede the image data
+fn png_dpi(mut chunks: &[u8]) -> Option<f32> {
+    while chunks.len() >= 8 {
+        let length = u32::from_be_bytes(chunks[0..4].try_into().ok()?) as usize;
+        let kind = &chunks[4..8];
+        let data = chunks.get(8..8 + length)?;
+        match kind {
+            b"pHYs" if data.len() == 9 => {
+                let pixels_per_unit = u32::from_be_bytes(data[0..4].try_into().ok()?);
+                // Unit 1 is the meter; unit 0 only specifies the aspect ratio
+                return (data[8] == 1).then(|| pixels_per_unit as f32 * CM_PER_INCH / 100.0);
+            }
+            b"IDAT" | b"IEND" => return None,
+            _ => {}
+        }
+        // Skip the chunk data and its CRC
+        chunks = chunks.get(8 + length + 4..)?;
+    }
+    None
+}
+
+/// Density from the JFIF `APP0` segment at the start of a JPEG
+fn jfif_dpi(segments: &[u8]) -> Option<f32> {
+    let (marker, rest) = segments.split_at_checked(2)?;
+    if marker != [0xFF, 0xE0] {
+        return None;
+    }
+    let length = u16::from_be_bytes(rest.get(0..2)?.try_into().ok()?) as usize;
+    let segment = rest.get(2..length)?;
+    if !segment.starts_with(b"JFIF\0") {
+        return None;
+    }
+    let units = *segment.get(7)?;
+    let x_density = u16::from_be_bytes(segment.get(8..10)?.try_into().ok()?) as f32;
+    match units {
+        1 => Some(x_density),
+        2 => Some(x_density * CM_PER_INCH),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::codecs::jpeg::{JpegEncoder, PixelDensity};
+    use image::codecs::png::PngEncoder;
+    use image::{ImageEncoder, RgbImage};
+
+    fn crc32(bytes: &[u8]) -> u32 {
+        !bytes.iter().fold(!0u32, |crc, &byte| {
+            (0..8).fold(crc ^ byte as u32, |crc, _| {
+                (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg())
+            })
+        })
+    }
+
+    /// PNG of `image` with a `pHYs` chunk inserted after `IHDR`
+    fn png_with_phys(image: &RgbImage, pixels_per_meter: u32, unit: u8) -> Vec<u8> {
+        let mut png = Vec::new();
+        PngEncoder::new(&mut png)
+            .write_image(
+                image.as_raw(),
+                image.width(),
+                image.height(),
+                image::ExtendedColorType::Rgb8,
+            )
+            .unwrap();
+
+        let mut chunk = b"pHYs".to_vec();
+        chunk.extend_from_slice(&pixels_per_meter.to_be_bytes());
+        chunk.extend_from_slice(&pixels_per_meter.to_be_bytes());
+        chunk.push(unit);
+        let mut phys = 9u32.to_be_bytes().to_vec();
+        phys.extend_from_slice(&chunk);
+        phys.extend_from_slice(&crc32(&chunk).to_be_bytes());
+
+        let after_ihdr = PNG_SIGNATURE.len() + 8 + 13 + 4;
+        png.splice(after_ihdr..after_ihdr, phys);
+        png
+    }
+
+    #[test]
+    fn test_embedded_dpi_reads_png_and_jpeg_density() {
+        // 11811 pixels per meter is 300 DPI
+        let image = RgbImage::new(2, 2);
+        let dpi = embedded_dpi(&png_with_phys(&image, 11811, 1)).unwrap();
+        assert!((dpi - 300.0).abs() < 0.1, "got {dpi}");
+        assert!(embedded_dpi(&png_with_phys(&image, 11811, 0)).is_none());
+
+        let mut jpeg = Vec::new();
+        let mut encoder = JpegEncoder::new(&mut jpeg);
+        encoder.set_pixel_density(PixelDensity::dpi(300));
+        encoder.encode_image(&RgbImage::new(4, 4)).unwrap();
+        assert_eq!(embedded_dpi(&jpeg), Some(300.0));
+
+        assert!(embedded_dpi(b"GIF89a").is_none());
+    }
+
+    #[test]
+    fn test_apply_embedded_dpi_respects_explicit_dpi() {
+        let png = png_with_phys(&RgbImage::new(2, 2), 11811, 1);
+        let mut config = TraceLowConfig::default();
+        apply_embedded_dpi(&mut config, &png);
+        assert_eq!(config.output_dpi, None, "disabled by default");
+
+        config.use_embedded_dpi = true;
+        apply_embedded_dpi(&mut config, &png);
+        assert!((config.output_dpi.unwrap() - 300.0).abs() < 0.1);
+
+        config.output_dpi = Some(150.0);
+        apply_embedded_dpi(&mut config, &png);
+        assert_eq!(config.output_dpi, Some(150.0));
+    }
+
+    #[test]
+    fn test_embedded_dpi_scan_exports_at_physical_size() {
+        // A 300 DPI scan 1.5in wide and 0.5in tall
+        let scan = RgbImage::from_fn(450, 150, |x, y| {
+            if (100..350).contains(&x) && (40..110).contains(&y) {
+                image::Rgb([20, 20, 20])
+            } else {
+                image::Rgb([250, 250, 250])
+            }
+        });
+        let png = png_with_phys(&scan, 11811, 1);
+
+        let image = crate::preprocessing::decode_image(&png, true).unwrap();
+        let mut config = TraceLowConfig {
+            use_embedded_dpi: true,
+            ..TraceLowConfig::default()
+        };
+        apply_embedded_dpi(&mut config, &png);
+        let svg = crate::vectorize_trace_low_rgba(&image, &config, None).unwrap();
+        assert!(svg.contains(r#"width="1.5in" height="0.5in" viewBox="0 0 450 150""#));
+
+        // Downscaled processing keeps the physical size of the source
+        let downscaled = TraceLowConfig {
+            max_image_size: 225,
+            ..config
+        };
+        let svg = crate::vectorize_trace_low_rgba(&image, &downscaled, None).unwrap();
+        assert!(svg.contains(r#"width="1.5in" height="0.5in" viewBox="0 0 225 75""#));
+    }
+}
@@ -1,7 +1,13 @@
 //! SVG generation utilities
 
-use crate::algorithms::{SvgElementType, SvgPath};
-use crate::config::SvgConfig;
+use crate::algorithms::{Point, SvgElementType, SvgPath};
+use crate::config::{ColorSpace, CoordinateOrigin, NonFiniteHandling, SvgConfig};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::borrow::Cow;
+
+/// XML declaration prepended when `SvgConfig::emit_xml_declaration` is set
+pub(crate) const XML_DECLARATION: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n";
 
 /// Generate complete SVG document from paths
 ///
@@ -19,120 +25,1892 @@ pub fn generate_svg_document(
     height: u32,
     config: &SvgConfig,
 ) -> String {
+    generate_svg_document_with_defs(paths, "", width, height, config)
+}
+
+/// Generate an SVG document with `defs` (e.g. gradient definitions) written verbatim
+/// after the header, shared by the plain and gradient document writers
+pub(crate) fn generate_svg_document_with_defs(
+    paths: &[SvgPath],
+    defs: &str,
+    width: u32,
+    height: u32,
+    config: &SvgConfig,
+) -> String {
+    if config.group_by_thickness {
+        let tiers = thickness_tiers(paths);
+        let layers: Vec<(&str, &[SvgPath])> = tiers
+            .iter()
+            .map(|(name, paths)| (*name, paths.as_slice()))
+            .collect();
+        return layered_svg_document(&layers, defs, width, height, config);
+    }
+
+    let mut svg = svg_document_open(width, height, config);
+    svg.push_str(defs);
+
+    // Add each path (no grouping in simplified config)
+    let paths = prepare_output_paths(paths, width, height, config);
+    let canvas_group = canvas_group_open(width, height, config);
+    if let Some(group) = &canvas_group {
+        svg.push_str(group);
+    }
+    push_svg_elements(&mut svg, &paths, (0, paths.len()), "  ", config);
+    if canvas_group.is_some() {
+        svg.push_str("  </g>\n");
+    }
+    if config.emit_palette_legend {
+        svg.push_str(&palette_legend(&paths, width, height, config));
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Generate an SVG document with each set of paths wrapped in a `<g id="name">` layer
+///
+/// Layers are drawn in order, so later layers sit on top. Path ids and draw-on
+/// timing run continuously across layers.
+///
+/// # Arguments
+/// * `layers` - Layer names with their paths, bottom layer first
+/// * `width` - SVG viewport width
+/// * `height` - SVG viewport height
+/// * `config` - SVG generation configuration
+pub fn generate_layered_svg_document(
+    layers: &[(&str, &[SvgPath])],
+    width: u32,
+    height: u32,
+    config: &SvgConfig,
+) -> String {
+    layered_svg_document(layers, "", width, height, config)
+}
+
+/// Layered document with `defs` written verbatim after the header
+pub(crate) fn layered_svg_document(
+    layers: &[(&str, &[SvgPath])],
+    defs: &str,
+    width: u32,
+    height: u32,
+    config: &SvgConfig,
+) -> String {
+    let mut svg = svg_document_open(width, height, config);
+    svg.push_str(defs);
+
+    let layers: Vec<(&str, Cow<'_, [SvgPath]>)> = layers
+        .iter()
+        .map(|&(name, paths)| (name, prepare_output_paths(paths, width, height, config)))
+        .collect();
+    let count = layers.iter().map(|(_, paths)| paths.len()).sum();
+    let canvas_group = canvas_group_open(width, height, config);
+    if let Some(group) = &canvas_group {
+        svg.push_str(group);
+    }
+    let mut offset = 0;
+    for (name, paths) in &layers {
+        svg.push_str(&format!("  <g id=\"{name}\">\n"));
+        push_svg_elements(&mut svg, paths, (offset, count), "    ", config);
+        svg.push_str("  </g>\n");
+        offset += paths.len();
+    }
+    if canvas_group.is_some() {
+        svg.push_str("  </g>\n");
+    }
+    if config.emit_palette_legend {
+        let paths: Vec<SvgPath> = layers
+            .iter()
+            .flat_map(|(_, paths)| paths.iter().cloned())
+            .collect();
+        svg.push_str(&palette_legend(&paths, width, height, config));
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Layer ids for `SvgConfig::group_by_thickness`, thinnest first
+const THICKNESS_TIER_IDS: [&str; 3] = ["thickness-thin", "thickness-medium", "thickness-thick"];
+
+/// Smallest spread between stroke widths, relative to the widest stroke, that is
+/// split into tiers; closer widths all count as medium
+const THICKNESS_TIER_MIN_SPREAD: f32 = 0.1;
+
+/// Split paths into thickness layers, drawn as unstroked fills, then thick, medium
+/// and thin strokes so fine lines stay visible on top
+///
+/// Stroke widths (which carry EDT-based width modulation from tracing) are split
+/// into three equal bands between the thinnest and thickest stroke. Empty layers
+/// are omitted.
+fn thickness_tiers(paths: &[SvgPath]) -> Vec<(&'static str, Vec<SvgPath>)> {
+    let is_stroked = |path: &SvgPath| path.stroke != "none" && path.stroke_width > 0.0;
+    let (min_width, max_width) = paths
+        .iter()
+        .filter(|path| is_stroked(path))
+        .fold((f32::INFINITY, 0.0f32), |(lo, hi), path| {
+            (lo.min(path.stroke_width), hi.max(path.stroke_width))
+        });
+    let spread = max_width - min_width;
+
+    let mut fills = Vec::new();
+    let mut tiers: [Vec<SvgPath>; 3] = Default::default();
+    for path in paths {
+        if !is_stroked(path) {
+            fills.push(path.clone());
+            continue;
+        }
+        let tier = if spread <= THICKNESS_TIER_MIN_SPREAD * max_width {
+            1
+        } else {
+            (((path.stroke_width - min_width) / spread * 3.0) as usize).min(2)
+        };
+        tiers[tier].push(path.clone());
+    }
+
+    let mut layers = vec![("fills", fills)];
+    layers.extend(THICKNESS_TIER_IDS.into_iter().zip(tiers).rev());
+    layers.retain(|(_, paths)| !paths.is_empty());
+    layers
+}
+
+/// Legend swatch size relative to the shorter side of the document
+const LEGEND_SWATCH_FRACTION: f32 = 0.04;
+
+/// Smallest legend swatch size in user units, so labels stay legible on small outputs
+const LEGEND_MIN_SWATCH: f32 = 8.0;
+
+/// Canvas coverage of each solid paint color, as `(hex, percent)` largest first
+///
+/// Fills count their enclosed area and strokes their length times width. Overlaps
+/// are not subtracted, so layered output can total more than 100%. Paints other
+/// than `#rrggbb` colors (`none`, gradients) are skipped.
+pub fn palette_coverage(paths: &[SvgPath], width: u32, height: u32) -> Vec<(String, f32)> {
+    let canvas_area = (width as f32 * height as f32).max(1.0);
+    let mut coverage: Vec<(String, f32)> = Vec::new();
+    let mut add = |paint: &str, area: f32| {
+        if !paint.starts_with('#') {
+            return;
+        }
+        let color = paint.to_ascii_lowercase();
+        match coverage.iter_mut().find(|(hex, _)| *hex == color) {
+            Some((_, total)) => *total += area,
+            None => coverage.push((color, area)),
+        }
+    };
+    for path in paths {
+        let (fill_area, outline_length) = element_area_and_outline(path);
+        add(&path.fill, fill_area);
+        if path.stroke_width > 0.0 {
+            add(&path.stroke, outline_length * path.stroke_width);
+        }
+    }
+
+    let mut coverage: Vec<(String, f32)> = coverage
+        .into_iter()
+        .map(|(color, area)| (color, (area / canvas_area * 100.0).min(100.0)))
+        .collect();
+    coverage.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    coverage
+}
+
+/// Enclosed area and outline length of an element in user units
+///
+/// Path data that cannot be parsed measures as zero.
+fn element_area_and_outline(path: &SvgPath) -> (f32, f32) {
+    use std::f32::consts::PI;
+
+    match &path.element_type {
+        SvgElementType::Path => {
+            let area = flatten_path_data(&path.data)
+                .map(|subpaths| {
+                    subpaths
+                        .iter()
+                        .map(|(points, _)| signed_area(points))
+                        .sum::<f32>()
+                        .abs()
+                })
+                .unwrap_or(0.0);
+            (area, path_data_length(&path.data).unwrap_or(0.0))
+        }
+        SvgElementType::Circle { r, .. } => (PI * r * r, 2.0 * PI * r),
+        SvgElementType::Ellipse { rx, ry, .. } => (PI * rx * ry, PI * (rx + ry)),
+        SvgElementType::Line { x1, y1, x2, y2 } => (0.0, (x2 - x1).hypot(y2 - y1)),
+        SvgElementType::Rect { width, height, .. } => (width * height, 2.0 * (width + height)),
+        SvgElementType::Polygon { points } => {
+            let values: Vec<f32> = points
+                .split(|c: char| c.is_whitespace() || c == ',')
+                .filter_map(|t| t.parse().ok())
+                .collect();
+            let ring: Vec<(f32, f32)> = values
+                .as_chunks::<2>()
+                .0
+                .iter()
+                .map(|&[x, y]| (x, y))
+                .collect();
+            let perimeter = (0..ring.len())
+                .map(|k| {
+                    let (a, b) = (ring[k], ring[(k + 1) % ring.len()]);
+                    (b.0 - a.0).hypot(b.1 - a.1)
+                })
+                .sum();
+            (signed_area(&ring).abs(), perimeter)
+        }
+    }
+}
+
+/// `<g class="legend">` with a labeled swatch for every color in `palette_coverage`,
+/// in a row along the bottom-left corner of the document
+///
+/// The legend sits outside any transformed canvas group so it is never mirrored.
+/// Each swatch is labeled with its hex color and coverage percentage.
+fn palette_legend(paths: &[SvgPath], width: u32, height: u32, config: &SvgConfig) -> String {
+    let coverage = palette_coverage(paths, width, height);
+    if coverage.is_empty() {
+        return String::new();
+    }
+
+    let (out_width, out_height) = config
+        .output_transform
+        .map_or((width, height), |t| t.output_dimensions(width, height));
+    let (min_x, min_y) = effective_origin(config).view_box_origin(out_width, out_height);
+    let swatch = (out_width.min(out_height) as f32 * LEGEND_SWATCH_FRACTION).max(LEGEND_MIN_SWATCH);
+    let font_size = swatch * 0.5;
+    let padding = swatch * 0.25;
+    // Wide enough for a "#rrggbb" label below each swatch
+    let cell = swatch * 2.5;
+    let legend_width = padding + cell * coverage.len() as f32;
+    let legend_height = padding * 2.0 + swatch + font_size * 2.4;
+    let left = min_x + padding;
+    let top = min_y + out_height as f32 - padding - legend_height;
+    let number = |value: f32| format!("{value:.prec$}", prec = config.precision as usize);
+
+    let mut legend = format!(
+        "  <g class=\"legend\" font-family=\"sans-serif\" font-size=\"{}\">\n",
+        number(font_size)
+    );
+    legend.push_str(&format!(
+        "    <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#ffffff\" fill-opacity=\"0.85\" stroke=\"#808080\" stroke-width=\"{}\"/>\n",
+        number(left),
+        number(top),
+        number(legend_width),
+        number(legend_height),
+        number(swatch * 0.05)
+    ));
+    for (index, (color, percent)) in coverage.iter().enumerate() {
+        let x = left + padding + cell * index as f32;
+        let y = top + padding;
+        legend.push_str(&format!(
+            "    <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{color}\" stroke=\"#000000\" stroke-width=\"{}\"/>\n",
+            number(x),
+            number(y),
+            number(swatch),
+            number(swatch),
+            number(swatch * 0.05)
+        ));
+        legend.push_str(&format!(
+            "    <text x=\"{}\" y=\"{}\">{color}</text>\n",
+            number(x),
+            number(y + swatch + font_size * 1.1)
+        ));
+        legend.push_str(&format!(
+            "    <text x=\"{}\" y=\"{}\">{percent:.1}%</text>\n",
+            number(x),
+            number(y + swatch + font_size * 2.3)
+        ));
+    }
+    legend.push_str("  </g>\n");
+    legend
+}
+
+/// Coordinate origin in effect; `output_transform` takes precedence and keeps the top-left origin
+pub(crate) fn effective_origin(config: &SvgConfig) -> CoordinateOrigin {
+    match config.output_transform {
+        Some(_) => CoordinateOrigin::TopLeft,
+        None => config.coordinate_origin,
+    }
+}
+
+/// Opening tag of the group wrapping all elements for `output_transform` or a
+/// y-up coordinate origin, if either needs one
+fn canvas_group_open(width: u32, height: u32, config: &SvgConfig) -> Option<String> {
+    let transform = match config.output_transform {
+        Some(transform) => transform.svg_transform(width, height),
+        None if effective_origin(config).flips_y() => "scale(1 -1)".to_string(),
+        None => return None,
+    };
+    Some(format!("  <g transform=\"{transform}\">\n"))
+}
+
+/// XML declaration, opening `<svg>` tag, metadata and draw-on styles of a document
+fn svg_document_open(width: u32, height: u32, config: &SvgConfig) -> String {
     let mut svg = String::new();
 
+    if config.emit_xml_declaration {
+        svg.push_str(XML_DECLARATION);
+    }
+
     // SVG header
+    let (out_width, out_height) = config
+        .output_transform
+        .map_or((width, height), |t| t.output_dimensions(width, height));
+    let (size_width, size_height) = physical_size_attributes(out_width, out_height, config);
+    let (min_x, min_y) = effective_origin(config).view_box_origin(out_width, out_height);
     svg.push_str(&format!(
-        r#"<svg width="{width}" height="{height}" viewBox="0 0 {width} {height}" xmlns="http://www.w3.org/2000/svg""#
+        r#"<svg width="{size_width}" height="{size_height}" viewBox="{min_x} {min_y} {out_width} {out_height}" xmlns="http://www.w3.org/2000/svg""#
     ));
 
     if config.optimize {
         svg.push_str(r#" shape-rendering="geometricPrecision""#);
     }
 
-    svg.push_str(">\n");
+    svg.push_str(">\n");
+
+    if config.include_metadata {
+        svg.push_str("  <!-- Generated by vec2art vectorize-core -->\n");
+    }
+    if let Some(comment) = source_hash_comment(config) {
+        svg.push_str(&comment);
+    }
+    if config.draw_on_animation.is_some() {
+        svg.push_str(DRAW_ON_STYLE);
+    }
+    svg
+}
+
+/// `width` and `height` attribute values, in inches when `physical_dpi` is set
+fn physical_size_attributes(width: u32, height: u32, config: &SvgConfig) -> (String, String) {
+    let dpi = config
+        .physical_dpi
+        .filter(|dpi| dpi.is_finite() && *dpi > 0.0);
+    let Some(dpi) = dpi else {
+        return (width.to_string(), height.to_string());
+    };
+    // Three decimals keep sizes within a thousandth of an inch
+    let inches = |pixels: u32| (pixels as f32 / dpi * 1000.0).round() / 1000.0;
+    (
+        format!("{}in", inches(width)),
+        format!("{}in", inches(height)),
+    )
+}
+
+/// Metadata comment line carrying the source hash when `embed_source_hash` is set
+fn source_hash_comment(config: &SvgConfig) -> Option<String> {
+    let hash = config
+        .source_hash
+        .as_ref()
+        .filter(|_| config.embed_source_hash)?;
+    Some(format!("  <!-- source-hash: {hash} -->\n"))
+}
+
+/// Sanitize, repair and close paths as configured before they are written
+fn prepare_output_paths<'a>(
+    paths: &'a [SvgPath],
+    width: u32,
+    height: u32,
+    config: &SvgConfig,
+) -> Cow<'a, [SvgPath]> {
+    let paths = sanitize_non_finite_paths(paths, width, height, config.non_finite_handling);
+    let paths = repair_self_intersecting_paths(paths, config);
+    let paths = close_coincident_paths(paths, config);
+    remap_coordinate_origin(paths, width, height, config)
+}
+
+/// Express every element's coordinates relative to `config.coordinate_origin`
+///
+/// Path data that cannot be parsed is left unchanged.
+fn remap_coordinate_origin<'a>(
+    paths: Cow<'a, [SvgPath]>,
+    width: u32,
+    height: u32,
+    config: &SvgConfig,
+) -> Cow<'a, [SvgPath]> {
+    let origin = effective_origin(config);
+    if origin == CoordinateOrigin::TopLeft {
+        return paths;
+    }
+
+    let (width, height) = (width as f32, height as f32);
+    let map = |x: f32, y: f32| origin.map_point((x, y), width, height);
+    let remapped = paths
+        .iter()
+        .map(|path| {
+            let mut remapped = path.clone();
+            remapped.element_type = match &path.element_type {
+                SvgElementType::Path => {
+                    if let Some(data) =
+                        remap_path_data(&path.data, origin, (width, height), config.precision)
+                    {
+                        remapped.data = data;
+                    }
+                    SvgElementType::Path
+                }
+                SvgElementType::Circle { cx, cy, r } => {
+                    let (cx, cy) = map(*cx, *cy);
+                    SvgElementType::Circle { cx, cy, r: *r }
+                }
+                SvgElementType::Ellipse { cx, cy, rx, ry } => {
+                    let (cx, cy) = map(*cx, *cy);
+                    SvgElementType::Ellipse {
+                        cx,
+                        cy,
+                        rx: *rx,
+                        ry: *ry,
+                    }
+                }
+                SvgElementType::Line { x1, y1, x2, y2 } => {
+                    let ((x1, y1), (x2, y2)) = (map(*x1, *y1), map(*x2, *y2));
+                    SvgElementType::Line { x1, y1, x2, y2 }
+                }
+                SvgElementType::Rect {
+                    x,
+                    y,
+                    width: w,
+                    height: h,
+                } => {
+                    // With y pointing up the bottom edge becomes the rect's origin
+                    let corner_y = if origin.flips_y() { *y + *h } else { *y };
+                    let (x, y) = map(*x, corner_y);
+                    SvgElementType::Rect {
+                        x,
+                        y,
+                        width: *w,
+                        height: *h,
+                    }
+                }
+                SvgElementType::Polygon { points } => SvgElementType::Polygon {
+                    points: remap_point_list(points, map, config.precision)
+                        .unwrap_or_else(|| points.clone()),
+                },
+            };
+            if let Some(region) = remapped.region_metadata.as_mut() {
+                let (x, y) = map(region.centroid.x, region.centroid.y);
+                region.centroid = Point { x, y };
+            }
+            remapped
+        })
+        .collect();
+    Cow::Owned(remapped)
+}
+
+/// Rewrite path data relative to `origin`, negating relative y offsets and
+/// mirroring arc sweeps when the y axis points up
+fn remap_path_data(
+    data: &str,
+    origin: CoordinateOrigin,
+    (width, height): (f32, f32),
+    precision: u8,
+) -> Option<String> {
+    let flip = origin.flips_y();
+    let map = |x: f32, y: f32| origin.map_point((x, y), width, height);
+
+    let mut output = Vec::new();
+    for (index, (command, mut args)) in parse_path_commands(data)?.into_iter().enumerate() {
+        match command {
+            // A leading relative moveto starts from (0,0), so its first point is absolute
+            'm' if index == 0 => {
+                if let Some([x, y]) = args.as_chunks_mut::<2>().0.first_mut() {
+                    (*x, *y) = map(*x, *y);
+                }
+                if flip {
+                    args.iter_mut().skip(3).step_by(2).for_each(|dy| *dy = -*dy);
+                }
+            }
+            'M' | 'L' | 'T' | 'C' | 'S' | 'Q' => {
+                for [x, y] in args.as_chunks_mut::<2>().0 {
+                    (*x, *y) = map(*x, *y);
+                }
+            }
+            'H' => args.iter_mut().for_each(|x| *x = map(*x, 0.0).0),
+            'V' => args.iter_mut().for_each(|y| *y = map(0.0, *y).1),
+            'A' => {
+                for [_, _, _, _, sweep, x, y] in args.as_chunks_mut::<7>().0 {
+                    (*x, *y) = map(*x, *y);
+                    if flip {
+                        *sweep = 1.0 - *sweep;
+                    }
+                }
+            }
+            'm' | 'l' | 't' | 'c' | 's' | 'q' if flip => {
+                args.iter_mut().skip(1).step_by(2).for_each(|dy| *dy = -*dy);
+            }
+            'v' if flip => args.iter_mut().for_each(|dy| *dy = -*dy),
+            'a' if flip => {
+                for [_, _, _, _, sweep, _, dy] in args.as_chunks_mut::<7>().0 {
+                    *sweep = 1.0 - *sweep;
+                    *dy = -*dy;
+                }
+            }
+            _ => {}
+        }
+
+        let is_arc = command.eq_ignore_ascii_case(&'a');
+        let mut segment = command.to_string();
+        for (position, value) in args.iter().enumerate() {
+            // Arc flags must stay bare 0/1
+            let text = if is_arc && matches!(position % 7, 3 | 4) {
+                format!("{value:.0}")
+            } else {
+                format!("{value:.prec$}", prec = precision as usize)
+            };
+            segment.push(' ');
+            segment.push_str(&text);
+        }
+        output.push(segment);
+    }
+    Some(output.join(" "))
+}
+
+/// Map each `x,y` pair of a polygon `points` list
+fn remap_point_list(
+    points: &str,
+    map: impl Fn(f32, f32) -> (f32, f32),
+    precision: u8,
+) -> Option<String> {
+    let values = points
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|t| !t.is_empty())
+        .map(|t| t.parse::<f32>().ok())
+        .collect::<Option<Vec<f32>>>()?;
+
+    let pairs: Vec<String> = values
+        .as_chunks::<2>()
+        .0
+        .iter()
+        .map(|&[x, y]| {
+            let (x, y) = map(x, y);
+            format!("{x:.prec$},{y:.prec$}", prec = precision as usize)
+        })
+        .collect();
+    Some(pairs.join(" "))
+}
+
+/// Write one element per line, numbering them from `offset` out of `count` in total
+fn push_svg_elements(
+    svg: &mut String,
+    paths: &[SvgPath],
+    (offset, count): (usize, usize),
+    indent: &str,
+    config: &SvgConfig,
+) {
+    for (index, path) in paths.iter().enumerate() {
+        svg.push_str(indent);
+        svg.push_str(&format_svg_path(path, (offset + index, count), config));
+        svg.push('\n');
+    }
+}
+
+/// Remove or clamp NaN/infinite coordinates so every emitted element is valid SVG
+///
+/// Returns the input untouched in the common case where all coordinates are finite.
+/// Path data that cannot be tokenized is dropped, since it cannot be repaired safely.
+fn sanitize_non_finite_paths(
+    paths: &[SvgPath],
+    width: u32,
+    height: u32,
+    handling: NonFiniteHandling,
+) -> Cow<'_, [SvgPath]> {
+    if !paths.iter().any(has_non_finite_coordinates) {
+        return Cow::Borrowed(paths);
+    }
+
+    let extent = (width as f32, height as f32);
+    let mut affected = 0;
+    let sanitized: Vec<SvgPath> = paths
+        .iter()
+        .filter_map(|path| {
+            if !has_non_finite_coordinates(path) {
+                return Some(path.clone());
+            }
+            affected += 1;
+            sanitize_path(path, extent, handling)
+        })
+        .collect();
+
+    log::warn!(
+        "Found non-finite coordinates in {} SVG elements ({:?}), {} dropped entirely",
+        affected,
+        handling,
+        paths.len() - sanitized.len()
+    );
+    Cow::Owned(sanitized)
+}
+
+fn has_non_finite_coordinates(path: &SvgPath) -> bool {
+    let all_finite = |values: &[f32]| values.iter().all(|v| v.is_finite());
+    match &path.element_type {
+        SvgElementType::Path => contains_non_finite_literal(&path.data),
+        SvgElementType::Circle { cx, cy, r } => !all_finite(&[*cx, *cy, *r]),
+        SvgElementType::Ellipse { cx, cy, rx, ry } => !all_finite(&[*cx, *cy, *rx, *ry]),
+        SvgElementType::Line { x1, y1, x2, y2 } => !all_finite(&[*x1, *y1, *x2, *y2]),
+        SvgElementType::Rect {
+            x,
+            y,
+            width,
+            height,
+        } => !all_finite(&[*x, *y, *width, *height]),
+        SvgElementType::Polygon { points } => contains_non_finite_literal(points),
+    }
+}
+
+/// Rust formats non-finite floats as `NaN`, `inf` and `-inf`
+fn contains_non_finite_literal(data: &str) -> bool {
+    let lower = data.to_ascii_lowercase();
+    lower.contains("nan") || lower.contains("inf")
+}
+
+/// Replace a non-finite value with 0 (NaN, -inf) or `extent` (+inf); finite values pass through
+fn clamp_non_finite(value: f32, extent: f32) -> f32 {
+    match value {
+        v if v.is_finite() => v,
+        v if v == f32::INFINITY => extent,
+        _ => 0.0,
+    }
+}
+
+fn sanitize_path(
+    path: &SvgPath,
+    (width, height): (f32, f32),
+    handling: NonFiniteHandling,
+) -> Option<SvgPath> {
+    let mut sanitized = path.clone();
+    let clamp = handling == NonFiniteHandling::Clamp;
+
+    sanitized.element_type = match &path.element_type {
+        SvgElementType::Path => {
+            sanitized.data = sanitize_path_data(&path.data, (width, height), handling)?;
+            SvgElementType::Path
+        }
+        SvgElementType::Polygon { points } => SvgElementType::Polygon {
+            points: sanitize_point_list(points, (width, height), handling)?,
+        },
+        _ if !clamp => return None,
+        SvgElementType::Circle { cx, cy, r } => SvgElementType::Circle {
+            cx: clamp_non_finite(*cx, width),
+            cy: clamp_non_finite(*cy, height),
+            r: clamp_non_finite(*r, 0.0),
+        },
+        SvgElementType::Ellipse { cx, cy, rx, ry } => SvgElementType::Ellipse {
+            cx: clamp_non_finite(*cx, width),
+            cy: clamp_non_finite(*cy, height),
+            rx: clamp_non_finite(*rx, 0.0),
+            ry: clamp_non_finite(*ry, 0.0),
+        },
+        SvgElementType::Line { x1, y1, x2, y2 } => SvgElementType::Line {
+            x1: clamp_non_finite(*x1, width),
+            y1: clamp_non_finite(*y1, height),
+            x2: clamp_non_finite(*x2, width),
+            y2: clamp_non_finite(*y2, height),
+        },
+        SvgElementType::Rect {
+            x,
+            y,
+            width: w,
+            height: h,
+        } => SvgElementType::Rect {
+            x: clamp_non_finite(*x, width),
+            y: clamp_non_finite(*y, height),
+            width: clamp_non_finite(*w, 0.0),
+            height: clamp_non_finite(*h, 0.0),
+        },
+    };
+
+    Some(sanitized)
+}
+
+/// Viewport extent for the `index`-th argument of a path command (x or y axis)
+fn argument_extent(command: char, index: usize, (width, height): (f32, f32)) -> f32 {
+    match command.to_ascii_uppercase() {
+        'H' => width,
+        'V' => height,
+        // rx ry rotation large-arc sweep x y
+        'A' => match index % 7 {
+            5 => width,
+            6 => height,
+            _ => 0.0,
+        },
+        _ if index % 2 == 0 => width,
+        _ => height,
+    }
+}
+
+fn sanitize_path_data(
+    data: &str,
+    extent: (f32, f32),
+    handling: NonFiniteHandling,
+) -> Option<String> {
+    let mut output: Vec<String> = Vec::new();
+    let mut has_drawing = false;
+    // Set when a moveto was dropped; the next recoverable segment restarts the subpath
+    let mut needs_move = false;
+    for (command, args) in absolute_path_segments(data, |command, args| {
+        if handling == NonFiniteHandling::Clamp {
+            for (index, value) in args.iter_mut().enumerate() {
+                *value = clamp_non_finite(*value, argument_extent(command, index, extent));
+            }
+        }
+    })? {
+        if !args.iter().all(|v| v.is_finite()) {
+            needs_move |= command == 'M';
+            continue;
+        }
+
+        if needs_move {
+            if let [.., x, y] = args[..] {
+                output.push(format!("M {x} {y}"));
+                needs_move = false;
+            }
+            continue;
+        }
+
+        has_drawing |= command != 'M';
+        let mut segment = command.to_string();
+        for value in &args {
+            segment.push_str(&format!(" {value}"));
+        }
+        output.push(segment);
+    }
+
+    has_drawing.then(|| output.join(" "))
+}
+
+/// Split path data into single segments with absolute coordinates, so segments can be
+/// dropped without shifting the relative commands after them
+///
+/// `H`/`V` become `L` so each segment carries its own end point. `fix` runs on every
+/// segment before its end point becomes the next segment's start. Returns `None` when
+/// a command has the wrong number of arguments.
+fn absolute_path_segments(
+    data: &str,
+    mut fix: impl FnMut(char, &mut Vec<f32>),
+) -> Option<Vec<(char, Vec<f32>)>> {
+    let mut segments = Vec::new();
+    let (mut current, mut start) = ((0.0f32, 0.0f32), (0.0f32, 0.0f32));
+    for (command, args) in parse_path_commands(data)? {
+        let upper = command.to_ascii_uppercase();
+        let relative = command.is_ascii_lowercase();
+        let arity = match upper {
+            'Z' => 0,
+            'H' | 'V' => 1,
+            'M' | 'L' | 'T' => 2,
+            'S' | 'Q' => 4,
+            'C' => 6,
+            'A' => 7,
+            _ => return None,
+        };
+        if arity == 0 {
+            if !args.is_empty() {
+                return None;
+            }
+            segments.push(('Z', Vec::new()));
+            current = start;
+            continue;
+        }
+        if args.is_empty() || args.len() % arity != 0 {
+            return None;
+        }
+
+        for (index, group) in args.chunks(arity).enumerate() {
+            let (dx, dy) = if relative { current } else { (0.0, 0.0) };
+            let (segment_command, mut absolute) = match upper {
+                'H' => ('L', vec![group[0] + dx, current.1]),
+                'V' => ('L', vec![current.0, group[0] + dy]),
+                'A' => {
+                    let mut arc = group.to_vec();
+                    arc[5] += dx;
+                    arc[6] += dy;
+                    ('A', arc)
+                }
+                // Extra pairs after a moveto are implicit linetos
+                'M' if index > 0 => ('L', vec![group[0] + dx, group[1] + dy]),
+                _ => {
+                    let points = group.as_chunks::<2>().0;
+                    (
+                        upper,
+                        points.iter().flat_map(|&[x, y]| [x + dx, y + dy]).collect(),
+                    )
+                }
+            };
+            fix(segment_command, &mut absolute);
+            if let [.., x, y] = absolute[..] {
+                current = (x, y);
+            }
+            if segment_command == 'M' {
+                start = current;
+            }
+            segments.push((segment_command, absolute));
+        }
+    }
+    Some(segments)
+}
+
+/// Sanitize a polygon `points` list of `x,y` pairs
+fn sanitize_point_list(
+    points: &str,
+    (width, height): (f32, f32),
+    handling: NonFiniteHandling,
+) -> Option<String> {
+    let values = points
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|t| !t.is_empty())
+        .map(|t| t.parse::<f32>().ok())
+        .collect::<Option<Vec<f32>>>()?;
+
+    let pairs: Vec<String> = values
+        .as_chunks::<2>()
+        .0
+        .iter()
+        .filter_map(|&[x, y]| match handling {
+            _ if x.is_finite() && y.is_finite() => Some((x, y)),
+            NonFiniteHandling::Drop => None,
+            NonFiniteHandling::Clamp => {
+                Some((clamp_non_finite(x, width), clamp_non_finite(y, height)))
+            }
+        })
+        .map(|(x, y)| format!("{x},{y}"))
+        .collect();
+
+    (pairs.len() >= 3).then(|| pairs.join(" "))
+}
+
+/// Format a single SVG element (path, circle, or ellipse)
+fn format_svg_path(path: &SvgPath, (index, count): (usize, usize), config: &SvgConfig) -> String {
+    let absolute = absolute_stroke_path(path, config);
+    let jittered = jittered_stroke_path(&absolute, index, config);
+    let outlined = outlined_fill_path(&jittered, config);
+    let halo = stroke_halo_path(&outlined, config)
+        .map(|(halo_path, halo_config)| format_svg_path(&halo_path, (index, count), &halo_config));
+    let expanded = expanded_stroke_path(&outlined, config);
+    let path: &SvgPath = &expanded;
+    let mut element = match &path.element_type {
+        SvgElementType::Path => {
+            let mut el = String::from("<path");
+            let data = config
+                .resample_even_spacing
+                .and_then(|spacing| resample_path_data(&path.data, spacing, config.precision));
+            let data = data.as_deref().unwrap_or(&path.data);
+            el.push_str(&format!(r#" d="{data}""#));
+            if let Some(attribute) = path_length_attribute(data, path, config) {
+                el.push_str(&attribute);
+            }
+            if let Some(attributes) = draw_on_attributes(data, path, (index, count), config) {
+                el.push_str(&attributes);
+            }
+            el
+        }
+        SvgElementType::Circle { cx, cy, r } => {
+            let mut el = String::from("<circle");
+            el.push_str(&format!(
+                r#" cx="{:.prec$}" cy="{:.prec$}" r="{:.prec$}""#,
+                cx,
+                cy,
+                r,
+                prec = config.precision as usize
+            ));
+            el
+        }
+        SvgElementType::Ellipse { cx, cy, rx, ry } => {
+            let mut el = String::from("<ellipse");
+            el.push_str(&format!(
+                r#" cx="{:.prec$}" cy="{:.prec$}" rx="{:.prec$}" ry="{:.prec$}""#,
+                cx,
+                cy,
+                rx,
+                ry,
+                prec = config.precision as usize
+            ));
+            el
+        }
+        SvgElementType::Line { x1, y1, x2, y2 } => {
+            let mut el = String::from("<line");
+            el.push_str(&format!(
+                r#" x1="{:.prec$}" y1="{:.prec$}" x2="{:.prec$}" y2="{:.prec$}""#,
+                x1,
+                y1,
+                x2,
+                y2,
+                prec = config.precision as usize
+            ));
+            el
+        }
+        SvgElementType::Rect { x, y, width, height } => {
+            let mut el = String::from("<rect");
+            el.push_str(&format!(
+                r#" x="{:.prec$}" y="{:.prec$}" width="{:.prec$}" height="{:.prec$}""#,
+                x,
+                y,
+                width,
+                height,
+                prec = config.precision as usize
+            ));
+            el
+        }
+        SvgElementType::Polygon { points } => {
+            let mut el = String::from("<polygon");
+            el.push_str(&format!(r#" points="{}""#, points));
+            el
+        }
+    };
+
+    // Index-based IDs are stable for identical input and configuration
+    if config.emit_path_ids {
+        element.push_str(&format!(r#" id="path-{index}""#));
+    }
+    if config.emit_source_color {
+        if let Some(color) = &path.source_color {
+            element.push_str(&format!(r#" data-source-color="{color}""#));
+        }
+    }
+    if config.emit_region_metadata {
+        if let Some(region) = &path.region_metadata {
+            element.push_str(&format!(
+                r#" data-centroid="{:.prec$},{:.prec$}" data-area="{}""#,
+                region.centroid.x,
+                region.centroid.y,
+                region.area,
+                prec = config.precision as usize
+            ));
+        }
+    }
+
+    // Add fill attribute
+    if path.fill != "none" {
+        element.push_str(&format!(
+            r#" fill="{}""#,
+            format_paint(&path.fill, config.color_space)
+        ));
+    } else {
+        element.push_str(r#" fill="none""#);
+    }
+
+    // Add stroke attributes
+    if path.stroke != "none" {
+        element.push_str(&format!(
+            r#" stroke="{}""#,
+            format_paint(&path.stroke, config.color_space)
+        ));
+    }
+
+    if path.stroke_width > 0.0 {
+        let stroke_width = path.stroke_width;
+        // An absolute width is written exactly as configured
+        let width_str = if config.absolute_stroke_width == Some(stroke_width) {
+            stroke_width.to_string()
+        } else if config.precision == 0 {
+            format!("{stroke_width:.0}")
+        } else {
+            format!("{:.prec$}", stroke_width, prec = config.precision as usize)
+        };
+        element.push_str(&format!(r#" stroke-width="{width_str}""#));
+        if config.shared_region_edges && path.stroke != "none" {
+            element.push_str(r#" stroke-linecap="round" stroke-linejoin="round""#);
+        }
+    }
+
+    if let Some(opacity) = path.stroke_opacity {
+        // Expanded strokes keep their opacity on the fill that replaced them
+        let attribute = match expanded {
+            Cow::Owned(_) => "fill-opacity",
+            Cow::Borrowed(_) => "stroke-opacity",
+        };
+        element.push_str(&format!(r#" {attribute}="{opacity:.2}""#));
+    }
+
+    element.push_str(" />");
+    let element = with_srgb_fallback(element, path, config.color_space);
+    match halo {
+        Some(halo) => format!("{halo}{element}"),
+        None => element,
+    }
+}
+
+/// Underlay element drawn behind a stroked path when `stroke_halo` is configured
+///
+/// The halo reuses the path geometry with the halo color and a stroke widened by
+/// the halo width on each side. It carries no `id` or source color so each
+/// traced path is described once.
+fn stroke_halo_path(path: &SvgPath, config: &SvgConfig) -> Option<(SvgPath, SvgConfig)> {
+    let (color, width) = config.stroke_halo.as_ref()?;
+    if path.stroke == "none" || path.stroke_width <= 0.0 || *width <= 0.0 {
+        return None;
+    }
+
+    let halo_path = SvgPath {
+        fill: "none".to_string(),
+        stroke: color.clone(),
+        stroke_width: path.stroke_width + 2.0 * width,
+        stroke_opacity: None,
+        source_color: None,
+        region_metadata: None,
+        ..path.clone()
+    };
+    let halo_config = SvgConfig {
+        emit_path_ids: false,
+        stroke_halo: None,
+        absolute_stroke_width: None,
+        stroke_width_jitter: 0.0,
+        ..config.clone()
+    };
+    Some((halo_path, halo_config))
+}
+
+/// Stroked path with the `absolute_stroke_width` override applied, if configured
+fn absolute_stroke_path<'a>(path: &'a SvgPath, config: &SvgConfig) -> Cow<'a, SvgPath> {
+    match config.absolute_stroke_width {
+        Some(width) if path.stroke != "none" && path.stroke_width > 0.0 => Cow::Owned(SvgPath {
+            stroke_width: width,
+            ..path.clone()
+        }),
+        _ => Cow::Borrowed(path),
+    }
+}
+
+/// Stroked path with its width scaled by a random factor within
+/// `1 ± stroke_width_jitter`, if configured
+///
+/// The factor depends only on the seed and the element's output index, so the
+/// same document always gets the same widths.
+fn jittered_stroke_path<'a>(
+    path: &'a SvgPath,
+    index: usize,
+    config: &SvgConfig,
+) -> Cow<'a, SvgPath> {
+    let jitter = config.stroke_width_jitter;
+    if jitter <= 0.0 || path.stroke == "none" || path.stroke_width <= 0.0 {
+        return Cow::Borrowed(path);
+    }
+
+    let mut rng =
+        ChaCha8Rng::seed_from_u64(config.stroke_width_jitter_seed.wrapping_add(index as u64));
+    let factor = 1.0 + jitter * rng.gen_range(-1.0f32..=1.0);
+    Cow::Owned(SvgPath {
+        stroke_width: path.stroke_width * factor,
+        ..path.clone()
+    })
+}
+
+/// Filled shape with the `fill_and_stroke` outline applied, if configured
+///
+/// Only shapes with a fill are outlined; stroke-only paths are left untouched.
+fn outlined_fill_path<'a>(path: &'a SvgPath, config: &SvgConfig) -> Cow<'a, SvgPath> {
+    match &config.fill_and_stroke {
+        Some((color, width)) if path.fill != "none" && *width > 0.0 => Cow::Owned(SvgPath {
+            stroke: color.clone(),
+            stroke_width: *width,
+            stroke_opacity: None,
+            ..path.clone()
+        }),
+        _ => Cow::Borrowed(path),
+    }
+}
+
+/// Stroke-only path replaced by a fill of its stroke outline, if `stroke_to_outline`
+/// is configured
+///
+/// Paths and lines are expanded; other elements, and path data that cannot be
+/// flattened, are left untouched.
+fn expanded_stroke_path<'a>(path: &'a SvgPath, config: &SvgConfig) -> Cow<'a, SvgPath> {
+    if !config.stroke_to_outline
+        || path.fill != "none"
+        || path.stroke == "none"
+        || path.stroke_width <= 0.0
+    {
+        return Cow::Borrowed(path);
+    }
+    let subpaths = match path.element_type {
+        SvgElementType::Path => flatten_path_data(&path.data),
+        SvgElementType::Line { x1, y1, x2, y2 } => Some(vec![(vec![(x1, y1), (x2, y2)], false)]),
+        _ => None,
+    };
+    let Some(subpaths) = subpaths else {
+        return Cow::Borrowed(path);
+    };
+
+    let mut fill = SvgPath::new_fill(
+        stroke_outline_data(&subpaths, path.stroke_width / 2.0, config.precision),
+        &path.stroke,
+    );
+    fill.stroke_opacity = path.stroke_opacity;
+    fill.source_color = path.source_color.clone();
+    fill.region_metadata = path.region_metadata;
+    Cow::Owned(fill)
+}
+
+/// SVG's default `stroke-miterlimit`; sharper joins are beveled
+const STROKE_MITER_LIMIT: f32 = 4.0;
+
+/// Outline of the area a stroke of `half_width` covers, as `M`/`L`/`Z` path data
+///
+/// Follows SVG's default stroke geometry: butt caps and miter joins, beveled past
+/// the miter limit. Each open subpath becomes one ring around both sides; each
+/// closed one becomes an outer and an inner ring of opposite direction. Inner
+/// joins pass through the vertex so the outline covers the stroke exactly under
+/// the nonzero fill rule. Zero-length subpaths, which a butt-capped stroke doesn't
+/// draw, are dropped.
+fn stroke_outline_data(subpaths: &[PolylineSubpath], half_width: f32, precision: u8) -> String {
+    let mut rings: Vec<Vec<(f32, f32)>> = Vec::new();
+    for (points, closed) in subpaths {
+        let points = dedupe_polyline_points(points);
+        let closed = *closed && points.len() >= 3;
+        if points.len() < 2 {
+            continue;
+        }
+        let reversed: Vec<(f32, f32)> = points.iter().rev().copied().collect();
+        if closed {
+            rings.push(offset_side(&points, true, half_width));
+            rings.push(offset_side(&reversed, true, half_width));
+        } else {
+            let mut ring = offset_side(&points, false, half_width);
+            ring.extend(offset_side(&reversed, false, half_width));
+            rings.push(ring);
+        }
+    }
+
+    let prec = precision as usize;
+    rings
+        .iter()
+        .map(|ring| {
+            let mut data = String::new();
+            for (i, (x, y)) in ring.iter().enumerate() {
+                let command = if i == 0 { "M" } else { " L" };
+                data.push_str(&format!("{command} {x:.prec$},{y:.prec$}"));
+            }
+            data.push_str(" Z");
+            data
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Points with consecutive duplicates, and a closing point repeating the first, removed
+fn dedupe_polyline_points(points: &[(f32, f32)]) -> Vec<(f32, f32)> {
+    const EPSILON: f32 = 1e-6;
+    let same = |a: (f32, f32), b: (f32, f32)| (a.0 - b.0).hypot(a.1 - b.1) < EPSILON;
+    let mut deduped: Vec<(f32, f32)> = Vec::with_capacity(points.len());
+    for &point in points {
+        if deduped.last().is_none_or(|&last| !same(last, point)) {
+            deduped.push(point);
+        }
+    }
+    if deduped.len() > 2 && same(deduped[0], deduped[deduped.len() - 1]) {
+        deduped.pop();
+    }
+    deduped
+}
+
+/// Offset of a polyline by `half_width` to the side of each segment's normal
+/// `(-dy, dx)`, with joins at every interior vertex (and every vertex if `closed`)
+fn offset_side(points: &[(f32, f32)], closed: bool, half_width: f32) -> Vec<(f32, f32)> {
+    let direction = |a: (f32, f32), b: (f32, f32)| {
+        let length = (b.0 - a.0).hypot(b.1 - a.1);
+        ((b.0 - a.0) / length, (b.1 - a.1) / length)
+    };
+    let normal = |(dx, dy): (f32, f32)| (-dy, dx);
+    let offset = |(x, y): (f32, f32), (nx, ny): (f32, f32), distance: f32| {
+        (x + nx * distance, y + ny * distance)
+    };
+
+    let n = points.len();
+    let segment = |i: usize| direction(points[i % n], points[(i + 1) % n]);
+    let mut side = Vec::with_capacity(n * 2);
+    if !closed {
+        side.push(offset(points[0], normal(segment(0)), half_width));
+    }
+    let joins = if closed { 0..n } else { 1..n - 1 };
+    for i in joins {
+        let vertex = points[i];
+        let (before, after) = (segment(i + n - 1), segment(i));
+        let (n1, n2) = (normal(before), normal(after));
+        let turn = before.0 * after.1 - before.1 * after.0;
+        let alignment = before.0 * after.0 + before.1 * after.1;
+        if turn.abs() < 1e-6 && alignment > 0.0 {
+            // Collinear: the offset passes straight through
+            side.push(offset(vertex, n1, half_width));
+        } else if turn > 0.0 {
+            // Turning toward this side: route through the vertex, which the
+            // opposite side's join covers
+            side.push(offset(vertex, n1, half_width));
+            side.push(vertex);
+            side.push(offset(vertex, n2, half_width));
+        } else {
+            let bisector = (n1.0 + n2.0, n1.1 + n2.1);
+            let cos_half_turn = bisector.0.hypot(bisector.1) / 2.0;
+            if cos_half_turn * STROKE_MITER_LIMIT >= 1.0 {
+                let length = 2.0 * cos_half_turn;
+                let miter = (bisector.0 / length, bisector.1 / length);
+                side.push(offset(vertex, miter, half_width / cos_half_turn));
+            } else {
+                side.push(offset(vertex, n1, half_width));
+                side.push(offset(vertex, n2, half_width));
+            }
+        }
+    }
+    if !closed {
+        side.push(offset(points[n - 1], normal(segment(n - 2)), half_width));
+    }
+    side
+}
+
+/// Convert an sRGB `#RRGGBB` color to Display-P3 components (0.0-1.0)
+///
+/// Both spaces share the sRGB transfer curve, so the conversion linearizes,
+/// applies the linear sRGB to linear P3 matrix and re-encodes.
+pub fn srgb_hex_to_display_p3(hex: &str) -> Option<[f32; 3]> {
+    let digits = hex.strip_prefix('#')?;
+    if digits.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(digits.get(i..i + 2)?, 16).ok();
+    let rgb = [channel(0)?, channel(2)?, channel(4)?];
+
+    let to_linear = |c: u8| {
+        let c = c as f32 / 255.0;
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    let to_encoded = |c: f32| {
+        let c = c.clamp(0.0, 1.0);
+        if c <= 0.003_130_8 {
+            c * 12.92
+        } else {
+            1.055 * c.powf(1.0 / 2.4) - 0.055
+        }
+    };
+
+    let [r, g, b] = rgb.map(to_linear);
+    Some([
+        to_encoded(0.822_462_1 * r + 0.177_538 * g),
+        to_encoded(0.033_194_2 * r + 0.966_805_8 * g),
+        to_encoded(0.017_082_7 * r + 0.072_397_4 * g + 0.910_519_9 * b),
+    ])
+}
+
+/// Format a paint value for the configured color space
+///
+/// Non-hex values (`none`, `url(#...)`, named colors) pass through unchanged.
+fn format_paint(color: &str, color_space: ColorSpace) -> Cow<'_, str> {
+    match color_space {
+        ColorSpace::Srgb => Cow::Borrowed(color),
+        ColorSpace::DisplayP3 => match srgb_hex_to_display_p3(color) {
+            Some([r, g, b]) => Cow::Owned(format!("color(display-p3 {r:.4} {g:.4} {b:.4})")),
+            None => Cow::Borrowed(color),
+        },
+    }
+}
+
+/// Wrap a P3-colored element in a group carrying its sRGB hex colors
+///
+/// Renderers that do not understand `color()` ignore the invalid presentation
+/// attribute and inherit the group's hex value instead.
+fn with_srgb_fallback(element: String, path: &SvgPath, color_space: ColorSpace) -> String {
+    if color_space != ColorSpace::DisplayP3 {
+        return element;
+    }
+
+    let mut fallback = String::new();
+    if srgb_hex_to_display_p3(&path.fill).is_some() {
+        fallback.push_str(&format!(r#" fill="{}""#, path.fill));
+    }
+    if srgb_hex_to_display_p3(&path.stroke).is_some() {
+        fallback.push_str(&format!(r#" stroke="{}""#, path.stroke));
+    }
+
+    if fallback.is_empty() {
+        element
+    } else {
+        format!("<g{fallback}>{element}</g>")
+    }
+}
+
+/// Resample polyline path data to points spaced evenly along its arc length
+///
+/// Each subpath keeps its start and end points; interior points are placed every
+/// `spacing` pixels so `stroke-dashoffset` animations progress at a constant rate.
+/// Curves and relative commands are flattened first (see [`flatten_path_data`]).
+/// Returns `None` for empty or unsupported path data or a non-positive spacing, in
+/// which case the original data should be used.
+///
+/// # Arguments
+/// * `data` - SVG path data
+/// * `spacing` - Distance between consecutive output points in pixels
+/// * `precision` - Decimal places for output coordinates
+pub fn resample_path_data(data: &str, spacing: f32, precision: u8) -> Option<String> {
+    if !spacing.is_finite() || spacing <= 0.0 {
+        return None;
+    }
+
+    let Some(subpaths) = flatten_path_data(data).filter(|subpaths| !subpaths.is_empty()) else {
+        log::debug!("Skipping even-spacing resample of unsupported path data");
+        return None;
+    };
+    let prec = precision as usize;
+    let mut parts: Vec<String> = Vec::new();
+    for (mut points, closed) in subpaths {
+        if closed {
+            if let Some(&first) = points.first() {
+                points.push(first);
+            }
+        }
+        let mut resampled = resample_points(&points, spacing);
+        if closed && resampled.len() > 1 {
+            resampled.pop();
+        }
+
+        for (i, (x, y)) in resampled.iter().enumerate() {
+            let command = if i == 0 { 'M' } else { 'L' };
+            parts.push(format!("{command} {x:.prec$} {y:.prec$}"));
+        }
+        if closed {
+            parts.push("Z".to_string());
+        }
+    }
+
+    Some(parts.join(" "))
+}
+
+/// Polyline points of a subpath and whether it is closed
+type PolylineSubpath = (Vec<(f32, f32)>, bool);
+
+/// Parse absolute `M`/`L`/`Z` path data into subpaths of points, tracking whether
+/// each one is closed
+///
+/// Returns `None` for empty data or any other command. Callers that rewrite path data
+/// use this to leave curves and relative commands untouched rather than flattening them.
+fn parse_polyline_subpaths(data: &str) -> Option<Vec<PolylineSubpath>> {
+    let mut subpaths: Vec<PolylineSubpath> = Vec::new();
+    for (command, args) in parse_path_commands(data)? {
+        match command {
+            'M' | 'L' => {
+                let (pairs, rest) = args.as_chunks::<2>();
+                if !rest.is_empty() {
+                    return None;
+                }
+                for (i, &[x, y]) in pairs.iter().enumerate() {
+                    if command == 'M' && i == 0 {
+                        subpaths.push((Vec::new(), false));
+                    }
+                    subpaths.last_mut()?.0.push((x, y));
+                }
+            }
+            'Z' | 'z' if args.is_empty() => subpaths.last_mut()?.1 = true,
+            _ => {
+                log::debug!("Leaving path data with '{command}' commands unchanged");
+                return None;
+            }
+        }
+    }
+
+    (!subpaths.is_empty()).then_some(subpaths)
+}
+
+/// `pathLength` declared on strokes so dash animations can use a fixed total
+pub const NORMALIZED_PATH_LENGTH: f32 = 100.0;
+
+/// `pathLength` attribute for a stroked path when `emit_path_length` or
+/// `draw_on_animation` is enabled
+///
+/// Zero-length paths get no attribute since a dash pattern can't be mapped onto them.
+fn path_length_attribute(
+    data: &str,
+    path: &SvgPath,
+    config: &SvgConfig,
+) -> Option<String> {
+    if !(config.emit_path_length || config.draw_on_animation.is_some()) || path.stroke == "none" {
+        return None;
+    }
+    let length = path_data_length(data)?;
+    (length > 0.0).then(|| format!(r#" pathLength="{NORMALIZED_PATH_LENGTH}""#))
+}
+
+/// Keyframes shared by every path animated with `draw_on_animation`
+const DRAW_ON_STYLE: &str =
+    "  <style>@keyframes vec2art-draw-on { to { stroke-dashoffset: 0; } }</style>\n";
+
+/// Dash and animation attributes that draw a stroked path on when
+/// `draw_on_animation` is set
+///
+/// The duration is split evenly across the `count` elements, so element `index`
+/// draws during its own time slot and the whole drawing finishes on time. The
+/// dash runs over the normalized `pathLength`, which is only declared on
+/// stroked paths with nonzero length; others get no attributes and stay static.
+fn draw_on_attributes(
+    data: &str,
+    path: &SvgPath,
+    (index, count): (usize, usize),
+    config: &SvgConfig,
+) -> Option<String> {
+    let duration = config.draw_on_animation.filter(|d| d.is_finite() && *d > 0.0)?;
+    path_length_attribute(data, path, config)?;
+
+    let slot = duration / count.max(1) as f32;
+    Some(format!(
+        r#" stroke-dasharray="{NORMALIZED_PATH_LENGTH}" stroke-dashoffset="{NORMALIZED_PATH_LENGTH}" style="animation: vec2art-draw-on {slot:.3}s linear {:.3}s forwards""#,
+        slot * index as f32
+    ))
+}
+
+/// Total drawn length of path data with `M`/`L`/`H`/`V`/`C`/`Q`/`Z` commands
+/// (absolute or relative); curves are measured by flattening
+///
+/// Returns `None` for unsupported commands or malformed data.
+pub fn path_data_length(data: &str) -> Option<f32> {
+    let distance = |a: &(f32, f32), b: &(f32, f32)| (b.0 - a.0).hypot(b.1 - a.1);
+
+    let length = flatten_path_data(data)?
+        .iter()
+        .map(|(points, closed)| {
+            let open: f32 = points.windows(2).map(|w| distance(&w[0], &w[1])).sum();
+            let closing = match (closed, points.first(), points.last()) {
+                (true, Some(first), Some(last)) => distance(last, first),
+                _ => 0.0,
+            };
+            open + closing
+        })
+        .sum();
+    Some(length)
+}
+
+/// Points along an element's outline, or the center of circles, ellipses and rects
+///
+/// Returns no points for unsupported path data and polygons.
+pub(crate) fn element_sample_points(path: &SvgPath) -> Vec<(f32, f32)> {
+    match path.element_type {
+        SvgElementType::Path => flatten_path_data(&path.data)
+            .map(|subpaths| subpaths.into_iter().flat_map(|(points, _)| points).collect())
+            .unwrap_or_default(),
+        SvgElementType::Circle { cx, cy, .. } | SvgElementType::Ellipse { cx, cy, .. } => {
+            vec![(cx, cy)]
+        }
+        SvgElementType::Line { x1, y1, x2, y2 } => vec![(x1, y1), (x2, y2)],
+        SvgElementType::Rect {
+            x,
+            y,
+            width,
+            height,
+        } => vec![(x + width / 2.0, y + height / 2.0)],
+        SvgElementType::Polygon { .. } => Vec::new(),
+    }
+}
+
+/// Flatten path data with `M`/`L`/`H`/`V`/`C`/`Q`/`Z` commands (absolute or
+/// relative) into polyline subpaths, sampling curves at fixed steps
+///
+/// Returns `None` for unsupported commands or malformed data.
+pub(crate) fn flatten_path_data(data: &str) -> Option<Vec<PolylineSubpath>> {
+    const CURVE_STEPS: usize = 16;
+
+    let mut subpaths: Vec<PolylineSubpath> = Vec::new();
+    let (mut current, mut start) = ((0.0f32, 0.0f32), (0.0f32, 0.0f32));
+    // Drawing after `Z` without a new `M` continues from the subpath start
+    fn line_to(subpaths: &mut Vec<PolylineSubpath>, from: (f32, f32), to: (f32, f32)) {
+        match subpaths.last_mut() {
+            Some((points, false)) => points.push(to),
+            _ => subpaths.push((vec![from, to], false)),
+        }
+    }
+    for (command, args) in parse_path_commands(data)? {
+        let relative = command.is_ascii_lowercase();
+        let point = |x: f32, y: f32, from: (f32, f32)| {
+            if relative {
+                (from.0 + x, from.1 + y)
+            } else {
+                (x, y)
+            }
+        };
+        match command.to_ascii_uppercase() {
+            'Z' => {
+                if let Some(last) = subpaths.last_mut() {
+                    last.1 = true;
+                }
+                current = start;
+            }
+            'H' | 'V' => {
+                for &value in &args {
+                    let next = match (command.to_ascii_uppercase(), relative) {
+                        ('H', true) => (current.0 + value, current.1),
+                        ('H', false) => (value, current.1),
+                        (_, true) => (current.0, current.1 + value),
+                        (_, false) => (current.0, value),
+                    };
+                    line_to(&mut subpaths, current, next);
+                    current = next;
+                }
+            }
+            'M' | 'L' => {
+                let (pairs, rest) = args.as_chunks::<2>();
+                if !rest.is_empty() {
+                    return None;
+                }
+                for (i, &[x, y]) in pairs.iter().enumerate() {
+                    let next = point(x, y, current);
+                    if command.eq_ignore_ascii_case(&'m') && i == 0 {
+                        start = next;
+                        subpaths.push((vec![next], false));
+                    } else {
+                        line_to(&mut subpaths, current, next);
+                    }
+                    current = next;
+                }
+            }
+            'C' | 'Q' => {
+                let arity = if command.eq_ignore_ascii_case(&'c') { 6 } else { 4 };
+                if args.is_empty() || args.len() % arity != 0 {
+                    return None;
+                }
+                for segment in args.chunks(arity) {
+                    let controls: Vec<(f32, f32)> = std::iter::once(current)
+                        .chain(segment.chunks(2).map(|p| point(p[0], p[1], current)))
+                        .collect();
+                    for step in 1..=CURVE_STEPS {
+                        let next = bezier_point(&controls, step as f32 / CURVE_STEPS as f32);
+                        line_to(&mut subpaths, current, next);
+                        current = next;
+                    }
+                    current = controls[controls.len() - 1];
+                }
+            }
+            _ => return None,
+        }
+    }
+
+    Some(subpaths)
+}
+
+/// Point at `t` on a Bézier curve with the given control points (de Casteljau)
+fn bezier_point(controls: &[(f32, f32)], t: f32) -> (f32, f32) {
+    let mut points = controls.to_vec();
+    while points.len() > 1 {
+        points = points
+            .windows(2)
+            .map(|w| (w[0].0 + (w[1].0 - w[0].0) * t, w[0].1 + (w[1].1 - w[0].1) * t))
+            .collect();
+    }
+    points[0]
+}
+
+/// Split path data into commands and their numeric arguments
+///
+/// The one path tokenizer behind flattening, remapping, sanitizing and polyline
+/// parsing. Handles commands written directly against numbers (`M10,20L30 40`),
+/// numbers separated only by a sign (`L50-60`) and the `NaN`/`inf` literals of
+/// non-finite coordinates. Returns `None` for numbers
+/// before the first command or unparseable numbers.
+fn parse_path_commands(data: &str) -> Option<Vec<(char, Vec<f32>)>> {
+    let mut commands: Vec<(char, Vec<f32>)> = Vec::new();
+    for token in data.replace(',', " ").split_whitespace() {
+        let mut rest = token;
+        while !rest.is_empty() {
+            let first = rest.chars().next()?;
+            if let Some(len) = non_finite_literal_len(rest) {
+                commands.last_mut()?.1.push(rest[..len].parse().ok()?);
+                rest = &rest[len..];
+            } else if first.is_ascii_alphabetic() {
+                commands.push((first, Vec::new()));
+                rest = &rest[1..];
+            } else {
+                // A sign ends the number unless it belongs to an exponent (`1e-5`)
+                let end = rest
+                    .char_indices()
+                    .skip(1)
+                    .find(|&(i, c)| {
+                        (c.is_ascii_alphabetic() && c != 'e')
+                            || (matches!(c, '-' | '+') && !rest[..i].ends_with('e'))
+                    })
+                    .map_or(rest.len(), |(i, _)| i);
+                commands.last_mut()?.1.push(rest[..end].parse().ok()?);
+                rest = &rest[end..];
+            }
+        }
+    }
+    Some(commands)
+}
+
+/// Length of a (signed) `NaN`, `inf` or `infinity` literal at the start of `text`
+fn non_finite_literal_len(text: &str) -> Option<usize> {
+    let sign = usize::from(text.starts_with(['+', '-']));
+    let unsigned = &text[sign..];
+    ["infinity", "inf", "nan"]
+        .iter()
+        .find(|literal| {
+            unsigned
+                .get(..literal.len())
+                .is_some_and(|prefix| prefix.eq_ignore_ascii_case(literal))
+        })
+        .map(|literal| sign + literal.len())
+}
+
+/// Walk a polyline and emit a point every `spacing` units of arc length plus the end point
+fn resample_points(points: &[(f32, f32)], spacing: f32) -> Vec<(f32, f32)> {
+    let Some(&start) = points.first() else {
+        return Vec::new();
+    };
+
+    let mut result = vec![start];
+    // Distance travelled since the last emitted point
+    let mut carried = 0.0f32;
+    for window in points.windows(2) {
+        let (a, b) = (window[0], window[1]);
+        let length = ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt();
+        if length <= f32::EPSILON {
+            continue;
+        }
+
+        let mut along = spacing - carried;
+        while along <= length {
+            let t = along / length;
+            result.push((a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t));
+            along += spacing;
+        }
+        carried = length - (along - spacing);
+    }
+
+    // Always finish on the original end point so the path's extent is unchanged
+    let end = points[points.len() - 1];
+    if let Some(&last) = result.last() {
+        if ((end.0 - last.0).powi(2) + (end.1 - last.1).powi(2)).sqrt() > spacing * 1e-3 {
+            result.push(end);
+        }
+    }
+
+    result
+}
+
+/// Indices of filled paths whose closed outlines cross themselves
+///
+/// A lobe folded back over the rest of the shape winds the opposite way, so it
+/// renders as a hole or bowtie artifact under the default nonzero fill rule.
+pub fn self_intersecting_path_indices(paths: &[SvgPath]) -> Vec<usize> {
+    paths
+        .iter()
+        .enumerate()
+        .filter(|(_, path)| matches!(path.element_type, SvgElementType::Path) && path.fill != "none")
+        .filter(|(_, path)| {
+            parse_polyline_subpaths(&path.data).is_some_and(|subpaths| {
+                subpaths
+                    .iter()
+                    .any(|(ring, closed)| *closed && find_crossing(ring).is_some())
+            })
+        })
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Repair self-intersecting filled paths when `repair_self_intersections` is set
+fn repair_self_intersecting_paths<'a>(
+    paths: Cow<'a, [SvgPath]>,
+    config: &SvgConfig,
+) -> Cow<'a, [SvgPath]> {
+    if !config.repair_self_intersections {
+        return paths;
+    }
+    let broken = self_intersecting_path_indices(&paths);
+    if broken.is_empty() {
+        return paths;
+    }
+
+    let mut repaired = paths.into_owned();
+    for &index in &broken {
+        if let Some(data) = repair_self_intersecting_data(&repaired[index].data, config.precision) {
+            repaired[index].data = data;
+        }
+    }
+    log::debug!("Repaired {} self-intersecting filled paths", broken.len());
+    Cow::Owned(repaired)
+}
+
+/// Close paths whose ends coincide at output precision when `auto_close_coincident` is set
+fn close_coincident_paths<'a>(
+    paths: Cow<'a, [SvgPath]>,
+    config: &SvgConfig,
+) -> Cow<'a, [SvgPath]> {
+    if !config.auto_close_coincident {
+        return paths;
+    }
+    let closable: Vec<(usize, String)> = paths
+        .iter()
+        .enumerate()
+        .filter(|(_, path)| matches!(path.element_type, SvgElementType::Path))
+        .filter_map(|(index, path)| {
+            close_coincident_data(&path.data, config.precision).map(|data| (index, data))
+        })
+        .collect();
+    if closable.is_empty() {
+        return paths;
+    }
+
+    let mut closed = paths.into_owned();
+    log::debug!("Closed {} paths with coincident end points", closable.len());
+    for (index, data) in closable {
+        closed[index].data = data;
+    }
+    Cow::Owned(closed)
+}
+
+/// Close open polyline subpaths whose first and last points round to the same
+/// coordinate at `precision` decimal places
+///
+/// The duplicate end point is dropped and `Z` appended, so the shape closes exactly
+/// on its start point. Returns `None` for non-polyline data or when nothing changed.
+pub fn close_coincident_data(data: &str, precision: u8) -> Option<String> {
+    let subpaths = parse_polyline_subpaths(data)?;
+    let scale = 10f32.powi(precision as i32);
+    let rounded = |(x, y): (f32, f32)| ((x * scale).round(), (y * scale).round());
+
+    let mut changed = false;
+    let prec = precision as usize;
+    let mut parts: Vec<String> = Vec::new();
+    for (mut points, mut closed) in subpaths {
+        if !closed && points.len() > 3 && rounded(points[0]) == rounded(points[points.len() - 1]) {
+            points.pop();
+            closed = true;
+            changed = true;
+        }
+
+        for (i, (x, y)) in points.iter().enumerate() {
+            let command = if i == 0 { 'M' } else { 'L' };
+            parts.push(format!("{command} {x:.prec$} {y:.prec$}"));
+        }
+        if closed {
+            parts.push("Z".to_string());
+        }
+    }
+
+    changed.then(|| parts.join(" "))
+}
+
+/// Split self-intersecting closed subpaths into simple loops wound the same way
+/// as the largest piece, so nonzero filling covers every lobe
+///
+/// Subpaths without crossings keep their points and orientation (holes stay holes).
+pub fn repair_self_intersecting_data(data: &str, precision: u8) -> Option<String> {
+    let subpaths = parse_polyline_subpaths(data)?;
+    let prec = precision as usize;
+    let mut parts: Vec<String> = Vec::new();
+    for (ring, closed) in subpaths {
+        let loops = if closed && find_crossing(&ring).is_some() {
+            orient_like_largest(split_into_simple_loops(ring))
+        } else {
+            vec![ring]
+        };
+
+        for points in loops {
+            for (i, (x, y)) in points.iter().enumerate() {
+                let command = if i == 0 { 'M' } else { 'L' };
+                parts.push(format!("{command} {x:.prec$} {y:.prec$}"));
+            }
+            if closed {
+                parts.push("Z".to_string());
+            }
+        }
+    }
+
+    Some(parts.join(" "))
+}
 
-    if config.include_metadata {
-        svg.push_str("  <!-- Generated by vec2art vectorize-core -->\n");
+/// First pair of non-adjacent edges of a closed ring that properly cross, with
+/// the crossing point
+fn find_crossing(ring: &[(f32, f32)]) -> Option<(usize, usize, (f32, f32))> {
+    let n = ring.len();
+    if n < 4 {
+        return None;
     }
+    let edge = |k: usize| (ring[k], ring[(k + 1) % n]);
 
-    // Add each path (no grouping in simplified config)
-    for path in paths {
-        svg.push_str("  ");
-        svg.push_str(&format_svg_path(path, config));
-        svg.push('\n');
+    for i in 0..n {
+        for j in (i + 2)..n {
+            if i == 0 && j == n - 1 {
+                continue; // Adjacent through the closing edge
+            }
+            if let Some(point) = segment_crossing(edge(i), edge(j)) {
+                return Some((i, j, point));
+            }
+        }
+    }
+    None
+}
+
+/// Crossing point of two segments, excluding touches at their end points
+fn segment_crossing(
+    (a, b): ((f32, f32), (f32, f32)),
+    (c, d): ((f32, f32), (f32, f32)),
+) -> Option<(f32, f32)> {
+    const EPSILON: f32 = 1e-5;
+    let r = (b.0 - a.0, b.1 - a.1);
+    let s = (d.0 - c.0, d.1 - c.1);
+    let denom = r.0 * s.1 - r.1 * s.0;
+    if denom.abs() <= f32::EPSILON {
+        return None; // Parallel or collinear: no single crossing point
     }
 
-    svg.push_str("</svg>");
-    svg
+    let ac = (c.0 - a.0, c.1 - a.1);
+    let t = (ac.0 * s.1 - ac.1 * s.0) / denom;
+    let u = (ac.0 * r.1 - ac.1 * r.0) / denom;
+    let inside = |v: f32| v > EPSILON && v < 1.0 - EPSILON;
+    (inside(t) && inside(u)).then_some((a.0 + r.0 * t, a.1 + r.1 * t))
 }
 
-/// Format a single SVG element (path, circle, or ellipse)
-fn format_svg_path(path: &SvgPath, config: &SvgConfig) -> String {
-    let mut element = match &path.element_type {
-        SvgElementType::Path => {
-            let mut el = String::from("<path");
-            el.push_str(&format!(r#" d="{}""#, path.data));
-            el
-        }
-        SvgElementType::Circle { cx, cy, r } => {
-            let mut el = String::from("<circle");
-            el.push_str(&format!(
-                r#" cx="{:.prec$}" cy="{:.prec$}" r="{:.prec$}""#,
-                cx,
-                cy,
-                r,
-                prec = config.precision as usize
-            ));
-            el
-        }
-        SvgElementType::Ellipse { cx, cy, rx, ry } => {
-            let mut el = String::from("<ellipse");
-            el.push_str(&format!(
-                r#" cx="{:.prec$}" cy="{:.prec$}" rx="{:.prec$}" ry="{:.prec$}""#,
-                cx,
-                cy,
-                rx,
-                ry,
-                prec = config.precision as usize
-            ));
-            el
-        }
-        SvgElementType::Line { x1, y1, x2, y2 } => {
-            let mut el = String::from("<line");
-            el.push_str(&format!(
-                r#" x1="{:.prec$}" y1="{:.prec$}" x2="{:.prec$}" y2="{:.prec$}""#,
-                x1,
-                y1,
-                x2,
-                y2,
-                prec = config.precision as usize
-            ));
-            el
-        }
-        SvgElementType::Rect { x, y, width, height } => {
-            let mut el = String::from("<rect");
-            el.push_str(&format!(
-                r#" x="{:.prec$}" y="{:.prec$}" width="{:.prec$}" height="{:.prec$}""#,
-                x,
-                y,
-                width,
-                height,
-                prec = config.precision as usize
-            ));
-            el
-        }
-        SvgElementType::Polygon { points } => {
-            let mut el = String::from("<polygon");
-            el.push_str(&format!(r#" points="{}""#, points));
-            el
-        }
-    };
+/// Repeatedly split a closed ring at crossings until every loop is simple
+fn split_into_simple_loops(ring: Vec<(f32, f32)>) -> Vec<Vec<(f32, f32)>> {
+    // Each split removes a crossing; the cap guards against numeric ping-pong
+    let mut budget = ring.len() * ring.len();
+    let mut pending = vec![ring];
+    let mut simple = Vec::new();
+
+    while let Some(ring) = pending.pop() {
+        let crossing = if budget > 0 { find_crossing(&ring) } else { None };
+        let Some((i, j, point)) = crossing else {
+            simple.push(ring);
+            continue;
+        };
+        budget -= 1;
 
-    // Add fill attribute
-    if path.fill != "none" {
-        element.push_str(&format!(r#" fill="{}""#, path.fill));
-    } else {
-        element.push_str(r#" fill="none""#);
-    }
+        let mut outer: Vec<(f32, f32)> = ring[..=i].to_vec();
+        outer.push(point);
+        outer.extend_from_slice(&ring[j + 1..]);
 
-    // Add stroke attributes
-    if path.stroke != "none" {
-        element.push_str(&format!(r#" stroke="{}""#, path.stroke));
+        let mut inner = vec![point];
+        inner.extend_from_slice(&ring[i + 1..=j]);
+
+        pending.extend([outer, inner].into_iter().filter(|l| l.len() >= 3));
     }
 
-    if path.stroke_width > 0.0 {
-        let stroke_width = path.stroke_width;
-        let width_str = if config.precision == 0 {
-            format!("{stroke_width:.0}")
-        } else {
-            format!("{:.prec$}", stroke_width, prec = config.precision as usize)
-        };
-        element.push_str(&format!(r#" stroke-width="{width_str}""#));
+    simple
+}
+
+/// Orient every loop like the one with the largest area, dropping degenerate loops
+fn orient_like_largest(loops: Vec<Vec<(f32, f32)>>) -> Vec<Vec<(f32, f32)>> {
+    let mut loops: Vec<(f32, Vec<(f32, f32)>)> = loops
+        .into_iter()
+        .map(|points| (signed_area(&points), points))
+        .filter(|(area, _)| area.abs() > 1e-6)
+        .collect();
+    let Some(positive) = loops
+        .iter()
+        .max_by(|a, b| a.0.abs().total_cmp(&b.0.abs()))
+        .map(|(area, _)| *area > 0.0)
+    else {
+        return Vec::new();
+    };
+
+    for (area, points) in &mut loops {
+        if (*area > 0.0) != positive {
+            points.reverse();
+        }
     }
+    loops.into_iter().map(|(_, points)| points).collect()
+}
 
-    element.push_str(" />");
-    element
+/// Shoelace signed area of a closed ring
+fn signed_area(points: &[(f32, f32)]) -> f32 {
+    let n = points.len();
+    (0..n)
+        .map(|k| {
+            let (a, b) = (points[k], points[(k + 1) % n]);
+            a.0 * b.1 - b.0 * a.1
+        })
+        .sum::<f32>()
+        / 2.0
 }
 
 /// Optimize SVG path data by removing unnecessary precision
@@ -242,6 +2020,91 @@ pub fn minify_svg(svg_content: &str) -> String {
         .join("")
 }
 
+/// Combine traced SVG documents into one sprite sheet of `<symbol>` definitions
+///
+/// Each `(name, document)` pair becomes `<symbol id="name" viewBox=...>` holding
+/// the document's content, usable via `<use href="#name"/>`. Names are reduced to
+/// `[A-Za-z0-9_-]` and made unique; ids inside each document are prefixed with
+/// the symbol id so gradients and path ids from different inputs can't collide.
+///
+/// # Arguments
+/// * `documents` - Symbol names paired with complete SVG documents
+///
+/// # Returns
+/// * `String` - Sprite SVG document with one symbol per input
+pub fn generate_svg_sprite(documents: &[(String, String)]) -> String {
+    use regex::Regex;
+
+    let view_box_regex = Regex::new(r#"viewBox="([^"]*)""#).unwrap();
+    let size_regex = Regex::new(r#"\b(width|height)="([^"]*)""#).unwrap();
+    let id_regex = Regex::new(r##"(\bid="|href="#|url\(#)([^")]+)"##).unwrap();
+
+    let mut sprite = String::from(r#"<svg xmlns="http://www.w3.org/2000/svg" style="display:none">"#);
+    sprite.push('\n');
+
+    let mut used_ids: Vec<String> = Vec::new();
+    for (name, document) in documents {
+        let mut base: String = name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '-' { c } else { '-' })
+            .collect();
+        if base.is_empty() {
+            base = "symbol".to_string();
+        }
+        let mut id = base.clone();
+        let mut suffix = 2;
+        while used_ids.contains(&id) {
+            id = format!("{base}-{suffix}");
+            suffix += 1;
+        }
+        used_ids.push(id.clone());
+
+        let Some(open_start) = document.find("<svg") else {
+            log::warn!("Sprite input '{name}' is not an SVG document, skipping");
+            continue;
+        };
+        let Some(open_len) = document[open_start..].find('>') else {
+            continue;
+        };
+        let open_tag = &document[open_start..open_start + open_len];
+        let body_start = open_start + open_len + 1;
+        let body_end = document.rfind("</svg>").filter(|&end| end >= body_start);
+        let body = &document[body_start..body_end.unwrap_or(document.len())];
+
+        let view_box = view_box_regex
+            .captures(open_tag)
+            .map(|caps| caps[1].to_string())
+            .or_else(|| {
+                let mut size = size_regex.captures_iter(open_tag);
+                let (first, second) = (size.next()?, size.next()?);
+                let (width, height) = if &first[1] == "width" {
+                    (first[2].to_string(), second[2].to_string())
+                } else {
+                    (second[2].to_string(), first[2].to_string())
+                };
+                Some(format!("0 0 {width} {height}"))
+            });
+
+        match view_box {
+            Some(view_box) => sprite.push_str(&format!(r#"  <symbol id="{id}" viewBox="{view_box}">"#)),
+            None => sprite.push_str(&format!(r#"  <symbol id="{id}">"#)),
+        }
+        sprite.push('\n');
+        let body = id_regex.replace_all(body, |caps: &regex::Captures| {
+            format!("{}{id}-{}", &caps[1], &caps[2])
+        });
+        for line in body.lines().filter(|line| !line.trim().is_empty()) {
+            sprite.push_str("  ");
+            sprite.push_str(line);
+            sprite.push('\n');
+        }
+        sprite.push_str("  </symbol>\n");
+    }
+
+    sprite.push_str("</svg>");
+    sprite
+}
+
 /// Advanced SVG optimization for colored output
 ///
 /// # Arguments
@@ -590,6 +2453,9 @@ pub fn create_rectangle(x: f32, y: f32, width: f32, height: f32, fill: Option<St
         fill: fill.unwrap_or("none".to_string()),
         stroke: "none".to_string(),
         stroke_width: 0.0,
+        stroke_opacity: None,
+        source_color: None,
+        region_metadata: None,
         element_type: SvgElementType::Path,
     }
 }
@@ -613,6 +2479,9 @@ pub fn create_circle(cx: f32, cy: f32, radius: f32, fill: Option<String>) -> Svg
         fill: fill.unwrap_or("none".to_string()),
         stroke: "none".to_string(),
         stroke_width: 0.0,
+        stroke_opacity: None,
+        source_color: None,
+        region_metadata: None,
         element_type: SvgElementType::Circle { cx, cy, r: radius },
     }
 }
@@ -628,6 +2497,9 @@ mod tests {
             fill: "red".to_string(),
             stroke: "none".to_string(),
             stroke_width: 0.0,
+            stroke_opacity: None,
+            source_color: None,
+            region_metadata: None,
             element_type: SvgElementType::Path,
         }];
 
@@ -640,6 +2512,694 @@ mod tests {
         assert!(svg.contains("fill=\"red\""));
     }
 
+    #[test]
+    fn test_generate_svg_document_with_path_ids() {
+        let paths: Vec<SvgPath> = (0..3)
+            .map(|i| SvgPath {
+                data: format!("M {i} 0 L 10 10"),
+                fill: "none".to_string(),
+                stroke: "black".to_string(),
+                stroke_width: 1.0,
+                stroke_opacity: None,
+                source_color: None,
+                region_metadata: None,
+                element_type: SvgElementType::Path,
+            })
+            .collect();
+
+        let config = SvgConfig {
+            emit_path_ids: true,
+            ..SvgConfig::default()
+        };
+        let svg = generate_svg_document(&paths, 100, 100, &config);
+
+        let first = svg.find(r#"id="path-0""#).unwrap();
+        let second = svg.find(r#"id="path-1""#).unwrap();
+        let third = svg.find(r#"id="path-2""#).unwrap();
+        assert!(first < second && second < third);
+        assert_eq!(svg, generate_svg_document(&paths, 100, 100, &config));
+
+        let svg_no_ids = generate_svg_document(&paths, 100, 100, &SvgConfig::default());
+        assert!(!svg_no_ids.contains("id="));
+    }
+
+    #[test]
+    fn test_display_p3_colors_with_srgb_fallback() {
+        let paths = vec![SvgPath::new_fill("M 0 0 L 10 0 L 10 10 Z".to_string(), "#FF0000")];
+        let config = SvgConfig {
+            color_space: ColorSpace::DisplayP3,
+            ..SvgConfig::default()
+        };
+        let svg = generate_svg_document(&paths, 10, 10, &config);
+
+        // Pure sRGB red sits inside the P3 gamut, away from its red primary
+        assert!(svg.contains(r#"fill="color(display-p3 0.9175 0.2003 0.1386)""#));
+        assert!(svg.contains(r##"<g fill="#FF0000">"##));
+
+        let srgb = generate_svg_document(&paths, 10, 10, &SvgConfig::default());
+        assert!(srgb.contains(r##"fill="#FF0000""##));
+        assert!(!srgb.contains("display-p3") && !srgb.contains("<g"));
+    }
+
+    #[test]
+    fn test_xml_declaration_is_opt_in() {
+        let paths = vec![SvgPath::new_stroke("M 0 0 L 10 10".to_string(), "#000000", 1.0)];
+        let config = SvgConfig {
+            emit_xml_declaration: true,
+            ..SvgConfig::default()
+        };
+
+        let svg = generate_svg_document(&paths, 10, 10, &config);
+        assert!(svg.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<svg "));
+        assert_eq!(svg.matches("<?xml").count(), 1);
+
+        let plain = generate_svg_document(&paths, 10, 10, &SvgConfig::default());
+        assert!(plain.starts_with("<svg "));
+    }
+
+    #[test]
+    fn test_stroke_halo_underlays_each_stroke() {
+        let paths = vec![
+            SvgPath::new_stroke("M 0 0 L 10 10".to_string(), "#000000", 1.5),
+            SvgPath::new_fill("M 0 0 L 10 0 L 10 10 Z".to_string(), "#FF0000"),
+        ];
+        let config = SvgConfig {
+            emit_path_ids: true,
+            stroke_halo: Some(("#FFFFFF".to_string(), 2.0)),
+            ..SvgConfig::default()
+        };
+        let svg = generate_svg_document(&paths, 10, 10, &config);
+
+        let halo = svg
+            .find(r##"<path d="M 0 0 L 10 10" fill="none" stroke="#FFFFFF" stroke-width="5.50" />"##)
+            .expect("halo underlay missing");
+        let stroke = svg.find(r#"id="path-0""#).unwrap();
+        assert!(halo < stroke);
+        assert_eq!(svg.matches("#FFFFFF").count(), 1);
+        assert_eq!(svg.matches(r#"id="path-0""#).count(), 1);
+    }
+
+    #[test]
+    fn test_group_by_thickness_splits_strokes_into_tiers() {
+        let paths = vec![
+            SvgPath::new_stroke("M 0 0 L 10 10".to_string(), "#000000", 1.0),
+            SvgPath::new_stroke("M 0 5 L 10 5".to_string(), "#000000", 3.0),
+            SvgPath::new_fill("M 0 0 L 10 0 L 10 10 Z".to_string(), "#FF0000"),
+            SvgPath::new_stroke("M 5 0 L 5 10".to_string(), "#000000", 2.0),
+            SvgPath::new_stroke("M 0 10 L 10 0".to_string(), "#000000", 1.2),
+        ];
+        let config = SvgConfig {
+            group_by_thickness: true,
+            ..SvgConfig::default()
+        };
+        let svg = generate_svg_document(&paths, 10, 10, &config);
+
+        let position = |id: &str| svg.find(&format!(r#"<g id="{id}">"#)).unwrap();
+        let fills = position("fills");
+        let thick = position("thickness-thick");
+        let medium = position("thickness-medium");
+        let thin = position("thickness-thin");
+        assert!(fills < thick && thick < medium && medium < thin);
+        assert!(svg[thin..].contains("M 0 0 L 10 10") && svg[thin..].contains("M 0 10 L 10 0"));
+        assert!(svg[medium..thin].contains("M 5 0 L 5 10"));
+        assert!(svg[thick..medium].contains("M 0 5 L 10 5"));
+
+        let uniform = generate_svg_document(&paths[..1], 10, 10, &config);
+        assert!(uniform.contains(r#"<g id="thickness-medium">"#));
+        assert_eq!(uniform.matches("<g ").count(), 1);
+    }
+
+    #[test]
+    fn test_palette_legend_lists_colors_with_coverage() {
+        let paths = vec![
+            SvgPath::new_fill("M 0 0 L 50 0 L 50 100 L 0 100 Z".to_string(), "#FF0000"),
+            create_rectangle(50.0, 50.0, 50.0, 50.0, Some("#0000ff".to_string())),
+            SvgPath::new_stroke("M 0 10 L 100 10".to_string(), "#000000", 2.0),
+        ];
+        let config = SvgConfig {
+            emit_palette_legend: true,
+            ..SvgConfig::default()
+        };
+
+        assert_eq!(
+            palette_coverage(&paths, 100, 100),
+            vec![
+                ("#ff0000".to_string(), 50.0),
+                ("#0000ff".to_string(), 25.0),
+                ("#000000".to_string(), 2.0),
+            ]
+        );
+        let svg = generate_svg_document(&paths, 100, 100, &config);
+        let legend = &svg[svg.find(r#"<g class="legend""#).unwrap()..];
+        for label in ["#ff0000", "50.0%", "#0000ff", "25.0%", "#000000", "2.0%"] {
+            assert!(
+                legend.contains(&format!(">{label}</text>")),
+                "missing {label}"
+            );
+        }
+        assert_eq!(legend.matches("<rect").count(), 4);
+
+        let plain = generate_svg_document(&paths, 100, 100, &SvgConfig::default());
+        assert!(!plain.contains("legend"));
+    }
+
+    #[test]
+    fn test_coordinate_origin_remaps_coordinates_and_view_box() {
+        let paths = vec![
+            SvgPath::new_fill("M 0 0 L 12 0 L 12 6 Z".to_string(), "#000000"),
+            create_rectangle(14.0, 2.0, 4.0, 6.0, Some("#000000".to_string())),
+            SvgPath {
+                element_type: SvgElementType::Circle {
+                    cx: 5.0,
+                    cy: 7.0,
+                    r: 2.0,
+                },
+                ..SvgPath::new_fill(String::new(), "#000000")
+            },
+        ];
+        let svg_for = |origin| {
+            let config = SvgConfig {
+                coordinate_origin: origin,
+                ..SvgConfig::default()
+            };
+            generate_svg_document(&paths, 20, 10, &config)
+        };
+
+        let center = svg_for(CoordinateOrigin::Center);
+        assert!(center.contains(r#"viewBox="-10 -5 20 10""#));
+        assert!(center.contains(r#"d="M -10.00 -5.00 L 2.00 -5.00 L 2.00 1.00 Z""#));
+        assert!(center.contains(r#"d="M 4.00 -3.00 h 4.00 v 6.00 h -4.00 Z""#));
+        assert!(center.contains(r#"cx="-5.00" cy="2.00""#));
+
+        let bottom_left = svg_for(CoordinateOrigin::BottomLeft);
+        assert!(bottom_left.contains(r#"viewBox="0 -10 20 10""#));
+        assert!(bottom_left.contains(r#"<g transform="scale(1 -1)">"#));
+        assert!(bottom_left.contains(r#"d="M 0.00 10.00 L 12.00 10.00 L 12.00 4.00 Z""#));
+        assert!(bottom_left.contains(r#"d="M 14.00 8.00 h 4.00 v -6.00 h -4.00 Z""#));
+        assert!(bottom_left.contains(r#"cx="5.00" cy="3.00""#));
+
+        // Every origin renders the same picture
+        let reference =
+            crate::svg_raster::rasterize_svg_luma(&svg_for(CoordinateOrigin::TopLeft), 20);
+        for svg in [center, bottom_left] {
+            assert_eq!(crate::svg_raster::rasterize_svg_luma(&svg, 20), reference);
+        }
+    }
+
+    #[test]
+    fn test_coordinate_origin_flips_relative_and_arc_commands() {
+        let data = remap_path_data(
+            "m 1 2 3 4 a 5 5 0 0 1 6 7 v 2",
+            CoordinateOrigin::BottomLeft,
+            (10.0, 10.0),
+            1,
+        )
+        .unwrap();
+        assert_eq!(data, "m 1.0 8.0 3.0 -4.0 a 5.0 5.0 0.0 0 0 6.0 -7.0 v -2.0");
+    }
+
+    #[test]
+    fn test_stroke_width_jitter_is_seeded_and_bounded() {
+        let mut paths: Vec<SvgPath> = (0..8)
+            .map(|i| SvgPath::new_stroke(format!("M 0 {i} L 10 {i}"), "#000000", 2.0))
+            .collect();
+        paths.push(SvgPath::new_fill(
+            "M 0 0 L 10 0 L 10 10 Z".to_string(),
+            "#FF0000",
+        ));
+        let config = SvgConfig {
+            stroke_width_jitter: 0.2,
+            ..SvgConfig::default()
+        };
+        let widths = |svg: &str| -> Vec<f32> {
+            svg.split(r#"stroke-width=""#)
+                .skip(1)
+                .map(|rest| rest[..rest.find('"').unwrap()].parse().unwrap())
+                .collect()
+        };
+
+        let svg = generate_svg_document(&paths, 10, 10, &config);
+        let jittered = widths(&svg);
+        assert_eq!(jittered.len(), 8);
+        assert!(jittered.iter().all(|w| (1.6..=2.4).contains(w)));
+        assert!(jittered.iter().any(|&w| (w - jittered[0]).abs() > 0.01));
+        assert_eq!(generate_svg_document(&paths, 10, 10, &config), svg);
+
+        let reseeded = SvgConfig {
+            stroke_width_jitter_seed: 7,
+            ..config
+        };
+        assert_ne!(
+            widths(&generate_svg_document(&paths, 10, 10, &reseeded)),
+            jittered
+        );
+    }
+
+    #[test]
+    fn test_fill_and_stroke_outlines_filled_shapes() {
+        let paths = vec![
+            SvgPath::new_fill("M 0 0 L 10 0 L 10 10 Z".to_string(), "#FF0000"),
+            SvgPath::new_stroke("M 0 0 L 10 10".to_string(), "#00FF00", 1.5),
+        ];
+        let plain = generate_svg_document(&paths, 10, 10, &SvgConfig::default());
+        assert!(!plain.contains("#000000"));
+
+        let config = SvgConfig {
+            fill_and_stroke: Some(("#000000".to_string(), 2.0)),
+            ..SvgConfig::default()
+        };
+        let svg = generate_svg_document(&paths, 10, 10, &config);
+
+        assert!(svg.contains(
+            r##"<path d="M 0 0 L 10 0 L 10 10 Z" fill="#FF0000" stroke="#000000" stroke-width="2.00" />"##
+        ));
+        assert!(svg.contains(r##"stroke="#00FF00" stroke-width="1.50""##));
+    }
+
+    #[test]
+    fn test_stroke_to_outline_fills_stroke_area() {
+        let paths = vec![
+            SvgPath::new_stroke("M 10 20 L 50 20".to_string(), "#00FF00", 4.0),
+            SvgPath::new_stroke("M 0 0 L 10 0 L 10 10".to_string(), "#0000FF", 2.0),
+            SvgPath::new_fill("M 0 0 L 10 0 L 10 10 Z".to_string(), "#FF0000"),
+        ];
+        let config = SvgConfig {
+            stroke_to_outline: true,
+            ..SvgConfig::default()
+        };
+        let svg = generate_svg_document(&paths, 60, 30, &config);
+
+        // A straight stroke becomes the rectangle its butt caps enclose
+        assert!(svg.contains(
+            r##"<path d="M 10.00,22.00 L 50.00,22.00 L 50.00,18.00 L 10.00,18.00 Z" fill="#00FF00" />"##
+        ));
+        // The outer corner is mitered, the inner one routed through the vertex
+        assert!(svg.contains(
+            "M 0.00,1.00 L 10.00,1.00 L 10.00,0.00 L 9.00,0.00 L 9.00,10.00 L 11.00,10.00 L 11.00,-1.00 L 0.00,-1.00 Z"
+        ));
+        assert!(svg.contains(r##"<path d="M 0 0 L 10 0 L 10 10 Z" fill="#FF0000" />"##));
+        assert!(!svg.contains("stroke="));
+
+        // Same coverage as the stroke, including the join
+        let corner = |config: &SvgConfig| {
+            let path = SvgPath::new_stroke("M 4 4 L 36 4 L 36 36".to_string(), "#000000", 4.0);
+            crate::svg_raster::rasterize_svg_luma(
+                &generate_svg_document(&[path], 40, 40, config),
+                40,
+            )
+            .unwrap()
+        };
+        let outline = corner(&config);
+        assert_eq!(outline.get_pixel(20, 4).0[0], 0);
+        assert_eq!(outline.get_pixel(36, 20).0[0], 0);
+        assert_eq!(outline.get_pixel(37, 3).0[0], 0);
+        assert_eq!(outline.get_pixel(35, 5).0[0], 0);
+        assert_eq!(outline.get_pixel(20, 20).0[0], 255);
+        // The raster preview draws round caps and joins, so only the ends and the
+        // miter tip may differ
+        let stroke = corner(&SvgConfig::default());
+        for (x, y, pixel) in outline.enumerate_pixels() {
+            if pixel.0[0].abs_diff(stroke.get_pixel(x, y).0[0]) > 128 {
+                let near =
+                    |(px, py): (f32, f32)| (x as f32 + 0.5 - px).hypot(y as f32 + 0.5 - py) < 3.0;
+                assert!(
+                    near((4.0, 4.0)) || near((36.0, 4.0)) || near((36.0, 36.0)),
+                    "({x}, {y})"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_path_data_length_measures_lines_and_curves() {
+        assert_eq!(path_data_length("M 0 0 L 3 4 L 3 10"), Some(11.0));
+        assert_eq!(path_data_length("M0,0 h10 v10 Z"), Some(20.0 + 200f32.sqrt()));
+        assert_eq!(path_data_length("M 5 5"), Some(0.0));
+        let curve = path_data_length("M 0 0 C 0 0 10 0 10 0").unwrap();
+        assert!((curve - 10.0).abs() < 1e-3);
+        let arc = path_data_length("M 0 0 Q 5 5 10 0").unwrap();
+        assert!(arc > 10.0 && arc < 200f32.sqrt());
+        assert_eq!(path_data_length("M 0 0 A 5 5 0 0 1 10 0"), None);
+    }
+
+    #[test]
+    fn test_generate_svg_sprite_one_symbol_per_input() {
+        let paths = vec![SvgPath::new_stroke("M 0 0 L 4 4".to_string(), "#000000", 1.0)];
+        let config = SvgConfig {
+            emit_path_ids: true,
+            emit_xml_declaration: true,
+            ..SvgConfig::default()
+        };
+        let documents = vec![
+            ("home icon".to_string(), generate_svg_document(&paths, 24, 24, &config)),
+            ("home icon".to_string(), generate_svg_document(&paths, 32, 16, &config)),
+            ("gear".to_string(), r##"<svg width="8" height="10"><path fill="url(#g0)" d="M 0 0"/></svg>"##.to_string()),
+        ];
+
+        let sprite = generate_svg_sprite(&documents);
+        assert!(sprite.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(!sprite.contains("<?xml"));
+        assert_eq!(sprite.matches("<symbol ").count(), 3);
+        assert_eq!(sprite.matches("</symbol>").count(), 3);
+        assert_eq!(sprite.matches("<svg").count(), 1);
+        assert!(sprite.contains(r#"<symbol id="home-icon" viewBox="0 0 24 24">"#));
+        assert!(sprite.contains(r#"<symbol id="home-icon-2" viewBox="0 0 32 16">"#));
+        assert!(sprite.contains(r#"<symbol id="gear" viewBox="0 0 8 10">"#));
+        assert!(sprite.contains(r#"id="home-icon-path-0""#));
+        assert!(sprite.contains(r#"id="home-icon-2-path-0""#));
+        assert!(sprite.contains("url(#gear-g0)"));
+    }
+
+    #[test]
+    fn test_emit_path_length_on_strokes_only() {
+        let paths = vec![
+            SvgPath::new_stroke("M 0 0 L 40 30".to_string(), "#000000", 2.0),
+            SvgPath::new_stroke("M 1 1 L 2 2 L 9 3".to_string(), "#000000", 2.0),
+            SvgPath::new_stroke("M 5 5".to_string(), "#000000", 2.0),
+            SvgPath::new_fill("M 0 0 L 10 0 L 10 10 Z".to_string(), "#ff0000"),
+        ];
+        let config = SvgConfig {
+            emit_path_length: true,
+            ..SvgConfig::default()
+        };
+
+        let svg = generate_svg_document(&paths, 50, 50, &config);
+        assert_eq!(svg.matches(r#"pathLength="100""#).count(), 2);
+        assert!(svg.contains(r#"d="M 0 0 L 40 30" pathLength="100""#));
+        assert!(!generate_svg_document(&paths, 50, 50, &SvgConfig::default()).contains("pathLength"));
+
+        let (gradient_svg, _) =
+            crate::svg_gradients::generate_svg_document_with_gradients(&paths, &[], 50, 50, &config);
+        assert_eq!(gradient_svg.matches(r#"pathLength="100""#).count(), 2);
+    }
+
+    #[test]
+    fn test_draw_on_animation_staggers_strokes() {
+        let paths = vec![
+            SvgPath::new_stroke("M 0 0 L 40 30".to_string(), "#000000", 2.0),
+            SvgPath::new_fill("M 0 0 L 10 0 L 10 10 Z".to_string(), "#ff0000"),
+            SvgPath::new_stroke("M 1 1 L 2 2 L 9 3".to_string(), "#000000", 2.0),
+            SvgPath::new_stroke("M 5 5".to_string(), "#000000", 2.0),
+        ];
+        let config = SvgConfig {
+            draw_on_animation: Some(2.0),
+            ..SvgConfig::default()
+        };
+
+        let svg = generate_svg_document(&paths, 50, 50, &config);
+        assert_eq!(svg.matches("@keyframes vec2art-draw-on").count(), 1);
+        assert_eq!(svg.matches(r#"stroke-dashoffset="100""#).count(), 2);
+        assert!(svg.contains(r#"pathLength="100" stroke-dasharray="100""#));
+        assert!(svg.contains("animation: vec2art-draw-on 0.500s linear 0.000s forwards"));
+        assert!(svg.contains("animation: vec2art-draw-on 0.500s linear 1.000s forwards"));
+
+        let plain = generate_svg_document(&paths, 50, 50, &SvgConfig::default());
+        assert!(!plain.contains("vec2art-draw-on") && !plain.contains("dasharray"));
+
+        let (gradient_svg, _) =
+            crate::svg_gradients::generate_svg_document_with_gradients(&paths, &[], 50, 50, &config);
+        assert!(gradient_svg.contains("@keyframes vec2art-draw-on"));
+        assert_eq!(gradient_svg.matches("animation: vec2art-draw-on").count(), 2);
+    }
+
+    #[test]
+    fn test_output_transform_mirrors_and_rotates() {
+        use crate::config::Transform;
+        use crate::svg_raster::rasterize_svg_luma;
+
+        // Black block in the left quarter of a 40x20 canvas
+        let paths = vec![SvgPath::new_fill(
+            "M 0 0 L 10 0 L 10 20 L 0 20 Z".to_string(),
+            "#000000",
+        )];
+        let render = |transform| {
+            let config = SvgConfig {
+                output_transform: transform,
+                ..SvgConfig::default()
+            };
+            let svg = generate_svg_document(&paths, 40, 20, &config);
+            (rasterize_svg_luma(&svg, 512).unwrap(), svg)
+        };
+
+        let (flipped, svg) = render(Some(Transform::FlipHorizontal));
+        assert!(svg.contains(r#"viewBox="0 0 40 20""#));
+        assert_eq!(flipped.dimensions(), (40, 20));
+        assert_eq!(flipped.get_pixel(35, 10).0[0], 0);
+        assert_eq!(flipped.get_pixel(5, 10).0[0], 255);
+
+        // Clockwise quarter turn moves the left edge to the top and swaps dimensions
+        let (rotated, svg) = render(Some(Transform::Rotate90));
+        assert!(svg.contains(r#"width="20" height="40" viewBox="0 0 20 40""#));
+        assert_eq!(rotated.get_pixel(10, 5).0[0], 0);
+        assert_eq!(rotated.get_pixel(10, 35).0[0], 255);
+
+        let (plain, svg) = render(None);
+        assert!(!svg.contains("<g"));
+        assert_eq!(plain.get_pixel(5, 10).0[0], 0);
+    }
+
+    /// Rasterize path data at pixel centers with the nonzero fill rule
+    fn nonzero_coverage(data: &str, size: u32) -> Vec<bool> {
+        let rings: Vec<Vec<(f32, f32)>> = parse_polyline_subpaths(data)
+            .unwrap()
+            .into_iter()
+            .map(|(ring, _)| ring)
+            .collect();
+        let winding = |(px, py): (f32, f32)| -> i32 {
+            let mut winding = 0;
+            for ring in &rings {
+                for k in 0..ring.len() {
+                    let (a, b) = (ring[k], ring[(k + 1) % ring.len()]);
+                    let side = (b.0 - a.0) * (py - a.1) - (px - a.0) * (b.1 - a.1);
+                    if a.1 <= py && b.1 > py && side > 0.0 {
+                        winding += 1;
+                    } else if a.1 > py && b.1 <= py && side < 0.0 {
+                        winding -= 1;
+                    }
+                }
+            }
+            winding
+        };
+        (0..size * size)
+            .map(|i| winding(((i % size) as f32 + 0.5, (i / size) as f32 + 0.5)) != 0)
+            .collect()
+    }
+
+    #[test]
+    fn test_repair_self_intersections_fills_folded_region() {
+        // A square whose top edge was simplified into a fold: the lobe below
+        // the crossing at (5, 7.5) winds against the square and punches a hole
+        let folded = "M 0 0 L 10 0 L 10 10 L 3 10 L 7 5 L 3 5 L 7 10 L 0 10 Z";
+        let paths = vec![SvgPath::new_fill(folded.to_string(), "#336699")];
+        assert_eq!(self_intersecting_path_indices(&paths), vec![0]);
+
+        let coverage = nonzero_coverage(folded, 10);
+        assert!(!coverage[6 * 10 + 5], "fold should render as a hole without repair");
+
+        let repaired = repair_self_intersecting_data(folded, 2).unwrap();
+        let repaired_paths = vec![SvgPath::new_fill(repaired.clone(), "#336699")];
+        assert!(self_intersecting_path_indices(&repaired_paths).is_empty());
+        assert!(nonzero_coverage(&repaired, 10).iter().all(|&filled| filled));
+
+        // The document generator applies the repair only when enabled
+        let config = SvgConfig {
+            repair_self_intersections: true,
+            ..SvgConfig::default()
+        };
+        assert!(generate_svg_document(&paths, 10, 10, &config).contains(&repaired));
+        assert!(generate_svg_document(&paths, 10, 10, &SvgConfig::default()).contains(folded));
+    }
+
+    #[test]
+    fn test_auto_close_coincident_closes_rounded_loop() {
+        // End point differs from the start only below the output precision
+        let near_closed = "M 1 1 L 9 1 L 9 9 L 1 9 L 1.001 0.999";
+        let closed = close_coincident_data(near_closed, 2).unwrap();
+        assert_eq!(closed, "M 1.00 1.00 L 9.00 1.00 L 9.00 9.00 L 1.00 9.00 Z");
+
+        // A visible gap, an already closed path and curves are left alone
+        assert!(close_coincident_data("M 1 1 L 9 1 L 9 9 L 1 9 L 1.1 1", 2).is_none());
+        assert!(close_coincident_data("M 1 1 L 9 1 L 9 9 L 1 9 Z", 2).is_none());
+        assert!(close_coincident_data("M 1 1 C 9 1 9 9 1 1", 2).is_none());
+
+        let paths = vec![SvgPath::new_fill(near_closed.to_string(), "#336699")];
+        let config = SvgConfig {
+            auto_close_coincident: true,
+            ..SvgConfig::default()
+        };
+        assert!(generate_svg_document(&paths, 10, 10, &config).contains(&closed));
+        assert!(generate_svg_document(&paths, 10, 10, &SvgConfig::default()).contains(near_closed));
+    }
+
+    #[test]
+    fn test_srgb_hex_to_display_p3() {
+        let white = srgb_hex_to_display_p3("#FFFFFF").unwrap();
+        assert!(white.iter().all(|c| (c - 1.0).abs() < 1e-4));
+        let black = srgb_hex_to_display_p3("#000000").unwrap();
+        assert!(black.iter().all(|&c| c.abs() < 1e-6));
+
+        assert!(srgb_hex_to_display_p3("none").is_none());
+        assert!(srgb_hex_to_display_p3("url(#g1)").is_none());
+        assert!(srgb_hex_to_display_p3("#FFF").is_none());
+    }
+
+    #[test]
+    fn test_resample_path_data_even_spacing() {
+        // Dense at the start, a single long segment at the end
+        let data = "M 0 0 L 1 0 L 2 0 L 2.5 0 L 10 0";
+        let resampled = resample_path_data(data, 2.0, 2).unwrap();
+        assert_eq!(
+            resampled,
+            "M 0.00 0.00 L 2.00 0.00 L 4.00 0.00 L 6.00 0.00 L 8.00 0.00 L 10.00 0.00"
+        );
+
+        // Spacing carries across corners and the closing segment of closed paths
+        let square = resample_path_data("M 0,0 L 3,0 L 3,3 L 0,3 Z", 2.0, 0).unwrap();
+        assert_eq!(square, "M 0 0 L 2 0 L 3 1 L 3 3 L 1 3 L 0 2 Z");
+
+        // Relative commands resolve against the current point
+        let relative = resample_path_data("m 0 0 l 3 0 l 0 3", 2.0, 0).unwrap();
+        assert_eq!(relative, "M 0 0 L 2 0 L 3 1 L 3 3");
+
+        // Curves are flattened, then spaced along their arc length
+        let curve = resample_path_data("M 0 0 C 0 10 10 10 10 0", 1.0, 3).unwrap();
+        let points = parse_polyline_subpaths(&curve).unwrap().remove(0).0;
+        assert_eq!(points[0], (0.0, 0.0));
+        assert_eq!(*points.last().unwrap(), (10.0, 0.0));
+        assert!(
+            points.iter().any(|&(_, y)| y > 7.0),
+            "Curve should keep its bulge"
+        );
+        for w in points[..points.len() - 1].windows(2) {
+            let step = (w[1].0 - w[0].0).hypot(w[1].1 - w[0].1);
+            assert!((step - 1.0).abs() < 0.02, "Uneven step {step}");
+        }
+
+        assert!(resample_path_data("M 0 0 A 5 5 0 0 1 10 0", 1.0, 2).is_none());
+        assert!(resample_path_data("", 1.0, 2).is_none());
+        assert!(resample_path_data("M 0 0 L 5 5", 0.0, 2).is_none());
+    }
+
+    #[test]
+    fn test_generate_svg_document_resamples_paths() {
+        let paths = vec![SvgPath::new_stroke("M 0 0 L 0.5 0 L 6 0".to_string(), "#000000", 1.0)];
+        let config = SvgConfig {
+            resample_even_spacing: Some(3.0),
+            ..SvgConfig::default()
+        };
+
+        let svg = generate_svg_document(&paths, 10, 10, &config);
+        assert!(svg.contains(r#"d="M 0.00 0.00 L 3.00 0.00 L 6.00 0.00""#));
+    }
+
+    #[test]
+    fn test_sanitize_drops_non_finite_segments() {
+        let degenerate = format!("M 0 0 L 10 10 C {} 1 2 3 4 5 L 20 20", f32::NAN);
+        let data = sanitize_path_data(&degenerate, (100.0, 100.0), NonFiniteHandling::Drop);
+        assert_eq!(data.as_deref(), Some("M 0 0 L 10 10 L 20 20"));
+
+        // A dropped moveto restarts the subpath at the next recoverable point
+        let bad_start = format!("M {} 0 L 10 10 L 20 20", f32::INFINITY);
+        let data = sanitize_path_data(&bad_start, (100.0, 100.0), NonFiniteHandling::Drop);
+        assert_eq!(data.as_deref(), Some("M 10 10 L 20 20"));
+
+        // Nothing left to draw
+        let data = sanitize_path_data("M 0 0 L NaN 4", (100.0, 100.0), NonFiniteHandling::Drop);
+        assert_eq!(data, None);
+    }
+
+    #[test]
+    fn test_path_parsers_share_one_tokenizer() {
+        let compact = "M0,0L10 0L10,10z";
+        assert_eq!(
+            parse_polyline_subpaths(compact),
+            Some(vec![(vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0)], true)])
+        );
+        let sanitized = sanitize_path_data(compact, (100.0, 100.0), NonFiniteHandling::Drop);
+        assert_eq!(sanitized.as_deref(), Some("M 0 0 L 10 0 L 10 10 Z"));
+        assert_eq!(flatten_path_data(compact), parse_polyline_subpaths(compact));
+        assert_eq!(
+            parse_path_commands("M10-20L1e-2-3"),
+            Some(vec![('M', vec![10.0, -20.0]), ('L', vec![0.01, -3.0])])
+        );
+
+        // Polyline parsing leaves curves to flattening
+        assert!(parse_polyline_subpaths("M 0 0 Q 5 5 10 0").is_none());
+        assert!(flatten_path_data("M 0 0 Q 5 5 10 0").is_some());
+    }
+
+    #[test]
+    fn test_sanitize_keeps_relative_segments_in_place() {
+        // The curve's control point is bad but its end point is known, so later
+        // relative segments still land where they were
+        let degenerate = format!("m 1 1 l 10 0 c 0 {} 5 5 10 10 l 0 10", f32::NAN);
+        let data = sanitize_path_data(&degenerate, (100.0, 100.0), NonFiniteHandling::Drop);
+        assert_eq!(data.as_deref(), Some("M 1 1 L 11 1 L 21 21"));
+
+        // After an unknown end point, relative segments wait for an absolute one
+        let lost = format!("M 0 0 l {} 5 l 3 3 L 40 40 h 5", f32::INFINITY);
+        let data = sanitize_path_data(&lost, (100.0, 100.0), NonFiniteHandling::Drop);
+        assert_eq!(data.as_deref(), Some("M 0 0 L 40 40 L 45 40"));
+
+        let data = sanitize_path_data(&lost, (100.0, 100.0), NonFiniteHandling::Clamp);
+        assert_eq!(
+            data.as_deref(),
+            Some("M 0 0 L 100 5 L 103 8 L 40 40 L 45 40")
+        );
+    }
+
+    #[test]
+    fn test_sanitize_clamps_non_finite_coordinates() {
+        let degenerate = format!("M 0 0 L {} {}", f32::INFINITY, f32::NAN);
+        let data = sanitize_path_data(&degenerate, (100.0, 50.0), NonFiniteHandling::Clamp);
+        assert_eq!(data.as_deref(), Some("M 0 0 L 100 0"));
+
+        let circle = SvgPath {
+            element_type: SvgElementType::Circle {
+                cx: f32::NEG_INFINITY,
+                cy: 5.0,
+                r: f32::NAN,
+            },
+            ..SvgPath::new_fill(String::new(), "#000000")
+        };
+        let sanitized = sanitize_non_finite_paths(
+            std::slice::from_ref(&circle),
+            100,
+            50,
+            NonFiniteHandling::Clamp,
+        );
+        assert_eq!(
+            sanitized[0].element_type,
+            SvgElementType::Circle {
+                cx: 0.0,
+                cy: 5.0,
+                r: 0.0
+            }
+        );
+    }
+
+    #[test]
+    fn test_generate_svg_document_never_emits_nan() {
+        let paths = vec![
+            SvgPath::new_stroke(format!("M 0 0 Q {} 3 4 4 L 8 8", f32::NAN), "#000000", 1.0),
+            SvgPath::new_stroke("M 1 1 L 2 2".to_string(), "#000000", 1.0),
+            SvgPath {
+                element_type: SvgElementType::Circle {
+                    cx: f32::NAN,
+                    cy: 1.0,
+                    r: 1.0,
+                },
+                ..SvgPath::new_fill(String::new(), "#000000")
+            },
+        ];
+
+        let svg = generate_svg_document(&paths, 10, 10, &SvgConfig::default());
+        assert!(!svg.to_lowercase().contains("nan"));
+        assert!(svg.contains(r#"d="M 0 0 L 8 8""#));
+        assert!(svg.contains(r#"d="M 1 1 L 2 2""#));
+        assert!(!svg.contains("<circle"));
+    }
+
     #[test]
     fn test_validate_svg_paths() {
         let valid_paths = vec![SvgPath {
@@ -647,6 +3207,9 @@ mod tests {
             fill: "blue".to_string(),
             stroke: "none".to_string(),
             stroke_width: 0.0,
+            stroke_opacity: None,
+            source_color: None,
+            region_metadata: None,
             element_type: SvgElementType::Path,
         }];
 
@@ -657,6 +3220,9 @@ mod tests {
             fill: "none".to_string(),
             stroke: "none".to_string(),
             stroke_width: 0.0,
+            stroke_opacity: None,
+            source_color: None,
+            region_metadata: None,
             element_type: SvgElementType::Path,
         }];
 
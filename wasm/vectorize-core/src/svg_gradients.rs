@@ -3,8 +3,9 @@
 //! This module provides functionality to generate SVG documents with gradient definitions
 //! based on gradient analysis results from the trace-low algorithm.
 
-use crate::algorithms::{SvgElementType, SvgPath};
-use crate::config::SvgConfig;
+use crate::algorithms::SvgPath;
+use crate::config::{CoordinateOrigin, SvgConfig};
+use std::collections::HashMap;
 
 /// SVG gradient definition
 #[derive(Debug, Clone)]
@@ -28,6 +29,112 @@ pub enum GradientDefinition {
     },
 }
 
+impl GradientDefinition {
+    /// Element id referenced as `url(#id)` by fills and strokes
+    pub fn id(&self) -> &str {
+        match self {
+            Self::Linear { id, .. } | Self::Radial { id, .. } => id,
+        }
+    }
+
+    /// Color stops in offset order
+    pub fn stops(&self) -> &[ColorStop] {
+        match self {
+            Self::Linear { stops, .. } | Self::Radial { stops, .. } => stops,
+        }
+    }
+
+    /// How much the gradient differs from a solid color: the widest RGB distance
+    /// between two stops, times the length it spreads over (the vector for linear
+    /// gradients, the diameter for radial ones)
+    fn impact(&self) -> f32 {
+        let extent = match self {
+            Self::Linear { x1, y1, x2, y2, .. } => (x2 - x1).hypot(y2 - y1),
+            Self::Radial { r, .. } => 2.0 * r,
+        };
+        let colors: Vec<[f32; 3]> = self
+            .stops()
+            .iter()
+            .filter_map(|stop| crate::utils::parse_color(&stop.color).ok())
+            .map(|rgba| [rgba[0], rgba[1], rgba[2]].map(f32::from))
+            .collect();
+        let spread = colors
+            .iter()
+            .flat_map(|a| colors.iter().map(move |b| (a, b)))
+            .map(|(a, b)| (0..3).map(|c| (a[c] - b[c]).powi(2)).sum::<f32>().sqrt())
+            .fold(0.0f32, f32::max);
+        spread * extent
+    }
+
+    /// Mean color of the stops, used when the gradient is replaced by a solid paint
+    fn solid_color(&self) -> String {
+        let colors: Vec<_> = self
+            .stops()
+            .iter()
+            .filter_map(|stop| crate::utils::parse_color(&stop.color).ok())
+            .collect();
+        if colors.is_empty() {
+            return "#000000".to_string();
+        }
+        let mean = |channel: usize| {
+            let total: u32 = colors.iter().map(|rgba| rgba[channel] as u32).sum();
+            (total as f32 / colors.len() as f32).round() as u8
+        };
+        format!("#{:02x}{:02x}{:02x}", mean(0), mean(1), mean(2))
+    }
+}
+
+/// Keep at most `max_gradients` gradient definitions, repainting the rest as solids
+///
+/// Gradients with the least impact, i.e. the smallest color change over the
+/// shortest distance, are dropped first. Fills and strokes that referenced a
+/// dropped gradient get the mean color of its stops. Returns the paths, the kept
+/// definitions in their original order and the number of gradients converted.
+pub fn limit_gradients(
+    paths: &[SvgPath],
+    gradients: &[GradientDefinition],
+    max_gradients: usize,
+) -> (Vec<SvgPath>, Vec<GradientDefinition>, usize) {
+    if gradients.len() <= max_gradients {
+        return (paths.to_vec(), gradients.to_vec(), 0);
+    }
+
+    let impacts: Vec<f32> = gradients.iter().map(GradientDefinition::impact).collect();
+    let mut ranked: Vec<usize> = (0..gradients.len()).collect();
+    ranked.sort_by(|&a, &b| impacts[b].total_cmp(&impacts[a]));
+    let mut keep = vec![false; gradients.len()];
+    for &index in &ranked[..max_gradients] {
+        keep[index] = true;
+    }
+
+    let solids: HashMap<String, String> = gradients
+        .iter()
+        .zip(&keep)
+        .filter(|(_, &kept)| !kept)
+        .map(|(gradient, _)| (format!("url(#{})", gradient.id()), gradient.solid_color()))
+        .collect();
+    let paths = paths
+        .iter()
+        .map(|path| {
+            let mut path = path.clone();
+            for paint in [&mut path.fill, &mut path.stroke] {
+                if let Some(solid) = solids.get(paint.as_str()) {
+                    *paint = solid.clone();
+                }
+            }
+            path
+        })
+        .collect();
+    let kept = gradients
+        .iter()
+        .zip(&keep)
+        .filter(|(_, &kept)| kept)
+        .map(|(gradient, _)| gradient.clone())
+        .collect();
+
+    (paths, kept, gradients.len() - max_gradients)
+}
+
 /// Color stop for SVG gradients
 #[derive(Debug, Clone)]
 pub struct ColorStop {
@@ -49,58 +156,146 @@ pub struct ColorStop {
 /// * `config` - SVG generation configuration
 ///
 /// # Returns
-/// * `String` - Complete SVG document with gradients
+/// * `(String, usize)` - Complete SVG document with gradients and the number of
+///   gradients converted to solid colors by `max_gradients`
 pub fn generate_svg_document_with_gradients(
     paths: &[SvgPath],
     gradients: &[GradientDefinition],
     width: u32,
     height: u32,
     config: &SvgConfig,
-) -> String {
-    let mut svg = String::new();
-
-    // SVG header
-    svg.push_str(&format!(
-        r#"<svg width="{}" height="{}" viewBox="0 0 {} {}" xmlns="http://www.w3.org/2000/svg""#,
-        width, height, width, height
-    ));
-
-    if config.optimize {
-        svg.push_str(r#" shape-rendering="geometricPrecision""#);
-    }
+) -> (String, usize) {
+    gradient_document(
+        paths,
+        gradients,
+        (width, height),
+        config,
+        |paths, defs, body_config| {
+            crate::svg::generate_svg_document_with_defs(paths, defs, width, height, body_config)
+        },
+    )
+}
 
-    svg.push_str(">\n");
+/// Generate a layered SVG document, one group per layer as in
+/// [`crate::svg::generate_layered_svg_document`], with gradient support
+///
+/// # Returns
+/// * `(String, usize)` - Complete SVG document with gradients and the number of
+///   gradients converted to solid colors by `max_gradients`
+pub fn generate_layered_svg_document_with_gradients(
+    layers: &[(&str, &[SvgPath])],
+    gradients: &[GradientDefinition],
+    width: u32,
+    height: u32,
+    config: &SvgConfig,
+) -> (String, usize) {
+    let paths: Vec<SvgPath> = layers
+        .iter()
+        .flat_map(|(_, paths)| paths.iter().cloned())
+        .collect();
+    gradient_document(
+        &paths,
+        gradients,
+        (width, height),
+        config,
+        |paths, defs, body_config| {
+            // Gradient limiting repaints paths in place, so layers keep their lengths
+            let mut rest = paths;
+            let layers: Vec<(&str, &[SvgPath])> = layers
+                .iter()
+                .map(|&(name, layer)| {
+                    let (head, tail) = rest.split_at(layer.len());
+                    rest = tail;
+                    (name, head)
+                })
+                .collect();
+            crate::svg::layered_svg_document(&layers, defs, width, height, body_config)
+        },
+    )
+}
 
-    if config.include_metadata {
-        svg.push_str("  <!-- Generated by vec2art vectorize-core with gradient support -->\n");
-    }
+/// Limit gradients, write their definitions and optimize the document that `body`
+/// writes around them from the (possibly repainted) paths, defs and body config
+fn gradient_document(
+    paths: &[SvgPath],
+    gradients: &[GradientDefinition],
+    (width, height): (u32, u32),
+    config: &SvgConfig,
+    body: impl FnOnce(&[SvgPath], &str, &SvgConfig) -> String,
+) -> (String, usize) {
+    let limited = config
+        .max_gradients
+        .filter(|&max| gradients.len() > max)
+        .map(|max| limit_gradients(paths, gradients, max));
+    let converted = limited.as_ref().map_or(0, |(_, _, converted)| *converted);
+    let (paths, gradients) = match &limited {
+        Some((limited_paths, kept, converted)) => {
+            log::info!(
+                "Gradient limit: kept {} of {} gradients, {} converted to solid colors",
+                kept.len(),
+                gradients.len(),
+                converted
+            );
+            (limited_paths.as_slice(), kept.as_slice())
+        }
+        None => (paths, gradients),
+    };
 
-    // Add gradient definitions
+    let origin = crate::svg::effective_origin(config);
+    let mut defs = String::new();
     if !gradients.is_empty() {
-        svg.push_str("  <defs>\n");
+        defs.push_str("  <defs>\n");
         for gradient in gradients {
-            svg.push_str("    ");
-            svg.push_str(&format_gradient_definition(gradient, config));
-            svg.push('\n');
+            let gradient = remap_gradient_origin(gradient, origin, width, height);
+            defs.push_str("    ");
+            defs.push_str(&format_gradient_definition(&gradient, config));
+            defs.push('\n');
         }
-        svg.push_str("  </defs>\n");
+        defs.push_str("  </defs>\n");
     }
 
-    // Add each path
-    for path in paths {
-        svg.push_str("  ");
-        svg.push_str(&format_svg_path(path, config));
-        svg.push('\n');
-    }
-
-    svg.push_str("</svg>");
+    // The declaration is added after optimization, which would otherwise minify it
+    let body_config = SvgConfig {
+        emit_xml_declaration: false,
+        ..config.clone()
+    };
+    let svg = body(paths, &defs, &body_config);
 
     // Apply optimization if enabled
-    if config.optimize {
+    let svg = if config.optimize {
         crate::svg::optimize_colored_svg(&svg, !gradients.is_empty(), config.precision)
     } else {
         svg
+    };
+
+    let svg = if config.emit_xml_declaration {
+        format!("{}{svg}", crate::svg::XML_DECLARATION)
+    } else {
+        svg
+    };
+    (svg, converted)
+}
+
+/// Express a gradient's user-space coordinates relative to `origin`, like the
+/// paths that reference it
+fn remap_gradient_origin(
+    gradient: &GradientDefinition,
+    origin: CoordinateOrigin,
+    width: u32,
+    height: u32,
+) -> GradientDefinition {
+    let map = |x: f32, y: f32| origin.map_point((x, y), width as f32, height as f32);
+    let mut gradient = gradient.clone();
+    match &mut gradient {
+        GradientDefinition::Linear { x1, y1, x2, y2, .. } => {
+            (*x1, *y1) = map(*x1, *y1);
+            (*x2, *y2) = map(*x2, *y2);
+        }
+        GradientDefinition::Radial { cx, cy, .. } => {
+            (*cx, *cy) = map(*cx, *cy);
+        }
     }
+    gradient
 }
 
 /// Generate optimized SVG document with advanced compression
@@ -123,7 +318,7 @@ pub fn generate_optimized_svg_document_with_gradients(
     config: &SvgConfig,
     enable_compression: bool,
 ) -> (String, f32) {
-    let original_svg =
+    let (original_svg, _) =
         generate_svg_document_with_gradients(paths, gradients, width, height, config);
 
     if !enable_compression {
@@ -198,85 +393,119 @@ fn format_gradient_definition(gradient: &GradientDefinition, _config: &SvgConfig
     }
 }
 
-/// Format a single SVG path element
-fn format_svg_path(path: &SvgPath, config: &SvgConfig) -> String {
-    let mut element = match &path.element_type {
-        SvgElementType::Path => {
-            let mut el = String::from("<path");
-            el.push_str(&format!(r#" d="{}""#, path.data));
-            el
-        }
-        SvgElementType::Circle { cx, cy, r } => {
-            let mut el = String::from("<circle");
-            el.push_str(&format!(
-                r#" cx="{:.prec$}" cy="{:.prec$}" r="{:.prec$}""#,
-                cx,
-                cy,
-                r,
-                prec = config.precision as usize
-            ));
-            el
-        }
-        SvgElementType::Ellipse { cx, cy, rx, ry } => {
-            let mut el = String::from("<ellipse");
-            el.push_str(&format!(
-                r#" cx="{:.prec$}" cy="{:.prec$}" rx="{:.prec$}" ry="{:.prec$}""#,
-                cx,
-                cy,
-                rx,
-                ry,
-                prec = config.precision as usize
-            ));
-            el
-        }
-        SvgElementType::Line { x1, y1, x2, y2 } => {
-            let mut el = String::from("<line");
-            el.push_str(&format!(
-                r#" x1="{:.prec$}" y1="{:.prec$}" x2="{:.prec$}" y2="{:.prec$}""#,
-                x1,
-                y1,
-                x2,
-                y2,
-                prec = config.precision as usize
-            ));
-            el
-        }
-        SvgElementType::Rect { x, y, width, height } => {
-            let mut el = String::from("<rect");
-            el.push_str(&format!(
-                r#" x="{:.prec$}" y="{:.prec$}" width="{:.prec$}" height="{:.prec$}""#,
-                x,
-                y,
-                width,
-                height,
-                prec = config.precision as usize
-            ));
-            el
-        }
-        SvgElementType::Polygon { points } => {
-            let mut el = String::from("<polygon");
-            el.push_str(&format!(r#" points="{}""#, points));
-            el
-        }
-    };
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    // Add fill attribute
-    if path.fill != "none" {
-        element.push_str(&format!(r#" fill="{}""#, path.fill));
-    } else {
-        element.push_str(r#" fill="none""#);
+    fn linear(index: usize, length: f32, end_color: &str) -> GradientDefinition {
+        let stop = |offset: f32, color: &str| ColorStop {
+            offset,
+            color: color.to_string(),
+            opacity: None,
+        };
+        GradientDefinition::Linear {
+            id: format!("gradient_linear__{index}"),
+            x1: 0.0,
+            y1: 0.0,
+            x2: length,
+            y2: 0.0,
+            stops: vec![stop(0.0, "#000000"), stop(100.0, end_color)],
+        }
     }
 
-    // Add stroke attribute
-    if path.stroke != "none" {
-        element.push_str(&format!(r#" stroke="{}""#, path.stroke));
-    }
+    #[test]
+    fn test_max_gradients_converts_weakest_to_solids() {
+        // Long, high-contrast gradients first; short or faint ones are weaker
+        let gradients = vec![
+            linear(0, 100.0, "#ffffff"),
+            linear(1, 2.0, "#ffffff"),
+            linear(2, 100.0, "#101010"),
+            linear(3, 80.0, "#ffffff"),
+        ];
+        let paths: Vec<SvgPath> = (0..gradients.len())
+            .map(|i| {
+                SvgPath::new_stroke(
+                    format!("M 0 {i} L 100 {i}"),
+                    &format!("url(#gradient_linear__{i})"),
+                    1.0,
+                )
+            })
+            .collect();
+
+        let (limited, kept, converted) = limit_gradients(&paths, &gradients, 2);
+        assert_eq!(converted, 2);
+        let kept_ids: Vec<&str> = kept.iter().map(GradientDefinition::id).collect();
+        assert_eq!(kept_ids, ["gradient_linear__0", "gradient_linear__3"]);
+        assert_eq!(limited[0].stroke, "url(#gradient_linear__0)");
+        assert_eq!(limited[1].stroke, "#808080");
+        assert_eq!(limited[2].stroke, "#080808");
+        assert_eq!(limited[3].stroke, "url(#gradient_linear__3)");
+
+        let config = SvgConfig {
+            max_gradients: Some(2),
+            optimize: false,
+            ..SvgConfig::default()
+        };
+        let (svg, converted) =
+            generate_svg_document_with_gradients(&paths, &gradients, 100, 10, &config);
+        assert_eq!(converted, 2);
+        assert_eq!(svg.matches("<linearGradient").count(), 2);
+        assert_eq!(svg.matches("url(#").count(), 2);
+
+        let uncapped = SvgConfig {
+            optimize: false,
+            ..SvgConfig::default()
+        };
+        let (svg, converted) =
+            generate_svg_document_with_gradients(&paths, &gradients, 100, 10, &uncapped);
+        assert_eq!(converted, 0);
+        assert_eq!(svg.matches("<linearGradient").count(), 4);
 
-    // Add stroke-width
-    if path.stroke_width > 0.0 {
-        element.push_str(&format!(r#" stroke-width="{}""#, path.stroke_width));
+        // Layers keep their own paths after the weakest gradients are repainted
+        let layers = [("back", &paths[..1]), ("front", &paths[1..])];
+        let (svg, converted) =
+            generate_layered_svg_document_with_gradients(&layers, &gradients, 100, 10, &config);
+        assert_eq!(converted, 2);
+        assert_eq!(svg.matches("<linearGradient").count(), 2);
+        let front = &svg[svg.find(r#"<g id="front">"#).unwrap()..];
+        let back = &svg[svg.find(r#"<g id="back">"#).unwrap()..svg.len() - front.len()];
+        assert_eq!(back.matches("<path").count(), 1);
+        assert!(back.contains("url(#gradient_linear__0)"));
+        assert_eq!(front.matches("<path").count(), 3);
+        assert!(front.contains("#808080"));
     }
 
-    element.push_str(" />");
-    element
+    #[test]
+    fn test_gradient_document_follows_coordinate_origin_and_grouping() {
+        use crate::config::CoordinateOrigin;
+
+        let gradients = vec![linear(0, 100.0, "#ffffff")];
+        let paths = vec![
+            SvgPath::new_stroke("M 0 5 L 100 5".to_string(), "url(#gradient_linear__0)", 1.0),
+            SvgPath::new_stroke("M 0 8 L 100 8".to_string(), "#000000", 6.0),
+        ];
+        let config = SvgConfig {
+            coordinate_origin: CoordinateOrigin::Center,
+            group_by_thickness: true,
+            optimize: false,
+            ..SvgConfig::default()
+        };
+        let (svg, _) = generate_svg_document_with_gradients(&paths, &gradients, 100, 10, &config);
+
+        assert!(svg.contains(r#"viewBox="-50 -5 100 10""#));
+        assert!(svg.contains(r#"x1="-50" y1="-5" x2="50" y2="-5""#));
+        assert!(svg.contains(r#"d="M -50.00 0.00 L 50.00 0.00""#));
+        assert!(svg.contains(r#"<g id="thickness-thin">"#));
+        assert!(svg.contains(r#"<g id="thickness-thick">"#));
+
+        let bottom_left = SvgConfig {
+            coordinate_origin: CoordinateOrigin::BottomLeft,
+            optimize: false,
+            ..SvgConfig::default()
+        };
+        let (svg, _) =
+            generate_svg_document_with_gradients(&paths, &gradients, 100, 10, &bottom_left);
+        assert!(svg.contains(r#"<g transform="scale(1 -1)">"#));
+        assert!(svg.contains(r#"x1="0" y1="10" x2="100" y2="10""#));
+    }
 }
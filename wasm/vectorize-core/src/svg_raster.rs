@@ -0,0 +1,228 @@
+//! Grayscale and coverage rasterization of generated SVG documents
+//!
+//! Documents are parsed with `usvg` and drawn with `resvg`, so every element type
+//! the SVG writer emits (paths, circles, ellipses, lines, rects and polygons, inside
+//! transformed groups) renders as a browser would. Whole documents render onto a
+//! white luminance canvas so two outputs can be compared numerically (e.g. with
+//! SSIM); single elements render as coverage masks for selection UIs.
+
+use image::{GrayImage, Luma};
+use resvg::{tiny_skia, usvg};
+
+/// Window size for block SSIM
+const SSIM_WINDOW: u32 = 8;
+
+/// Rasterize an SVG document to grayscale, scaled to fit within `max_dimension`
+///
+/// The canvas size comes from the root `viewBox` (or `width`/`height`). Returns
+/// `None` when the document has no usable size.
+pub fn rasterize_svg_luma(svg: &str, max_dimension: u32) -> Option<GrayImage> {
+    render_luma(svg, max_dimension)
+}
+
+/// Rasterize the coverage of the element with `id="element_id"` at `width`×`height`
+///
+/// The document is stretched over the canvas, so passing the source image size
+/// maps the element back to source pixels. Every element type the SVG writer
+/// emits is supported (the document is parsed with `usvg`, which normalizes
+/// shapes to paths). Pixels covered by the element's fill or stroke are white and
+/// all others black; paint colors and opacity are ignored. Returns `None` when the
+/// document can't be parsed or no element has that id.
+pub fn rasterize_element_mask(
+    svg: &str,
+    element_id: &str,
+    width: u32,
+    height: u32,
+) -> Option<GrayImage> {
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).ok()?;
+    let node = tree.node_by_id(element_id)?;
+    let (width, height) = (width.max(1), height.max(1));
+    let mut pixmap = tiny_skia::Pixmap::new(width, height)?;
+    let canvas = tiny_skia::Transform::from_scale(
+        width as f32 / tree.size().width(),
+        height as f32 / tree.size().height(),
+    );
+    paint_coverage(node, canvas, &mut pixmap);
+
+    Some(GrayImage::from_fn(width, height, |x, y| {
+        let covered = pixmap.pixel(x, y).is_some_and(|p| p.alpha() > 0);
+        Luma([if covered { 255 } else { 0 }])
+    }))
+}
+
+/// Paint every visible path under `node` in opaque black, ignoring its paint
+fn paint_coverage(node: &usvg::Node, canvas: tiny_skia::Transform, pixmap: &mut tiny_skia::Pixmap) {
+    match node {
+        usvg::Node::Group(group) => {
+            for child in group.children() {
+                paint_coverage(child, canvas, pixmap);
+            }
+        }
+        usvg::Node::Path(path) if path.is_visible() => {
+            let paint = tiny_skia::Paint {
+                anti_alias: false,
+                ..tiny_skia::Paint::default()
+            };
+            let transform = canvas.pre_concat(path.abs_transform());
+            if let Some(fill) = path.fill() {
+                let rule = match fill.rule() {
+                    usvg::FillRule::NonZero => tiny_skia::FillRule::Winding,
+                    usvg::FillRule::EvenOdd => tiny_skia::FillRule::EvenOdd,
+                };
+                pixmap.fill_path(path.data(), &paint, rule, transform, None);
+            }
+            if let Some(stroke) = path.stroke() {
+                pixmap.stroke_path(path.data(), &paint, &stroke.to_tiny_skia(), transform, None);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Render onto a white canvas, scaled to fit within `max_dimension`
+fn render_luma(svg: &str, max_dimension: u32) -> Option<GrayImage> {
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).ok()?;
+    let size = tree.size();
+    let scale = (max_dimension.max(1) as f32 / size.width().max(size.height())).min(1.0);
+    let width = ((size.width() * scale).round() as u32).max(1);
+    let height = ((size.height() * scale).round() as u32).max(1);
+
+    let mut pixmap = tiny_skia::Pixmap::new(width, height)?;
+    pixmap.fill(tiny_skia::Color::WHITE);
+    let canvas = tiny_skia::Transform::from_scale(
+        width as f32 / size.width(),
+        height as f32 / size.height(),
+    );
+    resvg::render(&tree, canvas, &mut pixmap.as_mut());
+
+    // The white background keeps every pixel opaque, so channels aren't premultiplied
+    Some(GrayImage::from_fn(width, height, |x, y| {
+        let luma = pixmap.pixel(x, y).map_or(255.0, |p| {
+            0.299 * p.red() as f32 + 0.587 * p.green() as f32 + 0.114 * p.blue() as f32
+        });
+        Luma([luma.round().clamp(0.0, 255.0) as u8])
+    }))
+}
+
+/// Mean structural similarity of two same-sized grayscale images
+///
+/// Computed over non-overlapping 8×8 blocks (one block for smaller images).
+/// Returns `None` when the sizes differ.
+pub fn ssim(a: &GrayImage, b: &GrayImage) -> Option<f32> {
+    const C1: f64 = 6.5025; // (0.01 * 255)^2
+    const C2: f64 = 58.5225; // (0.03 * 255)^2
+
+    if a.dimensions() != b.dimensions() {
+        return None;
+    }
+    let (width, height) = a.dimensions();
+    let window = SSIM_WINDOW.min(width).min(height).max(1);
+
+    let mut total = 0.0f64;
+    let mut blocks = 0u32;
+    for by in (0..=height.saturating_sub(window)).step_by(window as usize) {
+        for bx in (0..=width.saturating_sub(window)).step_by(window as usize) {
+            let samples: Vec<(f64, f64)> = (by..by + window)
+                .flat_map(|y| (bx..bx + window).map(move |x| (x, y)))
+                .map(|(x, y)| (a.get_pixel(x, y).0[0] as f64, b.get_pixel(x, y).0[0] as f64))
+                .collect();
+            let n = samples.len() as f64;
+            let mean_a = samples.iter().map(|s| s.0).sum::<f64>() / n;
+            let mean_b = samples.iter().map(|s| s.1).sum::<f64>() / n;
+            let (mut var_a, mut var_b, mut covariance) = (0.0, 0.0, 0.0);
+            for (va, vb) in &samples {
+                var_a += (va - mean_a).powi(2);
+                var_b += (vb - mean_b).powi(2);
+                covariance += (va - mean_a) * (vb - mean_b);
+            }
+            let (var_a, var_b, covariance) = (var_a / n, var_b / n, covariance / n);
+
+            total += ((2.0 * mean_a * mean_b + C1) * (2.0 * covariance + C2))
+                / ((mean_a.powi(2) + mean_b.powi(2) + C1) * (var_a + var_b + C2));
+            blocks += 1;
+        }
+    }
+
+    Some((total / blocks.max(1) as f64) as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rasterize_svg_luma_fills_and_strokes() {
+        let svg = r##"<svg width="20" height="10" viewBox="0 0 20 10" xmlns="http://www.w3.org/2000/svg">
+  <path d="M 0 0 L 10 0 L 10 10 L 0 10 Z" fill="#000000" stroke="none"/>
+  <path d="M 12 5 L 20 5" fill="none" stroke="#ffffff" stroke-width="2"/>
+  <circle cx="15" cy="2" r="1.5" fill="#808080"/>
+</svg>"##;
+        let image = rasterize_svg_luma(svg, 512).unwrap();
+        assert_eq!(image.dimensions(), (20, 10));
+        assert_eq!(image.get_pixel(5, 5).0[0], 0);
+        assert_eq!(image.get_pixel(15, 8).0[0], 255);
+        assert_eq!(image.get_pixel(15, 2).0[0], 128);
+
+        let small = rasterize_svg_luma(svg, 10).unwrap();
+        assert_eq!(small.dimensions(), (10, 5));
+        assert!(rasterize_svg_luma("<svg></svg>", 64).is_none());
+    }
+
+    #[test]
+    fn test_rasterize_svg_luma_applies_group_transforms() {
+        let svg = r##"<svg width="20" height="10" viewBox="0 0 20 10" xmlns="http://www.w3.org/2000/svg">
+  <g transform="translate(20 0) scale(-1 1)">
+  <path d="M 0 0 L 5 0 L 5 10 L 0 10 Z" fill="#000000"/>
+  </g>
+  <circle cx="2" cy="5" r="2" fill="#000000"/>
+</svg>"##;
+        let image = rasterize_svg_luma(svg, 512).unwrap();
+        assert_eq!(image.get_pixel(17, 5).0[0], 0);
+        assert_eq!(image.get_pixel(2, 2).0[0], 255);
+        assert_eq!(image.get_pixel(2, 5).0[0], 0);
+    }
+
+    #[test]
+    fn test_rasterize_svg_luma_draws_every_element_type() {
+        let svg = r##"<svg width="40" height="10" viewBox="0 0 40 10" xmlns="http://www.w3.org/2000/svg">
+  <rect x="0" y="0" width="6" height="10" fill="#000000"/>
+  <ellipse cx="10" cy="5" rx="2" ry="4" fill="#000000"/>
+  <line x1="16" y1="0" x2="16" y2="10" stroke="#000000" stroke-width="2"/>
+  <polygon points="20,0 30,0 30,10" fill="#000000"/>
+  <g transform="rotate(90 35 5)"><rect x="31" y="4" width="8" height="2" fill="#000000"/></g>
+</svg>"##;
+        let image = rasterize_svg_luma(svg, 512).unwrap();
+        for (x, y) in [(3, 5), (10, 5), (16, 5), (28, 2), (35, 1)] {
+            assert_eq!(image.get_pixel(x, y).0[0], 0, "pixel ({x}, {y})");
+        }
+        assert_eq!(image.get_pixel(22, 8).0[0], 255);
+        assert_eq!(image.get_pixel(38, 5).0[0], 255);
+    }
+
+    #[test]
+    fn test_rasterize_element_mask_selects_one_element() {
+        let svg = r##"<svg width="20" height="10" viewBox="0 0 20 10" xmlns="http://www.w3.org/2000/svg">
+  <path d="M 0 0 L 10 0 L 10 10 L 0 10 Z" fill="#ffffff" id="path-0"/>
+  <path d="M 12 5 L 20 5" fill="none" stroke="#202020" stroke-width="2" stroke-opacity="0.3" id="path-1"/>
+</svg>"##;
+        // Source image at twice the viewBox resolution
+        let mask = rasterize_element_mask(svg, "path-1", 40, 20).unwrap();
+        assert_eq!(mask.dimensions(), (40, 20));
+        assert_eq!(mask.get_pixel(30, 9).0[0], 255);
+        assert_eq!(mask.get_pixel(30, 2).0[0], 0);
+        assert_eq!(mask.get_pixel(5, 9).0[0], 0, "other elements are not drawn");
+
+        let fill = rasterize_element_mask(svg, "path-0", 20, 10).unwrap();
+        assert_eq!(fill.get_pixel(5, 5).0[0], 255);
+        assert!(rasterize_element_mask(svg, "path-2", 20, 10).is_none());
+    }
+
+    #[test]
+    fn test_ssim_identical_and_different() {
+        let a = GrayImage::from_fn(32, 32, |x, y| Luma([((x * 7 + y * 3) % 256) as u8]));
+        let inverted = GrayImage::from_fn(32, 32, |x, y| Luma([255 - a.get_pixel(x, y).0[0]]));
+        assert!((ssim(&a, &a).unwrap() - 1.0).abs() < 1e-6);
+        assert!(ssim(&a, &inverted).unwrap() < 0.5);
+        assert!(ssim(&a, &GrayImage::new(8, 8)).is_none());
+    }
+}
@@ -11,6 +11,7 @@
 
 use chrono::Utc;
 use serde::Serialize;
+use std::cell::RefCell;
 
 #[cfg(feature = "file-output")]
 use std::fs::{self, File, OpenOptions};
@@ -36,6 +37,7 @@ pub struct Resolved {
     // Segmentation & color (regions pipeline)
     pub slic_step_px: Option<u32>, // actual step/side length, not "area"
     pub slic_iters: Option<u32>,
+    pub slic_iters_used: Option<u32>, // fewer than slic_iters when SLIC converged early
     pub slic_compactness: Option<f32>,
     pub de_merge: Option<f64>, // LAB ΔE
     pub de_split: Option<f64>, // LAB ΔE
@@ -48,11 +50,15 @@ pub struct Resolved {
 
     // General
     pub min_region_area_px: Option<u32>,
+
+    // Multipass edge tracing
+    pub directional_passes: Option<Vec<crate::algorithms::tracing::trace_low::ProcessingDirection>>,
 }
 
 #[derive(Debug, Serialize, Default)]
 pub struct Guards {
     pub retries: u32,
+    pub denoise_escalations: u32, // noise-dominated edge passes re-run with raised thresholds
     pub edge_barrier_thresh: Option<u32>, // e.g., Sobel magnitude 0..255
     pub area_floor_px: Option<u32>,
 }
@@ -247,6 +253,37 @@ pub fn make_dump(
     }
 }
 
+/// Resolved parameters and guard counters reported by the pipeline during one run
+#[derive(Debug, Default)]
+pub struct RunRecord {
+    pub resolved: Resolved,
+    pub guards: Guards,
+}
+
+thread_local! {
+    /// Record being filled by `capture` on this thread, if any
+    static RUN_RECORD: RefCell<Option<RunRecord>> = const { RefCell::new(None) };
+}
+
+/// Run `f` and return what the pipeline reported while it ran on this thread
+///
+/// Pass the record's `resolved` and `guards` to `make_dump` to write them out.
+pub fn capture<R>(f: impl FnOnce() -> R) -> (R, RunRecord) {
+    let outer = RUN_RECORD.with(|record| record.replace(Some(RunRecord::default())));
+    let result = f();
+    let record = RUN_RECORD.with(|record| record.replace(outer));
+    (result, record.unwrap_or_default())
+}
+
+/// Update the record of the enclosing `capture`; a no-op outside one
+pub(crate) fn record(update: impl FnOnce(&mut RunRecord)) {
+    RUN_RECORD.with(|record| {
+        if let Some(record) = record.borrow_mut().as_mut() {
+            update(record);
+        }
+    });
+}
+
 // ----------------- internal helpers -----------------
 
 #[cfg(feature = "file-output")]
@@ -1,6 +1,8 @@
 //! Utility modules for core vectorization algorithms
 
+pub mod color;
 pub mod wasm_time;
 
+pub use color::{normalize_color, parse_color};
 // Re-export commonly used time types for convenience
 pub use wasm_time::{measure_and_log, measure_time, Instant, Timer};
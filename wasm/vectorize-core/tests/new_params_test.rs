@@ -142,6 +142,40 @@ fn test_svg_precision_in_output() {
     );
 }
 
+#[test]
+fn test_emit_path_ids_in_output() {
+    let img = ImageBuffer::from_fn(50, 50, |x, y| {
+        if (x / 10 + y / 10) % 2 == 0 {
+            Rgba([255, 255, 255, 255]) // White
+        } else {
+            Rgba([0, 0, 0, 255]) // Black
+        }
+    });
+
+    let config = ConfigBuilder::new()
+        .backend(TraceBackend::Edge)
+        .detail(0.5)
+        .unwrap()
+        .emit_path_ids(true)
+        .build()
+        .unwrap();
+    assert!(config.emit_path_ids);
+
+    let first = vectorize_trace_low_rgba(&img, &config, None).unwrap();
+    let second = vectorize_trace_low_rgba(&img, &config, None).unwrap();
+
+    assert!(first.contains(r#"id="path-0""#), "Paths should carry id attributes");
+    assert_eq!(first, second, "Identical runs should produce identical IDs");
+
+    let config_no_ids = TraceLowConfig {
+        backend: TraceBackend::Edge,
+        detail: 0.5,
+        ..Default::default()
+    };
+    let svg_no_ids = vectorize_trace_low_rgba(&img, &config_no_ids, None).unwrap();
+    assert!(!svg_no_ids.contains(" id=\""), "IDs should be off by default");
+}
+
 #[test]
 fn test_default_values() {
     let config = TraceLowConfig::default();
@@ -289,11 +289,15 @@ impl fmt::Display for ThreadingError {
 
 impl std::error::Error for ThreadingError {}
 
+/// Default number of retries for transient failures
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// Default base delay for exponential backoff between retries
+pub const DEFAULT_BASE_DELAY_MS: u64 = 1000;
+
 /// Error recovery manager for handling threading failures
 pub struct ErrorRecoveryManager {
-    #[allow(dead_code)]
     max_retries: u32,
-    #[allow(dead_code)]
     base_delay_ms: u64,
     #[allow(dead_code)]
     retry_counts: std::collections::HashMap<String, u32>,
@@ -309,6 +313,23 @@ impl ErrorRecoveryManager {
         }
     }
 
+    /// Replace the retry policy, forgetting retries counted under the old one
+    pub fn set_policy(&mut self, max_retries: u32, base_delay_ms: u64) {
+        self.max_retries = max_retries;
+        self.base_delay_ms = base_delay_ms;
+        self.retry_counts.clear();
+    }
+
+    /// Backoff delay before retry number `retry` (1-based), or `None` once
+    /// the retry budget is exhausted
+    pub fn retry_delay_ms(&self, retry: u32) -> Option<u64> {
+        if retry == 0 || retry > self.max_retries {
+            return None;
+        }
+        // Exponential backoff, capped to keep the shift in range
+        Some(self.base_delay_ms.saturating_mul(1 << (retry - 1).min(16)))
+    }
+
     /// Attempt to recover from a threading error
     #[allow(dead_code)]
     pub async fn recover_from_error(
@@ -359,8 +380,7 @@ impl ErrorRecoveryManager {
         let retry_count = self.retry_counts.get(reason).unwrap_or(&0) + 1;
         self.retry_counts.insert(reason.to_string(), retry_count);
 
-        if retry_count <= self.max_retries {
-            let delay = self.base_delay_ms * (1 << (retry_count - 1)); // Exponential backoff
+        if let Some(delay) = self.retry_delay_ms(retry_count) {
             Ok(RecoveryAction::RetryAfterDelay { delay_ms: delay })
         } else {
             Ok(RecoveryAction::FallbackToSingleThreaded {
@@ -428,6 +448,55 @@ pub enum RecoveryAction {
     Continue,
 }
 
+/// Whether a processing error message describes a transient GPU or allocation
+/// failure that may succeed on retry
+pub fn is_transient_error(message: &str) -> bool {
+    const TRANSIENT_MARKERS: [&str; 6] = [
+        "device lost",
+        "out of memory",
+        "allocation",
+        "failed to initialize",
+        "timed out",
+        "timeout",
+    ];
+    let message = message.to_lowercase();
+    TRANSIENT_MARKERS
+        .iter()
+        .any(|marker| message.contains(marker))
+}
+
+/// Wait `delay_ms` before retrying
+///
+/// Uses the global `setTimeout` in WASM (works in both windows and Web Workers)
+/// and blocks the calling thread natively.
+pub async fn backoff_delay(delay_ms: u64) {
+    if delay_ms == 0 {
+        return;
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+            let global = js_sys::global();
+            let set_timeout = js_sys::Reflect::get(&global, &JsValue::from_str("setTimeout"))
+                .ok()
+                .and_then(|f| f.dyn_into::<js_sys::Function>().ok());
+            match set_timeout {
+                Some(set_timeout) => {
+                    let _ = set_timeout.call2(&global, &resolve, &JsValue::from_f64(delay_ms as f64));
+                }
+                None => {
+                    let _ = resolve.call0(&JsValue::NULL);
+                }
+            }
+        });
+        let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    std::thread::sleep(std::time::Duration::from_millis(delay_ms));
+}
+
 /// Create error context with current environment information
 #[allow(dead_code)]
 pub fn create_error_context(error: ThreadingError) -> ErrorContext {
@@ -668,6 +737,25 @@ mod tests {
         assert_eq!(manager.base_delay_ms, 1000);
     }
 
+    #[test]
+    fn test_retry_delay_follows_policy() {
+        let mut manager = ErrorRecoveryManager::new(3, 100);
+        assert_eq!(manager.retry_delay_ms(1), Some(100));
+        assert_eq!(manager.retry_delay_ms(3), Some(400));
+        assert_eq!(manager.retry_delay_ms(4), None);
+
+        manager.set_policy(1, 50);
+        assert_eq!(manager.retry_delay_ms(1), Some(50));
+        assert_eq!(manager.retry_delay_ms(2), None);
+    }
+
+    #[test]
+    fn test_transient_error_detection() {
+        assert!(is_transient_error("GPU device lost during dispatch"));
+        assert!(is_transient_error("Failed to initialize GPU device"));
+        assert!(!is_transient_error("Config build failed: invalid detail"));
+    }
+
     #[test]
     fn test_environment_info_gathering() {
         let env_info = gather_environment_info();
@@ -134,6 +134,47 @@ pub struct GpuContext {
 // static GPU_CONTEXT: OnceLock<Arc<Mutex<Option<GpuContext>>>> = OnceLock::new();
 static GPU_STATUS: OnceLock<Arc<Mutex<GpuStatus>>> = OnceLock::new();
 
+// The processing device is thread-local since wgpu handles aren't `Send` on WASM
+#[cfg(feature = "gpu-acceleration")]
+thread_local! {
+    static PROCESSING_DEVICE: std::cell::RefCell<Option<Arc<vectorize_core::gpu::GpuDevice>>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// GPU device for image processing, created on first use and reused by later runs
+#[cfg(feature = "gpu-acceleration")]
+pub(crate) async fn processing_gpu_device() -> Option<Arc<vectorize_core::gpu::GpuDevice>> {
+    if let Some(device) = PROCESSING_DEVICE.with(|cached| cached.borrow().clone()) {
+        return Some(device);
+    }
+    let device = Arc::new(vectorize_core::gpu::device::try_init_gpu().await?);
+    PROCESSING_DEVICE.with(|cached| *cached.borrow_mut() = Some(device.clone()));
+    Some(device)
+}
+
+/// Destroy the cached GPU device along with every buffer and pipeline allocated on it
+///
+/// Resources left by cancelled or failed runs otherwise hold GPU memory until the
+/// garbage collector gets to them. GPU work still in flight fails with a lost device,
+/// and the next GPU run creates a fresh one. Complements `emergency_cleanup`, which
+/// only resets CPU-side recovery state.
+///
+/// Returns whether a device was released.
+#[wasm_bindgen]
+pub fn release_gpu_resources() -> bool {
+    #[cfg(feature = "gpu-acceleration")]
+    {
+        if let Some(gpu) = PROCESSING_DEVICE.with(|cached| cached.borrow_mut().take()) {
+            gpu.device.destroy();
+            log::info!("🧹 Released GPU device: {}", gpu.adapter_info.name);
+            return true;
+        }
+    }
+
+    log::debug!("No GPU resources to release");
+    false
+}
+
 /// Initialize GPU backend with automatic detection and fallback
 #[wasm_bindgen]
 pub async fn initialize_gpu_backend() -> Result<GpuBackendInfo, JsValue> {
@@ -486,6 +527,13 @@ mod tests {
         assert_eq!(active, status.backend());
     }
 
+    #[wasm_bindgen_test]
+    fn test_release_gpu_resources_without_device() {
+        // Nothing cached yet, and releasing twice is harmless
+        assert!(!release_gpu_resources());
+        assert!(!release_gpu_resources());
+    }
+
     #[wasm_bindgen_test]
     fn test_capability_report() {
         let report = get_gpu_capability_report();
@@ -22,15 +22,28 @@ mod vectorizer_refactored;
 #[cfg(feature = "generate-ts")]
 mod type_generation;
 
-use crate::error::ErrorRecoveryManager;
+use crate::error::{ErrorRecoveryManager, DEFAULT_BASE_DELAY_MS, DEFAULT_MAX_RETRIES};
+use crate::utils::emit_progress;
 use image::ImageBuffer;
 use js_sys::Function;
 use serde::{Deserialize, Serialize};
 use std::sync::Mutex;
 use vectorize_core::{
-    algorithms::{TraceBackend, tracing::trace_low::BackgroundRemovalAlgorithm}, 
+    algorithms::{
+        gradient_normal_map, HandDrawnTarget, TraceBackend,
+        tracing::trace_low::{
+            detect_background_removal_mask, BackgroundRemovalAlgorithm, MultipassMerge,
+            ProcessingDirection,
+        },
+    },
     config_builder::ConfigBuilder, 
-    vectorize_trace_low_rgba,
+    preprocessing::{
+        apply_embedded_dpi, combine_images, decode_image_fitted, difference_image, StackCombineMode, DEFAULT_DIFF_THRESHOLD,
+    },
+    svg::generate_svg_sprite,
+    telemetry,
+    vectorize_trace_low_layered_depth, vectorize_trace_low_progressive, vectorize_trace_low_rgba,
+    ColorSpace, CoordinateOrigin, NonFiniteHandling, TraceLowConfig, Transform,
 };
 use wasm_bindgen::prelude::*;
 use web_sys::ImageData;
@@ -59,7 +72,7 @@ pub fn wasm_init() {
     
     // Initialize error recovery manager
     if let Ok(mut manager) = ERROR_RECOVERY_MANAGER.lock() {
-        *manager = Some(ErrorRecoveryManager::new(3, 1000)); // 3 retries, 1000ms base delay
+        *manager = Some(ErrorRecoveryManager::new(DEFAULT_MAX_RETRIES, DEFAULT_BASE_DELAY_MS));
         log::info!("Error recovery manager initialized");
     }
 
@@ -110,6 +123,8 @@ impl WasmProgress {
 pub struct WasmVectorizer {
     backend: TraceBackend,
     config_builder: ConfigBuilder,
+    /// Directional passes chosen for the last frame, fed back for `directional_hysteresis`
+    directional_passes: Mutex<Option<Vec<ProcessingDirection>>>,
 }
 
 #[wasm_bindgen]
@@ -120,6 +135,7 @@ impl WasmVectorizer {
         WasmVectorizer {
             backend: TraceBackend::Edge,
             config_builder: ConfigBuilder::new(),
+            directional_passes: Mutex::new(None),
         }
     }
 
@@ -144,6 +160,20 @@ impl WasmVectorizer {
         unified_config::validate_config_json(config_json)
     }
 
+    /// Export backend + non-default settings + crate version as a compact recipe
+    /// string, suitable for pasting into bug reports
+    #[wasm_bindgen]
+    pub fn export_recipe(&self) -> Result<String, JsValue> {
+        unified_config::export_recipe(&self.config_builder)
+    }
+
+    /// Replace the current configuration with one from `export_recipe`
+    #[wasm_bindgen]
+    pub fn import_recipe(&mut self, recipe: &str) -> Result<(), JsValue> {
+        self.backend = unified_config::import_recipe(&mut self.config_builder, recipe)?;
+        Ok(())
+    }
+
     // ===== DEPRECATED INDIVIDUAL SETTERS (to be removed) =====
     // These remain temporarily for backward compatibility but should be removed
     // once frontend is fully migrated to the unified config system
@@ -156,6 +186,7 @@ impl WasmVectorizer {
             "centerline" => TraceBackend::Centerline,
             "superpixel" => TraceBackend::Superpixel,
             "dots" => TraceBackend::Dots,
+            "lowpoly" => TraceBackend::LowPoly,
             _ => {
                 return Err(JsValue::from_str(&format!("Unknown backend: {}", backend)));
             }
@@ -168,25 +199,9 @@ impl WasmVectorizer {
         
         self.backend = backend;
         
-        // CRITICAL FIX: Preserve existing user settings when setting backend
-        // This prevents loss of previously configured parameters
-        match self.config_builder.clone().backend(backend).build() {
-            Ok(_) => {
-                // If current config is valid, apply backend setting to existing config
-                self.config_builder = self.config_builder.clone().backend(backend);
-                log::info!("✅ WASM: Backend set to {:?} (preserving existing settings)", backend);
-                web_sys::console::log_1(&format!("✅ DIRECT: Backend preserving settings for {:?}", backend).into());
-            }
-            Err(e) => {
-                // DEBUGGING: Show WHY validation failed
-                web_sys::console::log_1(&format!("❌ DIRECT: Config validation FAILED, error: {}", e).into());
-                web_sys::console::log_1(&"❌ DIRECT: Falling back to fresh builder - THIS LOSES USER SETTINGS!".into());
-                
-                // If invalid, create fresh builder with backend only (user will re-apply settings)
-                self.config_builder = ConfigBuilder::new().backend(backend);
-                log::info!("✅ WASM: Backend set to {:?} (fresh config - settings need re-application)", backend);
-            }
-        }
+        // Keep every existing setting the new backend accepts; only settings it
+        // would reject (e.g. ETF/FDoG on dots) are cleared by the builder
+        self.config_builder = self.config_builder.clone().switch_backend(backend);
             
         log::info!("✅ WASM: Backend set to: {:?} with config preservation strategy", self.backend);
         Ok(())
@@ -196,21 +211,9 @@ impl WasmVectorizer {
     #[wasm_bindgen]
     pub fn set_detail(&mut self, detail: f32) -> Result<(), JsValue> {
         log::info!("🔧 WASM: set_detail called with detail={}", detail);
-        // Use try_build() to avoid recursive aliasing in clone operations
-        match self.config_builder.clone().build() {
-            Ok(_) => {
-                // If current config is valid, apply detail setting
-                self.config_builder = self.config_builder.clone().detail(detail)
-                    .map_err(|e| JsValue::from_str(&format!("Failed to set detail: {}", e)))?;
-                log::info!("✅ WASM: Detail set to {}", detail);
-            }
-            Err(_) => {
-                // If invalid, preserve settings and retry
-                self.config_builder = self.config_builder.clone().detail(detail)
-                    .map_err(|e| JsValue::from_str(&format!("Failed to set detail preserving settings: {}", e)))?;
-                log::info!("✅ WASM: Detail set to {} (preserving existing settings)", detail);
-            }
-        }
+        self.config_builder = self.config_builder.clone().detail(detail)
+            .map_err(|e| JsValue::from_str(&format!("Failed to set detail: {}", e)))?;
+        log::info!("✅ WASM: Detail set to {}", detail);
         Ok(())
     }
 
@@ -226,40 +229,17 @@ impl WasmVectorizer {
     #[wasm_bindgen]
     pub fn set_multipass(&mut self, enabled: bool) {
         log::info!("🔧 WASM: set_multipass called with enabled={}", enabled);
-        // Use try_build() to avoid recursive aliasing in clone operations
-        match self.config_builder.clone().build() {
-            Ok(_) => {
-                // If current config is valid, apply multipass setting
-                self.config_builder = self.config_builder.clone().multipass(enabled);
-                log::info!("✅ WASM: Multipass set to {} (cloned config)", enabled);
-            }
-            Err(_) => {
-                // If invalid, preserve settings and retry
-                self.config_builder = self.config_builder.clone().multipass(enabled);
-                log::info!("✅ WASM: Multipass set to {} (preserving existing settings)", enabled);
-            }
-        }
+        self.config_builder = self.config_builder.clone().multipass(enabled);
+        log::info!("✅ WASM: Multipass set to {}", enabled);
     }
 
     /// Set number of processing passes (1-10)
     #[wasm_bindgen]
     pub fn set_pass_count(&mut self, count: u32) -> Result<(), JsValue> {
         log::info!("🔧 WASM: set_pass_count called with count={}", count);
-        // Use try_build() to avoid recursive aliasing in clone operations
-        match self.config_builder.clone().build() {
-            Ok(_) => {
-                // If current config is valid, apply pass count setting
-                self.config_builder = self.config_builder.clone().pass_count(count)
-                    .map_err(|e| JsValue::from_str(&format!("Failed to set pass count: {}", e)))?;
-                log::info!("✅ WASM: Pass count set to {} (cloned config)", count);
-            }
-            Err(_) => {
-                // If invalid, preserve settings and retry
-                self.config_builder = self.config_builder.clone().pass_count(count)
-                    .map_err(|e| JsValue::from_str(&format!("Failed to set pass count preserving settings: {}", e)))?;
-                log::info!("✅ WASM: Pass count set to {} (preserving existing settings)", count);
-            }
-        }
+        self.config_builder = self.config_builder.clone().pass_count(count)
+            .map_err(|e| JsValue::from_str(&format!("Failed to set pass count: {}", e)))?;
+        log::info!("✅ WASM: Pass count set to {}", count);
         Ok(())
     }
 
@@ -275,38 +255,16 @@ impl WasmVectorizer {
     #[wasm_bindgen]
     pub fn set_reverse_pass(&mut self, enabled: bool) {
         log::info!("🔧 WASM: set_reverse_pass called with enabled={}", enabled);
-        // Use try_build() to avoid recursive aliasing in clone operations
-        match self.config_builder.clone().build() {
-            Ok(_) => {
-                // If current config is valid, apply reverse pass setting
-                self.config_builder = self.config_builder.clone().reverse_pass(enabled);
-                log::info!("✅ WASM: Reverse pass set to {} (cloned config)", enabled);
-            }
-            Err(_) => {
-                // If invalid, preserve settings and retry
-                self.config_builder = self.config_builder.clone().reverse_pass(enabled);
-                log::info!("✅ WASM: Reverse pass set to {} (preserving existing settings)", enabled);
-            }
-        }
+        self.config_builder = self.config_builder.clone().reverse_pass(enabled);
+        log::info!("✅ WASM: Reverse pass set to {}", enabled);
     }
 
     /// Enable or disable diagonal pass
     #[wasm_bindgen]
     pub fn set_diagonal_pass(&mut self, enabled: bool) {
         log::info!("🔧 WASM: set_diagonal_pass called with enabled={}", enabled);
-        // Use try_build() to avoid recursive aliasing in clone operations
-        match self.config_builder.clone().build() {
-            Ok(_) => {
-                // If current config is valid, apply diagonal pass setting
-                self.config_builder = self.config_builder.clone().diagonal_pass(enabled);
-                log::info!("✅ WASM: Diagonal pass set to {} (cloned config)", enabled);
-            }
-            Err(_) => {
-                // If invalid, preserve settings and retry
-                self.config_builder = self.config_builder.clone().diagonal_pass(enabled);
-                log::info!("✅ WASM: Diagonal pass set to {} (preserving existing settings)", enabled);
-            }
-        }
+        self.config_builder = self.config_builder.clone().diagonal_pass(enabled);
+        log::info!("✅ WASM: Diagonal pass set to {}", enabled);
     }
 
     /// Enable or disable ETF/FDoG edge detection
@@ -351,6 +309,45 @@ impl WasmVectorizer {
         Ok(())
     }
 
+    /// Set the margin a directional benefit must cross the threshold by before a
+    /// pass switches on or off relative to the previous `vectorize` call, so similar
+    /// frames select the same passes (0.0-0.5)
+    #[wasm_bindgen]
+    pub fn set_directional_hysteresis(&mut self, margin: f32) -> Result<(), JsValue> {
+        self.config_builder = self.config_builder.clone().directional_hysteresis(margin)
+            .map_err(|e| JsValue::from_str(&format!("Failed to set directional hysteresis: {}", e)))?;
+        Ok(())
+    }
+
+    /// Forget the directional passes of the previous frame, e.g. when starting a new
+    /// animation, so the next frame compares against the plain threshold
+    #[wasm_bindgen]
+    pub fn reset_directional_passes(&self) {
+        if let Ok(mut passes) = self.directional_passes.lock() {
+            *passes = None;
+        }
+    }
+
+    /// How edges found again by directional passes are combined: "union" (keep the
+    /// first detection), "weighted-average" (average overlapping detections into one
+    /// stroke) or "max-response" (keep the strongest detection)
+    #[wasm_bindgen]
+    pub fn set_multipass_merge(&mut self, mode: &str) -> Result<(), JsValue> {
+        let mode = match mode.to_lowercase().as_str() {
+            "union" => MultipassMerge::Union,
+            "weighted-average" => MultipassMerge::WeightedAverage,
+            "max-response" => MultipassMerge::MaxResponse,
+            _ => {
+                return Err(JsValue::from_str(&format!(
+                    "Invalid multipass merge mode: {}. Use 'union', 'weighted-average' or 'max-response'",
+                    mode
+                )))
+            }
+        };
+        self.config_builder = self.config_builder.clone().multipass_merge(mode);
+        Ok(())
+    }
+
     /// Enable or disable noise filtering
     #[wasm_bindgen]
     pub fn set_noise_filtering(&mut self, enabled: bool) {
@@ -383,6 +380,351 @@ impl WasmVectorizer {
         Ok(())
     }
 
+    /// Enable or disable deterministic `id` attributes on output paths
+    #[wasm_bindgen]
+    pub fn set_emit_path_ids(&mut self, enabled: bool) {
+        self.config_builder = self.config_builder.clone().emit_path_ids(enabled);
+    }
+
+    /// Prepend an XML declaration for strict SVG consumers (browsers don't need it)
+    #[wasm_bindgen]
+    pub fn set_emit_xml_declaration(&mut self, enabled: bool) {
+        self.config_builder = self.config_builder.clone().emit_xml_declaration(enabled);
+    }
+
+    /// Declare `pathLength="100"` on every stroke so `stroke-dasharray`/
+    /// `stroke-dashoffset` draw-on animations work in percent regardless of
+    /// the stroke's real length
+    #[wasm_bindgen]
+    pub fn set_emit_path_length(&mut self, enabled: bool) {
+        self.config_builder = self.config_builder.clone().emit_path_length(enabled);
+    }
+
+    /// Embed a `<!-- source-hash: ... -->` comment identifying the source image and
+    /// settings, so cached outputs can be keyed on it
+    #[wasm_bindgen]
+    pub fn set_embed_source_hash(&mut self, enabled: bool) {
+        self.config_builder = self.config_builder.clone().embed_source_hash(enabled);
+    }
+
+    /// Mirror or rotate the output: "none", "flip-horizontal", "flip-vertical",
+    /// "rotate-90", "rotate-180" or "rotate-270" (rotations are clockwise)
+    #[wasm_bindgen]
+    pub fn set_output_transform(&mut self, transform: &str) -> Result<(), JsValue> {
+        let transform = match transform.to_lowercase().as_str() {
+            "none" => None,
+            "flip-horizontal" | "flip-h" => Some(Transform::FlipHorizontal),
+            "flip-vertical" | "flip-v" => Some(Transform::FlipVertical),
+            "rotate-90" => Some(Transform::Rotate90),
+            "rotate-180" => Some(Transform::Rotate180),
+            "rotate-270" => Some(Transform::Rotate270),
+            _ => return Err(JsValue::from_str(&format!(
+                "Invalid output transform: {}. Use 'none', 'flip-horizontal', 'flip-vertical', 'rotate-90', 'rotate-180' or 'rotate-270'",
+                transform
+            ))),
+        };
+        self.config_builder = self.config_builder.clone().output_transform(transform);
+        Ok(())
+    }
+
+    /// Origin of emitted coordinates: "top-left" (SVG default), "center" or
+    /// "bottom-left" (y pointing up, as in CAD and plotter tools)
+    #[wasm_bindgen]
+    pub fn set_coordinate_origin(&mut self, origin: &str) -> Result<(), JsValue> {
+        let origin = match origin.to_lowercase().as_str() {
+            "top-left" => CoordinateOrigin::TopLeft,
+            "center" => CoordinateOrigin::Center,
+            "bottom-left" => CoordinateOrigin::BottomLeft,
+            _ => {
+                return Err(JsValue::from_str(&format!(
+                    "Invalid coordinate origin: {}. Use 'top-left', 'center' or 'bottom-left'",
+                    origin
+                )))
+            }
+        };
+        self.config_builder = self.config_builder.clone().coordinate_origin(origin);
+        Ok(())
+    }
+
+    /// Treatment of NaN/infinite coordinates: "drop" removes the offending segment,
+    /// "clamp" pins the value to the viewport
+    #[wasm_bindgen]
+    pub fn set_non_finite_handling(&mut self, handling: &str) -> Result<(), JsValue> {
+        let handling = match handling.to_lowercase().as_str() {
+            "drop" => NonFiniteHandling::Drop,
+            "clamp" => NonFiniteHandling::Clamp,
+            _ => {
+                return Err(JsValue::from_str(&format!(
+                    "Invalid non-finite handling: {}. Use 'drop' or 'clamp'",
+                    handling
+                )))
+            }
+        };
+        self.config_builder = self.config_builder.clone().non_finite_handling(handling);
+        Ok(())
+    }
+
+    /// Treat both a dark frame and a light mat around a scan as background,
+    /// tracing only the mid-tone artwork between them
+    #[wasm_bindgen]
+    pub fn set_dual_background(&mut self, enabled: bool) {
+        self.config_builder = self.config_builder.clone().dual_background(enabled);
+    }
+
+    /// Blend banded gradients (e.g. a JPEG sky) into smooth ramps so regions
+    /// don't trace as stair-steps
+    #[wasm_bindgen]
+    pub fn set_deband(&mut self, enabled: bool) {
+        self.config_builder = self.config_builder.clone().deband(enabled);
+    }
+
+    /// Alpha below which pixels count as transparent background (0-255, default: 10)
+    ///
+    /// Raise it to drop soft edges and faint anti-aliased transparency from the trace.
+    #[wasm_bindgen]
+    pub fn set_alpha_threshold(&mut self, threshold: u8) {
+        self.config_builder = self.config_builder.clone().alpha_threshold(threshold);
+    }
+
+    /// Fewest non-transparent pixels an image needs before it is traced (default: 10)
+    ///
+    /// Lower it so tiny intentional marks, such as small icons, aren't treated as empty.
+    #[wasm_bindgen]
+    pub fn set_min_content_pixels(&mut self, count: usize) {
+        self.config_builder = self.config_builder.clone().min_content_pixels(count);
+    }
+
+    /// Trace labels and other text as crisp centerlines while the selected
+    /// backend handles the rest of the image (default: false)
+    #[wasm_bindgen]
+    pub fn set_text_aware(&mut self, enabled: bool) {
+        self.config_builder = self.config_builder.clone().text_aware(enabled);
+    }
+
+    /// Convert images with an embedded ICC profile to sRGB in `vectorize_encoded` (default: true)
+    #[wasm_bindgen]
+    pub fn set_apply_icc_profile(&mut self, enabled: bool) {
+        self.config_builder = self.config_builder.clone().apply_icc_profile(enabled);
+    }
+
+    /// Largest image, in megapixels, `vectorize_encoded` will decode (1-1000, default: 400)
+    ///
+    /// Sources larger than the processing size are reduced while decoding, so images
+    /// too big to paint onto a canvas can still be traced from their file bytes.
+    #[wasm_bindgen]
+    pub fn set_max_decode_megapixels(&mut self, megapixels: u32) -> Result<(), JsValue> {
+        self.config_builder = self.config_builder.clone().max_decode_megapixels(megapixels)
+            .map_err(|e| JsValue::from_str(&format!("Failed to set maximum decode size: {}", e)))?;
+        Ok(())
+    }
+
+    /// Size the SVG in inches at this many pixels per inch for print output
+    /// (pass undefined to keep pixel units)
+    #[wasm_bindgen]
+    pub fn set_output_dpi(&mut self, dpi: Option<f32>) -> Result<(), JsValue> {
+        self.config_builder = self.config_builder.clone().output_dpi(dpi)
+            .map_err(|e| JsValue::from_str(&format!("Failed to set output DPI: {}", e)))?;
+        Ok(())
+    }
+
+    /// Take the output DPI from PNG/JPEG density metadata in `vectorize_encoded`
+    /// unless one was set with `set_output_dpi`
+    #[wasm_bindgen]
+    pub fn set_use_embedded_dpi(&mut self, enabled: bool) {
+        self.config_builder = self.config_builder.clone().use_embedded_dpi(enabled);
+    }
+
+    /// Resample polyline output to evenly spaced points for stroke-draw animation
+    /// (pass undefined to keep the traced point distribution)
+    #[wasm_bindgen]
+    pub fn set_resample_even_spacing(&mut self, spacing: Option<f32>) -> Result<(), JsValue> {
+        self.config_builder = self.config_builder.clone().resample_even_spacing(spacing)
+            .map_err(|e| JsValue::from_str(&format!("Failed to set resample spacing: {}", e)))?;
+        Ok(())
+    }
+
+    /// Make the SVG animate its strokes drawing on, in path order, over this many
+    /// seconds (pass undefined for static output)
+    #[wasm_bindgen]
+    pub fn set_draw_on_animation(&mut self, duration_seconds: Option<f32>) -> Result<(), JsValue> {
+        self.config_builder = self.config_builder.clone().draw_on_animation(duration_seconds)
+            .map_err(|e| JsValue::from_str(&format!("Failed to set draw-on animation: {}", e)))?;
+        Ok(())
+    }
+
+    /// Use an exact stroke width in output pixels, ignoring resolution-relative scaling
+    /// (pass undefined to go back to `set_stroke_width` scaling)
+    #[wasm_bindgen]
+    pub fn set_absolute_stroke_width(&mut self, width: Option<f32>) -> Result<(), JsValue> {
+        self.config_builder = self.config_builder.clone().absolute_stroke_width(width)
+            .map_err(|e| JsValue::from_str(&format!("Failed to set absolute stroke width: {}", e)))?;
+        Ok(())
+    }
+
+    /// Vary each stroke's width randomly by up to this fraction (0.0-0.5, default: 0.0)
+    ///
+    /// Widths are reproducible for the same settings and independent of the hand-drawn presets.
+    #[wasm_bindgen]
+    pub fn set_stroke_width_jitter(&mut self, jitter: f32) -> Result<(), JsValue> {
+        self.config_builder = self.config_builder.clone().stroke_width_jitter(jitter)
+            .map_err(|e| JsValue::from_str(&format!("Failed to set stroke width jitter: {}", e)))?;
+        Ok(())
+    }
+
+    /// Enable or disable stroke-opacity fading based on edge strength (edge/centerline only)
+    #[wasm_bindgen]
+    pub fn set_edge_strength_opacity(&mut self, enabled: bool) {
+        self.config_builder = self.config_builder.clone().edge_strength_opacity(enabled);
+    }
+
+    /// Attach each path's source-image color as `data-source-color` for client-side recoloring
+    #[wasm_bindgen]
+    pub fn set_emit_source_color(&mut self, enabled: bool) {
+        self.config_builder = self.config_builder.clone().emit_source_color(enabled);
+    }
+
+    /// Attach each superpixel region's `data-centroid` and `data-area` for
+    /// data-driven recoloring and analytics
+    #[wasm_bindgen]
+    pub fn set_emit_region_metadata(&mut self, enabled: bool) {
+        self.config_builder = self.config_builder.clone().emit_region_metadata(enabled);
+    }
+
+    /// Group strokes into `thickness-thin`/`-medium`/`-thick` layers so each tier
+    /// can be mapped to a different pen or style
+    #[wasm_bindgen]
+    pub fn set_group_by_thickness(&mut self, enabled: bool) {
+        self.config_builder = self.config_builder.clone().group_by_thickness(enabled);
+    }
+
+    /// Append a `<g class="legend">` of swatches labeled with each color's hex
+    /// value and coverage percentage
+    #[wasm_bindgen]
+    pub fn set_emit_palette_legend(&mut self, enabled: bool) {
+        self.config_builder = self.config_builder.clone().emit_palette_legend(enabled);
+    }
+
+    /// Guarantee at least this many paths at very low detail by progressively
+    /// lowering edge thresholds (0 disables)
+    #[wasm_bindgen]
+    pub fn set_guarantee_min_paths(&mut self, count: usize) {
+        self.config_builder = self.config_builder.clone().guarantee_min_paths(count);
+    }
+
+    /// Re-run the edge backend once with raised thresholds when the output is
+    /// mostly short noise paths
+    #[wasm_bindgen]
+    pub fn set_auto_denoise_escalate(&mut self, enabled: bool) {
+        self.config_builder = self.config_builder.clone().auto_denoise_escalate(enabled);
+    }
+
+    /// Keep vertices shared at junctions fixed during simplification so line
+    /// networks (maps, diagrams) stay connected
+    #[wasm_bindgen]
+    pub fn set_preserve_junction_topology(&mut self, enabled: bool) {
+        self.config_builder = self.config_builder.clone().preserve_junction_topology(enabled);
+    }
+
+    /// Remove spike vertices sharper than `degrees` (0-90) from traced lines
+    /// before fitting; 0 keeps every vertex
+    #[wasm_bindgen]
+    pub fn set_min_vertex_angle(&mut self, degrees: f32) -> Result<(), JsValue> {
+        self.config_builder = self.config_builder.clone().min_vertex_angle(degrees)
+            .map_err(|e| JsValue::from_str(&format!("Failed to set minimum vertex angle: {}", e)))?;
+        Ok(())
+    }
+
+    /// Replace near-straight traced edges with exactly straight lines, snapping
+    /// nearly horizontal or vertical ones to the axis (crisp architectural output)
+    #[wasm_bindgen]
+    pub fn set_snap_straight_lines(&mut self, enabled: bool) {
+        self.config_builder = self.config_builder.clone().snap_straight_lines(enabled);
+    }
+
+    /// Limit Douglas-Peucker split depth; deeper (pathological zigzag) paths are
+    /// simplified with Visvalingam-Whyatt
+    #[wasm_bindgen]
+    pub fn set_max_simplify_depth(&mut self, depth: u32) -> Result<(), JsValue> {
+        self.config_builder = self.config_builder.clone().max_simplify_depth(depth)
+            .map_err(|e| JsValue::from_str(&format!("Failed to set maximum simplification depth: {}", e)))?;
+        Ok(())
+    }
+
+    /// Draw a wider underlay of `color` behind every stroke, extending `width` pixels
+    /// on each side, so lines stay readable over busy backgrounds
+    #[wasm_bindgen]
+    pub fn set_stroke_halo(&mut self, color: &str, width: f32) -> Result<(), JsValue> {
+        self.config_builder = self.config_builder.clone().stroke_halo(Some((color.to_string(), width)))
+            .map_err(|e| JsValue::from_str(&format!("Failed to set stroke halo: {}", e)))?;
+        Ok(())
+    }
+
+    /// Remove the stroke halo underlay
+    #[wasm_bindgen]
+    pub fn clear_stroke_halo(&mut self) -> Result<(), JsValue> {
+        self.config_builder = self.config_builder.clone().stroke_halo(None)
+            .map_err(|e| JsValue::from_str(&format!("Failed to clear stroke halo: {}", e)))?;
+        Ok(())
+    }
+
+    /// Outline every filled region with a `width`-pixel stroke of `color`
+    /// (sticker/label style borders)
+    #[wasm_bindgen]
+    pub fn set_fill_and_stroke(&mut self, color: &str, width: f32) -> Result<(), JsValue> {
+        self.config_builder = self.config_builder.clone().fill_and_stroke(Some((color.to_string(), width)))
+            .map_err(|e| JsValue::from_str(&format!("Failed to set fill outline: {}", e)))?;
+        Ok(())
+    }
+
+    /// Expand every stroke into a filled outline at its width, for output that
+    /// scales identically in every renderer
+    #[wasm_bindgen]
+    pub fn set_stroke_to_outline(&mut self, enabled: bool) {
+        self.config_builder = self.config_builder.clone().stroke_to_outline(enabled);
+    }
+
+    /// Limit gradient output to this many definitions, turning the weakest into
+    /// solid colors (pass undefined to keep every gradient)
+    #[wasm_bindgen]
+    pub fn set_max_gradients(&mut self, max: Option<u32>) {
+        self.config_builder = self.config_builder.clone().max_gradients(max.map(|max| max as usize));
+    }
+
+    /// Split self-intersecting filled region outlines into simple polygons so
+    /// aggressively simplified regions fill without holes
+    #[wasm_bindgen]
+    pub fn set_repair_self_intersections(&mut self, enabled: bool) {
+        self.config_builder = self.config_builder.clone().repair_self_intersections(enabled);
+    }
+
+    /// Explicitly close paths whose end points round to the same coordinate,
+    /// preventing hairline gaps in filled shapes
+    #[wasm_bindgen]
+    pub fn set_auto_close_coincident(&mut self, enabled: bool) {
+        self.config_builder = self.config_builder.clone().auto_close_coincident(enabled);
+    }
+
+    /// Draw filled regions without an outline
+    #[wasm_bindgen]
+    pub fn clear_fill_and_stroke(&mut self) -> Result<(), JsValue> {
+        self.config_builder = self.config_builder.clone().fill_and_stroke(None)
+            .map_err(|e| JsValue::from_str(&format!("Failed to clear fill outline: {}", e)))?;
+        Ok(())
+    }
+
+    /// Set the output color space: "srgb" (hex colors) or "display-p3"
+    /// (`color(display-p3 r g b)` with an sRGB hex fallback)
+    #[wasm_bindgen]
+    pub fn set_color_space(&mut self, color_space: &str) -> Result<(), JsValue> {
+        let color_space = match color_space.to_lowercase().as_str() {
+            "srgb" => ColorSpace::Srgb,
+            "display-p3" | "p3" => ColorSpace::DisplayP3,
+            _ => return Err(JsValue::from_str(&format!("Invalid color space: {}. Use 'srgb' or 'display-p3'", color_space))),
+        };
+        self.config_builder = self.config_builder.clone().color_space(color_space);
+        Ok(())
+    }
+
     // === CENTERLINE BACKEND METHODS ===
     
     /// Enable or disable adaptive threshold
@@ -442,6 +784,29 @@ impl WasmVectorizer {
         Ok(())
     }
 
+    /// Set minimum connected-component area (pixels) kept in the binary image before tracing (0 = disabled)
+    #[wasm_bindgen]
+    pub fn set_prebinary_despeckle_px(&mut self, min_area_px: u32) {
+        self.config_builder = self.config_builder.clone().prebinary_despeckle_px(min_area_px);
+    }
+
+    /// Set a per-pixel detail weight map (luminance of `map`, same size as the traced image).
+    /// Bright areas trace with lower edge thresholds (more detail), dark areas with higher ones
+    #[wasm_bindgen]
+    pub fn set_detail_map(&mut self, map: &ImageData) -> Result<(), JsValue> {
+        let rgba: image::RgbaImage = ImageBuffer::from_raw(map.width(), map.height(), map.data().to_vec())
+            .ok_or_else(|| JsValue::from_str("Failed to create image buffer from detail map"))?;
+        let weights = image::imageops::grayscale(&rgba);
+        self.config_builder = self.config_builder.clone().detail_map(Some(weights));
+        Ok(())
+    }
+
+    /// Remove the detail map so detail is uniform across the image again
+    #[wasm_bindgen]
+    pub fn clear_detail_map(&mut self) {
+        self.config_builder = self.config_builder.clone().detail_map(None);
+    }
+
     // === DOTS BACKEND METHODS ===
 
     /// Set dot density threshold
@@ -466,6 +831,18 @@ impl WasmVectorizer {
         Ok(())
     }
 
+    /// Enable or disable skipping detected background entirely for dots
+    #[wasm_bindgen]
+    pub fn set_dots_skip_background(&mut self, enabled: bool) {
+        self.config_builder = self.config_builder.clone().dots_skip_background(enabled);
+    }
+
+    /// Enable or disable merging collinear dots into short dashes (pen plotter output)
+    #[wasm_bindgen]
+    pub fn set_dots_merge_to_dashes(&mut self, enabled: bool) {
+        self.config_builder = self.config_builder.clone().dots_merge_to_dashes(enabled);
+    }
+
 
     /// Enable or disable gradient-based sizing
     #[wasm_bindgen]
@@ -496,6 +873,20 @@ impl WasmVectorizer {
         Ok(())
     }
 
+    /// Set dot coloring ("black", "grayscale" for tonal stipple, or "source")
+    #[wasm_bindgen]
+    pub fn set_dot_color_mode(&mut self, mode: &str) -> Result<(), JsValue> {
+        use vectorize_core::algorithms::dots::dots::DotColorMode;
+        let color_mode = match mode.to_lowercase().as_str() {
+            "black" => DotColorMode::Black,
+            "grayscale" | "greyscale" => DotColorMode::Grayscale,
+            "source" | "source-color" => DotColorMode::SourceColor,
+            _ => return Err(JsValue::from_str(&format!("Invalid dot color mode: {}. Use 'black', 'grayscale' or 'source'", mode))),
+        };
+        self.config_builder = self.config_builder.clone().dot_color_mode(color_mode);
+        Ok(())
+    }
+
     /// Set dot grid pattern (0=Grid, 1=Hexagonal, 2=Random)
     #[wasm_bindgen]
     pub fn set_dot_grid_pattern(&mut self, pattern: u32) -> Result<(), JsValue> {
@@ -510,6 +901,34 @@ impl WasmVectorizer {
         Ok(())
     }
 
+    /// Enable halftone mode: one darkness-sized dot per cell of a rotated grid
+    #[wasm_bindgen]
+    pub fn set_halftone_mode(&mut self, enabled: bool) {
+        self.config_builder = self.config_builder.clone().halftone_mode(enabled);
+    }
+
+    /// Set the halftone screen angle in degrees (0-180)
+    #[wasm_bindgen]
+    pub fn set_halftone_angle(&mut self, degrees: f32) -> Result<(), JsValue> {
+        self.config_builder = self
+            .config_builder
+            .clone()
+            .halftone_angle(degrees)
+            .map_err(|e| JsValue::from_str(&format!("Failed to set halftone angle: {}", e)))?;
+        Ok(())
+    }
+
+    /// Set the halftone grid cell size in pixels (2-64)
+    #[wasm_bindgen]
+    pub fn set_halftone_cell_px(&mut self, cell_px: f32) -> Result<(), JsValue> {
+        self.config_builder = self
+            .config_builder
+            .clone()
+            .halftone_cell_px(cell_px)
+            .map_err(|e| JsValue::from_str(&format!("Failed to set halftone cell size: {}", e)))?;
+        Ok(())
+    }
+
     // === SUPERPIXEL BACKEND METHODS ===
 
     /// Set number of superpixels
@@ -536,6 +955,14 @@ impl WasmVectorizer {
         Ok(())
     }
 
+    /// Set SLIC early-exit threshold (max cluster center movement in pixels, 0 disables)
+    #[wasm_bindgen]
+    pub fn set_slic_convergence_threshold(&mut self, threshold: f32) -> Result<(), JsValue> {
+        self.config_builder = self.config_builder.clone().slic_convergence_threshold(threshold)
+            .map_err(|e| JsValue::from_str(&format!("Failed to set SLIC convergence threshold: {}", e)))?;
+        Ok(())
+    }
+
     /// Set boundary epsilon for superpixel simplification
     #[wasm_bindgen]
     pub fn set_boundary_epsilon(&mut self, epsilon: f32) -> Result<(), JsValue> {
@@ -552,6 +979,35 @@ impl WasmVectorizer {
         Ok(())
     }
     
+    /// Place more superpixels on high-detail areas (the subject) and fewer on flat
+    /// background, instead of seeding with the initialization pattern
+    #[wasm_bindgen]
+    pub fn set_superpixel_saliency_seeding(&mut self, enabled: bool) {
+        self.config_builder = self.config_builder.clone().superpixel_saliency_seeding(enabled);
+    }
+
+    /// Average region fill colors in LAB space instead of RGB
+    #[wasm_bindgen]
+    pub fn set_region_average_in_lab(&mut self, enabled: bool) {
+        self.config_builder = self.config_builder.clone().region_average_in_lab(enabled);
+    }
+
+    /// Blend superpixel region colors towards similar neighbours (0.0-1.0) to reduce
+    /// banding on gradients; region shapes are unchanged (default: 0.0)
+    #[wasm_bindgen]
+    pub fn set_region_color_smoothing(&mut self, strength: f32) -> Result<(), JsValue> {
+        self.config_builder = self.config_builder.clone().region_color_smoothing(strength)
+            .map_err(|e| JsValue::from_str(&format!("Failed to set region color smoothing: {}", e)))?;
+        Ok(())
+    }
+
+    /// Draw each boundary between two superpixel regions once, as a single
+    /// connected network, avoiding double-width seams (default: false)
+    #[wasm_bindgen]
+    pub fn set_shared_region_edges(&mut self, enabled: bool) {
+        self.config_builder = self.config_builder.clone().shared_region_edges(enabled);
+    }
+
     /// Deprecated: Use set_superpixel_initialization_pattern instead
     #[wasm_bindgen]
     pub fn set_initialization_pattern(&mut self, pattern: &str) -> Result<(), JsValue> {
@@ -582,6 +1038,22 @@ impl WasmVectorizer {
         self.config_builder = self.config_builder.clone().simplify_boundaries(enabled);
     }
 
+    /// Set distinct debug colors for superpixel regions
+    #[wasm_bindgen]
+    pub fn set_superpixel_debug_colors(&mut self, enabled: bool) {
+        self.config_builder = self.config_builder.clone().superpixel_debug_colors(enabled);
+    }
+
+    // === LOW-POLY BACKEND METHODS ===
+
+    /// Set approximate number of triangle vertices for the low-poly backend (16-5000)
+    #[wasm_bindgen]
+    pub fn set_lowpoly_vertex_count(&mut self, count: u32) -> Result<(), JsValue> {
+        self.config_builder = self.config_builder.clone().lowpoly_vertex_count(count)
+            .map_err(|e| JsValue::from_str(&format!("Failed to set low-poly vertex count: {}", e)))?;
+        Ok(())
+    }
+
     // === COLOR PRESERVATION METHODS ===
 
     /// Set preserve colors (generic)
@@ -644,48 +1116,24 @@ impl WasmVectorizer {
     #[wasm_bindgen]
     pub fn enable_background_removal(&mut self, enabled: bool) {
         log::info!("🔧 WASM: enable_background_removal called with enabled={}", enabled);
-        // CRITICAL FIX: Use try_build() to avoid recursive aliasing in clone operations
-        match self.config_builder.clone().build() {
-            Ok(_) => {
-                // If current config is valid, apply background removal to existing config
-                self.config_builder = self.config_builder.clone().background_removal(enabled);
-                log::info!("✅ WASM: Background removal enabled={} (preserving existing settings)", enabled);
-            }
-            Err(_) => {
-                // If invalid, preserve settings and retry
-                self.config_builder = self.config_builder.clone().background_removal(enabled);
-                log::info!("✅ WASM: Background removal enabled={} (preserving existing settings)", enabled);
-            }
-        }
+        self.config_builder = self.config_builder.clone().background_removal(enabled);
+        log::info!("✅ WASM: Background removal enabled={}", enabled);
     }
 
     /// Set background removal strength
     #[wasm_bindgen]
     pub fn set_background_removal_strength(&mut self, strength: f32) -> Result<(), JsValue> {
         log::info!("🔧 WASM: set_background_removal_strength called with strength={}", strength);
-        // CRITICAL FIX: Preserve existing user settings when setting background removal strength
-        match self.config_builder.clone().build() {
-            Ok(_) => {
-                self.config_builder = self.config_builder.clone()
-                    .background_removal_strength(strength)
-                    .map_err(|e| JsValue::from_str(&format!("Failed to set background removal strength: {}", e)))?;
-                log::info!("✅ WASM: Background removal strength set to {} (preserving existing settings)", strength);
-                Ok(())
-            }
-            Err(_) => {
-                self.config_builder = self.config_builder.clone().background_removal_strength(strength)
-                    .map_err(|e| JsValue::from_str(&format!("Failed to set background removal strength: {}", e)))?;
-                log::info!("✅ WASM: Background removal strength set to {} (cloned config)", strength);
-                Ok(())
-            }
-        }
+        self.config_builder = self.config_builder.clone().background_removal_strength(strength)
+            .map_err(|e| JsValue::from_str(&format!("Failed to set background removal strength: {}", e)))?;
+        log::info!("✅ WASM: Background removal strength set to {}", strength);
+        Ok(())
     }
 
     /// Set background removal algorithm
     #[wasm_bindgen]
     pub fn set_background_removal_algorithm(&mut self, algorithm: &str) -> Result<(), JsValue> {
         log::info!("🔧 WASM: set_background_removal_algorithm called with algorithm='{}'", algorithm);
-        // CRITICAL FIX: Use fresh builder to avoid recursive aliasing
         let algo = match algorithm.to_lowercase().as_str() {
             "otsu" => BackgroundRemovalAlgorithm::Otsu,
             "adaptive" => BackgroundRemovalAlgorithm::Adaptive,
@@ -695,40 +1143,21 @@ impl WasmVectorizer {
                 return Err(JsValue::from_str(&format!("Unknown background removal algorithm: {}. Valid options: otsu, adaptive, auto", algorithm)));
             }
         };
-        
-        match self.config_builder.clone().build() {
-            Ok(_) => {
-                self.config_builder = self.config_builder.clone().background_removal_algorithm(algo);
-                log::info!("✅ WASM: Background removal algorithm set to: {:?} (preserving existing settings)", algo);
-                Ok(())
-            }
-            Err(_) => {
-                self.config_builder = self.config_builder.clone().background_removal_algorithm(algo);
-                log::info!("✅ WASM: Background removal algorithm set to: {:?} (preserving existing settings)", algo);
-                Ok(())
-            }
-        }
+
+        self.config_builder = self.config_builder.clone().background_removal_algorithm(algo);
+        log::info!("✅ WASM: Background removal algorithm set to: {:?}", algo);
+        Ok(())
     }
 
     /// Set background removal threshold
     #[wasm_bindgen]
     pub fn set_background_removal_threshold(&mut self, threshold: f32) -> Result<(), JsValue> {
         log::info!("🔧 WASM: set_background_removal_threshold called with threshold={}", threshold);
-        // CRITICAL FIX: Use fresh builder to avoid recursive aliasing
         let threshold_u8 = (threshold.clamp(0.0, 255.0)) as u8;
-        
-        match self.config_builder.clone().build() {
-            Ok(_) => {
-                self.config_builder = self.config_builder.clone().background_removal_threshold(Some(threshold_u8));
-                log::info!("✅ WASM: Background removal threshold set to {} (u8: {}) (preserving existing settings)", threshold, threshold_u8);
-                Ok(())
-            }
-            Err(_) => {
-                self.config_builder = self.config_builder.clone().background_removal_threshold(Some(threshold_u8));
-                log::info!("✅ WASM: Background removal threshold set to {} (u8: {}) (preserving existing settings)", threshold, threshold_u8);
-                Ok(())
-            }
-        }
+
+        self.config_builder = self.config_builder.clone().background_removal_threshold(Some(threshold_u8));
+        log::info!("✅ WASM: Background removal threshold set to {} (u8: {})", threshold, threshold_u8);
+        Ok(())
     }
 
     // === HAND-DRAWN AESTHETICS METHODS ===
@@ -789,6 +1218,36 @@ impl WasmVectorizer {
         Ok(())
     }
 
+    /// Set custom pressure profile as width multipliers along each stroke (overrides preset)
+    #[wasm_bindgen]
+    pub fn set_custom_pressure_profile(&mut self, profile: Vec<f32>) -> Result<(), JsValue> {
+        log::info!("🔧 WASM: set_custom_pressure_profile called with profile={:?}", profile);
+
+        self.config_builder = self.config_builder.clone()
+            .custom_pressure_profile(profile)
+            .map_err(|e| JsValue::from_str(&format!("Failed to set pressure profile: {}", e)))?;
+        log::info!("✅ WASM: Pressure profile set");
+        Ok(())
+    }
+
+    /// Limit hand-drawn effects to "strokes-only", "fills-only" or "both" (overrides preset)
+    #[wasm_bindgen]
+    pub fn set_hand_drawn_apply_to(&mut self, target: &str) -> Result<(), JsValue> {
+        let target = match target.to_lowercase().as_str() {
+            "strokes-only" => HandDrawnTarget::StrokesOnly,
+            "fills-only" => HandDrawnTarget::FillsOnly,
+            "both" => HandDrawnTarget::Both,
+            _ => {
+                return Err(JsValue::from_str(&format!(
+                    "Invalid hand-drawn target: {}. Use 'strokes-only', 'fills-only' or 'both'",
+                    target
+                )))
+            }
+        };
+        self.config_builder = self.config_builder.clone().hand_drawn_apply_to(target);
+        Ok(())
+    }
+
     /// Set custom variable weights (overrides preset)
     #[wasm_bindgen]
     pub fn set_custom_variable_weights(&mut self, weights: f32) -> Result<(), JsValue> {
@@ -867,7 +1326,7 @@ impl WasmVectorizer {
                 svg_size: None,
                 processing_time_ms: Some(0.0),
             };
-            let _ = cb.call1(&JsValue::NULL, &serde_wasm_bindgen::to_value(&progress).unwrap());
+            emit_progress(cb, &progress);
         }
 
         // Convert ImageData to RGBA buffer
@@ -893,17 +1352,22 @@ impl WasmVectorizer {
                         TraceBackend::Centerline => "Centerline", 
                         TraceBackend::Superpixel => "Superpixel",
                         TraceBackend::Dots => "Dots",
+                        TraceBackend::LowPoly => "LowPoly",
                     }),
                 svg_size: None,
                 processing_time_ms: Some(js_sys::Date::now() - start_time),
             };
-            let _ = cb.call1(&JsValue::NULL, &serde_wasm_bindgen::to_value(&progress).unwrap());
+            emit_progress(cb, &progress);
         }
 
         // Build configuration
         // Build configuration WITH hand-drawn config
-        let (config, hand_drawn_config) = self.config_builder.clone().build_with_hand_drawn()
+        let (mut config, hand_drawn_config) = self.config_builder.clone().build_with_hand_drawn()
             .map_err(|e| JsValue::from_str(&format!("Configuration error: {}", e)))?;
+        if config.previous_directional_passes.is_none() {
+            config.previous_directional_passes =
+                self.directional_passes.lock().ok().and_then(|passes| passes.clone());
+        }
 
         // Log final configuration being used for processing
         log::info!("🚀 WASM: Final config for processing - Backend: {:?}, Detail: {}, Multipass: {}, Hand-drawn: {}",
@@ -918,8 +1382,15 @@ impl WasmVectorizer {
         }
 
         // Perform vectorization with hand-drawn config
-        let result = vectorize_trace_low_rgba(&img_buffer, &config, hand_drawn_config.as_ref())
-            .map_err(|e| JsValue::from_str(&format!("Vectorization failed: {e}")))?;
+        let (result, record) = telemetry::capture(|| {
+            vectorize_trace_low_rgba(&img_buffer, &config, hand_drawn_config.as_ref())
+        });
+        let result = result.map_err(|e| JsValue::from_str(&format!("Vectorization failed: {e}")))?;
+        if let Some(selected) = record.resolved.directional_passes {
+            if let Ok(mut passes) = self.directional_passes.lock() {
+                *passes = Some(selected);
+            }
+        }
 
         // Report progress: Complete
         if let Some(ref cb) = callback {
@@ -931,7 +1402,7 @@ impl WasmVectorizer {
                 svg_size: Some(svg_size),
                 processing_time_ms: Some(js_sys::Date::now() - start_time),
             };
-            let _ = cb.call1(&JsValue::NULL, &serde_wasm_bindgen::to_value(&progress).unwrap());
+            emit_progress(cb, &progress);
         }
 
         Ok(result)
@@ -942,6 +1413,281 @@ impl WasmVectorizer {
     pub fn vectorize(&self, image_data: &ImageData) -> Result<String, JsValue> {
         self.vectorize_with_progress(image_data, None)
     }
+
+    /// Vectorize with an early coarse preview, returning the full-quality SVG
+    ///
+    /// `callback` is called once with a fast low-detail SVG string before the
+    /// full-quality run starts; both runs share the decoded image. The preview is
+    /// traced at reduced resolution, so scale it to fit when displaying.
+    #[wasm_bindgen]
+    pub fn vectorize_progressive(&self, image_data: &ImageData, callback: Function) -> Result<String, JsValue> {
+        let image = ImageBuffer::from_raw(image_data.width(), image_data.height(), image_data.data().to_vec())
+            .ok_or_else(|| JsValue::from_str("Failed to create image buffer from ImageData"))?;
+
+        let (config, hand_drawn_config) = self.config_builder.clone().build_with_hand_drawn()
+            .map_err(|e| JsValue::from_str(&format!("Configuration error: {}", e)))?;
+
+        log::info!("🔧 WASM: Progressive vectorization of {}x{} image", image.width(), image.height());
+        vectorize_trace_low_progressive(&image, &config, hand_drawn_config.as_ref(), |preview| {
+            if let Err(e) = callback.call1(&JsValue::NULL, &JsValue::from_str(preview)) {
+                log::warn!("Preview callback failed: {:?}", e);
+            }
+        })
+        .map_err(|e| JsValue::from_str(&format!("Vectorization failed: {e}")))
+    }
+
+    /// Vectorize an encoded image file (PNG, JPEG, WebP, ...)
+    ///
+    /// Unlike canvas `ImageData`, the file bytes still carry any embedded ICC
+    /// profile, which is converted to sRGB unless disabled with `set_apply_icc_profile`.
+    /// Images beyond the browser canvas limit work too: anything larger than the
+    /// processing size is reduced while decoding, up to `set_max_decode_megapixels`.
+    #[wasm_bindgen]
+    pub fn vectorize_encoded(&self, bytes: &[u8]) -> Result<String, JsValue> {
+        let (mut config, hand_drawn_config) = self.config_builder.clone().build_with_hand_drawn()
+            .map_err(|e| JsValue::from_str(&format!("Configuration error: {}", e)))?;
+
+        let (image, (source_width, _)) = decode_image_fitted(
+            bytes,
+            config.apply_icc_profile,
+            config.max_image_size,
+            config.max_decode_megapixels,
+        )
+        .map_err(|e| JsValue::from_str(&format!("Failed to decode image: {e}")))?;
+        apply_embedded_dpi(&mut config, bytes);
+        // Densities count source pixels, which the decode may have merged
+        if let Some(dpi) = config.output_dpi.as_mut() {
+            *dpi *= image.width() as f32 / source_width as f32;
+        }
+
+        vectorize_trace_low_rgba(&image, &config, hand_drawn_config.as_ref())
+            .map_err(|e| JsValue::from_str(&format!("Vectorization failed: {e}")))
+    }
+
+    /// Combine a stack of same-sized images (e.g. exposures or focus brackets) and vectorize the result
+    ///
+    /// `mode` is one of "Max", "Min", "Average" or "MaxGradient" (sharpest source per region).
+    #[wasm_bindgen]
+    pub fn vectorize_combined(&self, images: Vec<ImageData>, mode: &str) -> Result<String, JsValue> {
+        let combine_mode = match mode.to_lowercase().as_str() {
+            "max" => StackCombineMode::Max,
+            "min" => StackCombineMode::Min,
+            "average" => StackCombineMode::Average,
+            "maxgradient" => StackCombineMode::MaxGradient,
+            _ => {
+                return Err(JsValue::from_str(&format!(
+                    "Invalid combine mode: '{}'. Valid options: Max, Min, Average, MaxGradient",
+                    mode
+                )))
+            }
+        };
+
+        let buffers = images
+            .iter()
+            .map(|image_data| {
+                ImageBuffer::from_raw(image_data.width(), image_data.height(), image_data.data().to_vec())
+                    .ok_or_else(|| JsValue::from_str("Failed to create image buffer from ImageData"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        log::info!("🔧 WASM: Combining {} images with mode {:?}", buffers.len(), combine_mode);
+        let combined = combine_images(&buffers, combine_mode)
+            .map_err(|e| JsValue::from_str(&format!("Image combination failed: {e}")))?;
+
+        let (config, hand_drawn_config) = self.config_builder.clone().build_with_hand_drawn()
+            .map_err(|e| JsValue::from_str(&format!("Configuration error: {}", e)))?;
+
+        vectorize_trace_low_rgba(&combined, &config, hand_drawn_config.as_ref())
+            .map_err(|e| JsValue::from_str(&format!("Vectorization failed: {e}")))
+    }
+
+    /// Trace several images with the current settings into one sprite SVG
+    ///
+    /// Each image becomes a `<symbol id="name" viewBox=...>` named by the matching
+    /// entry of `names`, usable in a page via `<use href="#name"/>`.
+    #[wasm_bindgen]
+    pub fn vectorize_sprite(&self, images: Vec<ImageData>, names: Vec<String>) -> Result<String, JsValue> {
+        if images.len() != names.len() {
+            return Err(JsValue::from_str(&format!(
+                "Sprite needs one name per image, got {} images and {} names",
+                images.len(),
+                names.len()
+            )));
+        }
+
+        let (config, hand_drawn_config) = self.config_builder.clone().build_with_hand_drawn()
+            .map_err(|e| JsValue::from_str(&format!("Configuration error: {}", e)))?;
+
+        log::info!("🔧 WASM: Tracing {} images into a sprite", images.len());
+        let documents = images
+            .iter()
+            .zip(names)
+            .map(|(image_data, name)| {
+                let image = ImageBuffer::from_raw(image_data.width(), image_data.height(), image_data.data().to_vec())
+                    .ok_or_else(|| JsValue::from_str("Failed to create image buffer from ImageData"))?;
+                let svg = vectorize_trace_low_rgba(&image, &config, hand_drawn_config.as_ref())
+                    .map_err(|e| JsValue::from_str(&format!("Vectorization of '{name}' failed: {e}")))?;
+                Ok((name, svg))
+            })
+            .collect::<Result<Vec<_>, JsValue>>()?;
+
+        Ok(generate_svg_sprite(&documents))
+    }
+
+    /// Vectorize only what changed between two same-sized images
+    ///
+    /// Pixels that differ significantly keep their `after` colors on a white
+    /// background, so the current backend traces just the changed regions.
+    #[wasm_bindgen]
+    pub fn vectorize_diff(&self, before: ImageData, after: ImageData) -> Result<String, JsValue> {
+        let before = ImageBuffer::from_raw(before.width(), before.height(), before.data().to_vec())
+            .ok_or_else(|| JsValue::from_str("Failed to create image buffer from before ImageData"))?;
+        let after = ImageBuffer::from_raw(after.width(), after.height(), after.data().to_vec())
+            .ok_or_else(|| JsValue::from_str("Failed to create image buffer from after ImageData"))?;
+
+        log::info!("🔧 WASM: Vectorizing difference of {}x{} images", after.width(), after.height());
+        let diff = difference_image(&before, &after, DEFAULT_DIFF_THRESHOLD)
+            .map_err(|e| JsValue::from_str(&format!("Image difference failed: {e}")))?;
+
+        let (config, hand_drawn_config) = self.config_builder.clone().build_with_hand_drawn()
+            .map_err(|e| JsValue::from_str(&format!("Configuration error: {}", e)))?;
+
+        vectorize_trace_low_rgba(&diff, &config, hand_drawn_config.as_ref())
+            .map_err(|e| JsValue::from_str(&format!("Vectorization failed: {e}")))
+    }
+
+    /// Vectorize into `background` and `foreground` `<g>` layers for parallax effects
+    ///
+    /// Paths on sharp, non-background pixels go to the foreground layer; paths on
+    /// smooth or background-colored areas (e.g. a defocused backdrop) go behind it.
+    #[wasm_bindgen]
+    pub fn vectorize_layered_depth(&self, image_data: &ImageData) -> Result<String, JsValue> {
+        let image = ImageBuffer::from_raw(image_data.width(), image_data.height(), image_data.data().to_vec())
+            .ok_or_else(|| JsValue::from_str("Failed to create image buffer from ImageData"))?;
+
+        let (config, hand_drawn_config) = self.config_builder.clone().build_with_hand_drawn()
+            .map_err(|e| JsValue::from_str(&format!("Configuration error: {}", e)))?;
+
+        log::info!("🔧 WASM: Vectorizing {}x{} image into depth layers", image.width(), image.height());
+        vectorize_trace_low_layered_depth(&image, &config, hand_drawn_config.as_ref())
+            .map_err(|e| JsValue::from_str(&format!("Vectorization failed: {e}")))
+    }
+
+    /// Find the detail level that produces an SVG close to `target_bytes` with the current settings
+    ///
+    /// Estimates are made on a reduced-resolution copy, so the result is approximate.
+    #[wasm_bindgen]
+    pub fn find_detail_for_size(&self, image_data: &ImageData, target_bytes: usize) -> Result<f32, JsValue> {
+        let image = ImageBuffer::from_raw(image_data.width(), image_data.height(), image_data.data().to_vec())
+            .ok_or_else(|| JsValue::from_str("Failed to create image buffer from ImageData"))?;
+
+        let (config, hand_drawn_config) = self.config_builder.clone().build_with_hand_drawn()
+            .map_err(|e| JsValue::from_str(&format!("Configuration error: {}", e)))?;
+
+        log::info!("🔧 WASM: Searching detail level for target size {} bytes", target_bytes);
+        vectorize_core::find_detail_for_size(&image, &config, hand_drawn_config.as_ref(), target_bytes)
+            .map_err(|e| JsValue::from_str(&format!("Detail search failed: {e}")))
+    }
+
+    /// Vectorize an image with two JSON configurations and compare the outputs
+    ///
+    /// Each config is applied on top of defaults, as with `apply_config_json`, and does not
+    /// affect this vectorizer's own configuration. Returns JSON with path, color and byte
+    /// counts for both runs, their `b - a` deltas, and the SSIM of the rasterized results.
+    #[wasm_bindgen]
+    pub fn compare_configs(
+        &self,
+        image_data: &ImageData,
+        config_a_json: &str,
+        config_b_json: &str,
+    ) -> Result<String, JsValue> {
+        let image = ImageBuffer::from_raw(image_data.width(), image_data.height(), image_data.data().to_vec())
+            .ok_or_else(|| JsValue::from_str("Failed to create image buffer from ImageData"))?;
+
+        let build_config = |config_json: &str| -> Result<TraceLowConfig, JsValue> {
+            let mut builder = ConfigBuilder::new();
+            unified_config::apply_config_json(&mut builder, config_json)?;
+            builder.build()
+                .map_err(|e| JsValue::from_str(&format!("Configuration error: {}", e)))
+        };
+        let config_a = build_config(config_a_json)?;
+        let config_b = build_config(config_b_json)?;
+
+        log::info!("🔧 WASM: Comparing two configurations");
+        let comparison = vectorize_core::compare_configs(&image, &config_a, &config_b, None)
+            .map_err(|e| JsValue::from_str(&format!("Config comparison failed: {e}")))?;
+        serde_json::to_string(&comparison)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize comparison: {e}")))
+    }
+
+    /// Run only background detection and return the mask as a grayscale image
+    ///
+    /// Background pixels are white and foreground pixels black. Detection uses the
+    /// current background removal strength, so the mask previews what removal will affect.
+    #[wasm_bindgen]
+    pub fn get_background_mask(&self, image_data: &ImageData) -> Result<ImageData, JsValue> {
+        let (width, height) = (image_data.width(), image_data.height());
+        let image = ImageBuffer::from_raw(width, height, image_data.data().to_vec())
+            .ok_or_else(|| JsValue::from_str("Failed to create image buffer from ImageData"))?;
+
+        let config = self.config_builder.clone().build()
+            .map_err(|e| JsValue::from_str(&format!("Configuration error: {}", e)))?;
+
+        let mask = detect_background_removal_mask(&image, &config);
+        let mask_pixels: Vec<u8> = mask
+            .iter()
+            .flat_map(|&is_background| {
+                let value = if is_background { 255 } else { 0 };
+                [value, value, value, 255]
+            })
+            .collect();
+
+        ImageData::new_with_u8_clamped_array_and_sh(wasm_bindgen::Clamped(&mask_pixels), width, height)
+    }
+
+    /// Mask of the source pixels covered by the path at `path_index` in the SVG output
+    ///
+    /// The image is traced with the current settings and only that path's fill and
+    /// stroke are rasterized at source resolution: covered pixels are white, all others
+    /// black. Indices match the `path-<index>` ids written when path ids are enabled.
+    #[wasm_bindgen]
+    pub fn path_coverage_mask(&self, image_data: &ImageData, path_index: usize) -> Result<ImageData, JsValue> {
+        let (width, height) = (image_data.width(), image_data.height());
+        let image = ImageBuffer::from_raw(width, height, image_data.data().to_vec())
+            .ok_or_else(|| JsValue::from_str("Failed to create image buffer from ImageData"))?;
+
+        let (config, hand_drawn_config) = self.config_builder.clone().build_with_hand_drawn()
+            .map_err(|e| JsValue::from_str(&format!("Configuration error: {}", e)))?;
+
+        let mask = vectorize_core::path_coverage_mask(&image, &config, hand_drawn_config.as_ref(), path_index)
+            .map_err(|e| JsValue::from_str(&format!("Path coverage mask failed: {e}")))?;
+        let mask_pixels: Vec<u8> = mask
+            .pixels()
+            .flat_map(|pixel| [pixel[0], pixel[0], pixel[0], 255])
+            .collect();
+
+        ImageData::new_with_u8_clamped_array_and_sh(wasm_bindgen::Clamped(&mask_pixels), width, height)
+    }
+
+    /// Export the image gradient field as a normal-map-style image
+    ///
+    /// Red/green hold the gradient direction (dx, dy) mapped to 0-255 with 128 as zero,
+    /// blue holds the gradient magnitude.
+    #[wasm_bindgen]
+    pub fn export_gradient_map(&self, image_data: &ImageData) -> Result<ImageData, JsValue> {
+        let (width, height) = (image_data.width(), image_data.height());
+        let image: image::RgbaImage =
+            ImageBuffer::from_raw(width, height, image_data.data().to_vec())
+                .ok_or_else(|| JsValue::from_str("Failed to create image buffer from ImageData"))?;
+
+        let gray = image::imageops::grayscale(&image);
+        let map_pixels: Vec<u8> = gradient_normal_map(&gray)
+            .pixels()
+            .flat_map(|pixel| [pixel[0], pixel[1], pixel[2], 255])
+            .collect();
+
+        ImageData::new_with_u8_clamped_array_and_sh(wasm_bindgen::Clamped(&map_pixels), width, height)
+    }
     
     /// GPU-accelerated vectorize function with automatic backend selection
     #[wasm_bindgen]
@@ -957,7 +1703,7 @@ impl WasmVectorizer {
                 svg_size: None,
                 processing_time_ms: Some(0.0),
             };
-            let _ = cb.call1(&JsValue::NULL, &serde_wasm_bindgen::to_value(&progress).unwrap());
+            emit_progress(cb, &progress);
         }
         
         // Try GPU acceleration first
@@ -975,7 +1721,7 @@ impl WasmVectorizer {
                         svg_size: Some(svg.len()),
                         processing_time_ms: Some(processing_time),
                     };
-                    let _ = cb.call1(&JsValue::NULL, &serde_wasm_bindgen::to_value(&progress).unwrap());
+                    emit_progress(cb, &progress);
                 },
                 Err(_) => {
                     let progress = WasmProgress {
@@ -985,7 +1731,7 @@ impl WasmVectorizer {
                         svg_size: None,
                         processing_time_ms: Some(processing_time),
                     };
-                    let _ = cb.call1(&JsValue::NULL, &serde_wasm_bindgen::to_value(&progress).unwrap());
+                    emit_progress(cb, &progress);
                 }
             }
         }
@@ -1008,6 +1754,7 @@ impl WasmVectorizer {
             TraceBackend::Centerline => "centerline".to_string(),
             TraceBackend::Superpixel => "superpixel".to_string(), 
             TraceBackend::Dots => "dots".to_string(),
+            TraceBackend::LowPoly => "lowpoly".to_string(),
         }
     }
 
@@ -1058,6 +1805,26 @@ impl WasmVectorizer {
         }
     }
 
+    /// List enabled settings whose prerequisites are off, as a JSON array of
+    /// `{setting, requires, message}` objects (empty when consistent)
+    #[wasm_bindgen]
+    pub fn validate_dependencies(&self) -> Result<String, JsValue> {
+        let warnings: Vec<serde_json::Value> = self
+            .config_builder
+            .validate_dependencies()
+            .iter()
+            .map(|w| {
+                serde_json::json!({
+                    "setting": w.setting,
+                    "requires": w.requires,
+                    "message": w.to_string(),
+                })
+            })
+            .collect();
+        serde_json::to_string(&warnings)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize dependency warnings: {}", e)))
+    }
+
     /// Validate current configuration and return validation results
     #[wasm_bindgen]
     pub fn validate_config(&self) -> String {
@@ -1108,6 +1875,9 @@ impl WasmVectorizer {
                     TraceBackend::Dots => {
                         info.push("Using Dots backend - good for artistic stippling effects".to_string());
                     }
+                    TraceBackend::LowPoly => {
+                        info.push("Using LowPoly backend - good for faceted geometric art".to_string());
+                    }
                 }
 
                 let mut result = String::new();
@@ -1164,6 +1934,7 @@ impl WasmVectorizer {
                 "centerline" => TraceBackend::Centerline,
                 "superpixel" => TraceBackend::Superpixel,
                 "dots" => TraceBackend::Dots,
+                "lowpoly" => TraceBackend::LowPoly,
                 _ => return Err(JsValue::from_str(&format!("Unknown backend: {}", backend)))
             });
             self.backend = match backend.to_lowercase().as_str() {
@@ -1171,6 +1942,7 @@ impl WasmVectorizer {
                 "centerline" => TraceBackend::Centerline,
                 "superpixel" => TraceBackend::Superpixel,
                 "dots" => TraceBackend::Dots,
+                "lowpoly" => TraceBackend::LowPoly,
                 _ => TraceBackend::Edge,
             };
         }
@@ -1247,6 +2019,12 @@ impl WasmVectorizer {
                 .map_err(|e| JsValue::from_str(&format!("Failed to set compactness: {}", e)))?;
         }
 
+        // Apply low-poly parameters
+        if let Some(vertex_count) = config.get("lowpolyVertexCount").and_then(|v| v.as_u64()) {
+            builder = builder.lowpoly_vertex_count(vertex_count as u32)
+                .map_err(|e| JsValue::from_str(&format!("Failed to set low-poly vertex count: {}", e)))?;
+        }
+
         // Apply background removal settings
         if let Some(enable_bg_removal) = config.get("enableBackgroundRemoval").and_then(|v| v.as_bool()) {
             log::info!("🔍 WASM DEBUG: Setting background_removal to {} from JSON config", enable_bg_removal);
@@ -1298,7 +2076,7 @@ impl WasmVectorizer {
 
         // Validate backend
         if let Some(backend) = config.get("backend").and_then(|v| v.as_str()) {
-            if !["edge", "centerline", "superpixel", "dots"].contains(&backend.to_lowercase().as_str()) {
+            if !["edge", "centerline", "superpixel", "dots", "lowpoly"].contains(&backend.to_lowercase().as_str()) {
                 validation["valid"] = serde_json::json!(false);
                 validation["errors"].as_array_mut().unwrap().push(
                     serde_json::json!(format!("Invalid backend: {}", backend))
@@ -1339,6 +2117,7 @@ impl WasmVectorizer {
             "centerline" => TraceBackend::Centerline,
             "superpixel" => TraceBackend::Superpixel,
             "dots" => TraceBackend::Dots,
+            "lowpoly" => TraceBackend::LowPoly,
             _ => return Err(JsValue::from_str(&format!("Unknown backend: {}", backend)))
         };
 
@@ -1375,6 +2154,8 @@ impl WasmVectorizer {
             "superpixelCompactness": config.superpixel_compactness,
             "superpixelFillRegions": config.superpixel_fill_regions,
             "superpixelStrokeRegions": config.superpixel_stroke_regions,
+
+            "lowpolyVertexCount": config.lowpoly_vertex_count,
         });
 
         Ok(serde_json::to_string(&config_json).unwrap())
@@ -1396,6 +2177,7 @@ impl WasmVectorizer {
                 TraceBackend::Centerline => "centerline",
                 TraceBackend::Superpixel => "superpixel",
                 TraceBackend::Dots => "dots",
+                TraceBackend::LowPoly => "lowpoly",
             },
             "detail": config.detail,
             "strokeWidth": config.stroke_px_at_1080p,
@@ -1421,6 +2203,8 @@ impl WasmVectorizer {
             "superpixelCompactness": config.superpixel_compactness,
             "superpixelFillRegions": config.superpixel_fill_regions,
             "superpixelStrokeRegions": config.superpixel_stroke_regions,
+
+            "lowpolyVertexCount": config.lowpoly_vertex_count,
         });
 
         Ok(serde_json::to_string(&config_json).unwrap())
@@ -1441,6 +2225,7 @@ pub fn get_available_backends() -> Vec<String> {
         "centerline".to_string(), 
         "superpixel".to_string(),
         "dots".to_string(),
+        "lowpoly".to_string(),
     ]
 }
 
@@ -1460,6 +2245,28 @@ pub fn get_wasm_info() -> String {
     )
 }
 
+/// Configure how transient GPU/allocation failures are retried: up to
+/// `max_retries` retries with exponential backoff starting at `base_delay_ms`
+#[wasm_bindgen]
+pub fn set_error_recovery_policy(max_retries: u32, base_delay_ms: u32) {
+    if let Ok(mut manager) = ERROR_RECOVERY_MANAGER.lock() {
+        manager
+            .get_or_insert_with(|| ErrorRecoveryManager::new(DEFAULT_MAX_RETRIES, DEFAULT_BASE_DELAY_MS))
+            .set_policy(max_retries, base_delay_ms as u64);
+        log::info!("Error recovery policy: {} retries, {}ms base delay", max_retries, base_delay_ms);
+    }
+}
+
+/// Backoff delay before retry number `retry` under the global recovery policy
+/// (`None` when retries are exhausted)
+pub(crate) fn recovery_retry_delay_ms(retry: u32) -> Option<u64> {
+    let manager = ERROR_RECOVERY_MANAGER.lock().ok()?;
+    match manager.as_ref() {
+        Some(manager) => manager.retry_delay_ms(retry),
+        None => ErrorRecoveryManager::new(DEFAULT_MAX_RETRIES, DEFAULT_BASE_DELAY_MS).retry_delay_ms(retry),
+    }
+}
+
 /// Emergency cleanup function for error recovery
 #[wasm_bindgen]
 pub fn emergency_cleanup() -> Result<(), JsValue> {
@@ -1467,7 +2274,7 @@ pub fn emergency_cleanup() -> Result<(), JsValue> {
     
     // Reinitialize error recovery manager
     if let Ok(mut manager) = ERROR_RECOVERY_MANAGER.lock() {
-        *manager = Some(ErrorRecoveryManager::new(3, 1000)); // Reset with default values
+        *manager = Some(ErrorRecoveryManager::new(DEFAULT_MAX_RETRIES, DEFAULT_BASE_DELAY_MS)); // Reset with default values
         log::info!("Error recovery manager reset");
     }
     
@@ -1597,7 +2404,7 @@ impl WasmGpuSelector {
         if self.gpu_available && pixel_count > 250_000 {
             match algorithm {
                 "edge" | "centerline" => format!("gpu-preferred ({})", algorithm),
-                "superpixel" | "dots" => format!("gpu-enhanced ({})", algorithm),
+                "superpixel" | "dots" | "lowpoly" => format!("gpu-enhanced ({})", algorithm),
                 _ => format!("gpu-fallback ({})", algorithm)
             }
         } else {
@@ -1727,7 +2534,7 @@ pub async fn vectorize_with_gpu_acceleration(
 pub use crate::gpu_backend::{
     detect_best_gpu_backend, get_active_gpu_backend, get_gpu_backend_status,
     get_gpu_capability_report, is_gpu_acceleration_available,
-    reset_gpu_backend,
+    release_gpu_resources, reset_gpu_backend,
 };
 
 // Re-export capability functions
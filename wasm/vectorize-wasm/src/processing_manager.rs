@@ -4,10 +4,15 @@
 //! backend selection, fallback logic, and error recovery for image vectorization.
 //! 
 //! Note: This module is designed for single-threaded WASM + Web Worker architecture.
+//! Native builds with the `parallel` feature add a multi-threaded CPU tier between
+//! GPU processing and the single-threaded fallback. Without the feature, and always on
+//! wasm32, threading is reported as unsupported and that tier is never scheduled.
 
 use wasm_bindgen::prelude::*;
 use serde::{Deserialize, Serialize};
+use crate::error::{backoff_delay, is_transient_error};
 use crate::gpu_backend::{GpuBackend, get_gpu_backend_status_internal, is_gpu_acceleration_available};
+use std::future::Future;
 use std::time::Instant;
 use image::{ImageBuffer, Rgba};
 
@@ -20,6 +25,8 @@ pub enum ProcessingBackend {
     WebGPU,
     /// WebGL2 accelerated processing (fast fallback)
     WebGL2,
+    /// CPU multi-threaded processing (native builds with the `parallel` feature)
+    CpuParallel,
     /// CPU single-threaded processing (standard fallback)
     CpuSingleThreaded,
 }
@@ -33,6 +40,8 @@ pub struct ProcessingResult {
     pub processing_time_ms: f64,
     pub error_message: Option<String>,
     pub svg_output: Option<String>,
+    /// Backends tried in order, ending with the one that ran (or the last failure)
+    pub attempted_backends: Vec<ProcessingBackend>,
 }
 
 /// Processing manager configuration
@@ -159,6 +168,13 @@ impl ProcessingManager {
             }
         }
 
+        // Prefer multi-threaded CPU over single-threaded when cores are available
+        if vectorize_core::execution::is_threading_supported()
+            && !backends.contains(&ProcessingBackend::CpuParallel)
+        {
+            backends.push(ProcessingBackend::CpuParallel);
+        }
+
         // Always add single-threaded CPU processing
         if !backends.contains(&ProcessingBackend::CpuSingleThreaded) {
             backends.push(ProcessingBackend::CpuSingleThreaded);
@@ -184,15 +200,13 @@ impl ProcessingManager {
         let trace_config = config.clone().build()
             .map_err(|e| format!("Config build failed: {:?}", e))?;
 
-        // Initialize GPU device
-        use vectorize_core::gpu::device::try_init_gpu;
-        
-        let gpu_device = match try_init_gpu().await {
+        // Reuse the cached GPU device (created on the first GPU run)
+        let gpu_device = match crate::gpu_backend::processing_gpu_device().await {
             Some(device) => {
                 if !device.supports_image_processing() {
                     return Err("GPU doesn't support required features for image processing".to_string());
                 }
-                std::sync::Arc::new(device)
+                device
             },
             None => return Err("Failed to initialize GPU device".to_string()),
         };
@@ -333,24 +347,33 @@ impl ProcessingManager {
         );
 
         let mut last_error = None;
+        let mut attempted_backends = Vec::new();
 
         for backend in processing_order {
+            attempted_backends.push(backend);
             let start_time = Instant::now();
             
             log::info!("Attempting processing with backend: {:?}", backend);
 
-            // Attempt processing with current backend
-            let result = match backend {
-                ProcessingBackend::WebGPU => {
-                    self.try_webgpu_processing(image_data, width, height, config).await
-                }
-                ProcessingBackend::WebGL2 => {
-                    self.try_webgl2_processing(image_data, width, height, config).await
-                }
-                ProcessingBackend::CpuSingleThreaded => {
-                    self.try_cpu_singlethreaded_processing(image_data, width, height, config).await
+            // Attempt processing with current backend, retrying transient failures
+            let manager: &Self = self;
+            let result = retry_transient_failures(backend, || async move {
+                match backend {
+                    ProcessingBackend::WebGPU => {
+                        manager.try_webgpu_processing(image_data, width, height, config).await
+                    }
+                    ProcessingBackend::WebGL2 => {
+                        manager.try_webgl2_processing(image_data, width, height, config).await
+                    }
+                    ProcessingBackend::CpuParallel => {
+                        manager.try_cpu_parallel_processing(image_data, width, height, config).await
+                    }
+                    ProcessingBackend::CpuSingleThreaded => {
+                        manager.try_cpu_singlethreaded_processing(image_data, width, height, config).await
+                    }
                 }
-            };
+            })
+            .await;
 
             let processing_time = start_time.elapsed().as_millis() as f64;
 
@@ -384,6 +407,7 @@ impl ProcessingManager {
                         processing_time_ms: processing_time,
                         error_message: None,
                         svg_output: Some(svg_output),
+                        attempted_backends,
                     };
                 }
                 Err(error) => {
@@ -403,6 +427,7 @@ impl ProcessingManager {
             processing_time_ms: 0.0,
             error_message: last_error,
             svg_output: None,
+            attempted_backends,
         }
     }
 
@@ -541,6 +566,23 @@ impl ProcessingManager {
         }
     }
 
+    async fn try_cpu_parallel_processing(
+        &self,
+        image_data: &[u8],
+        width: u32,
+        height: u32,
+        config: &vectorize_core::ConfigBuilder,
+    ) -> Result<String, String> {
+        use vectorize_core::execution::{current_num_threads, is_threading_supported};
+
+        if !is_threading_supported() {
+            return Err("Parallel CPU processing not available".to_string());
+        }
+
+        log::info!("🧵 Starting parallel CPU processing on {} threads", current_num_threads());
+        self.cpu_process_image(image_data, width, height, config)
+    }
+
     #[allow(dead_code)] // Reserved for future processing pipeline
     async fn try_cpu_singlethreaded_processing(
         &self,
@@ -549,12 +591,20 @@ impl ProcessingManager {
         height: u32,
         config: &vectorize_core::ConfigBuilder,
     ) -> Result<String, String> {
+        use vectorize_core::execution::{with_thread_pool, ThreadPoolConfig};
+
         // Single-threaded CPU processing - always available fallback
-        self.cpu_process_image(image_data, width, height, config).await
+        let single_thread = ThreadPoolConfig {
+            num_threads: Some(1),
+            ..Default::default()
+        };
+        with_thread_pool(&single_thread, || {
+            self.cpu_process_image(image_data, width, height, config)
+        })
     }
 
     #[allow(dead_code)] // Reserved for future processing pipeline
-    async fn cpu_process_image(
+    fn cpu_process_image(
         &self,
         image_data: &[u8],
         width: u32,
@@ -578,6 +628,53 @@ impl ProcessingManager {
     }
 }
 
+/// Run `attempt`, retrying transient GPU/allocation failures with backoff under
+/// the global error recovery policy (see `set_error_recovery_policy`)
+async fn retry_transient_failures<F, Fut>(
+    backend: ProcessingBackend,
+    attempt: F,
+) -> Result<String, String>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<String, String>>,
+{
+    retry_transient_failures_with(backend, crate::recovery_retry_delay_ms, attempt).await
+}
+
+/// Run `attempt`, retrying transient failures after the delay `retry_delay_ms`
+/// gives for each retry number, until it returns `None`
+async fn retry_transient_failures_with<D, F, Fut>(
+    backend: ProcessingBackend,
+    retry_delay_ms: D,
+    mut attempt: F,
+) -> Result<String, String>
+where
+    D: Fn(u32) -> Option<u64>,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<String, String>>,
+{
+    let mut retry = 0;
+    loop {
+        match attempt().await {
+            Err(error) if is_transient_error(&error) => {
+                retry += 1;
+                let Some(delay_ms) = retry_delay_ms(retry) else {
+                    return Err(error);
+                };
+                log::warn!(
+                    "Transient failure on {:?} ({}), retry {} in {}ms",
+                    backend,
+                    error,
+                    retry,
+                    delay_ms
+                );
+                backoff_delay(delay_ms).await;
+            }
+            result => return result,
+        }
+    }
+}
+
 /// Get backend performance statistics
 #[wasm_bindgen]
 pub fn get_backend_performance_report() -> String {
@@ -601,6 +698,12 @@ pub fn get_backend_performance_report() -> String {
         } else { 
             "✗ Not Available" 
         }));
+    report.push_str(&format!("• CPU Parallel: {}\n",
+        if vectorize_core::execution::is_threading_supported() {
+            "✓ Available"
+        } else {
+            "✗ Not Available"
+        }));
     report.push_str("• CPU Single-threaded: ✓ Always Available (via Web Worker)\n\n");
     
     // Recommended processing order
@@ -658,6 +761,54 @@ mod tests {
         assert!(!order.is_empty());
     }
 
+    #[test]
+    fn test_transient_retries_follow_recovery_policy() {
+        use std::pin::pin;
+        use std::task::{Context, Poll, Waker};
+
+        // Count attempts until a persistent transient error is given up on
+        let attempts_with_policy = |max_retries: u32| {
+            let policy = crate::error::ErrorRecoveryManager::new(max_retries, 0);
+            let mut attempts = 0;
+            let result = {
+                let future = pin!(retry_transient_failures_with(
+                    ProcessingBackend::WebGPU,
+                    |retry| policy.retry_delay_ms(retry),
+                    || {
+                        attempts += 1;
+                        std::future::ready(Err::<String, String>("GPU device lost".to_string()))
+                    }
+                ));
+                match future.poll(&mut Context::from_waker(Waker::noop())) {
+                    Poll::Ready(result) => result,
+                    Poll::Pending => panic!("retries with zero delay should complete immediately"),
+                }
+            };
+            assert!(result.is_err());
+            attempts
+        };
+
+        assert_eq!(attempts_with_policy(3), 4);
+        assert_eq!(attempts_with_policy(1), 2);
+    }
+
+    #[test]
+    fn test_parallel_cpu_tier_precedes_single_threaded() {
+        let mut config = ProcessingConfig::default();
+        config.set_try_gpu_acceleration(false);
+        let manager = ProcessingManager::new(config);
+        let order = manager.determine_processing_order();
+
+        if vectorize_core::execution::is_threading_supported() {
+            assert_eq!(
+                order,
+                vec![ProcessingBackend::CpuParallel, ProcessingBackend::CpuSingleThreaded]
+            );
+        } else {
+            assert_eq!(order, vec![ProcessingBackend::CpuSingleThreaded]);
+        }
+    }
+
     #[test]
     fn test_backend_performance_recording() {
         let config = ProcessingConfig::default();
@@ -11,7 +11,7 @@ mod tests {
         BackgroundRemovalAlgorithm, SuperpixelInitPattern, TraceBackend, TraceLowConfig,
     };
     use vectorize_core::algorithms::visual::color_processing::ColorSamplingMethod;
-    use vectorize_core::algorithms::dots::dots::{DotShape, GridPattern};
+    use vectorize_core::algorithms::dots::dots::{DotColorMode, DotShape, GridPattern};
 
     #[test]
     #[cfg(feature = "generate-ts")]
@@ -24,6 +24,7 @@ mod tests {
         ColorSamplingMethod::export().expect("Failed to export ColorSamplingMethod");
         DotShape::export().expect("Failed to export DotShape");
         GridPattern::export().expect("Failed to export GridPattern");
+        DotColorMode::export().expect("Failed to export DotColorMode");
 
         println!("TypeScript generation completed successfully");
     }
@@ -6,7 +6,10 @@
 
 use serde_json;
 use vectorize_core::{
-    algorithms::tracing::trace_low::{TraceLowConfig, TraceBackend},
+    algorithms::{
+        tracing::trace_low::{TraceLowConfig, TraceBackend},
+        HandDrawnTarget,
+    },
     config_builder::ConfigBuilder,
 };
 use wasm_bindgen::prelude::*;
@@ -57,6 +60,7 @@ pub fn apply_config_json(
     // Apply core settings
     builder = builder
         .backend(config.backend.clone())
+        .text_aware(config.text_aware)
         .detail(config.detail)
         .map_err(|e| JsValue::from_str(&format!("Failed to set detail: {}", e)))?
         .stroke_width(config.stroke_px_at_1080p)
@@ -66,7 +70,16 @@ pub fn apply_config_json(
     builder = builder
         .noise_filtering(config.noise_filtering)
         .noise_filter_spatial_sigma(config.noise_filter_spatial_sigma)
-        .noise_filter_range_sigma(config.noise_filter_range_sigma);
+        .noise_filter_range_sigma(config.noise_filter_range_sigma)
+        .prebinary_despeckle_px(config.prebinary_despeckle_px)
+        .apply_icc_profile(config.apply_icc_profile)
+        .use_embedded_dpi(config.use_embedded_dpi)
+        .dual_background(config.dual_background)
+        .deband(config.deband)
+        .alpha_threshold(config.alpha_threshold)
+        .min_content_pixels(config.min_content_pixels)
+        .max_decode_megapixels(config.max_decode_megapixels)
+        .map_err(|e| JsValue::from_str(&format!("Failed to set maximum decode size: {}", e)))?;
 
     if config.enable_background_removal {
         builder = builder
@@ -94,10 +107,55 @@ pub fn apply_config_json(
         }
     }
 
+    // Apply output settings
+    builder = builder
+        .emit_path_ids(config.emit_path_ids)
+        .emit_xml_declaration(config.emit_xml_declaration)
+        .emit_path_length(config.emit_path_length)
+        .embed_source_hash(config.embed_source_hash)
+        .output_transform(config.output_transform)
+        .coordinate_origin(config.coordinate_origin)
+        .non_finite_handling(config.non_finite_handling)
+        .edge_strength_opacity(config.edge_strength_opacity)
+        .emit_source_color(config.emit_source_color)
+        .emit_region_metadata(config.emit_region_metadata)
+        .group_by_thickness(config.group_by_thickness)
+        .emit_palette_legend(config.emit_palette_legend)
+        .color_space(config.color_space)
+        .guarantee_min_paths(config.guarantee_min_paths)
+        .auto_denoise_escalate(config.auto_denoise_escalate)
+        .preserve_junction_topology(config.preserve_junction_topology)
+        .min_vertex_angle(config.min_vertex_angle_deg)
+        .map_err(|e| JsValue::from_str(&format!("Failed to set minimum vertex angle: {}", e)))?
+        .max_simplify_depth(config.max_simplify_depth)
+        .map_err(|e| JsValue::from_str(&format!("Failed to set maximum simplification depth: {}", e)))?
+        .snap_straight_lines(config.snap_straight_lines)
+        .repair_self_intersections(config.repair_self_intersections)
+        .auto_close_coincident(config.auto_close_coincident)
+        .stroke_halo(config.stroke_halo.clone())
+        .map_err(|e| JsValue::from_str(&format!("Failed to set stroke halo: {}", e)))?
+        .fill_and_stroke(config.fill_and_stroke.clone())
+        .map_err(|e| JsValue::from_str(&format!("Failed to set fill outline: {}", e)))?
+        .stroke_to_outline(config.stroke_to_outline)
+        .max_gradients(config.max_gradients)
+        .resample_even_spacing(config.resample_even_spacing)
+        .map_err(|e| JsValue::from_str(&format!("Failed to set resample spacing: {}", e)))?
+        .draw_on_animation(config.draw_on_animation)
+        .map_err(|e| JsValue::from_str(&format!("Failed to set draw-on animation: {}", e)))?
+        .absolute_stroke_width(config.absolute_stroke_width)
+        .map_err(|e| JsValue::from_str(&format!("Failed to set absolute stroke width: {}", e)))?
+        .stroke_width_jitter(config.stroke_width_jitter)
+        .map_err(|e| JsValue::from_str(&format!("Failed to set stroke width jitter: {}", e)))?
+        .output_dpi(config.output_dpi)
+        .map_err(|e| JsValue::from_str(&format!("Failed to set output DPI: {}", e)))?;
+
     // Apply directional pass settings
     builder = builder
         .reverse_pass(config.enable_reverse_pass)
-        .diagonal_pass(config.enable_diagonal_pass);
+        .diagonal_pass(config.enable_diagonal_pass)
+        .directional_hysteresis(config.directional_hysteresis)
+        .map_err(|e| JsValue::from_str(&format!("Failed to set directional hysteresis: {}", e)))?
+        .multipass_merge(config.multipass_merge);
 
     // Apply ETF/FDoG settings if enabled
     if config.enable_etf_fdog {
@@ -131,11 +189,19 @@ pub fn apply_config_json(
     let tremor = original_json.get("handDrawnTremorStrength").and_then(|v| v.as_f64()).map(|v| v as f32);
     let weights = original_json.get("handDrawnVariableWeights").and_then(|v| v.as_f64()).map(|v| v as f32);
     let tapering = original_json.get("handDrawnTapering").and_then(|v| v.as_f64()).map(|v| v as f32);
+    let pressure_profile: Option<Vec<f32>> = original_json.get("handDrawnPressureProfile")
+        .and_then(|v| v.as_array())
+        .map(|values| values.iter().filter_map(|v| v.as_f64()).map(|v| v as f32).collect());
+    let apply_to: Option<HandDrawnTarget> = original_json.get("handDrawnApplyTo")
+        .map(|v| serde_json::from_value(v.clone()))
+        .transpose()
+        .map_err(|e| JsValue::from_str(&format!("Invalid handDrawnApplyTo: {}", e)))?;
 
     // Check if custom values are being used
     let has_custom_values = (tremor.is_some() && tremor.unwrap() > 0.0) ||
                            (weights.is_some() && weights.unwrap() > 0.0) ||
-                           (tapering.is_some() && tapering.unwrap() > 0.0);
+                           (tapering.is_some() && tapering.unwrap() > 0.0) ||
+                           pressure_profile.as_ref().is_some_and(|p| !p.is_empty());
 
     // Determine effective preset: if custom values but preset is "none", use "subtle" as base
     let effective_preset = if has_custom_values && hand_drawn_preset == "none" {
@@ -178,6 +244,18 @@ pub fn apply_config_json(
                     .map_err(|e| JsValue::from_str(&format!("Failed to set tapering: {}", e)))?;
             }
         }
+
+        if let Some(profile) = pressure_profile.filter(|p| !p.is_empty()) {
+            log::info!("   Applying hand-drawn pressure profile: {:?}", profile);
+            builder = builder
+                .custom_pressure_profile(profile)
+                .map_err(|e| JsValue::from_str(&format!("Failed to set pressure profile: {}", e)))?;
+        }
+
+        if let Some(target) = apply_to {
+            log::info!("   Applying hand-drawn target: {:?}", target);
+            builder = builder.hand_drawn_apply_to(target);
+        }
     }
 
     // Apply algorithm-specific settings based on backend
@@ -202,14 +280,21 @@ pub fn apply_config_json(
                 .map_err(|e| JsValue::from_str(&format!("Failed to set compactness: {}", e)))?
                 .slic_iterations(config.superpixel_slic_iterations)
                 .map_err(|e| JsValue::from_str(&format!("Failed to set iterations: {}", e)))?
+                .slic_convergence_threshold(config.slic_convergence_threshold)
+                .map_err(|e| JsValue::from_str(&format!("Failed to set SLIC convergence threshold: {}", e)))?
                 .superpixel_initialization_pattern(&match config.superpixel_initialization_pattern {
                     vectorize_core::algorithms::tracing::trace_low::SuperpixelInitPattern::Square => "square",
                     vectorize_core::algorithms::tracing::trace_low::SuperpixelInitPattern::Hexagonal => "hexagonal",
                     vectorize_core::algorithms::tracing::trace_low::SuperpixelInitPattern::Poisson => "poisson",
                 })
                 .map_err(|e| JsValue::from_str(&format!("Failed to set initialization pattern: {}", e)))?
+                .superpixel_saliency_seeding(config.superpixel_saliency_seeding)
+                .region_average_in_lab(config.region_average_in_lab)
+                .region_color_smoothing(config.region_color_smoothing)
+                .map_err(|e| JsValue::from_str(&format!("Failed to set region color smoothing: {}", e)))?
                 .fill_regions(config.superpixel_fill_regions)
                 .stroke_regions(config.superpixel_stroke_regions)
+                .shared_region_edges(config.shared_region_edges)
                 .simplify_boundaries(config.superpixel_simplify_boundaries)
                 .boundary_epsilon(config.superpixel_boundary_epsilon)
                 .map_err(|e| JsValue::from_str(&format!("Failed to set boundary epsilon: {}", e)))?
@@ -219,7 +304,8 @@ pub fn apply_config_json(
                 .superpixel_enhance_edges(config.superpixel_enhance_edges)
                 .superpixel_merge_threshold(config.superpixel_merge_threshold)
                 .map_err(|e| JsValue::from_str(&format!("Failed to set merge threshold: {}", e)))?
-                .enable_advanced_merging(config.enable_advanced_merging);
+                .enable_advanced_merging(config.enable_advanced_merging)
+                .superpixel_debug_colors(config.superpixel_debug_colors);
         }
         TraceBackend::Dots => {
             builder = builder
@@ -230,7 +316,14 @@ pub fn apply_config_json(
                 .adaptive_sizing(config.dot_adaptive_sizing)
                 .set_gradient_based_sizing(config.dot_gradient_based_sizing)
                 .dot_shape(config.dot_shape)
-                .dot_grid_pattern(config.dot_grid_pattern);
+                .dot_grid_pattern(config.dot_grid_pattern)
+                .dots_skip_background(config.dots_skip_background)
+                .dots_merge_to_dashes(config.dots_merge_to_dashes)
+                .halftone_mode(config.halftone_mode)
+                .halftone_angle(config.halftone_angle)
+                .map_err(|e| JsValue::from_str(&format!("Failed to set halftone angle: {}", e)))?
+                .halftone_cell_px(config.halftone_cell_px)
+                .map_err(|e| JsValue::from_str(&format!("Failed to set halftone cell size: {}", e)))?;
 
             // Only apply size variation when neither adaptive nor gradient-based sizing is enabled
             if !config.dot_adaptive_sizing && !config.dot_gradient_based_sizing {
@@ -239,6 +332,11 @@ pub fn apply_config_json(
                     .map_err(|e| JsValue::from_str(&format!("Failed to set dot size variation: {}", e)))?;
             }
         }
+        TraceBackend::LowPoly => {
+            builder = builder
+                .lowpoly_vertex_count(config.lowpoly_vertex_count)
+                .map_err(|e| JsValue::from_str(&format!("Failed to set low-poly vertex count: {}", e)))?;
+        }
     }
 
     // Apply color settings based on backend
@@ -265,6 +363,9 @@ pub fn apply_config_json(
         TraceBackend::Dots => {
             // For dots backend, use preserve_colors (which sets dot_preserve_colors)
             builder = builder.preserve_colors(config.dot_preserve_colors);
+            if let Some(mode) = config.dot_color_mode {
+                builder = builder.dot_color_mode(mode);
+            }
             // Dots backend doesn't use color sampling method
         }
         TraceBackend::Superpixel => {
@@ -300,6 +401,9 @@ pub fn apply_config_json(
             log::info!("   - Palette method: {:?}", config.palette_method);
             log::info!("   - Palette dithering: {}", config.palette_dithering);
         }
+        TraceBackend::LowPoly => {
+            // Low-poly facets always take the average source color of each triangle
+        }
     }
 
     // Replace the config_builder
@@ -324,6 +428,78 @@ pub fn get_config_json(config_builder: &ConfigBuilder) -> Result<String, JsValue
     Ok(json)
 }
 
+/// Crate version embedded in recipes so bug reports identify the producing build
+const RECIPE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Export the current configuration as a compact, reproducible recipe string
+///
+/// The recipe is short JSON holding the crate version (`v`) and only the settings
+/// (`c`) that differ from `TraceLowConfig::default()`, plus any hand-drawn preset
+/// and overrides. `import_recipe` replays it onto the defaults.
+pub fn export_recipe(config_builder: &ConfigBuilder) -> Result<String, JsValue> {
+    let config = config_builder.clone().build()
+        .map_err(|e| JsValue::from_str(&format!("Failed to build config: {}", e)))?;
+
+    let current = serde_json::to_value(&config)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize config: {}", e)))?;
+    let defaults = serde_json::to_value(TraceLowConfig::default())
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize defaults: {}", e)))?;
+
+    let mut changed = serde_json::Map::new();
+    if let (Some(current), Some(defaults)) = (current.as_object(), defaults.as_object()) {
+        for (key, value) in current {
+            if defaults.get(key) != Some(value) {
+                changed.insert(key.clone(), value.clone());
+            }
+        }
+    }
+
+    if let Ok(serde_json::Value::Object(hand_drawn)) =
+        serde_json::to_value(config_builder.hand_drawn_settings())
+    {
+        changed.extend(hand_drawn);
+    }
+
+    Ok(serde_json::json!({ "v": RECIPE_VERSION, "c": changed }).to_string())
+}
+
+/// Apply a recipe produced by `export_recipe`, returning the resulting backend
+///
+/// Settings missing from the recipe fall back to `TraceLowConfig::default()`, so
+/// the result does not depend on the configuration in place before the import.
+pub fn import_recipe(
+    config_builder: &mut ConfigBuilder,
+    recipe: &str
+) -> Result<TraceBackend, JsValue> {
+    let recipe: serde_json::Value = serde_json::from_str(recipe.trim())
+        .map_err(|e| JsValue::from_str(&format!("Invalid recipe: {}", e)))?;
+
+    let version = recipe.get("v").and_then(|v| v.as_str()).unwrap_or("unknown");
+    if version != RECIPE_VERSION {
+        log::warn!(
+            "Recipe was exported by version {}, running {}; output may differ",
+            version,
+            RECIPE_VERSION
+        );
+    }
+
+    let mut config = serde_json::to_value(TraceLowConfig::default())
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize defaults: {}", e)))?;
+    if let (Some(config), Some(changed)) = (
+        config.as_object_mut(),
+        recipe.get("c").and_then(|c| c.as_object()),
+    ) {
+        config.extend(changed.clone());
+    }
+
+    let backend = serde_json::from_value::<TraceLowConfig>(config.clone())
+        .map_err(|e| JsValue::from_str(&format!("Invalid recipe settings: {}", e)))?
+        .backend;
+    apply_config_json(config_builder, &config.to_string())?;
+
+    Ok(backend)
+}
+
 /// Validate configuration JSON without applying it
 ///
 /// Useful for frontend validation before processing
@@ -342,3 +518,38 @@ pub fn validate_config_json(config_json: &str) -> Result<bool, JsValue> {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recipe_round_trip_reproduces_config() {
+        let builder = ConfigBuilder::new()
+            .backend(TraceBackend::Dots)
+            .detail(0.7)
+            .unwrap()
+            .dot_density(0.15)
+            .unwrap()
+            .hand_drawn_preset("medium")
+            .unwrap()
+            .custom_tremor(0.3)
+            .unwrap()
+            .hand_drawn_apply_to(HandDrawnTarget::StrokesOnly);
+
+        let recipe = export_recipe(&builder).unwrap();
+        assert!(recipe.len() < get_config_json(&builder).unwrap().len() / 4);
+        assert!(recipe.contains(RECIPE_VERSION));
+
+        let mut imported = ConfigBuilder::new().backend(TraceBackend::Superpixel);
+        let backend = import_recipe(&mut imported, &recipe).unwrap();
+
+        assert_eq!(backend, TraceBackend::Dots);
+        assert_eq!(
+            serde_json::to_value(imported.clone().build().unwrap()).unwrap(),
+            serde_json::to_value(builder.clone().build().unwrap()).unwrap()
+        );
+        assert_eq!(imported.hand_drawn_settings(), builder.hand_drawn_settings());
+        assert_eq!(export_recipe(&imported).unwrap(), recipe);
+    }
+}
@@ -55,3 +55,16 @@ macro_rules! console_log_macro {
 
 #[allow(unused_imports)]
 pub(crate) use console_log_macro as console_log;
+
+/// Send a progress update to a JS progress callback
+///
+/// A progress value that fails to serialize is logged and skipped rather than
+/// unwrapped, so progress reporting can never abort the vectorization it describes.
+pub fn emit_progress<T: serde::Serialize>(callback: &js_sys::Function, progress: &T) {
+    match serde_wasm_bindgen::to_value(progress) {
+        Ok(value) => {
+            let _ = callback.call1(&JsValue::NULL, &value);
+        }
+        Err(e) => log::warn!("Skipping progress callback: failed to serialize progress: {e}"),
+    }
+}
@@ -3,6 +3,7 @@
 //! This module provides a clean WASM interface using the immutable configuration
 //! system, eliminating all the recursive aliasing issues and bandaid fixes.
 
+use crate::utils::emit_progress;
 use crate::wasm_config::WasmConfigManager;
 use image::ImageBuffer;
 use js_sys::Function;
@@ -277,7 +278,7 @@ impl WasmVectorizerRefactored {
                 "message": "Starting vectorization...",
                 "processing_time_ms": 0.0,
             });
-            let _ = cb.call1(&JsValue::NULL, &serde_wasm_bindgen::to_value(&progress).unwrap());
+            emit_progress(cb, &progress);
         }
 
         // Report progress: Processing
@@ -288,7 +289,7 @@ impl WasmVectorizerRefactored {
                 "message": format!("Processing with {} backend...", self.config_manager.get_backend()),
                 "processing_time_ms": js_sys::Date::now() - start_time,
             });
-            let _ = cb.call1(&JsValue::NULL, &serde_wasm_bindgen::to_value(&progress).unwrap());
+            emit_progress(cb, &progress);
         }
 
         // Process the image
@@ -303,7 +304,7 @@ impl WasmVectorizerRefactored {
                 "svg_size": result.len(),
                 "processing_time_ms": js_sys::Date::now() - start_time,
             });
-            let _ = cb.call1(&JsValue::NULL, &serde_wasm_bindgen::to_value(&progress).unwrap());
+            emit_progress(cb, &progress);
         }
 
         Ok(result)
@@ -38,6 +38,7 @@ impl WasmConfigManager {
             "centerline" => TraceBackend::Centerline,
             "superpixel" => TraceBackend::Superpixel,
             "dots" => TraceBackend::Dots,
+            "lowpoly" => TraceBackend::LowPoly,
             _ => return Err(JsValue::from_str(&format!("Unknown backend: {}", backend))),
         };
 
@@ -246,6 +247,7 @@ impl WasmConfigManager {
             TraceBackend::Centerline => "centerline".to_string(),
             TraceBackend::Superpixel => "superpixel".to_string(),
             TraceBackend::Dots => "dots".to_string(),
+            TraceBackend::LowPoly => "lowpoly".to_string(),
         }
     }
 